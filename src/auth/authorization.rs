@@ -1,6 +1,6 @@
 use crate::auth::AuthenticationScheme;
 use crate::bail_status as bail;
-use crate::headers::{Header, HeaderName, HeaderValue, Headers, AUTHORIZATION};
+use crate::headers::{Header, HeaderName, HeaderValue, HeaderValues, Headers, AUTHORIZATION};
 
 /// Credentials to authenticate a user agent with a server.
 ///
@@ -47,14 +47,17 @@ impl Authorization {
 
     /// Create a new instance from headers.
     pub fn from_headers(headers: impl AsRef<Headers>) -> crate::Result<Option<Self>> {
-        let headers = match headers.as_ref().get(AUTHORIZATION) {
-            Some(headers) => headers,
-            None => return Ok(None),
-        };
+        match headers.as_ref().get(AUTHORIZATION) {
+            Some(values) => Self::from_values(values).map(Some),
+            None => Ok(None),
+        }
+    }
 
+    /// Create a new instance from the values of a credentials header.
+    pub(crate) fn from_values(values: &HeaderValues) -> crate::Result<Self> {
         // If we successfully parsed the header then there's always at least one
         // entry. We want the last entry.
-        let value = headers.iter().last().unwrap();
+        let value = values.iter().last().unwrap();
 
         let mut iter = value.as_str().splitn(2, ' ');
         let scheme = iter.next();
@@ -65,10 +68,10 @@ impl Authorization {
             (Some(scheme), Some(credentials)) => (scheme.parse()?, credentials.to_owned()),
         };
 
-        Ok(Some(Self {
+        Ok(Self {
             scheme,
             credentials,
-        }))
+        })
     }
 
     /// Get the authorization scheme.
@@ -1,7 +1,8 @@
 use crate::headers::{HeaderName, HeaderValue, Headers, AUTHORIZATION};
+use crate::utils::constant_time_eq;
 use crate::Status;
 use crate::{
-    auth::{AuthenticationScheme, Authorization},
+    auth::{AuthenticationScheme, Authorization, BasicCharset},
     headers::Header,
 };
 use crate::{bail_status as bail, ensure_status as ensure};
@@ -38,10 +39,13 @@ use crate::{bail_status as bail, ensure_status as ensure};
 pub struct BasicAuth {
     username: String,
     password: String,
+    charset: BasicCharset,
 }
 
 impl BasicAuth {
     /// Create a new instance of `BasicAuth`.
+    ///
+    /// The credentials are encoded as UTF-8.
     pub fn new<U, P>(username: U, password: P) -> Self
     where
         U: AsRef<str>,
@@ -49,7 +53,11 @@ impl BasicAuth {
     {
         let username = username.as_ref().to_owned();
         let password = password.as_ref().to_owned();
-        Self { username, password }
+        Self {
+            username,
+            password,
+            charset: BasicCharset::Utf8,
+        }
     }
 
     /// Create a new instance from headers.
@@ -58,7 +66,10 @@ impl BasicAuth {
             Some(auth) => auth,
             None => return Ok(None),
         };
+        Self::from_authorization(&auth).map(Some)
+    }
 
+    pub(crate) fn from_authorization(auth: &Authorization) -> crate::Result<Self> {
         let scheme = auth.scheme();
         ensure!(
             matches!(scheme, AuthenticationScheme::Basic),
@@ -66,13 +77,23 @@ impl BasicAuth {
             "Expected basic auth scheme found `{}`",
             scheme
         );
-        Self::from_credentials(auth.credentials()).map(Some)
+        Self::from_credentials(auth.credentials())
     }
 
     /// Create a new instance from the base64 encoded credentials.
+    ///
+    /// Credentials are decoded as UTF-8 when they are valid UTF-8, and as
+    /// ISO-8859-1 otherwise.
     pub fn from_credentials(credentials: impl AsRef<[u8]>) -> crate::Result<Self> {
         let bytes = base64::decode(credentials).status(400)?;
-        let credentials = String::from_utf8(bytes).status(400)?;
+        let (credentials, charset) = match String::from_utf8(bytes) {
+            Ok(credentials) => (credentials, BasicCharset::Utf8),
+            Err(err) => {
+                let bytes = err.into_bytes();
+                let credentials = bytes.into_iter().map(char::from).collect();
+                (credentials, BasicCharset::Iso8859_1)
+            }
+        };
 
         let mut iter = credentials.splitn(2, ':');
         let username = iter.next();
@@ -84,7 +105,11 @@ impl BasicAuth {
             (None, _) => bail!(400, "Expected basic auth to contain a username"),
         };
 
-        Ok(Self { username, password })
+        Ok(Self {
+            username,
+            password,
+            charset,
+        })
     }
 
     /// Get the username.
@@ -96,6 +121,47 @@ impl BasicAuth {
     pub fn password(&self) -> &str {
         self.password.as_str()
     }
+
+    /// Get the encoding of the credentials.
+    pub fn charset(&self) -> BasicCharset {
+        self.charset
+    }
+
+    /// Set the encoding of the credentials.
+    ///
+    /// Use the charset requested by the server's
+    /// [`BasicChallenge`](crate::auth::BasicChallenge) if there is one.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the username or password can't be represented in
+    /// the encoding.
+    pub fn set_charset(&mut self, charset: BasicCharset) -> crate::Result<()> {
+        crate::ensure!(
+            charset.encode(&self.username).is_some() && charset.encode(&self.password).is_some(),
+            "Basic auth credentials can't be encoded as {}",
+            charset
+        );
+        self.charset = charset;
+        Ok(())
+    }
+
+    /// Check the credentials against a known username and password.
+    ///
+    /// The comparison takes the same amount of time wherever the credentials
+    /// differ, so it doesn't reveal how much of them was guessed correctly.
+    pub fn verify(&self, username: impl AsRef<str>, password: impl AsRef<str>) -> bool {
+        let username = constant_time_eq(self.username.as_bytes(), username.as_ref().as_bytes());
+        let password = constant_time_eq(self.password.as_bytes(), password.as_ref().as_bytes());
+        username & password
+    }
+
+    pub(crate) fn credentials(&self) -> String {
+        let mut bytes = self.charset.encode(&self.username).unwrap();
+        bytes.push(b':');
+        bytes.extend(self.charset.encode(&self.password).unwrap());
+        base64::encode(bytes)
+    }
 }
 
 impl Header for BasicAuth {
@@ -105,8 +171,7 @@ impl Header for BasicAuth {
 
     fn header_value(&self) -> HeaderValue {
         let scheme = AuthenticationScheme::Basic;
-        let credentials = base64::encode(format!("{}:{}", self.username, self.password));
-        let auth = Authorization::new(scheme, credentials);
+        let auth = Authorization::new(scheme, self.credentials());
         auth.header_value()
     }
 }
@@ -132,6 +197,34 @@ mod test {
         Ok(())
     }
 
+    #[test]
+    fn iso_8859_1() -> crate::Result<()> {
+        let mut authz = BasicAuth::new("test", "123\u{a3}");
+        assert_eq!(authz.credentials(), "dGVzdDoxMjPCow==");
+        authz.set_charset(BasicCharset::Iso8859_1)?;
+        assert_eq!(authz.credentials(), "dGVzdDoxMjOj");
+
+        let authz = BasicAuth::from_credentials("dGVzdDoxMjOj")?;
+        assert_eq!(authz.password(), "123\u{a3}");
+        assert_eq!(authz.charset(), BasicCharset::Iso8859_1);
+        let authz = BasicAuth::from_credentials("dGVzdDoxMjPCow==")?;
+        assert_eq!(authz.password(), "123\u{a3}");
+        assert_eq!(authz.charset(), BasicCharset::Utf8);
+
+        let mut authz = BasicAuth::new("n\u{f6}ri", "\u{1f41f}");
+        assert!(authz.set_charset(BasicCharset::Iso8859_1).is_err());
+        assert_eq!(authz.charset(), BasicCharset::Utf8);
+        Ok(())
+    }
+
+    #[test]
+    fn verify() {
+        let authz = BasicAuth::new("nori", "secret_fish!!");
+        assert!(authz.verify("nori", "secret_fish!!"));
+        assert!(!authz.verify("nori", "secret_fish!"));
+        assert!(!authz.verify("chashu", "secret_fish!!"));
+    }
+
     #[test]
     fn bad_request_on_parse_error() {
         let mut headers = Headers::new();
@@ -0,0 +1,188 @@
+use crate::auth::params::write_quoted;
+use crate::auth::{AuthenticationScheme, BasicCharset, WwwAuthenticate};
+use crate::headers::{Header, HeaderName, HeaderValue, Headers, WWW_AUTHENTICATE};
+use crate::parse_utils::is_quotable;
+use crate::{bail_status as bail, ensure_status as ensure};
+
+/// A challenge for HTTP Basic authentication.
+///
+/// Servers send this in the `WWW-Authenticate` header of a `401
+/// Unauthorized` response, clients answer it with a
+/// [`BasicAuth`](crate::auth::BasicAuth) encoded in the requested charset.
+///
+/// # Specifications
+///
+/// - [RFC 7617, section 2: The 'Basic' Authentication Scheme](https://tools.ietf.org/html/rfc7617#section-2)
+///
+/// # Examples
+///
+/// ```
+/// # fn main() -> http_types::Result<()> {
+/// #
+/// use http_types::Response;
+/// use http_types::auth::{BasicAuth, BasicChallenge, BasicCharset};
+///
+/// let challenge = BasicChallenge::new("foo")?;
+///
+/// let mut res = Response::new(401);
+/// res.insert_header(&challenge, &challenge)?;
+/// assert_eq!(res["WWW-Authenticate"], r#"Basic realm="foo", charset="UTF-8""#);
+///
+/// let challenge = BasicChallenge::from_headers(res)?.unwrap();
+/// let mut authz = BasicAuth::new("test", "123\u{a3}");
+/// if let Some(charset) = challenge.charset() {
+///     authz.set_charset(charset)?;
+/// }
+/// assert_eq!(authz.charset(), BasicCharset::Utf8);
+/// #
+/// # Ok(()) }
+/// ```
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct BasicChallenge {
+    realm: String,
+    charset: Option<BasicCharset>,
+}
+
+impl BasicChallenge {
+    /// Create a new instance of `BasicChallenge`.
+    ///
+    /// The challenge asks for UTF-8 encoded credentials.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the realm contains non-ASCII or control
+    /// characters.
+    pub fn new(realm: impl Into<String>) -> crate::Result<Self> {
+        let mut challenge = Self {
+            realm: String::new(),
+            charset: Some(BasicCharset::Utf8),
+        };
+        challenge.set_realm(realm)?;
+        Ok(challenge)
+    }
+
+    /// Create a new instance from headers.
+    ///
+    /// Only the first Basic challenge is used, challenges for other schemes
+    /// are ignored.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the realm is missing, or the `charset` auth-param
+    /// is anything other than `UTF-8`.
+    pub fn from_headers(headers: impl AsRef<Headers>) -> crate::Result<Option<Self>> {
+        let authenticate = match WwwAuthenticate::from_headers(headers)? {
+            Some(authenticate) => authenticate,
+            None => return Ok(None),
+        };
+        let challenge = match authenticate.get(AuthenticationScheme::Basic) {
+            Some(challenge) => challenge,
+            None => return Ok(None),
+        };
+        let realm = match challenge.realm() {
+            Some(realm) => realm.to_string(),
+            None => bail!(400, "Basic challenge is missing a realm"),
+        };
+        let charset = match challenge.param("charset") {
+            Some(charset) if charset.eq_ignore_ascii_case("UTF-8") => Some(BasicCharset::Utf8),
+            Some(charset) => bail!(400, "`{}` is not a valid basic auth charset", charset),
+            None => None,
+        };
+        Ok(Some(Self { realm, charset }))
+    }
+
+    /// Get the realm.
+    pub fn realm(&self) -> &str {
+        &self.realm
+    }
+
+    /// Set the realm.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the realm contains non-ASCII or control
+    /// characters.
+    pub fn set_realm(&mut self, realm: impl Into<String>) -> crate::Result<()> {
+        let realm = realm.into();
+        ensure!(is_quotable(&realm), 400, "Invalid basic realm");
+        self.realm = realm;
+        Ok(())
+    }
+
+    /// Get the charset the server expects credentials to be encoded in.
+    pub fn charset(&self) -> Option<BasicCharset> {
+        self.charset
+    }
+
+    /// Set the charset the server expects credentials to be encoded in.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error for charsets other than [`BasicCharset::Utf8`], the
+    /// only value defined for the `charset` auth-param.
+    pub fn set_charset(&mut self, charset: Option<BasicCharset>) -> crate::Result<()> {
+        ensure!(
+            matches!(charset, None | Some(BasicCharset::Utf8)),
+            400,
+            "Basic challenges can only request UTF-8 credentials"
+        );
+        self.charset = charset;
+        Ok(())
+    }
+}
+
+impl Header for BasicChallenge {
+    fn header_name(&self) -> HeaderName {
+        WWW_AUTHENTICATE
+    }
+
+    fn header_value(&self) -> HeaderValue {
+        let mut output = String::from("Basic ");
+        write_quoted(&mut output, "realm", &self.realm);
+        if let Some(charset) = self.charset {
+            write_quoted(&mut output, "charset", charset.as_str());
+        }
+
+        // SAFETY: the internal string is validated to be ASCII.
+        unsafe { HeaderValue::from_bytes_unchecked(output.into()) }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn charset() -> crate::Result<()> {
+        let mut headers = Headers::new();
+        headers.insert(WWW_AUTHENTICATE, "Bearer, basic REALM=foo, CHARSET=utf-8")?;
+        let challenge = BasicChallenge::from_headers(&headers)?.unwrap();
+        assert_eq!(challenge.realm(), "foo");
+        assert_eq!(challenge.charset(), Some(BasicCharset::Utf8));
+
+        let mut challenge = BasicChallenge::new("foo")?;
+        challenge.set_charset(None)?;
+        assert_eq!(challenge.header_value(), r#"Basic realm="foo""#);
+        assert!(challenge
+            .set_charset(Some(BasicCharset::Iso8859_1))
+            .is_err());
+        challenge.apply_header(&mut headers);
+        assert_eq!(BasicChallenge::from_headers(headers)?, Some(challenge));
+        Ok(())
+    }
+
+    #[test]
+    fn bad_request_on_parse_error() {
+        for input in [
+            r#"Basic charset="EBCDIC", realm="foo""#,
+            r#"Basic realm="foo", charset="ISO-8859-1""#,
+            r#"Basic realm="foo", charset=utf8"#,
+            "Basic charset=utf-8",
+        ] {
+            let mut headers = Headers::new();
+            headers.insert(WWW_AUTHENTICATE, input).unwrap();
+            let err = BasicChallenge::from_headers(headers).unwrap_err();
+            assert_eq!(err.status(), 400, "{} should not parse", input);
+        }
+    }
+}
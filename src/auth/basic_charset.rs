@@ -0,0 +1,56 @@
+use std::convert::TryFrom;
+use std::fmt::{self, Display};
+use std::str::FromStr;
+
+use crate::bail_status as bail;
+
+/// The character encoding of Basic authentication credentials.
+///
+/// # Specifications
+///
+/// - [RFC 7617, section 2.1: The 'charset' auth-param](https://tools.ietf.org/html/rfc7617#section-2.1)
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub enum BasicCharset {
+    /// UTF-8, the only encoding servers can request with the `charset`
+    /// auth-param.
+    Utf8,
+    /// ISO-8859-1, which many older clients use for credentials.
+    Iso8859_1,
+}
+
+impl BasicCharset {
+    /// Get the formatted string.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Utf8 => "UTF-8",
+            Self::Iso8859_1 => "ISO-8859-1",
+        }
+    }
+
+    /// Encode `s`, returning `None` if it contains characters which can't be
+    /// represented in this encoding.
+    pub(crate) fn encode(&self, s: &str) -> Option<Vec<u8>> {
+        match self {
+            Self::Utf8 => Some(s.as_bytes().to_vec()),
+            Self::Iso8859_1 => s.chars().map(|c| u8::try_from(c).ok()).collect(),
+        }
+    }
+}
+
+impl Display for BasicCharset {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for BasicCharset {
+    type Err = crate::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "utf-8" | "utf8" => Ok(Self::Utf8),
+            "iso-8859-1" | "latin1" => Ok(Self::Iso8859_1),
+            _ => bail!(400, "`{}` is not a supported basic auth charset", s),
+        }
+    }
+}
@@ -0,0 +1,185 @@
+use crate::auth::params::{form_params, parse_token68};
+use crate::auth::{AuthenticationScheme, Authorization};
+use crate::headers::{Header, HeaderName, HeaderValue, Headers, AUTHORIZATION};
+use crate::{bail_status as bail, ensure_status as ensure};
+use crate::{Method, Request};
+
+/// HTTP Bearer token authorization.
+///
+/// # Specifications
+///
+/// - [RFC 6750: The OAuth 2.0 Authorization Framework: Bearer Token Usage](https://tools.ietf.org/html/rfc6750)
+///
+/// # Examples
+///
+/// ```
+/// # fn main() -> http_types::Result<()> {
+/// #
+/// use http_types::Request;
+/// use http_types::auth::BearerAuth;
+///
+/// let authz = BearerAuth::new("mF_9.B5f-4.1JqM")?;
+///
+/// let mut req = Request::get("https://example.com/resource");
+/// req.insert_header(&authz, &authz);
+/// assert_eq!(req["Authorization"], "Bearer mF_9.B5f-4.1JqM");
+///
+/// let authz = BearerAuth::from_headers(req)?.unwrap();
+/// assert_eq!(authz.token(), "mF_9.B5f-4.1JqM");
+/// #
+/// # Ok(()) }
+/// ```
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct BearerAuth {
+    token: String,
+}
+
+impl BearerAuth {
+    /// Create a new instance of `BearerAuth`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the token does not match the `b64token` syntax.
+    pub fn new(token: impl Into<String>) -> crate::Result<Self> {
+        let token = token.into();
+        ensure!(
+            matches!(parse_token68(&token), (Some(b64token), "") if b64token.len() == token.len()),
+            400,
+            "Invalid bearer token"
+        );
+        Ok(Self { token })
+    }
+
+    /// Create a new instance from headers.
+    pub fn from_headers(headers: impl AsRef<Headers>) -> crate::Result<Option<Self>> {
+        let auth = match Authorization::from_headers(headers)? {
+            Some(auth) => auth,
+            None => return Ok(None),
+        };
+
+        let scheme = auth.scheme();
+        ensure!(
+            matches!(scheme, AuthenticationScheme::Bearer),
+            400,
+            "Expected bearer auth scheme found `{}`",
+            scheme
+        );
+        Self::from_credentials(auth.credentials()).map(Some)
+    }
+
+    /// Create a new instance from the credentials of an `Authorization`
+    /// header.
+    pub fn from_credentials(credentials: impl AsRef<str>) -> crate::Result<Self> {
+        Self::new(credentials.as_ref())
+    }
+
+    /// Extract the access token from a request.
+    ///
+    /// Besides the `Authorization` header, this looks for an `access_token`
+    /// parameter in a form-encoded body or the URL query. The body is only
+    /// read for methods other than `GET` and `HEAD` with a form content type,
+    /// and is left intact for later use.
+    ///
+    /// Tokens sent in the URL are likely to end up in logs, servers accepting
+    /// them should send `Cache-Control: private` in their responses.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `400 Bad Request` error if the token is malformed, or if more
+    /// than one method was used to send it.
+    pub async fn from_request(req: &mut Request) -> crate::Result<Option<Self>> {
+        let mut tokens = vec![];
+        if let Some(auth) = Self::from_headers(&*req)? {
+            tokens.push(auth);
+        }
+
+        if !matches!(req.method(), Method::Get | Method::Head) {
+            for (name, value) in form_params(req).await? {
+                if name == "access_token" {
+                    tokens.push(Self::new(value)?);
+                }
+            }
+        }
+
+        for (name, value) in req.url().query_pairs() {
+            if name == "access_token" {
+                tokens.push(Self::new(value)?);
+            }
+        }
+
+        match tokens.len() {
+            0 | 1 => Ok(tokens.pop()),
+            _ => bail!(400, "Bearer token was sent more than once"),
+        }
+    }
+
+    /// Get the access token.
+    pub fn token(&self) -> &str {
+        &self.token
+    }
+}
+
+impl Header for BearerAuth {
+    fn header_name(&self) -> HeaderName {
+        AUTHORIZATION
+    }
+
+    fn header_value(&self) -> HeaderValue {
+        let auth = Authorization::new(AuthenticationScheme::Bearer, self.token.clone());
+        auth.header_value()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::mime;
+
+    #[test]
+    fn smoke() -> crate::Result<()> {
+        let authz = BearerAuth::new("mF_9.B5f-4.1JqM==")?;
+        let mut headers = Headers::new();
+        authz.apply_header(&mut headers);
+
+        let authz = BearerAuth::from_headers(headers)?.unwrap();
+        assert_eq!(authz.token(), "mF_9.B5f-4.1JqM==");
+        Ok(())
+    }
+
+    #[test]
+    fn bad_request_on_parse_error() {
+        for input in ["Bearer abc def", "Bearer a=b", "Basic bm9yaTpmaXNo"] {
+            let mut headers = Headers::new();
+            headers.insert(AUTHORIZATION, input).unwrap();
+            let err = BearerAuth::from_headers(headers).unwrap_err();
+            assert_eq!(err.status(), 400, "{} should not parse", input);
+        }
+        assert!(BearerAuth::new("").is_err());
+        assert!(BearerAuth::new("abc ").is_err());
+    }
+
+    #[async_std::test]
+    async fn from_request() -> crate::Result<()> {
+        let mut req = Request::get("https://example.com/?access_token=from-query");
+        let authz = BearerAuth::from_request(&mut req).await?.unwrap();
+        assert_eq!(authz.token(), "from-query");
+
+        let mut req = Request::post("https://example.com/");
+        req.set_body("a=b&access_token=from-form");
+        req.set_content_type(mime::FORM);
+        let authz = BearerAuth::from_request(&mut req).await?.unwrap();
+        assert_eq!(authz.token(), "from-form");
+        assert_eq!(req.body_string().await?, "a=b&access_token=from-form");
+
+        let mut req = Request::get("https://example.com/");
+        req.set_body("access_token=ignored");
+        req.set_content_type(mime::FORM);
+        assert_eq!(BearerAuth::from_request(&mut req).await?, None);
+
+        let mut req = Request::get("https://example.com/?access_token=from-query");
+        BearerAuth::new("from-header")?.apply_header(&mut req);
+        let err = BearerAuth::from_request(&mut req).await.unwrap_err();
+        assert_eq!(err.status(), 400);
+        Ok(())
+    }
+}
@@ -0,0 +1,351 @@
+use crate::auth::{AuthenticationScheme, BearerError, Challenge, WwwAuthenticate};
+use crate::ensure_status as ensure;
+use crate::headers::{Header, HeaderName, HeaderValue, Headers, WWW_AUTHENTICATE};
+use crate::parse_utils::is_quotable;
+use crate::{Response, StatusCode};
+
+/// A challenge for Bearer token authentication.
+///
+/// Resource servers send this when a request lacks a valid access token,
+/// optionally describing why the token was rejected.
+///
+/// # Specifications
+///
+/// - [RFC 6750, section 3: The WWW-Authenticate Response Header Field](https://tools.ietf.org/html/rfc6750#section-3)
+///
+/// # Examples
+///
+/// ```
+/// # fn main() -> http_types::Result<()> {
+/// #
+/// use http_types::auth::{BearerChallenge, BearerError};
+/// use http_types::StatusCode;
+///
+/// let mut challenge = BearerChallenge::invalid_token("The access token expired")?;
+/// challenge.set_realm(Some("example".into()))?;
+///
+/// let res = challenge.into_response();
+/// assert_eq!(res.status(), StatusCode::Unauthorized);
+/// assert_eq!(
+///     res["WWW-Authenticate"],
+///     r#"Bearer realm="example", error="invalid_token", error_description="The access token expired""#
+/// );
+///
+/// let challenge = BearerChallenge::from_headers(res)?.unwrap();
+/// assert_eq!(challenge.error(), Some(&BearerError::InvalidToken));
+/// #
+/// # Ok(()) }
+/// ```
+#[derive(Debug, Clone, Default, Eq, PartialEq)]
+pub struct BearerChallenge {
+    realm: Option<String>,
+    scope: Option<String>,
+    error: Option<BearerError>,
+    error_description: Option<String>,
+    error_uri: Option<String>,
+}
+
+impl BearerChallenge {
+    /// Create a new instance of `BearerChallenge` without an error.
+    ///
+    /// This should be used when the request did not include any
+    /// authentication information.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create a challenge for a malformed request.
+    pub fn invalid_request(description: impl Into<String>) -> crate::Result<Self> {
+        Self::with_error(BearerError::InvalidRequest, description.into())
+    }
+
+    /// Create a challenge for an expired, revoked or otherwise invalid token.
+    pub fn invalid_token(description: impl Into<String>) -> crate::Result<Self> {
+        Self::with_error(BearerError::InvalidToken, description.into())
+    }
+
+    /// Create a challenge for a token which lacks the required `scope`.
+    pub fn insufficient_scope(scope: impl Into<String>) -> crate::Result<Self> {
+        let mut challenge = Self::new();
+        challenge.set_error(Some(BearerError::InsufficientScope))?;
+        challenge.set_scope(Some(scope.into()))?;
+        Ok(challenge)
+    }
+
+    fn with_error(error: BearerError, description: String) -> crate::Result<Self> {
+        let mut challenge = Self::new();
+        challenge.set_error(Some(error))?;
+        challenge.set_error_description(Some(description))?;
+        Ok(challenge)
+    }
+
+    /// Create a new instance from headers.
+    ///
+    /// Only the first Bearer challenge is used, challenges for other schemes
+    /// are ignored.
+    pub fn from_headers(headers: impl AsRef<Headers>) -> crate::Result<Option<Self>> {
+        let authenticate = match WwwAuthenticate::from_headers(headers)? {
+            Some(authenticate) => authenticate,
+            None => return Ok(None),
+        };
+        let challenge = match authenticate.get(AuthenticationScheme::Bearer) {
+            Some(challenge) => challenge,
+            None => return Ok(None),
+        };
+        Ok(Some(Self {
+            realm: challenge.realm().map(String::from),
+            scope: challenge.param("scope").map(String::from),
+            error: challenge.param("error").map(str::parse).transpose()?,
+            error_description: challenge.param("error_description").map(String::from),
+            error_uri: challenge.param("error_uri").map(String::from),
+        }))
+    }
+
+    /// Get the realm.
+    pub fn realm(&self) -> Option<&str> {
+        self.realm.as_deref()
+    }
+
+    /// Set the realm.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the realm contains control or non-ASCII
+    /// characters.
+    pub fn set_realm(&mut self, realm: Option<String>) -> crate::Result<()> {
+        if let Some(realm) = &realm {
+            ensure!(is_quotable(realm), 400, "Invalid bearer realm");
+        }
+        self.realm = realm;
+        Ok(())
+    }
+
+    /// Get the space-delimited list of scopes required to access the
+    /// resource.
+    pub fn scope(&self) -> Option<&str> {
+        self.scope.as_deref()
+    }
+
+    /// Set the space-delimited list of scopes required to access the
+    /// resource.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the scope contains characters other than printable
+    /// ASCII, `"` or `\`.
+    pub fn set_scope(&mut self, scope: Option<String>) -> crate::Result<()> {
+        if let Some(scope) = &scope {
+            ensure!(is_nqschar(scope), 400, "Invalid bearer scope");
+        }
+        self.scope = scope;
+        Ok(())
+    }
+
+    /// Get the error code.
+    pub fn error(&self) -> Option<&BearerError> {
+        self.error.as_ref()
+    }
+
+    /// Set the error code.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if an extension error code is empty or contains
+    /// characters other than printable ASCII, `"` or `\`.
+    pub fn set_error(&mut self, error: Option<BearerError>) -> crate::Result<()> {
+        if let Some(error) = &error {
+            ensure!(
+                !error.as_str().is_empty() && is_nqschar(error.as_str()),
+                400,
+                "Invalid bearer error code"
+            );
+        }
+        self.error = error;
+        Ok(())
+    }
+
+    /// Get the human-readable description of the error.
+    pub fn error_description(&self) -> Option<&str> {
+        self.error_description.as_deref()
+    }
+
+    /// Set the human-readable description of the error.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the description contains characters other than
+    /// printable ASCII, `"` or `\`.
+    pub fn set_error_description(&mut self, description: Option<String>) -> crate::Result<()> {
+        if let Some(description) = &description {
+            ensure!(
+                is_nqschar(description),
+                400,
+                "Invalid bearer error description"
+            );
+        }
+        self.error_description = description;
+        Ok(())
+    }
+
+    /// Get the URI of a web page describing the error.
+    pub fn error_uri(&self) -> Option<&str> {
+        self.error_uri.as_deref()
+    }
+
+    /// Set the URI of a web page describing the error.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the URI contains characters other than printable
+    /// ASCII, `"` or `\`.
+    pub fn set_error_uri(&mut self, uri: Option<String>) -> crate::Result<()> {
+        if let Some(uri) = &uri {
+            ensure!(is_nqschar(uri), 400, "Invalid bearer error URI");
+        }
+        self.error_uri = uri;
+        Ok(())
+    }
+
+    /// Get the status code a response carrying this challenge should use.
+    ///
+    /// This is `401 Unauthorized` unless the error code calls for another
+    /// status.
+    pub fn status(&self) -> StatusCode {
+        match &self.error {
+            Some(error) => error.status(),
+            None => StatusCode::Unauthorized,
+        }
+    }
+
+    /// Create a response carrying this challenge, with the status code
+    /// matching the error.
+    pub fn into_response(self) -> Response {
+        let mut res = Response::new(self.status());
+        self.apply_header(&mut res);
+        res
+    }
+}
+
+impl From<BearerChallenge> for Challenge {
+    fn from(bearer: BearerChallenge) -> Self {
+        let mut challenge = Challenge::new(AuthenticationScheme::Bearer);
+        let params = [
+            ("realm", bearer.realm),
+            ("scope", bearer.scope),
+            ("error", bearer.error.map(|error| error.to_string())),
+            ("error_description", bearer.error_description),
+            ("error_uri", bearer.error_uri),
+        ];
+        for (name, value) in params {
+            if let Some(value) = value {
+                challenge
+                    .insert_param(name, value)
+                    .expect("bearer parameters are validated on construction");
+            }
+        }
+        challenge
+    }
+}
+
+impl Header for BearerChallenge {
+    fn header_name(&self) -> HeaderName {
+        WWW_AUTHENTICATE
+    }
+
+    fn header_value(&self) -> HeaderValue {
+        let output = Challenge::from(self.clone()).to_string();
+
+        // SAFETY: the internal string is validated to be ASCII.
+        unsafe { HeaderValue::from_bytes_unchecked(output.into()) }
+    }
+}
+
+/// NQSCHAR = %x20-21 / %x23-5B / %x5D-7E
+pub(super) fn is_nqschar(s: &str) -> bool {
+    s.bytes()
+        .all(|b| (b' '..=b'~').contains(&b) && b != b'"' && b != b'\\')
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn responses() -> crate::Result<()> {
+        let res = BearerChallenge::new().into_response();
+        assert_eq!(res.status(), StatusCode::Unauthorized);
+        assert_eq!(res[WWW_AUTHENTICATE], "Bearer");
+
+        let res = BearerChallenge::insufficient_scope("read write")?.into_response();
+        assert_eq!(res.status(), StatusCode::Forbidden);
+        assert_eq!(
+            res[WWW_AUTHENTICATE],
+            r#"Bearer scope="read write", error="insufficient_scope""#
+        );
+
+        let mut challenge = BearerChallenge::invalid_request("Token sent twice")?;
+        challenge.set_error_uri(Some("https://example.com/errors".into()))?;
+        let res = challenge.clone().into_response();
+        assert_eq!(res.status(), StatusCode::BadRequest);
+        assert_eq!(BearerChallenge::from_headers(res)?, Some(challenge));
+        Ok(())
+    }
+
+    #[test]
+    fn invalid_values() {
+        assert!(BearerChallenge::invalid_token(r#"say "hi""#).is_err());
+        assert!(BearerChallenge::insufficient_scope("caf\u{e9}").is_err());
+        assert!(BearerChallenge::new()
+            .set_realm(Some("new\nline".into()))
+            .is_err());
+        assert!(BearerChallenge::new()
+            .set_realm(Some("r\u{e9}alm".into()))
+            .is_err());
+    }
+
+    #[test]
+    fn quoted_realm() -> crate::Result<()> {
+        let mut challenge = BearerChallenge::new();
+        challenge.set_realm(Some(r#"say "hi""#.into()))?;
+        let res = challenge.clone().into_response();
+        assert_eq!(res[WWW_AUTHENTICATE], r#"Bearer realm="say \"hi\"""#);
+        assert_eq!(BearerChallenge::from_headers(res)?, Some(challenge));
+        Ok(())
+    }
+
+    #[test]
+    fn ignore_other_schemes() -> crate::Result<()> {
+        let mut res = Response::new(401);
+        res.insert_header(WWW_AUTHENTICATE, r#"Basic realm="simple""#)?;
+        assert_eq!(BearerChallenge::from_headers(&res)?, None);
+
+        res.append_header(WWW_AUTHENTICATE, r#"Bearer error="use_dpop_nonce""#)?;
+        let challenge = BearerChallenge::from_headers(&res)?.unwrap();
+        let error = BearerError::Other("use_dpop_nonce".into());
+        assert_eq!(challenge.error(), Some(&error));
+        assert_eq!(challenge.status(), StatusCode::Unauthorized);
+        Ok(())
+    }
+
+    #[test]
+    fn extension_error_codes() -> crate::Result<()> {
+        let mut challenge = BearerChallenge::new();
+        challenge.set_error(Some(BearerError::Other("use_dpop_nonce".into())))?;
+        let res = challenge.clone().into_response();
+        assert_eq!(res[WWW_AUTHENTICATE], r#"Bearer error="use_dpop_nonce""#);
+        assert_eq!(BearerChallenge::from_headers(res)?, Some(challenge));
+
+        assert!(BearerChallenge::new()
+            .set_error(Some(BearerError::Other(r#"say "hi""#.into())))
+            .is_err());
+        assert!(BearerChallenge::new()
+            .set_error(Some(BearerError::Other(String::new())))
+            .is_err());
+
+        let mut res = Response::new(401);
+        res.insert_header(WWW_AUTHENTICATE, r#"Bearer error="""#)?;
+        let err = BearerChallenge::from_headers(&res).unwrap_err();
+        assert_eq!(err.status(), 400);
+        Ok(())
+    }
+}
@@ -0,0 +1,72 @@
+use std::fmt::{self, Display};
+use std::str::FromStr;
+
+use super::bearer_challenge::is_nqschar;
+use crate::bail_status as bail;
+use crate::StatusCode;
+
+/// An error code returned in a Bearer challenge.
+///
+/// # Specifications
+///
+/// - [RFC 6750, section 3.1: Error Codes](https://tools.ietf.org/html/rfc6750#section-3.1)
+/// - [RFC 6749, section 8.5: Defining Additional Error Codes](https://tools.ietf.org/html/rfc6749#section-8.5)
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub enum BearerError {
+    /// The request is missing a required parameter, includes an unsupported
+    /// parameter or parameter value, repeats the same parameter, uses more
+    /// than one method for including an access token, or is otherwise
+    /// malformed.
+    InvalidRequest,
+    /// The access token provided is expired, revoked, malformed, or invalid
+    /// for other reasons.
+    InvalidToken,
+    /// The request requires higher privileges than provided by the access
+    /// token.
+    InsufficientScope,
+    /// An extension error code, such as `use_dpop_nonce`.
+    Other(String),
+}
+
+impl BearerError {
+    /// Get the formatted string.
+    pub fn as_str(&self) -> &str {
+        match self {
+            Self::InvalidRequest => "invalid_request",
+            Self::InvalidToken => "invalid_token",
+            Self::InsufficientScope => "insufficient_scope",
+            Self::Other(code) => code,
+        }
+    }
+
+    /// Get the status code a response carrying this error should use.
+    ///
+    /// Extension error codes use `401 Unauthorized`.
+    pub fn status(&self) -> StatusCode {
+        match self {
+            Self::InvalidRequest => StatusCode::BadRequest,
+            Self::InvalidToken | Self::Other(_) => StatusCode::Unauthorized,
+            Self::InsufficientScope => StatusCode::Forbidden,
+        }
+    }
+}
+
+impl Display for BearerError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for BearerError {
+    type Err = crate::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "invalid_request" => Ok(Self::InvalidRequest),
+            "invalid_token" => Ok(Self::InvalidToken),
+            "insufficient_scope" => Ok(Self::InsufficientScope),
+            s if !s.is_empty() && is_nqschar(s) => Ok(Self::Other(s.to_owned())),
+            s => bail!(400, "`{}` is not a valid bearer error code", s),
+        }
+    }
+}
@@ -0,0 +1,282 @@
+use crate::auth::params::{parse_params, parse_token68, write_quoted};
+use crate::auth::AuthenticationScheme;
+use crate::parse_utils::{is_quotable, is_token, parse_token};
+use crate::{bail_status as bail, ensure_status as ensure};
+
+use std::fmt::{self, Display};
+
+/// A single authentication challenge.
+///
+/// A challenge consists of an authentication scheme, followed by either a
+/// `token68` or a list of parameters.
+///
+/// # Specifications
+///
+/// - [RFC 9110, section 11.3: Challenge and Response](https://www.rfc-editor.org/rfc/rfc9110#section-11.3)
+///
+/// # Examples
+///
+/// ```
+/// # fn main() -> http_types::Result<()> {
+/// #
+/// use http_types::auth::{AuthenticationScheme, Challenge};
+///
+/// let mut challenge = Challenge::new(AuthenticationScheme::Bearer);
+/// challenge.set_realm("example")?;
+/// challenge.insert_param("scope", "openid profile")?;
+///
+/// assert_eq!(challenge.scheme(), Some(AuthenticationScheme::Bearer));
+/// assert_eq!(challenge.param("Scope"), Some("openid profile"));
+/// assert_eq!(challenge.to_string(), r#"Bearer realm="example", scope="openid profile""#);
+/// #
+/// # Ok(()) }
+/// ```
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Challenge {
+    scheme: String,
+    token68: Option<String>,
+    params: Vec<(String, String)>,
+}
+
+impl Challenge {
+    /// Create a new instance of `Challenge`.
+    pub fn new(scheme: AuthenticationScheme) -> Self {
+        Self {
+            scheme: scheme.to_string(),
+            token68: None,
+            params: vec![],
+        }
+    }
+
+    /// Create a new instance of `Challenge` with a scheme not covered by
+    /// [`AuthenticationScheme`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the scheme is not a valid token.
+    pub fn with_scheme_name(scheme: impl Into<String>) -> crate::Result<Self> {
+        let scheme = scheme.into();
+        ensure!(
+            is_token(&scheme),
+            400,
+            "Invalid authentication scheme `{}`",
+            scheme
+        );
+        Ok(Self {
+            scheme,
+            token68: None,
+            params: vec![],
+        })
+    }
+
+    /// Parse a comma separated list of challenges.
+    ///
+    /// This is also used for credentials, which share the same syntax but only
+    /// contain a single entry.
+    pub(crate) fn parse_list(mut input: &str) -> crate::Result<Vec<Self>> {
+        let mut challenges = vec![];
+        loop {
+            input = input.trim_start_matches([' ', '\t', ',']);
+            if input.is_empty() {
+                return Ok(challenges);
+            }
+            let (scheme, rest) = match parse_token(input) {
+                (Some(scheme), rest) => (scheme.to_string(), rest),
+                (None, _) => bail!(400, "Invalid authentication scheme"),
+            };
+            if !rest.is_empty() && !rest.starts_with([' ', '\t', ',']) {
+                bail!(400, "Invalid authentication scheme `{}`", scheme);
+            }
+            let rest = rest.trim_start_matches([' ', '\t']);
+
+            let (token68, rest) = parse_token68(rest);
+            let (params, rest) = match token68 {
+                Some(_) => (vec![], rest),
+                None => parse_params(rest)?,
+            };
+            challenges.push(Self {
+                scheme,
+                token68: token68.map(String::from),
+                params,
+            });
+            input = rest;
+        }
+    }
+
+    /// Get the authentication scheme, if it is a known one.
+    pub fn scheme(&self) -> Option<AuthenticationScheme> {
+        self.scheme.parse().ok()
+    }
+
+    /// Get the name of the authentication scheme, as it was sent.
+    pub fn scheme_name(&self) -> &str {
+        &self.scheme
+    }
+
+    /// Returns `true` if the challenge uses `scheme`.
+    pub fn is_scheme(&self, scheme: AuthenticationScheme) -> bool {
+        self.scheme.eq_ignore_ascii_case(&scheme.to_string())
+    }
+
+    /// Get the token68, if the challenge uses one instead of parameters.
+    pub fn token68(&self) -> Option<&str> {
+        self.token68.as_deref()
+    }
+
+    /// Set the token68, removing all parameters.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the value is not a valid token68.
+    pub fn set_token68(&mut self, token68: impl Into<String>) -> crate::Result<()> {
+        let token68 = token68.into();
+        ensure!(
+            matches!(parse_token68(&token68), (Some(_), "")),
+            400,
+            "Invalid token68"
+        );
+        self.params.clear();
+        self.token68 = Some(token68);
+        Ok(())
+    }
+
+    /// Get the value of a parameter.
+    ///
+    /// Parameter names are case-insensitive.
+    pub fn param(&self, name: &str) -> Option<&str> {
+        self.params
+            .iter()
+            .find(|(key, _)| key.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// Set the value of a parameter, removing the token68 if there is one.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the name is not a valid token, or the value
+    /// contains control characters or non-ASCII characters.
+    pub fn insert_param(
+        &mut self,
+        name: impl Into<String>,
+        value: impl Into<String>,
+    ) -> crate::Result<()> {
+        let name = name.into().to_ascii_lowercase();
+        let value = value.into();
+        ensure!(is_token(&name), 400, "Invalid auth-param name `{}`", name);
+        ensure!(
+            is_quotable(&value),
+            400,
+            "Invalid value for auth-param `{}`",
+            name
+        );
+        self.token68 = None;
+        match self.params.iter_mut().find(|(key, _)| *key == name) {
+            Some((_, existing)) => *existing = value,
+            None => self.params.push((name, value)),
+        }
+        Ok(())
+    }
+
+    /// Remove a parameter, returning its value.
+    pub fn remove_param(&mut self, name: &str) -> Option<String> {
+        let index = self
+            .params
+            .iter()
+            .position(|(key, _)| key.eq_ignore_ascii_case(name))?;
+        Some(self.params.remove(index).1)
+    }
+
+    /// An iterator visiting all parameters, with lowercase names.
+    pub fn params(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.params
+            .iter()
+            .map(|(name, value)| (name.as_str(), value.as_str()))
+    }
+
+    /// Get the protection space of the challenge.
+    pub fn realm(&self) -> Option<&str> {
+        self.param("realm")
+    }
+
+    /// Set the protection space of the challenge.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the realm contains control characters or
+    /// non-ASCII characters.
+    pub fn set_realm(&mut self, realm: impl Into<String>) -> crate::Result<()> {
+        self.insert_param("realm", realm)
+    }
+}
+
+impl Display for Challenge {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut output = format!("{} ", self.scheme);
+        if let Some(token68) = &self.token68 {
+            output.push_str(token68);
+        }
+        // Parameter values are always quoted, some recipients only accept the
+        // quoted-string syntax for well-known parameters such as the realm.
+        for (name, value) in &self.params {
+            write_quoted(&mut output, name, value);
+        }
+        f.write_str(output.trim_end())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn parse_list() -> crate::Result<()> {
+        let challenges = Challenge::parse_list(
+            r#"Newauth realm="apps", type=1, title="Login to \"apps\"", Basic realm="simple", Bearer abc+/==, Negotiate"#,
+        )?;
+        assert_eq!(challenges.len(), 4);
+        assert_eq!(challenges[0].scheme(), None);
+        assert_eq!(challenges[0].scheme_name(), "Newauth");
+        assert_eq!(challenges[0].param("type"), Some("1"));
+        assert_eq!(challenges[0].param("title"), Some(r#"Login to "apps""#));
+        assert_eq!(challenges[1].scheme(), Some(AuthenticationScheme::Basic));
+        assert_eq!(challenges[1].realm(), Some("simple"));
+        assert_eq!(challenges[2].token68(), Some("abc+/=="));
+        assert_eq!(challenges[2].params().count(), 0);
+        assert!(challenges[3].is_scheme(AuthenticationScheme::Negotiate));
+        assert_eq!(challenges[3].token68(), None);
+
+        assert_eq!(
+            challenges[0].to_string(),
+            r#"Newauth realm="apps", type="1", title="Login to \"apps\"""#
+        );
+        assert_eq!(challenges[2].to_string(), "Bearer abc+/==");
+        assert_eq!(challenges[3].to_string(), "Negotiate");
+
+        assert!(Challenge::parse_list(r#"Basic="realm""#).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn params() -> crate::Result<()> {
+        let mut challenge = Challenge::with_scheme_name("Newauth")?;
+        challenge.set_token68("abc")?;
+        challenge.insert_param("Error", "invalid_token")?;
+        assert_eq!(challenge.token68(), None);
+        challenge.insert_param("error", "insufficient_scope")?;
+        assert_eq!(challenge.param("ERROR"), Some("insufficient_scope"));
+        assert_eq!(
+            challenge.remove_param("error").as_deref(),
+            Some("insufficient_scope")
+        );
+        assert_eq!(challenge.param("error"), None);
+
+        assert!(challenge.insert_param("bad name", "value").is_err());
+        assert!(challenge.insert_param("name", "new\nline").is_err());
+        assert!(challenge.insert_param("name", "caf\u{e9}").is_err());
+        assert!(challenge.set_realm("r\u{e9}alm").is_err());
+        assert!(challenge.set_token68("not a token").is_err());
+        assert!(Challenge::with_scheme_name("Bad Scheme").is_err());
+        Ok(())
+    }
+}
@@ -0,0 +1,70 @@
+use std::fmt::{self, Display};
+use std::str::FromStr;
+
+use md5::Md5;
+use sha2::{Digest, Sha256};
+
+use crate::bail_status as bail;
+use crate::utils::hex;
+
+/// The hash algorithm used by Digest access authentication.
+///
+/// # Specifications
+///
+/// - [RFC 7616, section 3.2: Hash Algorithms for HTTP Digest Authentication](https://tools.ietf.org/html/rfc7616#section-3.2)
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub enum DigestAlgorithm {
+    /// MD5, kept for backwards compatibility with RFC 2617 clients.
+    Md5,
+    /// MD5 with the client nonce mixed into the user hash.
+    Md5Sess,
+    /// SHA-256.
+    Sha256,
+    /// SHA-256 with the client nonce mixed into the user hash.
+    Sha256Sess,
+}
+
+impl DigestAlgorithm {
+    /// Get the formatted string.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Md5 => "MD5",
+            Self::Md5Sess => "MD5-sess",
+            Self::Sha256 => "SHA-256",
+            Self::Sha256Sess => "SHA-256-sess",
+        }
+    }
+
+    /// Returns `true` if this is a session variant of the algorithm.
+    pub fn is_session(&self) -> bool {
+        matches!(self, Self::Md5Sess | Self::Sha256Sess)
+    }
+
+    /// Hash `data`, returning the lowercase hex digest.
+    pub(crate) fn hash(&self, data: impl AsRef<[u8]>) -> String {
+        match self {
+            Self::Md5 | Self::Md5Sess => hex(&Md5::digest(data)),
+            Self::Sha256 | Self::Sha256Sess => hex(&Sha256::digest(data)),
+        }
+    }
+}
+
+impl Display for DigestAlgorithm {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for DigestAlgorithm {
+    type Err = crate::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "md5" => Ok(Self::Md5),
+            "md5-sess" => Ok(Self::Md5Sess),
+            "sha-256" => Ok(Self::Sha256),
+            "sha-256-sess" => Ok(Self::Sha256Sess),
+            _ => bail!(400, "`{}` is not a supported digest algorithm", s),
+        }
+    }
+}
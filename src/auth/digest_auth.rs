@@ -0,0 +1,617 @@
+use crate::auth::params::{write_quoted, write_token};
+use crate::auth::{
+    AuthenticationScheme, Challenge, DigestAlgorithm, DigestChallenge, DigestQop, NonceState,
+    NonceTracker,
+};
+use crate::headers::{Header, HeaderName, HeaderValue, Headers, AUTHORIZATION};
+use crate::parse_utils::is_quotable;
+use crate::utils::{constant_time_eq, fill_random, percent_decode};
+use crate::Method;
+use crate::{bail_status as bail, ensure_status as ensure};
+
+use std::borrow::Cow;
+use std::fmt::Write;
+
+/// HTTP Digest access authentication.
+///
+/// # Specifications
+///
+/// - [RFC 7616: HTTP Digest Access Authentication](https://tools.ietf.org/html/rfc7616)
+///
+/// # Examples
+///
+/// ```
+/// # fn main() -> http_types::Result<()> {
+/// #
+/// use http_types::{Method, Request, Response};
+/// use http_types::auth::{DigestAuth, DigestChallenge, MemoryNonceTracker, NonceState};
+///
+/// // The server sends a challenge, and tracks the nonce it used.
+/// let mut nonces = MemoryNonceTracker::new();
+/// let challenge = DigestChallenge::new("http-auth@example.org")?;
+/// nonces.issue(challenge.nonce());
+/// let mut res = Response::new(401);
+/// res.insert_header(&challenge, &challenge);
+///
+/// // The client answers the challenge.
+/// let challenge = DigestChallenge::from_headers(&res)?.unwrap();
+/// let authz = DigestAuth::new(&challenge, "Mufasa", "Circle of Life", Method::Get, "/dir/index.html")?;
+/// let mut req = Request::get("https://example.org/dir/index.html");
+/// req.insert_header(&authz, &authz);
+///
+/// // The server verifies the credentials, and only then records the nonce
+/// // count.
+/// let authz = DigestAuth::from_headers(&req)?.unwrap();
+/// assert_eq!(authz.username(), "Mufasa");
+/// assert_eq!(authz.check_nonce(&nonces), NonceState::Valid);
+/// assert!(authz.verify(req.method(), "Circle of Life"));
+/// assert_eq!(authz.record_nonce(&mut nonces), NonceState::Valid);
+///
+/// // Replaying the same request is detected.
+/// assert_eq!(authz.check_nonce(&nonces), NonceState::Replayed);
+/// #
+/// # Ok(()) }
+/// ```
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct DigestAuth {
+    username: String,
+    userhash: bool,
+    realm: String,
+    nonce: String,
+    uri: String,
+    response: String,
+    algorithm: DigestAlgorithm,
+    cnonce: Option<String>,
+    opaque: Option<String>,
+    qop: Option<DigestQop>,
+    nonce_count: Option<u32>,
+}
+
+impl DigestAuth {
+    /// Create a new instance of `DigestAuth` by answering a challenge.
+    ///
+    /// `uri` is the request target, usually the path and query of the URL.
+    /// A hashed username is sent if the challenge allows it.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the challenge only supports `qop=auth-int`, which
+    /// requires hashing the request body, or if `uri` contains non-ASCII or
+    /// control characters.
+    pub fn new(
+        challenge: &DigestChallenge,
+        username: impl AsRef<str>,
+        password: impl AsRef<str>,
+        method: Method,
+        uri: impl Into<String>,
+    ) -> crate::Result<Self> {
+        let uri = uri.into();
+        ensure!(is_quotable(&uri), 400, "Invalid digest URI");
+        let qop = match challenge.qop() {
+            [] => None,
+            qop if qop.contains(&DigestQop::Auth) => Some(DigestQop::Auth),
+            _ => crate::bail!("Digest challenges requiring auth-int are not supported"),
+        };
+        let cnonce = qop.map(|_| {
+            let mut bytes = [0; 24];
+            fill_random(&mut bytes);
+            base64::encode(bytes)
+        });
+
+        let algorithm = challenge.algorithm();
+        let username = username.as_ref();
+        let mut auth = Self {
+            username: username.to_string(),
+            userhash: challenge.userhash(),
+            realm: challenge.realm().to_string(),
+            nonce: challenge.nonce().to_string(),
+            uri,
+            response: String::new(),
+            algorithm,
+            cnonce,
+            opaque: challenge.opaque().map(String::from),
+            qop,
+            nonce_count: qop.map(|_| 1),
+        };
+        if auth.userhash {
+            auth.username = Self::userhash(algorithm, username, &auth.realm);
+        }
+        let ha1 = Self::ha1(algorithm, username, &auth.realm, password);
+        auth.response = auth.compute_response(method, &ha1);
+        Ok(auth)
+    }
+
+    /// Create a new instance from headers.
+    pub fn from_headers(headers: impl AsRef<Headers>) -> crate::Result<Option<Self>> {
+        let headers = match headers.as_ref().get(AUTHORIZATION) {
+            Some(headers) => headers,
+            None => return Ok(None),
+        };
+
+        // If we successfully parsed the header then there's always at least one
+        // entry. We want the last entry.
+        let value = headers.iter().last().unwrap();
+        let credentials = match Challenge::parse_list(value.as_str())?.into_iter().next() {
+            Some(credentials) => credentials,
+            None => bail!(400, "Could not find scheme"),
+        };
+        ensure!(
+            credentials.is_scheme(AuthenticationScheme::Digest),
+            400,
+            "Expected digest auth scheme found `{}`",
+            credentials.scheme_name()
+        );
+        ensure!(
+            credentials.token68().is_none(),
+            400,
+            "Expected digest auth parameters"
+        );
+
+        let required = |name| match credentials.param(name) {
+            Some(value) => Ok(value.to_string()),
+            None => bail!(400, "Digest credentials are missing `{}`", name),
+        };
+        let username = match credentials.param("username*") {
+            Some(username) => decode_ext_value(username)?,
+            None => required("username")?,
+        };
+        let algorithm = match credentials.param("algorithm") {
+            Some(algorithm) => algorithm.parse()?,
+            None => DigestAlgorithm::Md5,
+        };
+        let qop = credentials.param("qop").map(str::parse).transpose()?;
+        let (cnonce, nonce_count) = match qop {
+            Some(_) => {
+                let nonce_count = u32::from_str_radix(&required("nc")?, 16);
+                let nonce_count = match nonce_count {
+                    Ok(nonce_count) => nonce_count,
+                    Err(_) => bail!(400, "Invalid digest nonce count"),
+                };
+                (Some(required("cnonce")?), Some(nonce_count))
+            }
+            None => (None, None),
+        };
+
+        Ok(Some(Self {
+            username,
+            userhash: is_true(credentials.param("userhash")),
+            realm: required("realm")?,
+            nonce: required("nonce")?,
+            uri: required("uri")?,
+            response: required("response")?,
+            algorithm,
+            cnonce,
+            opaque: credentials.param("opaque").map(String::from),
+            qop,
+            nonce_count,
+        }))
+    }
+
+    /// Compute the hash of the username, realm and password.
+    ///
+    /// Servers can store this instead of the password, and pass it to
+    /// [`DigestAuth::verify_ha1`]. The hash has to be stored separately for
+    /// each algorithm, but is the same for the session variants.
+    pub fn ha1(
+        algorithm: DigestAlgorithm,
+        username: impl AsRef<str>,
+        realm: impl AsRef<str>,
+        password: impl AsRef<str>,
+    ) -> String {
+        algorithm.hash(format!(
+            "{}:{}:{}",
+            username.as_ref(),
+            realm.as_ref(),
+            password.as_ref()
+        ))
+    }
+
+    /// Compute the hashed username clients send when `userhash` is enabled.
+    pub fn userhash(
+        algorithm: DigestAlgorithm,
+        username: impl AsRef<str>,
+        realm: impl AsRef<str>,
+    ) -> String {
+        algorithm.hash(format!("{}:{}", username.as_ref(), realm.as_ref()))
+    }
+
+    /// Get the username.
+    ///
+    /// If [`DigestAuth::is_userhash`] is `true` this is the hashed username,
+    /// servers should look up the user by comparing it against
+    /// [`DigestAuth::userhash`].
+    pub fn username(&self) -> &str {
+        &self.username
+    }
+
+    /// Returns `true` if the username is hashed.
+    pub fn is_userhash(&self) -> bool {
+        self.userhash
+    }
+
+    /// Get the realm.
+    pub fn realm(&self) -> &str {
+        &self.realm
+    }
+
+    /// Get the nonce from the challenge.
+    pub fn nonce(&self) -> &str {
+        &self.nonce
+    }
+
+    /// Get the request target the credentials were computed for.
+    ///
+    /// Servers should check this matches the target of the request.
+    pub fn uri(&self) -> &str {
+        &self.uri
+    }
+
+    /// Get the hash algorithm.
+    pub fn algorithm(&self) -> DigestAlgorithm {
+        self.algorithm
+    }
+
+    /// Get the client nonce.
+    pub fn cnonce(&self) -> Option<&str> {
+        self.cnonce.as_deref()
+    }
+
+    /// Get the opaque value from the challenge.
+    pub fn opaque(&self) -> Option<&str> {
+        self.opaque.as_deref()
+    }
+
+    /// Get the quality of protection.
+    pub fn qop(&self) -> Option<DigestQop> {
+        self.qop
+    }
+
+    /// Get the number of requests the client has sent with this nonce.
+    pub fn nonce_count(&self) -> Option<u32> {
+        self.nonce_count
+    }
+
+    /// Check the nonce and nonce count against a tracker, without recording
+    /// them.
+    ///
+    /// If the nonce is stale, the server should send a new challenge with
+    /// `stale=true`. Credentials without a nonce count are checked with a
+    /// count of `0`, so their nonce can only be used once.
+    pub fn check_nonce(&self, tracker: &impl NonceTracker) -> NonceState {
+        tracker.check(&self.nonce, self.nonce_count.unwrap_or(0))
+    }
+
+    /// Record the nonce count with a tracker, so the request can't be
+    /// replayed.
+    ///
+    /// Only call this once [`DigestAuth::verify`] or
+    /// [`DigestAuth::verify_ha1`] succeeded. Requests are only valid if this
+    /// returns [`NonceState::Valid`].
+    pub fn record_nonce(&self, tracker: &mut impl NonceTracker) -> NonceState {
+        tracker.record(&self.nonce, self.nonce_count.unwrap_or(0))
+    }
+
+    /// Verify the credentials using the user's password.
+    ///
+    /// Credentials using `qop=auth-int` are always rejected.
+    pub fn verify(&self, method: Method, password: impl AsRef<str>) -> bool {
+        // A hashed username can't be used to compute the HA1, callers have to
+        // look up the user and use `verify_ha1` instead.
+        if self.userhash {
+            return false;
+        }
+        let ha1 = Self::ha1(self.algorithm, &self.username, &self.realm, password);
+        self.verify_ha1(method, &ha1)
+    }
+
+    /// Verify the credentials using the stored hash of the username, realm
+    /// and password, as computed by [`DigestAuth::ha1`].
+    ///
+    /// Credentials using `qop=auth-int` are always rejected.
+    pub fn verify_ha1(&self, method: Method, ha1: &str) -> bool {
+        if self.qop == Some(DigestQop::AuthInt) {
+            return false;
+        }
+        let expected = self.compute_response(method, ha1);
+        constant_time_eq(
+            expected.as_bytes(),
+            self.response.to_ascii_lowercase().as_bytes(),
+        )
+    }
+
+    /// Compute the request digest.
+    fn compute_response(&self, method: Method, ha1: &str) -> String {
+        let algorithm = self.algorithm;
+        let ha1: Cow<'_, str> = match (algorithm.is_session(), &self.cnonce) {
+            (true, Some(cnonce)) => algorithm
+                .hash(format!("{}:{}:{}", ha1, self.nonce, cnonce))
+                .into(),
+            _ => ha1.into(),
+        };
+        let ha2 = algorithm.hash(format!("{}:{}", method, self.uri));
+        match (self.qop, &self.cnonce) {
+            (Some(qop), Some(cnonce)) => algorithm.hash(format!(
+                "{}:{}:{:08x}:{}:{}:{}",
+                ha1,
+                self.nonce,
+                self.nonce_count.unwrap_or(0),
+                cnonce,
+                qop,
+                ha2
+            )),
+            _ => algorithm.hash(format!("{}:{}:{}", ha1, self.nonce, ha2)),
+        }
+    }
+}
+
+impl Header for DigestAuth {
+    fn header_name(&self) -> HeaderName {
+        AUTHORIZATION
+    }
+
+    fn header_value(&self) -> HeaderValue {
+        let mut output = String::from("Digest ");
+        if is_quotable(&self.username) {
+            write_quoted(&mut output, "username", &self.username);
+        } else {
+            write_token(
+                &mut output,
+                "username*",
+                &format!("UTF-8''{}", encode_ext_value(&self.username)),
+            );
+        }
+        write_quoted(&mut output, "realm", &self.realm);
+        write_quoted(&mut output, "uri", &self.uri);
+        write_token(&mut output, "algorithm", self.algorithm.as_str());
+        write_quoted(&mut output, "nonce", &self.nonce);
+        if let (Some(qop), Some(cnonce)) = (self.qop, &self.cnonce) {
+            let nonce_count = format!("{:08x}", self.nonce_count.unwrap_or(0));
+            write_token(&mut output, "nc", &nonce_count);
+            write_quoted(&mut output, "cnonce", cnonce);
+            write_token(&mut output, "qop", qop.as_str());
+        }
+        write_quoted(&mut output, "response", &self.response);
+        if let Some(opaque) = &self.opaque {
+            write_quoted(&mut output, "opaque", opaque);
+        }
+        if self.userhash {
+            write_token(&mut output, "userhash", "true");
+        }
+
+        // SAFETY: the internal string is validated to be ASCII.
+        unsafe { HeaderValue::from_bytes_unchecked(output.into()) }
+    }
+}
+
+fn is_true(value: Option<&str>) -> bool {
+    matches!(value, Some(value) if value.eq_ignore_ascii_case("true"))
+}
+
+/// Decode an RFC 8187 `ext-value`, which must use UTF-8.
+fn decode_ext_value(input: &str) -> crate::Result<String> {
+    let (charset, rest) = match input.split_once('\'') {
+        Some(parts) => parts,
+        None => bail!(400, "Invalid extended digest username"),
+    };
+    ensure!(
+        charset.eq_ignore_ascii_case("utf-8"),
+        400,
+        "Unsupported charset `{}` in digest username",
+        charset
+    );
+    let value = match rest.split_once('\'') {
+        Some((_language, value)) => value,
+        None => bail!(400, "Invalid extended digest username"),
+    };
+
+    match percent_decode(value) {
+        Some(value) => Ok(value),
+        None => bail!(400, "Invalid percent-encoding in digest username"),
+    }
+}
+
+/// Encode a value as the `value-chars` of an RFC 8187 `ext-value`.
+fn encode_ext_value(input: &str) -> String {
+    let mut output = String::new();
+    for byte in input.bytes() {
+        if byte.is_ascii_alphanumeric() || b"!#$&+-.^_`|~".contains(&byte) {
+            output.push(byte as char);
+        } else {
+            write!(output, "%{:02X}", byte).unwrap();
+        }
+    }
+    output
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::auth::MemoryNonceTracker;
+    use crate::headers::WWW_AUTHENTICATE;
+
+    const NONCE: &str = "7ypf/xlj9XXwfDPEoM4URrv/xwf94BcCAzFZH4GiTo0v";
+    const CNONCE: &str = "f2/wE4q74E6zIJEtWaHKaf5wv/H5QzzpXusqGemxURZJ";
+    const OPAQUE: &str = "FQhe/qaU925kfnzjCev0ciny7QMkPqMAFRtzCUYo5tdS";
+
+    fn rfc_credentials(algorithm: &str, response: &str) -> crate::Result<DigestAuth> {
+        let mut headers = Headers::new();
+        headers.insert(
+            AUTHORIZATION,
+            format!(
+                r#"Digest username="Mufasa", realm="http-auth@example.org", uri="/dir/index.html", algorithm={}, nonce="{}", nc=00000001, cnonce="{}", qop=auth, response="{}", opaque="{}""#,
+                algorithm, NONCE, CNONCE, response, OPAQUE
+            ),
+        )?;
+        Ok(DigestAuth::from_headers(headers)?.unwrap())
+    }
+
+    #[test]
+    fn rfc_examples() -> crate::Result<()> {
+        let md5 = rfc_credentials("MD5", "8ca523f5e9506fed4657c9700eebdbec")?;
+        assert_eq!(md5.algorithm(), DigestAlgorithm::Md5);
+        assert_eq!(md5.nonce_count(), Some(1));
+        assert!(md5.verify(Method::Get, "Circle of Life"));
+        assert!(!md5.verify(Method::Get, "circle of life"));
+        assert!(!md5.verify(Method::Post, "Circle of Life"));
+
+        let sha256 = rfc_credentials(
+            "SHA-256",
+            "753927fa0e85d155564e2e272a28d1802ca10daf4496794697cf8db5856cb6c1",
+        )?;
+        let ha1 = DigestAuth::ha1(
+            DigestAlgorithm::Sha256,
+            "Mufasa",
+            "http-auth@example.org",
+            "Circle of Life",
+        );
+        assert!(sha256.verify_ha1(Method::Get, &ha1));
+        Ok(())
+    }
+
+    #[test]
+    fn round_trip() -> crate::Result<()> {
+        for algorithm in [
+            DigestAlgorithm::Md5,
+            DigestAlgorithm::Md5Sess,
+            DigestAlgorithm::Sha256,
+            DigestAlgorithm::Sha256Sess,
+        ] {
+            let mut challenge = DigestChallenge::new("realm")?;
+            challenge.set_algorithm(algorithm);
+            challenge.set_opaque(Some("opaque".into()))?;
+            let mut headers = Headers::new();
+            challenge.apply_header(&mut headers);
+            let challenge = DigestChallenge::from_headers(&headers)?.unwrap();
+            assert_eq!(challenge.algorithm(), algorithm);
+
+            let authz = DigestAuth::new(&challenge, "Jäsøn Doe", "secret", Method::Get, "/a?b")?;
+            authz.apply_header(&mut headers);
+            assert!(headers[AUTHORIZATION]
+                .as_str()
+                .contains("username*=UTF-8''J%C3%A4s%C3%B8n%20Doe"));
+
+            let parsed = DigestAuth::from_headers(&headers)?.unwrap();
+            assert_eq!(parsed, authz);
+            assert!(parsed.verify(Method::Get, "secret"));
+            assert!(!parsed.verify(Method::Get, "wrong"));
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn userhash_and_legacy() -> crate::Result<()> {
+        let mut challenge = DigestChallenge::new("realm")?;
+        challenge.set_userhash(true);
+        challenge.set_qop(vec![]);
+
+        let authz = DigestAuth::new(&challenge, "nori", "fish", Method::Put, "/")?;
+        assert!(authz.is_userhash());
+        assert_eq!(
+            authz.username(),
+            DigestAuth::userhash(DigestAlgorithm::Sha256, "nori", "realm")
+        );
+        assert_eq!(authz.qop(), None);
+        assert_eq!(authz.cnonce(), None);
+        assert!(!authz.verify(Method::Put, "fish"));
+        let ha1 = DigestAuth::ha1(DigestAlgorithm::Sha256, "nori", "realm", "fish");
+        assert!(authz.verify_ha1(Method::Put, &ha1));
+
+        let mut nonces = MemoryNonceTracker::new();
+        assert_eq!(authz.check_nonce(&nonces), NonceState::Stale);
+        nonces.issue(challenge.nonce());
+        assert_eq!(authz.check_nonce(&nonces), NonceState::Valid);
+        assert_eq!(authz.record_nonce(&mut nonces), NonceState::Valid);
+        assert_eq!(authz.check_nonce(&nonces), NonceState::Replayed);
+        assert_eq!(authz.record_nonce(&mut nonces), NonceState::Replayed);
+
+        challenge.set_qop(vec![DigestQop::AuthInt]);
+        assert!(DigestAuth::new(&challenge, "nori", "fish", Method::Put, "/").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn failed_verification_keeps_nonce_count() -> crate::Result<()> {
+        let challenge = DigestChallenge::new("realm")?;
+        let mut nonces = MemoryNonceTracker::new();
+        nonces.issue(challenge.nonce());
+        let authz = DigestAuth::new(&challenge, "nori", "fish", Method::Get, "/")?;
+        assert_eq!(authz.nonce_count(), Some(1));
+
+        // Anyone who saw the challenge can send the highest nonce count.
+        let mut forged = authz.clone();
+        forged.nonce_count = Some(u32::MAX);
+        assert_eq!(forged.check_nonce(&nonces), NonceState::Valid);
+        assert!(!forged.verify(Method::Get, "fish"));
+
+        assert_eq!(authz.check_nonce(&nonces), NonceState::Valid);
+        assert!(authz.verify(Method::Get, "fish"));
+        assert_eq!(authz.record_nonce(&mut nonces), NonceState::Valid);
+        Ok(())
+    }
+
+    #[test]
+    fn reject_invalid_values() -> crate::Result<()> {
+        assert!(DigestChallenge::new("r\u{e9}alm").is_err());
+        assert!(DigestChallenge::new("realm\r\nX-Injected: 1").is_err());
+
+        let mut challenge = DigestChallenge::new("realm")?;
+        assert!(challenge.set_realm("r\u{e9}alm").is_err());
+        assert!(challenge.set_opaque(Some("op\naque".into())).is_err());
+        assert_eq!(challenge.realm(), "realm");
+        assert_eq!(challenge.opaque(), None);
+
+        assert!(DigestAuth::new(&challenge, "nori", "fish", Method::Get, "/caf\u{e9}").is_err());
+        assert!(DigestAuth::new(&challenge, "nori", "fish", Method::Get, "/\r\n").is_err());
+
+        let authz = DigestAuth::new(&challenge, "no\r\nri", "fish", Method::Get, "/")?;
+        let mut headers = Headers::new();
+        authz.apply_header(&mut headers);
+        assert!(headers[AUTHORIZATION]
+            .as_str()
+            .contains("username*=UTF-8''no%0D%0Ari"));
+        let parsed = DigestAuth::from_headers(&headers)?.unwrap();
+        assert_eq!(parsed.username(), "no\r\nri");
+        Ok(())
+    }
+
+    #[test]
+    fn skip_unsupported_challenges() -> crate::Result<()> {
+        let mut headers = Headers::new();
+        headers.append(
+            WWW_AUTHENTICATE,
+            r#"Digest realm="a", nonce="b", algorithm=SHA-512-256, qop="auth""#,
+        )?;
+        headers.append(
+            WWW_AUTHENTICATE,
+            r#"Basic realm="a", Digest realm="a", nonce="c", algorithm=SHA-256, qop="auth""#,
+        )?;
+        let challenge = DigestChallenge::from_headers(&headers)?.unwrap();
+        assert_eq!(challenge.nonce(), "c");
+        assert_eq!(challenge.algorithm(), DigestAlgorithm::Sha256);
+
+        headers.remove(WWW_AUTHENTICATE);
+        headers.insert(
+            WWW_AUTHENTICATE,
+            r#"Digest realm="a", nonce="b", algorithm=SHA-512-256"#,
+        )?;
+        let err = DigestChallenge::from_headers(&headers).unwrap_err();
+        assert_eq!(err.status(), 400);
+        Ok(())
+    }
+
+    #[test]
+    fn bad_request_on_parse_error() {
+        let inputs = [
+            "Basic bm9yaTpmaXNo",
+            r#"Digest realm="a", nonce="b", uri="/", response="c""#,
+            r#"Digest username="a", realm="a", nonce="b", uri="/", response="c", qop=auth"#,
+            r#"Digest username="a", realm="a", nonce="b", uri="/", response="c", algorithm=SHA-1"#,
+        ];
+        for input in inputs {
+            let mut headers = Headers::new();
+            headers.insert(AUTHORIZATION, input).unwrap();
+            let err = DigestAuth::from_headers(headers).unwrap_err();
+            assert_eq!(err.status(), 400, "{} should not parse", input);
+        }
+    }
+}
@@ -0,0 +1,282 @@
+use crate::auth::params::{write_quoted, write_token};
+use crate::auth::{AuthenticationScheme, Challenge, DigestAlgorithm, DigestQop};
+use crate::headers::{Header, HeaderName, HeaderValue, Headers, WWW_AUTHENTICATE};
+use crate::parse_utils::is_quotable;
+use crate::utils::fill_random;
+use crate::{bail_status as bail, ensure_status as ensure};
+
+/// A challenge for HTTP Digest access authentication.
+///
+/// Servers send this in the `WWW-Authenticate` header of a `401
+/// Unauthorized` response, clients answer it with a
+/// [`DigestAuth`](crate::auth::DigestAuth).
+///
+/// # Specifications
+///
+/// - [RFC 7616, section 3.3: The WWW-Authenticate Response Header Field](https://tools.ietf.org/html/rfc7616#section-3.3)
+///
+/// # Examples
+///
+/// ```
+/// # fn main() -> http_types::Result<()> {
+/// #
+/// use http_types::Response;
+/// use http_types::auth::{DigestAlgorithm, DigestChallenge, DigestQop};
+///
+/// let mut challenge = DigestChallenge::new("http-auth@example.org")?;
+/// challenge.set_algorithm(DigestAlgorithm::Sha256);
+///
+/// let mut res = Response::new(401);
+/// res.insert_header(&challenge, &challenge);
+///
+/// let challenge = DigestChallenge::from_headers(res)?.unwrap();
+/// assert_eq!(challenge.realm(), "http-auth@example.org");
+/// assert_eq!(challenge.algorithm(), DigestAlgorithm::Sha256);
+/// assert_eq!(challenge.qop(), [DigestQop::Auth]);
+/// #
+/// # Ok(()) }
+/// ```
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct DigestChallenge {
+    realm: String,
+    nonce: String,
+    opaque: Option<String>,
+    stale: bool,
+    algorithm: DigestAlgorithm,
+    qop: Vec<DigestQop>,
+    userhash: bool,
+}
+
+impl DigestChallenge {
+    /// Create a new instance of `DigestChallenge`.
+    ///
+    /// The challenge uses SHA-256 with `qop=auth`, and a randomly generated
+    /// nonce. Use [`DigestChallenge::set_nonce`] to use nonces which encode
+    /// server-side state instead.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the realm contains non-ASCII or control
+    /// characters.
+    pub fn new(realm: impl Into<String>) -> crate::Result<Self> {
+        let mut bytes = [0; 24];
+        fill_random(&mut bytes);
+        let mut challenge = Self {
+            realm: String::new(),
+            nonce: base64::encode(bytes),
+            opaque: None,
+            stale: false,
+            algorithm: DigestAlgorithm::Sha256,
+            qop: vec![DigestQop::Auth],
+            userhash: false,
+        };
+        challenge.set_realm(realm)?;
+        Ok(challenge)
+    }
+
+    /// Create a new instance from headers.
+    ///
+    /// The first Digest challenge with a supported algorithm and valid
+    /// parameters is used. Challenges for other schemes are ignored.
+    ///
+    /// # Errors
+    ///
+    /// Returns the error of the first Digest challenge if none of them can
+    /// be used.
+    pub fn from_headers(headers: impl AsRef<Headers>) -> crate::Result<Option<Self>> {
+        let headers = match headers.as_ref().get(WWW_AUTHENTICATE) {
+            Some(headers) => headers,
+            None => return Ok(None),
+        };
+
+        let mut error = None;
+        for value in headers {
+            for challenge in Challenge::parse_list(value.as_str())? {
+                if challenge.is_scheme(AuthenticationScheme::Digest) {
+                    match Self::from_challenge(&challenge) {
+                        Ok(challenge) => return Ok(Some(challenge)),
+                        Err(err) => {
+                            error.get_or_insert(err);
+                        }
+                    }
+                }
+            }
+        }
+        match error {
+            Some(error) => Err(error),
+            None => Ok(None),
+        }
+    }
+
+    fn from_challenge(challenge: &Challenge) -> crate::Result<Self> {
+        let realm = match challenge.param("realm") {
+            Some(realm) => realm.to_string(),
+            None => bail!(400, "Digest challenge is missing a realm"),
+        };
+        let nonce = match challenge.param("nonce") {
+            Some(nonce) => nonce.to_string(),
+            None => bail!(400, "Digest challenge is missing a nonce"),
+        };
+        let algorithm = match challenge.param("algorithm") {
+            Some(algorithm) => algorithm.parse()?,
+            None => DigestAlgorithm::Md5,
+        };
+        let qop = match challenge.param("qop") {
+            Some(qop) => qop
+                .split(',')
+                .map(|qop| qop.trim().parse())
+                .collect::<crate::Result<_>>()?,
+            None => vec![],
+        };
+        Ok(Self {
+            realm,
+            nonce,
+            opaque: challenge.param("opaque").map(String::from),
+            stale: is_true(challenge.param("stale")),
+            algorithm,
+            qop,
+            userhash: is_true(challenge.param("userhash")),
+        })
+    }
+
+    /// Get the realm.
+    pub fn realm(&self) -> &str {
+        &self.realm
+    }
+
+    /// Set the realm.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the realm contains non-ASCII or control
+    /// characters.
+    pub fn set_realm(&mut self, realm: impl Into<String>) -> crate::Result<()> {
+        let realm = realm.into();
+        ensure!(is_quotable(&realm), 400, "Invalid digest realm");
+        self.realm = realm;
+        Ok(())
+    }
+
+    /// Get the nonce.
+    pub fn nonce(&self) -> &str {
+        &self.nonce
+    }
+
+    /// Set the nonce.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the nonce contains control or non-ASCII
+    /// characters.
+    pub fn set_nonce(&mut self, nonce: impl Into<String>) -> crate::Result<()> {
+        let nonce = nonce.into();
+        ensure!(
+            nonce.bytes().all(|b| (b' '..=b'~').contains(&b)),
+            400,
+            "Invalid digest nonce"
+        );
+        self.nonce = nonce;
+        Ok(())
+    }
+
+    /// Get the opaque value, which clients return unchanged.
+    pub fn opaque(&self) -> Option<&str> {
+        self.opaque.as_deref()
+    }
+
+    /// Set the opaque value, which clients return unchanged.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the value contains non-ASCII or control
+    /// characters.
+    pub fn set_opaque(&mut self, opaque: Option<String>) -> crate::Result<()> {
+        if let Some(opaque) = &opaque {
+            ensure!(is_quotable(opaque), 400, "Invalid digest opaque value");
+        }
+        self.opaque = opaque;
+        Ok(())
+    }
+
+    /// Returns `true` if the previous request was rejected because its nonce
+    /// was stale.
+    pub fn is_stale(&self) -> bool {
+        self.stale
+    }
+
+    /// Set whether the previous request was rejected because its nonce was
+    /// stale.
+    ///
+    /// Clients will retry a stale request with the new nonce without
+    /// prompting the user for credentials again.
+    pub fn set_stale(&mut self, stale: bool) {
+        self.stale = stale;
+    }
+
+    /// Get the hash algorithm.
+    pub fn algorithm(&self) -> DigestAlgorithm {
+        self.algorithm
+    }
+
+    /// Set the hash algorithm.
+    pub fn set_algorithm(&mut self, algorithm: DigestAlgorithm) {
+        self.algorithm = algorithm;
+    }
+
+    /// Get the supported qualities of protection.
+    pub fn qop(&self) -> &[DigestQop] {
+        &self.qop
+    }
+
+    /// Set the supported qualities of protection.
+    ///
+    /// An empty list falls back to the RFC 2069 compatible computation, which
+    /// does not protect against chosen plaintext attacks.
+    pub fn set_qop(&mut self, qop: Vec<DigestQop>) {
+        self.qop = qop;
+    }
+
+    /// Returns `true` if clients may send a hashed username.
+    pub fn userhash(&self) -> bool {
+        self.userhash
+    }
+
+    /// Set whether clients may send a hashed username.
+    pub fn set_userhash(&mut self, userhash: bool) {
+        self.userhash = userhash;
+    }
+}
+
+impl Header for DigestChallenge {
+    fn header_name(&self) -> HeaderName {
+        WWW_AUTHENTICATE
+    }
+
+    fn header_value(&self) -> HeaderValue {
+        let mut output = String::from("Digest ");
+        write_quoted(&mut output, "realm", &self.realm);
+        if !self.qop.is_empty() {
+            let qop: Vec<_> = self.qop.iter().map(DigestQop::as_str).collect();
+            write_quoted(&mut output, "qop", &qop.join(", "));
+        }
+        write_token(&mut output, "algorithm", self.algorithm.as_str());
+        write_quoted(&mut output, "nonce", &self.nonce);
+        if let Some(opaque) = &self.opaque {
+            write_quoted(&mut output, "opaque", opaque);
+        }
+        if self.stale {
+            write_token(&mut output, "stale", "true");
+        }
+        if self.userhash {
+            write_token(&mut output, "userhash", "true");
+        }
+        write_token(&mut output, "charset", "UTF-8");
+
+        // SAFETY: the internal string is validated to be ASCII.
+        unsafe { HeaderValue::from_bytes_unchecked(output.into()) }
+    }
+}
+
+fn is_true(value: Option<&str>) -> bool {
+    matches!(value, Some(value) if value.eq_ignore_ascii_case("true"))
+}
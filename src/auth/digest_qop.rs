@@ -0,0 +1,45 @@
+use std::fmt::{self, Display};
+use std::str::FromStr;
+
+use crate::bail_status as bail;
+
+/// The quality of protection applied by Digest access authentication.
+///
+/// # Specifications
+///
+/// - [RFC 7616, section 3.3: The WWW-Authenticate Response Header Field](https://tools.ietf.org/html/rfc7616#section-3.3)
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub enum DigestQop {
+    /// Authentication only.
+    Auth,
+    /// Authentication with integrity protection of the message body.
+    AuthInt,
+}
+
+impl DigestQop {
+    /// Get the formatted string.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Auth => "auth",
+            Self::AuthInt => "auth-int",
+        }
+    }
+}
+
+impl Display for DigestQop {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for DigestQop {
+    type Err = crate::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "auth" => Ok(Self::Auth),
+            "auth-int" => Ok(Self::AuthInt),
+            _ => bail!(400, "`{}` is not a recognized digest qop value", s),
+        }
+    }
+}
@@ -26,9 +26,114 @@
 mod authentication_scheme;
 mod authorization;
 mod basic_auth;
+mod basic_challenge;
+mod basic_charset;
+mod bearer_auth;
+mod bearer_challenge;
+mod bearer_error;
+mod challenge;
+#[cfg(feature = "digest-auth")]
+mod digest_algorithm;
+#[cfg(feature = "digest-auth")]
+mod digest_auth;
+#[cfg(feature = "digest-auth")]
+mod digest_challenge;
+#[cfg(feature = "digest-auth")]
+mod digest_qop;
+#[cfg(feature = "digest-auth")]
+mod nonce_tracker;
+#[cfg(feature = "oauth1")]
+mod oauth1_auth;
+#[cfg(feature = "oauth1")]
+mod oauth1_signature_method;
+mod params;
+mod proxy_authenticate;
+mod proxy_authorization;
+mod proxy_basic_auth;
+#[cfg(feature = "scram")]
+mod scram_algorithm;
+#[cfg(feature = "scram")]
+mod scram_auth;
+#[cfg(feature = "scram")]
+mod scram_authentication_info;
+#[cfg(feature = "scram")]
+mod scram_challenge;
+#[cfg(feature = "scram")]
+mod scram_client;
+#[cfg(feature = "scram")]
+mod scram_credentials;
+#[cfg(feature = "scram")]
+mod scram_message;
+#[cfg(feature = "scram")]
+mod scram_server;
+#[cfg(feature = "web-push")]
+mod vapid_auth;
+#[cfg(feature = "web-push")]
+mod vapid_key;
 mod www_authenticate;
 
+#[cfg(feature = "http-signatures")]
+#[cfg_attr(feature = "docs", doc(cfg(feature = "http-signatures")))]
+pub mod signatures;
+
 pub use authentication_scheme::AuthenticationScheme;
 pub use authorization::Authorization;
 pub use basic_auth::BasicAuth;
+pub use basic_challenge::BasicChallenge;
+pub use basic_charset::BasicCharset;
+pub use bearer_auth::BearerAuth;
+pub use bearer_challenge::BearerChallenge;
+pub use bearer_error::BearerError;
+pub use challenge::Challenge;
+#[cfg(feature = "digest-auth")]
+#[cfg_attr(feature = "docs", doc(cfg(feature = "digest-auth")))]
+pub use digest_algorithm::DigestAlgorithm;
+#[cfg(feature = "digest-auth")]
+#[cfg_attr(feature = "docs", doc(cfg(feature = "digest-auth")))]
+pub use digest_auth::DigestAuth;
+#[cfg(feature = "digest-auth")]
+#[cfg_attr(feature = "docs", doc(cfg(feature = "digest-auth")))]
+pub use digest_challenge::DigestChallenge;
+#[cfg(feature = "digest-auth")]
+#[cfg_attr(feature = "docs", doc(cfg(feature = "digest-auth")))]
+pub use digest_qop::DigestQop;
+#[cfg(feature = "digest-auth")]
+#[cfg_attr(feature = "docs", doc(cfg(feature = "digest-auth")))]
+pub use nonce_tracker::{MemoryNonceTracker, NonceState, NonceTracker};
+#[cfg(feature = "oauth1")]
+#[cfg_attr(feature = "docs", doc(cfg(feature = "oauth1")))]
+pub use oauth1_auth::OAuth1Auth;
+#[cfg(feature = "oauth1")]
+#[cfg_attr(feature = "docs", doc(cfg(feature = "oauth1")))]
+pub use oauth1_signature_method::OAuth1SignatureMethod;
+pub use proxy_authenticate::ProxyAuthenticate;
+pub use proxy_authorization::ProxyAuthorization;
+pub use proxy_basic_auth::ProxyBasicAuth;
+#[cfg(feature = "scram")]
+#[cfg_attr(feature = "docs", doc(cfg(feature = "scram")))]
+pub use scram_algorithm::ScramAlgorithm;
+#[cfg(feature = "scram")]
+#[cfg_attr(feature = "docs", doc(cfg(feature = "scram")))]
+pub use scram_auth::ScramAuth;
+#[cfg(feature = "scram")]
+#[cfg_attr(feature = "docs", doc(cfg(feature = "scram")))]
+pub use scram_authentication_info::ScramAuthenticationInfo;
+#[cfg(feature = "scram")]
+#[cfg_attr(feature = "docs", doc(cfg(feature = "scram")))]
+pub use scram_challenge::ScramChallenge;
+#[cfg(feature = "scram")]
+#[cfg_attr(feature = "docs", doc(cfg(feature = "scram")))]
+pub use scram_client::ScramClient;
+#[cfg(feature = "scram")]
+#[cfg_attr(feature = "docs", doc(cfg(feature = "scram")))]
+pub use scram_credentials::ScramCredentials;
+#[cfg(feature = "scram")]
+#[cfg_attr(feature = "docs", doc(cfg(feature = "scram")))]
+pub use scram_server::ScramServer;
+#[cfg(feature = "web-push")]
+#[cfg_attr(feature = "docs", doc(cfg(feature = "web-push")))]
+pub use vapid_auth::VapidAuth;
+#[cfg(feature = "web-push")]
+#[cfg_attr(feature = "docs", doc(cfg(feature = "web-push")))]
+pub use vapid_key::VapidKey;
 pub use www_authenticate::WwwAuthenticate;
@@ -0,0 +1,85 @@
+use std::collections::HashMap;
+
+/// The result of checking a nonce and nonce count with a [`NonceTracker`].
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum NonceState {
+    /// The nonce is known and the nonce count has not been used before.
+    Valid,
+    /// The nonce is unknown or has expired. The client should be sent a new
+    /// challenge with `stale=true`, so it can retry without prompting the
+    /// user.
+    Stale,
+    /// The nonce count has been used before, the request may be a replay.
+    Replayed,
+}
+
+/// Tracks the nonces issued in Digest challenges and the nonce counts used
+/// with them, to detect replayed requests.
+///
+/// Nonces are public, so a nonce count must only be recorded once the
+/// credentials using it were verified. Otherwise anyone could use up a
+/// nonce by sending a high nonce count with a bogus response.
+///
+/// Implement this for shared storage when running several servers, or to
+/// expire nonces after some time.
+pub trait NonceTracker {
+    /// Check `nonce_count` for `nonce`, without recording it.
+    ///
+    /// The nonce count is `0` if the client did not send one, which happens
+    /// for challenges without `qop`.
+    fn check(&self, nonce: &str, nonce_count: u32) -> NonceState;
+
+    /// Record `nonce_count` for `nonce` if it is valid.
+    ///
+    /// Returns the same state as [`NonceTracker::check`] would have before
+    /// recording, so a concurrent request which recorded the same nonce
+    /// count first is reported as [`NonceState::Replayed`].
+    fn record(&mut self, nonce: &str, nonce_count: u32) -> NonceState;
+}
+
+/// A [`NonceTracker`] which keeps nonces in memory.
+///
+/// Nonce counts must strictly increase for each nonce. Nonces never expire,
+/// call [`MemoryNonceTracker::revoke`] to remove them.
+#[derive(Debug, Default)]
+pub struct MemoryNonceTracker {
+    nonces: HashMap<String, Option<u32>>,
+}
+
+impl MemoryNonceTracker {
+    /// Create a new instance of `MemoryNonceTracker`.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Start tracking a nonce sent in a challenge.
+    pub fn issue(&mut self, nonce: impl Into<String>) {
+        self.nonces.insert(nonce.into(), None);
+    }
+
+    /// Stop tracking a nonce, further requests using it will be stale.
+    pub fn revoke(&mut self, nonce: &str) {
+        self.nonces.remove(nonce);
+    }
+}
+
+impl NonceTracker for MemoryNonceTracker {
+    fn check(&self, nonce: &str, nonce_count: u32) -> NonceState {
+        match self.nonces.get(nonce) {
+            None => NonceState::Stale,
+            Some(Some(last)) if nonce_count <= *last => NonceState::Replayed,
+            Some(_) => NonceState::Valid,
+        }
+    }
+
+    fn record(&mut self, nonce: &str, nonce_count: u32) -> NonceState {
+        match self.nonces.get_mut(nonce) {
+            None => NonceState::Stale,
+            Some(Some(last)) if nonce_count <= *last => NonceState::Replayed,
+            Some(last) => {
+                *last = Some(nonce_count);
+                NonceState::Valid
+            }
+        }
+    }
+}
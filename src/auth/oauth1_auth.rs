@@ -0,0 +1,504 @@
+use crate::auth::params::{form_params, write_quoted};
+use crate::auth::{AuthenticationScheme, Challenge, OAuth1SignatureMethod};
+use crate::headers::{Header, HeaderName, HeaderValue, Headers, AUTHORIZATION};
+use crate::utils::{constant_time_eq, fill_random, percent_decode};
+use crate::Request;
+use crate::{bail_status as bail, ensure_status as ensure};
+
+use std::fmt::Write;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// OAuth 1.0 request authorization.
+///
+/// Clients sign each request with the consumer secret and, once they
+/// obtained one, the token secret. Servers look up those secrets by
+/// [`consumer_key`](OAuth1Auth::consumer_key) and
+/// [`token`](OAuth1Auth::token), and recompute the signature to verify it.
+///
+/// The signature covers the method, the URL, the query parameters and the
+/// parameters of `application/x-www-form-urlencoded` bodies, so the request
+/// must be complete before it is signed.
+///
+/// # Specifications
+///
+/// - [RFC 5849: The OAuth 1.0 Protocol](https://tools.ietf.org/html/rfc5849)
+///
+/// # Examples
+///
+/// ```
+/// # fn main() -> http_types::Result<()> { async_std::task::block_on(async {
+/// #
+/// use http_types::auth::{OAuth1Auth, OAuth1SignatureMethod};
+/// use http_types::Request;
+///
+/// let mut req = Request::get("https://photos.example.net/photos?file=vacation.jpg");
+///
+/// // The client signs the request.
+/// let mut authz = OAuth1Auth::new("dpf43f3p2l4k3l03");
+/// authz.set_token(Some("nnch734d00sl2jdk".into()));
+/// authz.set_signature_method(OAuth1SignatureMethod::HmacSha256);
+/// authz.sign(&mut req, "kd94hf93k423kf44", Some("pfkkdhi9sl3r4s00")).await?;
+/// req.insert_header(&authz, &authz);
+///
+/// // The server verifies the signature.
+/// let authz = OAuth1Auth::from_headers(&req)?.unwrap();
+/// assert_eq!(authz.consumer_key(), "dpf43f3p2l4k3l03");
+/// assert!(authz.verify(&mut req, "kd94hf93k423kf44", Some("pfkkdhi9sl3r4s00")).await?);
+/// #
+/// # Ok(()) }) }
+/// ```
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct OAuth1Auth {
+    realm: Option<String>,
+    consumer_key: String,
+    token: Option<String>,
+    signature_method: OAuth1SignatureMethod,
+    timestamp: Option<u64>,
+    nonce: Option<String>,
+    version: bool,
+    callback: Option<String>,
+    verifier: Option<String>,
+    signature: String,
+}
+
+impl OAuth1Auth {
+    /// Create a new instance of `OAuth1Auth`.
+    ///
+    /// The credentials use `HMAC-SHA1`, the current time and a random nonce.
+    /// They are not valid until [`OAuth1Auth::sign`] was called.
+    pub fn new(consumer_key: impl Into<String>) -> Self {
+        let mut bytes = [0; 24];
+        fill_random(&mut bytes);
+        let mut auth = Self {
+            realm: None,
+            consumer_key: consumer_key.into(),
+            token: None,
+            signature_method: OAuth1SignatureMethod::HmacSha1,
+            timestamp: None,
+            nonce: Some(base64::encode_config(bytes, base64::URL_SAFE_NO_PAD)),
+            version: false,
+            callback: None,
+            verifier: None,
+            signature: String::new(),
+        };
+        auth.set_timestamp(Some(SystemTime::now()));
+        auth
+    }
+
+    /// Create a new instance from headers.
+    pub fn from_headers(headers: impl AsRef<Headers>) -> crate::Result<Option<Self>> {
+        let headers = match headers.as_ref().get(AUTHORIZATION) {
+            Some(headers) => headers,
+            None => return Ok(None),
+        };
+
+        // If we successfully parsed the header then there's always at least one
+        // entry. We want the last entry.
+        let value = headers.iter().last().unwrap();
+        let credentials = match Challenge::parse_list(value.as_str())?.into_iter().next() {
+            Some(credentials) => credentials,
+            None => bail!(400, "Could not find scheme"),
+        };
+        ensure!(
+            credentials.is_scheme(AuthenticationScheme::OAuth),
+            400,
+            "Expected OAuth scheme found `{}`",
+            credentials.scheme_name()
+        );
+
+        let param = |name: &str| match credentials.param(name).map(percent_decode) {
+            Some(Some(value)) => Ok(Some(value)),
+            Some(None) => bail!(400, "Invalid percent-encoding in OAuth credentials"),
+            None => Ok(None),
+        };
+        let required = |name: &str| match param(name)? {
+            Some(value) => Ok(value),
+            None => bail!(400, "OAuth credentials are missing `{}`", name),
+        };
+        let timestamp = match param("oauth_timestamp")? {
+            Some(timestamp) => match timestamp.parse() {
+                Ok(secs) if UNIX_EPOCH.checked_add(Duration::from_secs(secs)).is_some() => {
+                    Some(secs)
+                }
+                _ => bail!(400, "Invalid OAuth timestamp `{}`", timestamp),
+            },
+            None => None,
+        };
+        let version = match param("oauth_version")? {
+            Some(version) if version == "1.0" => true,
+            Some(version) => bail!(400, "Unsupported OAuth version `{}`", version),
+            None => false,
+        };
+        Ok(Some(Self {
+            realm: credentials.realm().map(String::from),
+            consumer_key: required("oauth_consumer_key")?,
+            token: param("oauth_token")?,
+            signature_method: required("oauth_signature_method")?.parse()?,
+            timestamp,
+            nonce: param("oauth_nonce")?,
+            version,
+            callback: param("oauth_callback")?,
+            verifier: param("oauth_verifier")?,
+            signature: required("oauth_signature")?,
+        }))
+    }
+
+    /// Get the protection realm.
+    pub fn realm(&self) -> Option<&str> {
+        self.realm.as_deref()
+    }
+
+    /// Set the protection realm.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the realm contains control or non-ASCII
+    /// characters.
+    pub fn set_realm(&mut self, realm: Option<String>) -> crate::Result<()> {
+        if let Some(realm) = &realm {
+            ensure!(
+                realm.bytes().all(|b| (b' '..=b'~').contains(&b)),
+                400,
+                "Invalid OAuth realm"
+            );
+        }
+        self.realm = realm;
+        Ok(())
+    }
+
+    /// Get the key identifying the client.
+    pub fn consumer_key(&self) -> &str {
+        &self.consumer_key
+    }
+
+    /// Get the token identifying the resource owner's authorization.
+    pub fn token(&self) -> Option<&str> {
+        self.token.as_deref()
+    }
+
+    /// Set the token identifying the resource owner's authorization.
+    ///
+    /// Requests for temporary credentials are sent without a token.
+    pub fn set_token(&mut self, token: Option<String>) {
+        self.token = token;
+    }
+
+    /// Get the signature method.
+    pub fn signature_method(&self) -> OAuth1SignatureMethod {
+        self.signature_method
+    }
+
+    /// Set the signature method.
+    pub fn set_signature_method(&mut self, signature_method: OAuth1SignatureMethod) {
+        self.signature_method = signature_method;
+    }
+
+    /// Get the time the request was signed at.
+    pub fn timestamp(&self) -> Option<SystemTime> {
+        self.timestamp
+            .and_then(|secs| UNIX_EPOCH.checked_add(Duration::from_secs(secs)))
+    }
+
+    /// Set the time the request was signed at.
+    pub fn set_timestamp(&mut self, timestamp: Option<SystemTime>) {
+        self.timestamp = timestamp.map(|timestamp| match timestamp.duration_since(UNIX_EPOCH) {
+            Ok(duration) => duration.as_secs(),
+            Err(_) => 0,
+        });
+    }
+
+    /// Get the nonce, which servers use to detect replayed requests.
+    pub fn nonce(&self) -> Option<&str> {
+        self.nonce.as_deref()
+    }
+
+    /// Set the nonce.
+    pub fn set_nonce(&mut self, nonce: Option<String>) {
+        self.nonce = nonce;
+    }
+
+    /// Get the URI the server redirects to after the resource owner
+    /// authorized the temporary credentials.
+    pub fn callback(&self) -> Option<&str> {
+        self.callback.as_deref()
+    }
+
+    /// Set the callback URI, or `oob` if the client can not receive
+    /// callbacks.
+    pub fn set_callback(&mut self, callback: Option<String>) {
+        self.callback = callback;
+    }
+
+    /// Get the verification code the client received after the resource
+    /// owner authorized the temporary credentials.
+    pub fn verifier(&self) -> Option<&str> {
+        self.verifier.as_deref()
+    }
+
+    /// Set the verification code, when exchanging temporary credentials for
+    /// token credentials.
+    pub fn set_verifier(&mut self, verifier: Option<String>) {
+        self.verifier = verifier;
+    }
+
+    /// Get the signature.
+    pub fn signature(&self) -> &str {
+        &self.signature
+    }
+
+    /// Sign `req`.
+    ///
+    /// `token_secret` is `None` when requesting temporary credentials. The
+    /// body of `req` is only read if it is form encoded, and is left intact
+    /// for later use.
+    pub async fn sign(
+        &mut self,
+        req: &mut Request,
+        consumer_secret: &str,
+        token_secret: Option<&str>,
+    ) -> crate::Result<()> {
+        self.signature = self
+            .compute_signature(req, consumer_secret, token_secret)
+            .await?;
+        Ok(())
+    }
+
+    /// Returns `true` if the credentials carry a valid signature of `req`.
+    ///
+    /// This only checks the signature. Servers should also reject timestamps
+    /// outside of the window they accept, and nonces they have seen before
+    /// with the same timestamp.
+    pub async fn verify(
+        &self,
+        req: &mut Request,
+        consumer_secret: &str,
+        token_secret: Option<&str>,
+    ) -> crate::Result<bool> {
+        let expected = self
+            .compute_signature(req, consumer_secret, token_secret)
+            .await?;
+        Ok(constant_time_eq(
+            expected.as_bytes(),
+            self.signature.as_bytes(),
+        ))
+    }
+
+    async fn compute_signature(
+        &self,
+        req: &mut Request,
+        consumer_secret: &str,
+        token_secret: Option<&str>,
+    ) -> crate::Result<String> {
+        let key = format!(
+            "{}&{}",
+            percent_encode(consumer_secret),
+            percent_encode(token_secret.unwrap_or_default())
+        );
+        let base = match self.signature_method {
+            OAuth1SignatureMethod::Plaintext => String::new(),
+            _ => self.signature_base_string(req).await?,
+        };
+        Ok(self.signature_method.sign(&key, &base))
+    }
+
+    /// Create the signature base string for `req`.
+    ///
+    /// The body of `req` is only read if it is form encoded, and is left
+    /// intact for later use.
+    pub async fn signature_base_string(&self, req: &mut Request) -> crate::Result<String> {
+        let mut params: Vec<(String, String)> = self
+            .oauth_params()
+            .into_iter()
+            .map(|(name, value)| (percent_encode(name), percent_encode(&value)))
+            .collect();
+
+        for (name, value) in req.url().query_pairs() {
+            params.push((percent_encode(&name), percent_encode(&value)));
+        }
+        for (name, value) in form_params(req).await? {
+            params.push((percent_encode(&name), percent_encode(&value)));
+        }
+        params.retain(|(name, _)| name != "oauth_signature");
+        params.sort();
+        let params: Vec<_> = params
+            .iter()
+            .map(|(name, value)| format!("{}={}", name, value))
+            .collect();
+
+        let url = req.url();
+        let mut base_uri = format!("{}://{}", url.scheme(), url.host_str().unwrap_or_default());
+        if let Some(port) = url.port() {
+            base_uri.push_str(&format!(":{}", port));
+        }
+        base_uri.push_str(match url.path() {
+            "" => "/",
+            path => path,
+        });
+
+        Ok(format!(
+            "{}&{}&{}",
+            req.method(),
+            percent_encode(&base_uri),
+            percent_encode(&params.join("&"))
+        ))
+    }
+
+    /// The protocol parameters covered by the signature, in the order they
+    /// are sent in.
+    fn oauth_params(&self) -> Vec<(&'static str, String)> {
+        let mut params = vec![("oauth_consumer_key", self.consumer_key.clone())];
+        if let Some(token) = &self.token {
+            params.push(("oauth_token", token.clone()));
+        }
+        params.push((
+            "oauth_signature_method",
+            self.signature_method.as_str().to_string(),
+        ));
+        if let Some(timestamp) = self.timestamp {
+            params.push(("oauth_timestamp", timestamp.to_string()));
+        }
+        if let Some(nonce) = &self.nonce {
+            params.push(("oauth_nonce", nonce.clone()));
+        }
+        if self.version {
+            params.push(("oauth_version", String::from("1.0")));
+        }
+        if let Some(callback) = &self.callback {
+            params.push(("oauth_callback", callback.clone()));
+        }
+        if let Some(verifier) = &self.verifier {
+            params.push(("oauth_verifier", verifier.clone()));
+        }
+        params
+    }
+}
+
+impl Header for OAuth1Auth {
+    fn header_name(&self) -> HeaderName {
+        AUTHORIZATION
+    }
+
+    fn header_value(&self) -> HeaderValue {
+        let mut output = String::from("OAuth ");
+        if let Some(realm) = &self.realm {
+            write_quoted(&mut output, "realm", realm);
+        }
+        for (name, value) in self.oauth_params() {
+            write_quoted(&mut output, name, &percent_encode(&value));
+        }
+        write_quoted(
+            &mut output,
+            "oauth_signature",
+            &percent_encode(&self.signature),
+        );
+
+        // SAFETY: the internal string is validated to be ASCII.
+        unsafe { HeaderValue::from_bytes_unchecked(output.into()) }
+    }
+}
+
+/// Percent-encode everything but the RFC 3986 unreserved characters.
+fn percent_encode(input: &str) -> String {
+    let mut output = String::new();
+    for byte in input.bytes() {
+        if byte.is_ascii_alphanumeric() || b"-._~".contains(&byte) {
+            output.push(byte as char);
+        } else {
+            write!(output, "%{:02X}", byte).unwrap();
+        }
+    }
+    output
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::mime;
+
+    #[async_std::test]
+    async fn rfc_5849_hmac_sha1() -> crate::Result<()> {
+        let mut req =
+            Request::get("http://photos.example.net/photos?file=vacation.jpg&size=original");
+        req.insert_header(
+            AUTHORIZATION,
+            r#"OAuth realm="Photos", oauth_consumer_key="dpf43f3p2l4k3l03", oauth_token="nnch734d00sl2jdk", oauth_signature_method="HMAC-SHA1", oauth_timestamp="137131202", oauth_nonce="chapoH", oauth_signature="MdpQcU8iPSUjWoN%2FUDMsK2sui9I%3D""#,
+        )?;
+
+        let authz = OAuth1Auth::from_headers(&req)?.unwrap();
+        assert_eq!(authz.realm(), Some("Photos"));
+        assert_eq!(authz.signature(), "MdpQcU8iPSUjWoN/UDMsK2sui9I=");
+        assert!(
+            authz
+                .verify(&mut req, "kd94hf93k423kf44", Some("pfkkdhi9sl3r4s00"))
+                .await?
+        );
+        assert!(
+            !authz
+                .verify(&mut req, "kd94hf93k423kf44", Some("wrong"))
+                .await?
+        );
+
+        let mut signed = authz.clone();
+        signed
+            .sign(&mut req, "kd94hf93k423kf44", Some("pfkkdhi9sl3r4s00"))
+            .await?;
+        assert_eq!(signed, authz);
+        assert_eq!(signed.header_value(), req[AUTHORIZATION].as_str());
+        Ok(())
+    }
+
+    #[async_std::test]
+    async fn rfc_5849_signature_base_string() -> crate::Result<()> {
+        let mut req = Request::post("http://example.com/request?b5=%3D%253D&a3=a&c%40=&a2=r%20b");
+        req.set_body("c2&a3=2+q");
+        req.set_content_type(mime::FORM);
+
+        let mut authz = OAuth1Auth::new("9djdj82h48djs9d2");
+        authz.set_token(Some("kkk9d7dh3k39sjv7".into()));
+        authz.set_timestamp(Some(UNIX_EPOCH + Duration::from_secs(137131201)));
+        authz.set_nonce(Some("7d8f3e4a".into()));
+        assert_eq!(
+            authz.signature_base_string(&mut req).await?,
+            "POST&http%3A%2F%2Fexample.com%2Frequest&a2%3Dr%2520b%26a3%3D2%2520q\
+             %26a3%3Da%26b5%3D%253D%25253D%26c%2540%3D%26c2%3D%26oauth_consumer_\
+             key%3D9djdj82h48djs9d2%26oauth_nonce%3D7d8f3e4a%26oauth_signature_m\
+             ethod%3DHMAC-SHA1%26oauth_timestamp%3D137131201%26oauth_token%3Dkkk\
+             9d7dh3k39sjv7"
+        );
+        assert_eq!(req.body_string().await?, "c2&a3=2+q");
+        Ok(())
+    }
+
+    #[async_std::test]
+    async fn plaintext() -> crate::Result<()> {
+        let mut req = Request::post("https://photos.example.net/initiate");
+        let mut authz = OAuth1Auth::new("dpf43f3p2l4k3l03");
+        authz.set_signature_method(OAuth1SignatureMethod::Plaintext);
+        authz.set_callback(Some("http://printer.example.com/ready".into()));
+        authz.sign(&mut req, "kd94hf93k423kf44", None).await?;
+        assert_eq!(authz.signature(), "kd94hf93k423kf44&");
+        req.insert_header(&authz, &authz)?;
+
+        let authz = OAuth1Auth::from_headers(&req)?.unwrap();
+        assert_eq!(authz.callback(), Some("http://printer.example.com/ready"));
+        assert!(authz.verify(&mut req, "kd94hf93k423kf44", None).await?);
+        Ok(())
+    }
+
+    #[test]
+    fn bad_request_on_parse_error() {
+        for input in [
+            r#"OAuth oauth_signature_method="HMAC-SHA1", oauth_signature="abc""#,
+            r#"OAuth oauth_consumer_key="key", oauth_signature_method="RSA-SHA1", oauth_signature="abc""#,
+            r#"OAuth oauth_consumer_key="key", oauth_signature_method="PLAINTEXT", oauth_signature="%zz""#,
+            r#"OAuth oauth_consumer_key="key", oauth_signature_method="PLAINTEXT", oauth_signature="a", oauth_version="2.0""#,
+            r#"OAuth oauth_consumer_key="key", oauth_signature_method="PLAINTEXT", oauth_signature="a", oauth_timestamp="18446744073709551615""#,
+            r#"Bearer abc"#,
+        ] {
+            let mut headers = Headers::new();
+            headers.insert(AUTHORIZATION, input).unwrap();
+            let err = OAuth1Auth::from_headers(headers).unwrap_err();
+            assert_eq!(err.status(), 400, "{} should not parse", input);
+        }
+    }
+}
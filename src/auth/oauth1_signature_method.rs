@@ -0,0 +1,66 @@
+use std::fmt::{self, Display};
+use std::str::FromStr;
+
+use hmac::Hmac;
+use sha1::Sha1;
+use sha2::Sha256;
+
+use crate::bail_status as bail;
+use crate::utils::hmac;
+
+/// The method used to sign OAuth 1.0 requests.
+///
+/// # Specifications
+///
+/// - [RFC 5849, section 3.4: Signature](https://tools.ietf.org/html/rfc5849#section-3.4)
+/// - [draft-ietf-oauth-signature-hmac-sha256](https://datatracker.ietf.org/doc/html/draft-ietf-oauth-signature-hmac-sha256)
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub enum OAuth1SignatureMethod {
+    /// HMAC-SHA1 over the signature base string.
+    HmacSha1,
+    /// HMAC-SHA256 over the signature base string.
+    HmacSha256,
+    /// The secrets themselves, which must only be sent over TLS.
+    Plaintext,
+}
+
+impl OAuth1SignatureMethod {
+    /// Get the formatted string.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::HmacSha1 => "HMAC-SHA1",
+            Self::HmacSha256 => "HMAC-SHA256",
+            Self::Plaintext => "PLAINTEXT",
+        }
+    }
+
+    /// Sign `base` with `key`, returning the base64 encoded signature.
+    ///
+    /// `PLAINTEXT` ignores the base string and returns the key.
+    pub(crate) fn sign(&self, key: &str, base: &str) -> String {
+        match self {
+            Self::HmacSha1 => base64::encode(hmac::<Hmac<Sha1>>(key.as_ref(), base.as_ref())),
+            Self::HmacSha256 => base64::encode(hmac::<Hmac<Sha256>>(key.as_ref(), base.as_ref())),
+            Self::Plaintext => key.to_string(),
+        }
+    }
+}
+
+impl Display for OAuth1SignatureMethod {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for OAuth1SignatureMethod {
+    type Err = crate::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "HMAC-SHA1" => Ok(Self::HmacSha1),
+            "HMAC-SHA256" => Ok(Self::HmacSha256),
+            "PLAINTEXT" => Ok(Self::Plaintext),
+            _ => bail!(400, "`{}` is not a supported OAuth signature method", s),
+        }
+    }
+}
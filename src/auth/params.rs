@@ -0,0 +1,164 @@
+//! Parsing and formatting of auth-params.
+//!
+//! <https://www.rfc-editor.org/rfc/rfc9110#section-11.2>
+
+use crate::bail_status as bail;
+use crate::parse_utils::{parse_quoted_string, parse_token};
+use crate::{mime, Body, Request};
+
+use std::fmt::Write;
+
+/// token68 = 1*( ALPHA / DIGIT / "-" / "." / "_" / "~" / "+" / "/" ) *"="
+///
+/// Only matches if the token68 makes up the whole challenge.
+pub(crate) fn parse_token68(input: &str) -> (Option<&str>, &str) {
+    let end = input
+        .find(|c: char| !(c.is_ascii_alphanumeric() || "-._~+/".contains(c)))
+        .unwrap_or(input.len());
+    if end == 0 {
+        return (None, input);
+    }
+    let end = end + input[end..].len() - input[end..].trim_start_matches('=').len();
+    let rest = input[end..].trim_start_matches([' ', '\t']);
+    if rest.is_empty() || rest.starts_with(',') {
+        (Some(&input[..end]), rest)
+    } else {
+        (None, input)
+    }
+}
+
+/// Parse a comma separated list of `name=value` auth-params.
+///
+/// Parsing stops at the end of the input, or at a token which is not
+/// followed by `=`, which marks the start of the next challenge. Names are
+/// lowercased since they are case-insensitive.
+pub(crate) fn parse_params(mut input: &str) -> crate::Result<(Vec<(String, String)>, &str)> {
+    let mut params = vec![];
+    loop {
+        input = input.trim_start_matches([' ', '\t', ',']);
+        let (name, rest) = match parse_token(input) {
+            (Some(name), rest) => (name, rest.trim_start_matches([' ', '\t'])),
+            (None, "") => return Ok((params, input)),
+            (None, _) => bail!(400, "Invalid auth-param name"),
+        };
+        let rest = match rest.strip_prefix('=') {
+            Some(rest) => rest.trim_start_matches([' ', '\t']),
+            None => return Ok((params, input)),
+        };
+        let (value, rest) = match parse_quoted_string(rest) {
+            (Some(value), rest) => (value.into_owned(), rest),
+            (None, _) => match parse_unquoted(rest) {
+                (Some(value), rest) => (value.to_string(), rest),
+                (None, _) => bail!(400, "Invalid value for auth-param `{}`", name),
+            },
+        };
+        params.push((name.to_ascii_lowercase(), value));
+
+        input = rest.trim_start_matches([' ', '\t']);
+        if !input.is_empty() && !input.starts_with(',') {
+            bail!(400, "Expected a comma after auth-param `{}`", name);
+        }
+    }
+}
+
+/// Parse an unquoted auth-param value.
+///
+/// This is a token, but base64 values are accepted too since RFC 7804 sends
+/// them unquoted.
+fn parse_unquoted(input: &str) -> (Option<&str>, &str) {
+    let mut rest = input;
+    loop {
+        rest = parse_token(rest).1;
+        match rest.strip_prefix('/') {
+            Some(after) => rest = after,
+            None => break,
+        }
+    }
+    let rest = rest.trim_start_matches('=');
+    match input.len() - rest.len() {
+        0 => (None, input),
+        end => (Some(&input[..end]), rest),
+    }
+}
+
+/// Append `name="value"` to `output`, escaping the value as a quoted-string.
+pub(crate) fn write_quoted(output: &mut String, name: &str, value: &str) {
+    if !output.is_empty() && !output.ends_with(' ') {
+        output.push_str(", ");
+    }
+    write!(output, "{}=\"", name).unwrap();
+    for c in value.chars() {
+        if let '"' | '\\' = c {
+            output.push('\\');
+        }
+        output.push(c);
+    }
+    output.push('"');
+}
+
+/// Append `name=value` to `output` without quoting the value.
+///
+/// The value must be a valid token.
+#[cfg_attr(
+    not(any(feature = "digest-auth", feature = "scram", feature = "web-push")),
+    allow(dead_code)
+)]
+pub(crate) fn write_token(output: &mut String, name: &str, value: &str) {
+    if !output.is_empty() && !output.ends_with(' ') {
+        output.push_str(", ");
+    }
+    write!(output, "{}={}", name, value).unwrap();
+}
+
+/// Read the parameters of a form encoded request body.
+///
+/// Returns no parameters if the body isn't form encoded. The body is left
+/// intact for later use.
+pub(crate) async fn form_params(req: &mut Request) -> crate::Result<Vec<(String, String)>> {
+    let is_form = match req.content_type() {
+        Some(content_type) => content_type.essence() == mime::FORM.essence(),
+        None => false,
+    };
+    if !is_form {
+        return Ok(vec![]);
+    }
+    let body = req.take_body().into_bytes().await?;
+    let params = url::form_urlencoded::parse(&body).into_owned().collect();
+    req.set_body(Body::from_bytes(body));
+    Ok(params)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn parse() -> crate::Result<()> {
+        let (params, rest) =
+            parse_params(r#"Realm="a \"b\"", nc=00000001 ,, qop = auth, Basic realm="c""#)?;
+        assert_eq!(
+            params,
+            [
+                ("realm".to_string(), r#"a "b""#.to_string()),
+                ("nc".to_string(), "00000001".to_string()),
+                ("qop".to_string(), "auth".to_string()),
+            ]
+        );
+        assert_eq!(rest, r#"Basic realm="c""#);
+
+        let (params, _) = parse_params("sid=AAAA, data=dj0/cnJ==")?;
+        assert_eq!(params[1], ("data".to_string(), "dj0/cnJ==".to_string()));
+
+        assert!(parse_params(r#"realm="unterminated"#).is_err());
+        assert!(parse_params("realm=a b=c").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn write() {
+        let mut output = "Digest ".to_string();
+        write_quoted(&mut output, "realm", r#"a "b""#);
+        write_token(&mut output, "algorithm", "SHA-256");
+        assert_eq!(output, r#"Digest realm="a \"b\"", algorithm=SHA-256"#);
+    }
+}
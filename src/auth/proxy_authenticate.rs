@@ -0,0 +1,91 @@
+use crate::auth::WwwAuthenticate;
+use crate::headers::{Header, HeaderName, HeaderValue, Headers, PROXY_AUTHENTICATE};
+
+use std::fmt::{self, Debug};
+use std::ops::{Deref, DerefMut};
+
+/// Define the authentication methods that should be used to gain access to a
+/// resource behind a proxy.
+///
+/// This has the same format as [`WwwAuthenticate`], but applies to the proxy
+/// rather than the origin server.
+///
+/// # Specifications
+///
+/// - [RFC 9110, section 11.7.1: Proxy-Authenticate](https://www.rfc-editor.org/rfc/rfc9110#section-11.7.1)
+///
+/// # Examples
+///
+/// ```
+/// # fn main() -> http_types::Result<()> {
+/// #
+/// use http_types::Response;
+/// use http_types::auth::{AuthenticationScheme, Challenge, ProxyAuthenticate};
+///
+/// let mut challenge = Challenge::new(AuthenticationScheme::Basic);
+/// challenge.set_realm("proxy")?;
+///
+/// let mut authz = ProxyAuthenticate::new();
+/// authz.push(challenge);
+///
+/// let mut res = Response::new(407);
+/// res.insert_header(&authz, &authz);
+/// assert_eq!(res["Proxy-Authenticate"], r#"Basic realm="proxy""#);
+///
+/// let authz = ProxyAuthenticate::from_headers(res)?.unwrap();
+/// let challenge = authz.get(AuthenticationScheme::Basic).unwrap();
+/// assert_eq!(challenge.realm(), Some("proxy"));
+/// #
+/// # Ok(()) }
+/// ```
+#[derive(Clone, Eq, PartialEq)]
+pub struct ProxyAuthenticate {
+    inner: WwwAuthenticate,
+}
+
+impl ProxyAuthenticate {
+    /// Create a new instance of `ProxyAuthenticate`.
+    pub fn new() -> Self {
+        Self {
+            inner: WwwAuthenticate::new(),
+        }
+    }
+
+    /// Create a new instance from headers.
+    pub fn from_headers(headers: impl AsRef<Headers>) -> crate::Result<Option<Self>> {
+        match headers.as_ref().get(PROXY_AUTHENTICATE) {
+            Some(values) => Ok(Some(Self {
+                inner: WwwAuthenticate::from_values(values)?,
+            })),
+            None => Ok(None),
+        }
+    }
+}
+
+impl Header for ProxyAuthenticate {
+    fn header_name(&self) -> HeaderName {
+        PROXY_AUTHENTICATE
+    }
+    fn header_value(&self) -> HeaderValue {
+        self.inner.header_value()
+    }
+}
+
+impl Deref for ProxyAuthenticate {
+    type Target = WwwAuthenticate;
+    fn deref(&self) -> &Self::Target {
+        &self.inner
+    }
+}
+
+impl DerefMut for ProxyAuthenticate {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.inner
+    }
+}
+
+impl Debug for ProxyAuthenticate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.inner.fmt(f)
+    }
+}
@@ -0,0 +1,80 @@
+use crate::auth::{AuthenticationScheme, Authorization};
+use crate::headers::{Header, HeaderName, HeaderValue, Headers, PROXY_AUTHORIZATION};
+
+use std::ops::{Deref, DerefMut};
+
+/// Credentials to authenticate a user agent with a proxy.
+///
+/// This has the same format as [`Authorization`], but is consumed by the
+/// first proxy which requires authentication.
+///
+/// # Specifications
+///
+/// - [RFC 9110, section 11.7.2: Proxy-Authorization](https://www.rfc-editor.org/rfc/rfc9110#section-11.7.2)
+///
+/// # Examples
+///
+/// ```
+/// # fn main() -> http_types::Result<()> {
+/// #
+/// use http_types::Request;
+/// use http_types::auth::{AuthenticationScheme, ProxyAuthorization};
+///
+/// let scheme = AuthenticationScheme::Basic;
+/// let credentials = "0xdeadbeef202020";
+/// let authz = ProxyAuthorization::new(scheme, credentials.into());
+///
+/// let mut req = Request::get("https://example.com");
+/// req.insert_header(&authz, &authz);
+///
+/// let authz = ProxyAuthorization::from_headers(req)?.unwrap();
+/// assert_eq!(authz.scheme(), AuthenticationScheme::Basic);
+/// assert_eq!(authz.credentials(), credentials);
+/// #
+/// # Ok(()) }
+/// ```
+#[derive(Debug)]
+pub struct ProxyAuthorization {
+    inner: Authorization,
+}
+
+impl ProxyAuthorization {
+    /// Create a new instance of `ProxyAuthorization`.
+    pub fn new(scheme: AuthenticationScheme, credentials: String) -> Self {
+        Self {
+            inner: Authorization::new(scheme, credentials),
+        }
+    }
+
+    /// Create a new instance from headers.
+    pub fn from_headers(headers: impl AsRef<Headers>) -> crate::Result<Option<Self>> {
+        match headers.as_ref().get(PROXY_AUTHORIZATION) {
+            Some(values) => Ok(Some(Self {
+                inner: Authorization::from_values(values)?,
+            })),
+            None => Ok(None),
+        }
+    }
+}
+
+impl Header for ProxyAuthorization {
+    fn header_name(&self) -> HeaderName {
+        PROXY_AUTHORIZATION
+    }
+    fn header_value(&self) -> HeaderValue {
+        self.inner.header_value()
+    }
+}
+
+impl Deref for ProxyAuthorization {
+    type Target = Authorization;
+    fn deref(&self) -> &Self::Target {
+        &self.inner
+    }
+}
+
+impl DerefMut for ProxyAuthorization {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.inner
+    }
+}
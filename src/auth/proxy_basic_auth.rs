@@ -0,0 +1,122 @@
+use crate::auth::{AuthenticationScheme, Authorization, BasicAuth, ProxyAuthorization};
+use crate::headers::{Header, HeaderName, HeaderValue, Headers, PROXY_AUTHORIZATION};
+
+use std::ops::{Deref, DerefMut};
+
+/// HTTP Basic authorization with a proxy.
+///
+/// This has the same API as [`BasicAuth`], but uses the
+/// `Proxy-Authorization` header.
+///
+/// # Specifications
+///
+/// - [RFC7617](https://tools.ietf.org/html/rfc7617)
+/// - [RFC 9110, section 11.7.2: Proxy-Authorization](https://www.rfc-editor.org/rfc/rfc9110#section-11.7.2)
+///
+/// # Examples
+///
+/// ```
+/// # fn main() -> http_types::Result<()> {
+/// #
+/// use http_types::Request;
+/// use http_types::auth::ProxyBasicAuth;
+///
+/// let authz = ProxyBasicAuth::new("nori", "secret_fish!!");
+///
+/// let mut req = Request::get("https://example.com");
+/// req.insert_header(&authz, &authz)?;
+/// assert!(req.header("Authorization").is_none());
+///
+/// let authz = ProxyBasicAuth::from_headers(req)?.unwrap();
+/// assert!(authz.verify("nori", "secret_fish!!"));
+/// #
+/// # Ok(()) }
+/// ```
+#[derive(Debug)]
+pub struct ProxyBasicAuth {
+    inner: BasicAuth,
+}
+
+impl ProxyBasicAuth {
+    /// Create a new instance of `ProxyBasicAuth`.
+    pub fn new<U, P>(username: U, password: P) -> Self
+    where
+        U: AsRef<str>,
+        P: AsRef<str>,
+    {
+        Self {
+            inner: BasicAuth::new(username, password),
+        }
+    }
+
+    /// Create a new instance from headers.
+    pub fn from_headers(headers: impl AsRef<Headers>) -> crate::Result<Option<Self>> {
+        let auth = match ProxyAuthorization::from_headers(headers)? {
+            Some(auth) => auth,
+            None => return Ok(None),
+        };
+        Ok(Some(Self {
+            inner: BasicAuth::from_authorization(&auth)?,
+        }))
+    }
+
+    /// Create a new instance from the base64 encoded credentials.
+    ///
+    /// Credentials are decoded as UTF-8 when they are valid UTF-8, and as
+    /// ISO-8859-1 otherwise.
+    pub fn from_credentials(credentials: impl AsRef<[u8]>) -> crate::Result<Self> {
+        Ok(Self {
+            inner: BasicAuth::from_credentials(credentials)?,
+        })
+    }
+}
+
+impl Header for ProxyBasicAuth {
+    fn header_name(&self) -> HeaderName {
+        PROXY_AUTHORIZATION
+    }
+
+    fn header_value(&self) -> HeaderValue {
+        let auth = Authorization::new(AuthenticationScheme::Basic, self.inner.credentials());
+        auth.header_value()
+    }
+}
+
+impl Deref for ProxyBasicAuth {
+    type Target = BasicAuth;
+    fn deref(&self) -> &Self::Target {
+        &self.inner
+    }
+}
+
+impl DerefMut for ProxyBasicAuth {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.inner
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::headers::AUTHORIZATION;
+
+    #[test]
+    fn smoke() -> crate::Result<()> {
+        let mut authz = ProxyBasicAuth::new("test", "123\u{a3}");
+        authz.set_charset(crate::auth::BasicCharset::Iso8859_1)?;
+
+        let mut headers = Headers::new();
+        authz.apply_header(&mut headers);
+        assert_eq!(headers[PROXY_AUTHORIZATION], "Basic dGVzdDoxMjOj");
+
+        let authz = ProxyBasicAuth::from_headers(&headers)?.unwrap();
+        assert_eq!(authz.username(), "test");
+        assert_eq!(authz.password(), "123\u{a3}");
+
+        headers.insert(PROXY_AUTHORIZATION, "Bearer abc")?;
+        headers.insert(AUTHORIZATION, "Basic dGVzdDoxMjOj")?;
+        let err = ProxyBasicAuth::from_headers(headers).unwrap_err();
+        assert_eq!(err.status(), 400);
+        Ok(())
+    }
+}
@@ -0,0 +1,101 @@
+use std::fmt::{self, Display};
+use std::str::FromStr;
+
+use hmac::Hmac;
+use sha1::Sha1;
+use sha2::{Digest, Sha256};
+
+use crate::auth::AuthenticationScheme;
+use crate::bail_status as bail;
+use crate::utils::hmac;
+
+/// The hash function used by a SCRAM exchange.
+///
+/// # Specifications
+///
+/// - [RFC 5802: Salted Challenge Response Authentication Mechanism (SCRAM)](https://tools.ietf.org/html/rfc5802)
+/// - [RFC 7677: SCRAM-SHA-256 and SCRAM-SHA-256-PLUS](https://tools.ietf.org/html/rfc7677)
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub enum ScramAlgorithm {
+    /// SCRAM-SHA-1.
+    Sha1,
+    /// SCRAM-SHA-256.
+    Sha256,
+}
+
+impl ScramAlgorithm {
+    /// Get the formatted string.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Sha1 => "SCRAM-SHA-1",
+            Self::Sha256 => "SCRAM-SHA-256",
+        }
+    }
+
+    /// Get the authentication scheme using this algorithm.
+    pub fn scheme(&self) -> AuthenticationScheme {
+        match self {
+            Self::Sha1 => AuthenticationScheme::ScramSha1,
+            Self::Sha256 => AuthenticationScheme::ScramSha256,
+        }
+    }
+
+    /// Get the algorithm used by an authentication scheme, if it is a SCRAM
+    /// scheme.
+    pub(crate) fn from_scheme(scheme: AuthenticationScheme) -> Option<Self> {
+        match scheme {
+            AuthenticationScheme::ScramSha1 => Some(Self::Sha1),
+            AuthenticationScheme::ScramSha256 => Some(Self::Sha256),
+            _ => None,
+        }
+    }
+
+    /// H(), the hash function.
+    pub(crate) fn hash(&self, data: &[u8]) -> Vec<u8> {
+        match self {
+            Self::Sha1 => Sha1::digest(data).to_vec(),
+            Self::Sha256 => Sha256::digest(data).to_vec(),
+        }
+    }
+
+    /// HMAC() using the hash function.
+    pub(crate) fn hmac(&self, key: &[u8], data: &[u8]) -> Vec<u8> {
+        match self {
+            Self::Sha1 => hmac::<Hmac<Sha1>>(key, data),
+            Self::Sha256 => hmac::<Hmac<Sha256>>(key, data),
+        }
+    }
+
+    /// Hi(), which is PBKDF2 with a single block of output.
+    pub(crate) fn hi(&self, password: &[u8], salt: &[u8], iterations: u32) -> Vec<u8> {
+        let mut block = salt.to_vec();
+        block.extend_from_slice(&1u32.to_be_bytes());
+        let mut u = self.hmac(password, &block);
+        let mut output = u.clone();
+        for _ in 1..iterations {
+            u = self.hmac(password, &u);
+            for (output, u) in output.iter_mut().zip(&u) {
+                *output ^= u;
+            }
+        }
+        output
+    }
+}
+
+impl Display for ScramAlgorithm {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for ScramAlgorithm {
+    type Err = crate::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_uppercase().as_str() {
+            "SCRAM-SHA-1" => Ok(Self::Sha1),
+            "SCRAM-SHA-256" => Ok(Self::Sha256),
+            _ => bail!(400, "`{}` is not a supported SCRAM algorithm", s),
+        }
+    }
+}
@@ -0,0 +1,97 @@
+use crate::auth::scram_message::{decode_data, header_value, scheme};
+use crate::auth::{Challenge, ScramAlgorithm};
+use crate::headers::{Header, HeaderName, HeaderValue, Headers, AUTHORIZATION};
+use crate::{bail_status as bail, ensure_status as ensure};
+
+/// A client message of a SCRAM exchange, sent in the `Authorization` header.
+///
+/// These are created by a [`ScramClient`](crate::auth::ScramClient), and
+/// read by a [`ScramServer`](crate::auth::ScramServer).
+///
+/// # Specifications
+///
+/// - [RFC 7804, section 5: SCRAM-SHA-256 and SCRAM-SHA-1 HTTP Authentication](https://tools.ietf.org/html/rfc7804#section-5)
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct ScramAuth {
+    pub(crate) algorithm: ScramAlgorithm,
+    pub(crate) realm: Option<String>,
+    pub(crate) sid: Option<String>,
+    pub(crate) data: String,
+}
+
+impl ScramAuth {
+    /// Create a new instance from headers.
+    pub fn from_headers(headers: impl AsRef<Headers>) -> crate::Result<Option<Self>> {
+        let headers = match headers.as_ref().get(AUTHORIZATION) {
+            Some(headers) => headers,
+            None => return Ok(None),
+        };
+
+        // If we successfully parsed the header then there's always at least one
+        // entry. We want the last entry.
+        let value = headers.iter().last().unwrap();
+        let credentials = match Challenge::parse_list(value.as_str())?.into_iter().next() {
+            Some(credentials) => credentials,
+            None => bail!(400, "Could not find scheme"),
+        };
+        let algorithm = scheme(&credentials)?;
+        let data = match credentials.param("data") {
+            Some(data) => decode_data(data)?,
+            None => bail!(400, "SCRAM credentials must contain data"),
+        };
+        let sid = credentials.param("sid").map(String::from);
+        if let Some(sid) = &sid {
+            ensure!(is_sid(sid), 400, "Invalid SCRAM session id `{}`", sid);
+        }
+        Ok(Some(Self {
+            algorithm,
+            realm: credentials.realm().map(String::from),
+            sid,
+            data,
+        }))
+    }
+
+    /// Get the algorithm.
+    pub fn algorithm(&self) -> ScramAlgorithm {
+        self.algorithm
+    }
+
+    /// Get the realm.
+    pub fn realm(&self) -> Option<&str> {
+        self.realm.as_deref()
+    }
+
+    /// Get the session id, which is only sent with the client's final
+    /// message.
+    pub fn sid(&self) -> Option<&str> {
+        self.sid.as_deref()
+    }
+
+    /// Get the decoded SCRAM message.
+    pub fn data(&self) -> &str {
+        &self.data
+    }
+}
+
+impl Header for ScramAuth {
+    fn header_name(&self) -> HeaderName {
+        AUTHORIZATION
+    }
+
+    fn header_value(&self) -> HeaderValue {
+        header_value(
+            Some(self.algorithm.scheme()),
+            self.realm.as_deref(),
+            self.sid.as_deref(),
+            Some(&self.data),
+        )
+    }
+}
+
+/// Session ids are sent unquoted, so they have to be tokens.
+pub(crate) fn is_sid(sid: &str) -> bool {
+    !sid.is_empty()
+        && sid
+            .bytes()
+            .all(|b| b.is_ascii_alphanumeric() || b"-._~+".contains(&b))
+}
@@ -0,0 +1,71 @@
+use crate::auth::params::parse_params;
+use crate::auth::scram_auth::is_sid;
+use crate::auth::scram_message::{decode_data, header_value};
+use crate::headers::{Header, HeaderName, HeaderValue, Headers, AUTHENTICATION_INFO};
+use crate::{bail_status as bail, ensure_status as ensure};
+
+/// The final server message of a SCRAM exchange, sent in the
+/// `Authentication-Info` header of the successful response.
+///
+/// These are created by a [`ScramServer`](crate::auth::ScramServer), and
+/// let a [`ScramClient`](crate::auth::ScramClient) verify the server knew
+/// its credentials.
+///
+/// # Specifications
+///
+/// - [RFC 7804, section 5: SCRAM-SHA-256 and SCRAM-SHA-1 HTTP Authentication](https://tools.ietf.org/html/rfc7804#section-5)
+/// - [RFC 7615: HTTP Authentication-Info and Proxy-Authentication-Info Response Header Fields](https://tools.ietf.org/html/rfc7615)
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct ScramAuthenticationInfo {
+    pub(crate) sid: String,
+    pub(crate) data: String,
+}
+
+impl ScramAuthenticationInfo {
+    /// Create a new instance from headers.
+    pub fn from_headers(headers: impl AsRef<Headers>) -> crate::Result<Option<Self>> {
+        let headers = match headers.as_ref().get(AUTHENTICATION_INFO) {
+            Some(headers) => headers,
+            None => return Ok(None),
+        };
+
+        // If we successfully parsed the header then there's always at least one
+        // entry. We want the last entry.
+        let value = headers.iter().last().unwrap();
+        let (params, rest) = parse_params(value.as_str())?;
+        ensure!(rest.is_empty(), 400, "Invalid Authentication-Info header");
+        let param = |name| {
+            params
+                .iter()
+                .find(|(key, _)| key == name)
+                .map(|(_, value)| value.as_str())
+        };
+        match (param("sid"), param("data")) {
+            (Some(sid), Some(data)) if is_sid(sid) => Ok(Some(Self {
+                sid: sid.to_string(),
+                data: decode_data(data)?,
+            })),
+            _ => bail!(400, "SCRAM authentication info must contain a sid and data"),
+        }
+    }
+
+    /// Get the session id.
+    pub fn sid(&self) -> &str {
+        &self.sid
+    }
+
+    /// Get the decoded SCRAM message.
+    pub fn data(&self) -> &str {
+        &self.data
+    }
+}
+
+impl Header for ScramAuthenticationInfo {
+    fn header_name(&self) -> HeaderName {
+        AUTHENTICATION_INFO
+    }
+
+    fn header_value(&self) -> HeaderValue {
+        header_value(None, None, Some(&self.sid), Some(&self.data))
+    }
+}
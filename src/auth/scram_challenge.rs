@@ -0,0 +1,135 @@
+use crate::auth::scram_auth::is_sid;
+use crate::auth::scram_message::{decode_data, header_value, scheme, validate_realm};
+use crate::auth::{ScramAlgorithm, WwwAuthenticate};
+use crate::ensure_status as ensure;
+use crate::headers::{Header, HeaderName, HeaderValue, Headers, WWW_AUTHENTICATE};
+
+/// A server message of a SCRAM exchange, sent in the `WWW-Authenticate`
+/// header.
+///
+/// The first challenge, created with [`ScramChallenge::new`], only invites
+/// the client to start an exchange. The second one is created by a
+/// [`ScramServer`](crate::auth::ScramServer) and carries the salt and
+/// iteration count for the user.
+///
+/// # Specifications
+///
+/// - [RFC 7804, section 5: SCRAM-SHA-256 and SCRAM-SHA-1 HTTP Authentication](https://tools.ietf.org/html/rfc7804#section-5)
+///
+/// # Examples
+///
+/// ```
+/// # fn main() -> http_types::Result<()> {
+/// #
+/// use http_types::Response;
+/// use http_types::auth::{ScramAlgorithm, ScramChallenge};
+///
+/// let mut challenge = ScramChallenge::new(ScramAlgorithm::Sha256);
+/// challenge.set_realm(Some("testrealm@example.com".into()))?;
+///
+/// let mut res = Response::new(401);
+/// res.insert_header(&challenge, &challenge)?;
+/// assert_eq!(res["WWW-Authenticate"], r#"SCRAM-SHA-256 realm="testrealm@example.com""#);
+///
+/// let challenge = ScramChallenge::from_headers(res)?.unwrap();
+/// assert_eq!(challenge.algorithm(), ScramAlgorithm::Sha256);
+/// assert_eq!(challenge.data(), None);
+/// #
+/// # Ok(()) }
+/// ```
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct ScramChallenge {
+    pub(crate) algorithm: ScramAlgorithm,
+    pub(crate) realm: Option<String>,
+    pub(crate) sid: Option<String>,
+    pub(crate) data: Option<String>,
+}
+
+impl ScramChallenge {
+    /// Create a new instance of `ScramChallenge` inviting the client to start
+    /// an exchange.
+    pub fn new(algorithm: ScramAlgorithm) -> Self {
+        Self {
+            algorithm,
+            realm: None,
+            sid: None,
+            data: None,
+        }
+    }
+
+    /// Create a new instance from headers.
+    ///
+    /// Only the first SCRAM challenge is used, challenges for other schemes
+    /// are ignored.
+    pub fn from_headers(headers: impl AsRef<Headers>) -> crate::Result<Option<Self>> {
+        let authenticate = match WwwAuthenticate::from_headers(headers)? {
+            Some(authenticate) => authenticate,
+            None => return Ok(None),
+        };
+        let challenge = match authenticate
+            .iter()
+            .find(|challenge| scheme(challenge).is_ok())
+        {
+            Some(challenge) => challenge,
+            None => return Ok(None),
+        };
+        let sid = challenge.param("sid").map(String::from);
+        if let Some(sid) = &sid {
+            ensure!(is_sid(sid), 400, "Invalid SCRAM session id `{}`", sid);
+        }
+        Ok(Some(Self {
+            algorithm: scheme(challenge)?,
+            realm: challenge.realm().map(String::from),
+            sid,
+            data: challenge.param("data").map(decode_data).transpose()?,
+        }))
+    }
+
+    /// Get the algorithm.
+    pub fn algorithm(&self) -> ScramAlgorithm {
+        self.algorithm
+    }
+
+    /// Get the realm.
+    pub fn realm(&self) -> Option<&str> {
+        self.realm.as_deref()
+    }
+
+    /// Set the realm.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the realm contains non-ASCII or control
+    /// characters.
+    pub fn set_realm(&mut self, realm: Option<String>) -> crate::Result<()> {
+        validate_realm(&realm)?;
+        self.realm = realm;
+        Ok(())
+    }
+
+    /// Get the session id, which is only sent with the server's first
+    /// message.
+    pub fn sid(&self) -> Option<&str> {
+        self.sid.as_deref()
+    }
+
+    /// Get the decoded SCRAM message, if there is one.
+    pub fn data(&self) -> Option<&str> {
+        self.data.as_deref()
+    }
+}
+
+impl Header for ScramChallenge {
+    fn header_name(&self) -> HeaderName {
+        WWW_AUTHENTICATE
+    }
+
+    fn header_value(&self) -> HeaderValue {
+        header_value(
+            Some(self.algorithm.scheme()),
+            self.realm.as_deref(),
+            self.sid.as_deref(),
+            self.data.as_deref(),
+        )
+    }
+}
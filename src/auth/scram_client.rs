@@ -0,0 +1,419 @@
+use crate::auth::scram_message::{
+    attribute, decode, escape_username, nonce, parse_attributes, validate_realm,
+};
+use crate::auth::{ScramAlgorithm, ScramAuth, ScramAuthenticationInfo, ScramChallenge};
+use crate::utils::constant_time_eq;
+use crate::{bail_status as bail, ensure_status as ensure};
+
+use std::fmt::{self, Debug};
+
+/// The GS2 header of clients without channel binding support.
+const GS2_HEADER: &str = "n,,";
+
+/// The highest iteration count accepted by default. This covers current
+/// recommendations for PBKDF2, while bounding the work a server can demand.
+const DEFAULT_MAX_ITERATIONS: u32 = 2_000_000;
+
+/// The client side of a SCRAM exchange.
+///
+/// The exchange takes two round trips: [`ScramClient::start`] creates the
+/// credentials for the first request, [`ScramClient::respond`] answers the
+/// server's challenge, and [`ScramClient::finish`] verifies the server's
+/// signature in the successful response.
+///
+/// # Specifications
+///
+/// - [RFC 7804: Salted Challenge Response HTTP Authentication Mechanism](https://tools.ietf.org/html/rfc7804)
+///
+/// # Examples
+///
+/// ```
+/// # fn main() -> http_types::Result<()> {
+/// #
+/// use http_types::auth::{
+///     ScramAlgorithm, ScramAuth, ScramAuthenticationInfo, ScramChallenge, ScramClient,
+///     ScramCredentials, ScramServer,
+/// };
+/// use http_types::{Request, Response};
+///
+/// // The server stores the salted password of each user.
+/// let credentials = ScramCredentials::new(ScramAlgorithm::Sha256, "pencil");
+///
+/// // The client sends its first message.
+/// let mut client = ScramClient::new(ScramAlgorithm::Sha256, "user", "pencil");
+/// let authz = client.start()?;
+/// let mut req = Request::get("https://example.com/resource");
+/// req.insert_header(&authz, &authz)?;
+///
+/// // The server looks up the user and challenges the client. It keeps the
+/// // `ScramServer` until the client answers, using the session id as key.
+/// let authz = ScramAuth::from_headers(&req)?.unwrap();
+/// let mut server = ScramServer::start(&authz)?;
+/// assert_eq!(server.username(), "user");
+/// let challenge = server.challenge(&credentials)?;
+/// let mut res = Response::new(401);
+/// res.insert_header(&challenge, &challenge)?;
+///
+/// // The client proves it knows the password.
+/// let challenge = ScramChallenge::from_headers(&res)?.unwrap();
+/// let authz = client.respond(&challenge)?;
+/// let mut req = Request::get("https://example.com/resource");
+/// req.insert_header(&authz, &authz)?;
+///
+/// // The server verifies the proof, and proves it knows the credentials.
+/// let authz = ScramAuth::from_headers(&req)?.unwrap();
+/// assert_eq!(authz.sid(), server.sid());
+/// let info = server.finish(&authz)?;
+/// let mut res = Response::new(200);
+/// res.insert_header(&info, &info)?;
+///
+/// // The client verifies the server.
+/// let info = ScramAuthenticationInfo::from_headers(&res)?.unwrap();
+/// client.finish(&info)?;
+/// assert!(client.is_authenticated());
+/// #
+/// # Ok(()) }
+/// ```
+#[derive(Clone)]
+pub struct ScramClient {
+    algorithm: ScramAlgorithm,
+    username: String,
+    password: String,
+    realm: Option<String>,
+    nonce: String,
+    max_iterations: u32,
+    state: State,
+}
+
+#[derive(Clone)]
+enum State {
+    Initial,
+    Started {
+        client_first_bare: String,
+    },
+    Responded {
+        sid: String,
+        server_signature: Vec<u8>,
+    },
+    Authenticated,
+}
+
+impl ScramClient {
+    /// Create a new instance of `ScramClient`.
+    ///
+    /// Passwords are used as given, they are not normalized with SASLprep.
+    pub fn new(
+        algorithm: ScramAlgorithm,
+        username: impl Into<String>,
+        password: impl Into<String>,
+    ) -> Self {
+        Self {
+            algorithm,
+            username: username.into(),
+            password: password.into(),
+            realm: None,
+            nonce: nonce(),
+            max_iterations: DEFAULT_MAX_ITERATIONS,
+            state: State::Initial,
+        }
+    }
+
+    /// Create a new instance of `ScramClient` answering a server's initial
+    /// challenge, using its algorithm and realm.
+    pub fn from_challenge(
+        challenge: &ScramChallenge,
+        username: impl Into<String>,
+        password: impl Into<String>,
+    ) -> Self {
+        let mut client = Self::new(challenge.algorithm, username, password);
+        client.realm = challenge.realm.clone();
+        client
+    }
+
+    /// Get the algorithm.
+    pub fn algorithm(&self) -> ScramAlgorithm {
+        self.algorithm
+    }
+
+    /// Get the username.
+    pub fn username(&self) -> &str {
+        &self.username
+    }
+
+    /// Get the realm.
+    pub fn realm(&self) -> Option<&str> {
+        self.realm.as_deref()
+    }
+
+    /// Set the realm.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the realm contains non-ASCII or control
+    /// characters.
+    pub fn set_realm(&mut self, realm: Option<String>) -> crate::Result<()> {
+        validate_realm(&realm)?;
+        self.realm = realm;
+        Ok(())
+    }
+
+    /// Get the highest iteration count accepted from the server.
+    pub fn max_iterations(&self) -> u32 {
+        self.max_iterations
+    }
+
+    /// Set the highest iteration count accepted from the server.
+    ///
+    /// Each iteration costs the client one HMAC, so this bounds the time
+    /// [`ScramClient::respond`] takes. Defaults to 2,000,000.
+    pub fn set_max_iterations(&mut self, max_iterations: u32) {
+        self.max_iterations = max_iterations;
+    }
+
+    /// Returns `true` if the server's signature has been verified.
+    pub fn is_authenticated(&self) -> bool {
+        matches!(self.state, State::Authenticated)
+    }
+
+    /// Create the client's first message.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the exchange has already been started.
+    pub fn start(&mut self) -> crate::Result<ScramAuth> {
+        crate::ensure!(
+            matches!(self.state, State::Initial),
+            "SCRAM exchange has already been started"
+        );
+        let client_first_bare = format!("n={},r={}", escape_username(&self.username), self.nonce);
+        let data = format!("{}{}", GS2_HEADER, client_first_bare);
+        self.state = State::Started { client_first_bare };
+        Ok(ScramAuth {
+            algorithm: self.algorithm,
+            realm: self.realm.clone(),
+            sid: None,
+            data,
+        })
+    }
+
+    /// Answer the server's challenge with the client's final message.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the exchange hasn't been started, or the challenge
+    /// is malformed or doesn't continue this exchange. Iteration counts above
+    /// [`ScramClient::max_iterations`] are rejected as well.
+    pub fn respond(&mut self, challenge: &ScramChallenge) -> crate::Result<ScramAuth> {
+        let client_first_bare = match &self.state {
+            State::Started { client_first_bare } => client_first_bare,
+            _ => crate::bail!("SCRAM exchange has not been started"),
+        };
+        ensure!(
+            challenge.algorithm == self.algorithm,
+            400,
+            "Expected a {} challenge",
+            self.algorithm
+        );
+        let (sid, server_first) = match (&challenge.sid, &challenge.data) {
+            (Some(sid), Some(data)) => (sid, data),
+            _ => bail!(400, "SCRAM challenge must contain a sid and data"),
+        };
+
+        let attributes = parse_attributes(server_first)?;
+        let nonce = attribute(&attributes, 'r')?;
+        ensure!(
+            nonce.len() > self.nonce.len() && nonce.starts_with(&self.nonce),
+            400,
+            "SCRAM server nonce does not extend the client nonce"
+        );
+        let salt = decode(attribute(&attributes, 's')?)?;
+        let iterations = match attribute(&attributes, 'i')?.parse::<u32>() {
+            Ok(iterations) if iterations > 0 => iterations,
+            _ => bail!(400, "Invalid SCRAM iteration count"),
+        };
+        ensure!(
+            iterations <= self.max_iterations,
+            400,
+            "SCRAM iteration count {} exceeds the maximum of {}",
+            iterations,
+            self.max_iterations
+        );
+
+        let client_final_without_proof = format!("c={},r={}", base64::encode(GS2_HEADER), nonce);
+        let auth_message = format!(
+            "{},{},{}",
+            client_first_bare, server_first, client_final_without_proof
+        );
+
+        let algorithm = self.algorithm;
+        let salted_password = algorithm.hi(self.password.as_bytes(), &salt, iterations);
+        let client_key = algorithm.hmac(&salted_password, b"Client Key");
+        let stored_key = algorithm.hash(&client_key);
+        let client_signature = algorithm.hmac(&stored_key, auth_message.as_bytes());
+        let proof: Vec<u8> = client_key
+            .iter()
+            .zip(&client_signature)
+            .map(|(a, b)| a ^ b)
+            .collect();
+        let server_key = algorithm.hmac(&salted_password, b"Server Key");
+        let server_signature = algorithm.hmac(&server_key, auth_message.as_bytes());
+
+        self.state = State::Responded {
+            sid: sid.clone(),
+            server_signature,
+        };
+        Ok(ScramAuth {
+            algorithm,
+            realm: None,
+            sid: Some(sid.clone()),
+            data: format!("{},p={}", client_final_without_proof, base64::encode(proof)),
+        })
+    }
+
+    /// Verify the server's final message.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `401 Unauthorized` error if the server reported an error or
+    /// its signature doesn't match, which means it doesn't know the user's
+    /// credentials.
+    pub fn finish(&mut self, info: &ScramAuthenticationInfo) -> crate::Result<()> {
+        let (sid, server_signature) = match &self.state {
+            State::Responded {
+                sid,
+                server_signature,
+            } => (sid, server_signature),
+            _ => crate::bail!("SCRAM exchange is not awaiting the server's final message"),
+        };
+        ensure!(info.sid == *sid, 400, "SCRAM session id does not match");
+
+        let attributes = parse_attributes(&info.data)?;
+        if let Ok(error) = attribute(&attributes, 'e') {
+            bail!(401, "SCRAM authentication failed: {}", error);
+        }
+        let verifier = decode(attribute(&attributes, 'v')?)?;
+        ensure!(
+            constant_time_eq(&verifier, server_signature),
+            401,
+            "SCRAM server signature does not match"
+        );
+        self.state = State::Authenticated;
+        Ok(())
+    }
+}
+
+impl Debug for ScramClient {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ScramClient")
+            .field("algorithm", &self.algorithm)
+            .field("username", &self.username)
+            .field("realm", &self.realm)
+            .field("is_authenticated", &self.is_authenticated())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn challenge(algorithm: ScramAlgorithm, data: &str) -> ScramChallenge {
+        ScramChallenge {
+            algorithm,
+            realm: None,
+            sid: Some("AAAABBBBCCCCDDDD".into()),
+            data: Some(data.into()),
+        }
+    }
+
+    fn info(data: &str) -> ScramAuthenticationInfo {
+        ScramAuthenticationInfo {
+            sid: "AAAABBBBCCCCDDDD".into(),
+            data: data.into(),
+        }
+    }
+
+    #[test]
+    fn rfc_7677_example() -> crate::Result<()> {
+        let mut client = ScramClient::new(ScramAlgorithm::Sha256, "user", "pencil");
+        client.nonce = "rOprNGfwEbeRWgbNEkqO".into();
+        assert_eq!(client.start()?.data(), "n,,n=user,r=rOprNGfwEbeRWgbNEkqO");
+
+        let server_first = "r=rOprNGfwEbeRWgbNEkqO%hvYDpWUa2RaTCAfuxFIlj)hNlF$k0,s=W22ZaJ0SNY7soEsUEjb6gQ==,i=4096";
+        let authz = client.respond(&challenge(ScramAlgorithm::Sha256, server_first))?;
+        assert_eq!(authz.sid(), Some("AAAABBBBCCCCDDDD"));
+        assert_eq!(
+            authz.data(),
+            "c=biws,r=rOprNGfwEbeRWgbNEkqO%hvYDpWUa2RaTCAfuxFIlj)hNlF$k0,p=dHzbZapWIk4jUhN+Ute9ytag9zjfMHgsqmmiz7AndVQ="
+        );
+
+        client.finish(&info("v=6rriTRBi23WpRR/wtup+mMhUZUn/dB5nLTJRsjl95G4="))?;
+        assert!(client.is_authenticated());
+        Ok(())
+    }
+
+    #[test]
+    fn rfc_5802_example() -> crate::Result<()> {
+        let mut client = ScramClient::new(ScramAlgorithm::Sha1, "user", "pencil");
+        client.nonce = "fyko+d2lbbFgONRv9qkxdawL".into();
+        client.start()?;
+
+        let server_first = "r=fyko+d2lbbFgONRv9qkxdawL3rfcNHYJY1ZVvWVs7j,s=QSXCR+Q6sek8bf92,i=4096";
+        let authz = client.respond(&challenge(ScramAlgorithm::Sha1, server_first))?;
+        assert_eq!(
+            authz.data(),
+            "c=biws,r=fyko+d2lbbFgONRv9qkxdawL3rfcNHYJY1ZVvWVs7j,p=v0X8v3Bz2T0CJGbJQyF0X+HI4Ts="
+        );
+
+        let mut forged = client.clone();
+        let err = forged
+            .finish(&info("v=AAAAAAAAAAAAAAAAAAAAAAAAAAA="))
+            .unwrap_err();
+        assert_eq!(err.status(), 401);
+        assert!(!forged.is_authenticated());
+
+        client.finish(&info("v=rmF9pqV8S7suAoZWja4dJRkFsKQ="))?;
+        assert!(client.is_authenticated());
+        Ok(())
+    }
+
+    #[test]
+    fn rejects_bad_challenges() -> crate::Result<()> {
+        let mut client = ScramClient::new(ScramAlgorithm::Sha256, "user", "pencil");
+        assert!(client
+            .respond(&challenge(ScramAlgorithm::Sha256, ""))
+            .is_err());
+        client.nonce = "abc".into();
+        client.start()?;
+        assert!(client.start().is_err());
+
+        for data in [
+            "r=abc,s=QSXC,i=4096",
+            "r=xyzdef,s=QSXC,i=4096",
+            "r=abcdef,s=QSXC,i=0",
+        ] {
+            let err = client
+                .respond(&challenge(ScramAlgorithm::Sha256, data))
+                .unwrap_err();
+            assert_eq!(err.status(), 400, "{} should be rejected", data);
+        }
+        let err = client
+            .respond(&challenge(ScramAlgorithm::Sha1, "r=abcdef,s=QSXC,i=4096"))
+            .unwrap_err();
+        assert_eq!(err.status(), 400);
+
+        let err = client
+            .respond(&challenge(
+                ScramAlgorithm::Sha256,
+                "r=abcdef,s=QSXC,i=4294967295",
+            ))
+            .unwrap_err();
+        assert_eq!(err.status(), 400);
+        client.set_max_iterations(1000);
+        let err = client
+            .respond(&challenge(ScramAlgorithm::Sha256, "r=abcdef,s=QSXC,i=4096"))
+            .unwrap_err();
+        assert_eq!(err.status(), 400);
+        Ok(())
+    }
+}
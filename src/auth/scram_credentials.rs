@@ -0,0 +1,161 @@
+use crate::auth::ScramAlgorithm;
+use crate::utils::{constant_time_eq, fill_random};
+
+use std::fmt::{self, Debug};
+
+/// The iteration count used for new credentials.
+const DEFAULT_ITERATIONS: u32 = 4096;
+
+/// The salted password of a user, as stored by a SCRAM server.
+///
+/// Servers only store the salt, iteration count and keys derived from the
+/// salted password. The keys are enough to verify a client, but can't be
+/// used to impersonate it.
+///
+/// # Specifications
+///
+/// - [RFC 5802, section 3: SCRAM Algorithm Overview](https://tools.ietf.org/html/rfc5802#section-3)
+///
+/// # Examples
+///
+/// ```
+/// # fn main() -> http_types::Result<()> {
+/// #
+/// use http_types::auth::{ScramAlgorithm, ScramCredentials};
+///
+/// let credentials = ScramCredentials::new(ScramAlgorithm::Sha256, "pencil");
+/// assert_eq!(credentials.iterations(), 4096);
+/// assert!(credentials.verify("pencil"));
+/// assert!(!credentials.verify("crayon"));
+/// #
+/// # Ok(()) }
+/// ```
+#[derive(Clone, Eq, PartialEq)]
+pub struct ScramCredentials {
+    algorithm: ScramAlgorithm,
+    salt: Vec<u8>,
+    iterations: u32,
+    stored_key: Vec<u8>,
+    server_key: Vec<u8>,
+}
+
+impl ScramCredentials {
+    /// Create new credentials for a password, with a random salt and 4096
+    /// iterations.
+    ///
+    /// Passwords are used as given, they are not normalized with SASLprep.
+    pub fn new(algorithm: ScramAlgorithm, password: impl AsRef<str>) -> Self {
+        let mut salt = vec![0; 16];
+        fill_random(&mut salt);
+        Self::derive(algorithm, password.as_ref(), salt, DEFAULT_ITERATIONS)
+    }
+
+    /// Create credentials for a password with a known salt and iteration
+    /// count.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the salt is empty or the iteration count is zero.
+    pub fn with_salt(
+        algorithm: ScramAlgorithm,
+        password: impl AsRef<str>,
+        salt: Vec<u8>,
+        iterations: u32,
+    ) -> crate::Result<Self> {
+        crate::ensure!(!salt.is_empty(), "SCRAM salts must not be empty");
+        crate::ensure!(iterations > 0, "SCRAM iteration counts must be positive");
+        Ok(Self::derive(algorithm, password.as_ref(), salt, iterations))
+    }
+
+    /// Restore credentials from their stored parts.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the salt is empty, the iteration count is zero, or
+    /// the keys don't have the output length of the algorithm's hash.
+    pub fn from_keys(
+        algorithm: ScramAlgorithm,
+        salt: Vec<u8>,
+        iterations: u32,
+        stored_key: Vec<u8>,
+        server_key: Vec<u8>,
+    ) -> crate::Result<Self> {
+        crate::ensure!(!salt.is_empty(), "SCRAM salts must not be empty");
+        crate::ensure!(iterations > 0, "SCRAM iteration counts must be positive");
+        let len = algorithm.hash(&[]).len();
+        crate::ensure!(
+            stored_key.len() == len && server_key.len() == len,
+            "{} keys must be {} bytes long",
+            algorithm,
+            len
+        );
+        Ok(Self {
+            algorithm,
+            salt,
+            iterations,
+            stored_key,
+            server_key,
+        })
+    }
+
+    fn derive(algorithm: ScramAlgorithm, password: &str, salt: Vec<u8>, iterations: u32) -> Self {
+        let salted_password = algorithm.hi(password.as_bytes(), &salt, iterations);
+        let client_key = algorithm.hmac(&salted_password, b"Client Key");
+        Self {
+            algorithm,
+            stored_key: algorithm.hash(&client_key),
+            server_key: algorithm.hmac(&salted_password, b"Server Key"),
+            salt,
+            iterations,
+        }
+    }
+
+    /// Get the algorithm the keys were derived with.
+    pub fn algorithm(&self) -> ScramAlgorithm {
+        self.algorithm
+    }
+
+    /// Get the salt.
+    pub fn salt(&self) -> &[u8] {
+        &self.salt
+    }
+
+    /// Get the iteration count.
+    pub fn iterations(&self) -> u32 {
+        self.iterations
+    }
+
+    /// Get the `StoredKey`, which verifies client proofs.
+    pub fn stored_key(&self) -> &[u8] {
+        &self.stored_key
+    }
+
+    /// Get the `ServerKey`, which signs the server's final message.
+    pub fn server_key(&self) -> &[u8] {
+        &self.server_key
+    }
+
+    /// Check a password against the credentials.
+    ///
+    /// This salts and hashes the password, so it is as expensive as the
+    /// iteration count makes it.
+    pub fn verify(&self, password: impl AsRef<str>) -> bool {
+        let other = Self::derive(
+            self.algorithm,
+            password.as_ref(),
+            self.salt.clone(),
+            self.iterations,
+        );
+        constant_time_eq(&self.stored_key, &other.stored_key)
+    }
+}
+
+impl Debug for ScramCredentials {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ScramCredentials")
+            .field("algorithm", &self.algorithm)
+            .field("salt", &base64::encode(&self.salt))
+            .field("iterations", &self.iterations)
+            .finish()
+    }
+}
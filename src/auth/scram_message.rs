@@ -0,0 +1,153 @@
+//! Encoding and parsing of the messages exchanged by SCRAM.
+//!
+//! <https://tools.ietf.org/html/rfc5802#section-7>
+
+use crate::auth::params::{write_quoted, write_token};
+use crate::auth::{AuthenticationScheme, Challenge, ScramAlgorithm};
+use crate::headers::HeaderValue;
+use crate::parse_utils::is_quotable;
+use crate::utils::fill_random;
+use crate::{bail_status as bail, ensure_status as ensure};
+
+/// Split a message into its `name=value` attributes.
+pub(crate) fn parse_attributes(message: &str) -> crate::Result<Vec<(char, &str)>> {
+    message
+        .split(',')
+        .map(|attribute| {
+            let mut chars = attribute.chars();
+            match (chars.next(), chars.next()) {
+                (Some(name), Some('=')) if name.is_ascii_alphabetic() => {
+                    Ok((name, &attribute[2..]))
+                }
+                _ => bail!(400, "Invalid SCRAM attribute `{}`", attribute),
+            }
+        })
+        .collect()
+}
+
+/// Get the value of a required attribute.
+pub(crate) fn attribute<'a>(attributes: &[(char, &'a str)], name: char) -> crate::Result<&'a str> {
+    match attributes.iter().find(|(key, _)| *key == name) {
+        Some((_, value)) => Ok(value),
+        None => bail!(400, "SCRAM message is missing the `{}` attribute", name),
+    }
+}
+
+/// Decode a base64 attribute value.
+pub(crate) fn decode(value: &str) -> crate::Result<Vec<u8>> {
+    match base64::decode(value) {
+        Ok(bytes) => Ok(bytes),
+        Err(_) => bail!(400, "Invalid base64 in SCRAM message"),
+    }
+}
+
+/// Escape `=` and `,` in a username.
+pub(crate) fn escape_username(username: &str) -> String {
+    username.replace('=', "=3D").replace(',', "=2C")
+}
+
+/// Reverse [`escape_username`].
+pub(crate) fn unescape_username(username: &str) -> crate::Result<String> {
+    let mut output = String::with_capacity(username.len());
+    let mut parts = username.split('=');
+    output.push_str(parts.next().unwrap_or_default());
+    for part in parts {
+        if let Some(rest) = part.strip_prefix("3D") {
+            output.push('=');
+            output.push_str(rest);
+        } else if let Some(rest) = part.strip_prefix("2C") {
+            output.push(',');
+            output.push_str(rest);
+        } else {
+            bail!(400, "Invalid escape sequence in SCRAM username");
+        }
+    }
+    Ok(output)
+}
+
+/// Generate a random nonce.
+pub(crate) fn nonce() -> String {
+    let mut bytes = [0; 18];
+    fill_random(&mut bytes);
+    base64::encode(bytes)
+}
+
+/// Decode the `data` auth-param into a message.
+///
+/// Trailing newlines are ignored, since the examples in RFC 7804 include
+/// them.
+pub(crate) fn decode_data(data: &str) -> crate::Result<String> {
+    match String::from_utf8(decode(data)?) {
+        Ok(message) => Ok(message.trim_end_matches(['\r', '\n']).to_string()),
+        Err(_) => bail!(400, "SCRAM message is not valid UTF-8"),
+    }
+}
+
+/// Parse the SCRAM scheme of a challenge or credentials.
+pub(crate) fn scheme(challenge: &Challenge) -> crate::Result<ScramAlgorithm> {
+    let algorithm = challenge.scheme().and_then(ScramAlgorithm::from_scheme);
+    match algorithm {
+        Some(algorithm) => Ok(algorithm),
+        None => bail!(
+            400,
+            "Expected SCRAM auth scheme found `{}`",
+            challenge.scheme_name()
+        ),
+    }
+}
+
+/// Ensure a realm can be sent as a quoted-string.
+pub(crate) fn validate_realm(realm: &Option<String>) -> crate::Result<()> {
+    if let Some(realm) = realm {
+        ensure!(is_quotable(realm), 400, "Invalid SCRAM realm");
+    }
+    Ok(())
+}
+
+/// Format the auth-params shared by all SCRAM headers.
+pub(crate) fn header_value(
+    scheme: Option<AuthenticationScheme>,
+    realm: Option<&str>,
+    sid: Option<&str>,
+    data: Option<&str>,
+) -> HeaderValue {
+    let mut output = match scheme {
+        Some(scheme) => format!("{} ", scheme),
+        None => String::new(),
+    };
+    if let Some(realm) = realm {
+        write_quoted(&mut output, "realm", realm);
+    }
+    if let Some(sid) = sid {
+        write_token(&mut output, "sid", sid);
+    }
+    if let Some(data) = data {
+        write_quoted(&mut output, "data", &base64::encode(data));
+    }
+
+    // SAFETY: the internal string is validated to be ASCII.
+    unsafe { HeaderValue::from_bytes_unchecked(output.trim_end().to_string().into()) }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn username() -> crate::Result<()> {
+        let escaped = escape_username("a=b,c");
+        assert_eq!(escaped, "a=3Db=2Cc");
+        assert_eq!(unescape_username(&escaped)?, "a=b,c");
+        assert!(unescape_username("a=b").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn attributes() -> crate::Result<()> {
+        let attributes = parse_attributes("r=abc,s=QSXCR+Q6sek8bf92,i=4096")?;
+        assert_eq!(attribute(&attributes, 's')?, "QSXCR+Q6sek8bf92");
+        assert!(attribute(&attributes, 'p').is_err());
+        assert!(parse_attributes("r=abc,,i=1").is_err());
+        Ok(())
+    }
+}
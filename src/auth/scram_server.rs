@@ -0,0 +1,347 @@
+use crate::auth::scram_message::{attribute, decode, nonce, parse_attributes, unescape_username};
+use crate::auth::{
+    ScramAlgorithm, ScramAuth, ScramAuthenticationInfo, ScramChallenge, ScramCredentials,
+};
+use crate::utils::{constant_time_eq, fill_random, hex};
+use crate::{bail_status as bail, ensure_status as ensure};
+
+/// The server side of a SCRAM exchange.
+///
+/// [`ScramServer::start`] reads the client's first message, after which the
+/// server looks up the credentials of the [`username`](ScramServer::username)
+/// and answers with [`ScramServer::challenge`]. HTTP requests are independent
+/// of each other, so the server keeps the `ScramServer` until the client's
+/// final message arrives with the same [`sid`](ScramServer::sid), and then
+/// verifies it with [`ScramServer::finish`].
+///
+/// Servers should challenge unknown users with made up credentials, to avoid
+/// revealing which users exist.
+///
+/// See [`ScramClient`](crate::auth::ScramClient) for an example of a full
+/// exchange.
+///
+/// # Specifications
+///
+/// - [RFC 7804: Salted Challenge Response HTTP Authentication Mechanism](https://tools.ietf.org/html/rfc7804)
+#[derive(Debug, Clone)]
+pub struct ScramServer {
+    algorithm: ScramAlgorithm,
+    realm: Option<String>,
+    username: String,
+    gs2_header: String,
+    client_first_bare: String,
+    nonce: String,
+    state: State,
+}
+
+#[derive(Debug, Clone)]
+enum State {
+    Started,
+    Challenged {
+        sid: String,
+        server_first: String,
+        credentials: ScramCredentials,
+    },
+    Authenticated {
+        sid: String,
+    },
+}
+
+impl ScramServer {
+    /// Start an exchange from the client's first message.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `400 Bad Request` error if the message is malformed, or
+    /// requests channel binding or an authorization identity.
+    pub fn start(auth: &ScramAuth) -> crate::Result<Self> {
+        ensure!(
+            auth.sid.is_none(),
+            400,
+            "SCRAM client first message must not contain a sid"
+        );
+        let mut parts = auth.data.splitn(3, ',');
+        let (flag, authzid, client_first_bare) = match (parts.next(), parts.next(), parts.next()) {
+            (Some(flag), Some(authzid), Some(bare)) => (flag, authzid, bare),
+            _ => bail!(400, "Invalid SCRAM client first message"),
+        };
+        ensure!(
+            flag == "n" || flag == "y",
+            400,
+            "SCRAM channel binding is not supported"
+        );
+        ensure!(
+            authzid.is_empty(),
+            400,
+            "SCRAM authorization identities are not supported"
+        );
+
+        let attributes = parse_attributes(client_first_bare)?;
+        ensure!(
+            attributes.first().map(|(name, _)| *name) == Some('n'),
+            400,
+            "SCRAM client first message must start with the username"
+        );
+        let username = unescape_username(attribute(&attributes, 'n')?)?;
+        let nonce = attribute(&attributes, 'r')?;
+        ensure!(
+            !nonce.is_empty() && nonce.bytes().all(|b| b.is_ascii_graphic() && b != b','),
+            400,
+            "Invalid SCRAM client nonce"
+        );
+
+        Ok(Self {
+            algorithm: auth.algorithm,
+            realm: auth.realm.clone(),
+            username,
+            gs2_header: format!("{},,", flag),
+            client_first_bare: client_first_bare.to_string(),
+            nonce: nonce.to_string(),
+            state: State::Started,
+        })
+    }
+
+    /// Get the algorithm.
+    pub fn algorithm(&self) -> ScramAlgorithm {
+        self.algorithm
+    }
+
+    /// Get the realm the client asked to authenticate in.
+    pub fn realm(&self) -> Option<&str> {
+        self.realm.as_deref()
+    }
+
+    /// Get the username.
+    pub fn username(&self) -> &str {
+        &self.username
+    }
+
+    /// Get the session id, once the client has been challenged.
+    pub fn sid(&self) -> Option<&str> {
+        match &self.state {
+            State::Started => None,
+            State::Challenged { sid, .. } | State::Authenticated { sid } => Some(sid),
+        }
+    }
+
+    /// Returns `true` if the client's proof has been verified.
+    pub fn is_authenticated(&self) -> bool {
+        matches!(self.state, State::Authenticated { .. })
+    }
+
+    /// Challenge the client with the salt and iteration count of the user's
+    /// credentials.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the client has already been challenged, or the
+    /// credentials were derived with another algorithm.
+    pub fn challenge(&mut self, credentials: &ScramCredentials) -> crate::Result<ScramChallenge> {
+        let mut bytes = [0; 12];
+        fill_random(&mut bytes);
+        self.challenge_with(credentials, nonce(), hex(&bytes))
+    }
+
+    fn challenge_with(
+        &mut self,
+        credentials: &ScramCredentials,
+        server_nonce: String,
+        sid: String,
+    ) -> crate::Result<ScramChallenge> {
+        crate::ensure!(
+            matches!(self.state, State::Started),
+            "SCRAM client has already been challenged"
+        );
+        crate::ensure!(
+            credentials.algorithm() == self.algorithm,
+            "Expected {} credentials",
+            self.algorithm
+        );
+        self.nonce.push_str(&server_nonce);
+        let server_first = format!(
+            "r={},s={},i={}",
+            self.nonce,
+            base64::encode(credentials.salt()),
+            credentials.iterations()
+        );
+        self.state = State::Challenged {
+            sid: sid.clone(),
+            server_first: server_first.clone(),
+            credentials: credentials.clone(),
+        };
+        Ok(ScramChallenge {
+            algorithm: self.algorithm,
+            realm: self.realm.clone(),
+            sid: Some(sid),
+            data: Some(server_first),
+        })
+    }
+
+    /// Verify the client's final message, returning the server's final
+    /// message.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `401 Unauthorized` error if the client's proof doesn't
+    /// match, and a `400 Bad Request` error if the message is malformed or
+    /// doesn't continue this exchange.
+    pub fn finish(&mut self, auth: &ScramAuth) -> crate::Result<ScramAuthenticationInfo> {
+        let (sid, server_first, credentials) = match &self.state {
+            State::Challenged {
+                sid,
+                server_first,
+                credentials,
+            } => (sid, server_first, credentials),
+            _ => crate::bail!("SCRAM client has not been challenged"),
+        };
+        ensure!(
+            auth.algorithm == self.algorithm,
+            400,
+            "Expected {} credentials",
+            self.algorithm
+        );
+        ensure!(
+            auth.sid.as_deref() == Some(sid.as_str()),
+            400,
+            "SCRAM session id does not match"
+        );
+
+        let (client_final_without_proof, proof) = match auth.data.rsplit_once(",p=") {
+            Some((message, proof)) => (message, decode(proof)?),
+            None => bail!(400, "SCRAM client final message must contain a proof"),
+        };
+        let attributes = parse_attributes(client_final_without_proof)?;
+        ensure!(
+            decode(attribute(&attributes, 'c')?)? == self.gs2_header.as_bytes(),
+            400,
+            "SCRAM channel binding does not match"
+        );
+        ensure!(
+            attribute(&attributes, 'r')? == self.nonce,
+            400,
+            "SCRAM nonce does not match"
+        );
+
+        let auth_message = format!(
+            "{},{},{}",
+            self.client_first_bare, server_first, client_final_without_proof
+        );
+        let algorithm = self.algorithm;
+        let client_signature = algorithm.hmac(credentials.stored_key(), auth_message.as_bytes());
+        ensure!(
+            proof.len() == client_signature.len(),
+            401,
+            "SCRAM client proof does not match"
+        );
+        let client_key: Vec<u8> = proof
+            .iter()
+            .zip(&client_signature)
+            .map(|(a, b)| a ^ b)
+            .collect();
+        ensure!(
+            constant_time_eq(&algorithm.hash(&client_key), credentials.stored_key()),
+            401,
+            "SCRAM client proof does not match"
+        );
+
+        let server_signature = algorithm.hmac(credentials.server_key(), auth_message.as_bytes());
+        let info = ScramAuthenticationInfo {
+            sid: sid.clone(),
+            data: format!("v={}", base64::encode(server_signature)),
+        };
+        self.state = State::Authenticated { sid: sid.clone() };
+        Ok(info)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::headers::{Header, Headers, AUTHENTICATION_INFO, AUTHORIZATION, WWW_AUTHENTICATE};
+
+    fn credentials() -> ScramCredentials {
+        let salt = base64::decode("W22ZaJ0SNY7soEsUEjb6gQ==").unwrap();
+        ScramCredentials::with_salt(ScramAlgorithm::Sha256, "pencil", salt, 4096).unwrap()
+    }
+
+    /// The exchange from RFC 7804, section 5, with the trailing newlines
+    /// of its messages.
+    #[test]
+    fn rfc_7804_example() -> crate::Result<()> {
+        let mut headers = Headers::new();
+        headers.insert(
+            AUTHORIZATION,
+            r#"SCRAM-SHA-256 realm="testrealm@example.com", data=biwsbj11c2VyLHI9ck9wck5HZndFYmVSV2diTkVrcU8K"#,
+        )?;
+        let authz = ScramAuth::from_headers(&headers)?.unwrap();
+        assert_eq!(authz.realm(), Some("testrealm@example.com"));
+        let mut server = ScramServer::start(&authz)?;
+        assert_eq!(server.username(), "user");
+
+        let challenge = server.challenge_with(
+            &credentials(),
+            "%hvYDpWUa2RaTCAfuxFIlj)hNlF$k0".into(),
+            "AAAABBBBCCCCDDDD".into(),
+        )?;
+        let mut headers = Headers::new();
+        headers.insert(
+            WWW_AUTHENTICATE,
+            "SCRAM-SHA-256 sid=AAAABBBBCCCCDDDD, data=cj1yT3ByTkdmd0ViZVJXZ2JORWtxTyVodllEcFdVYTJSYVRDQWZ1eEZJbGopaE5sRiRrMCxzPVcyMlphSjBTTlk3c29Fc1VFamI2Z1E9PSxpPTQwOTYK",
+        )?;
+        let expected = ScramChallenge::from_headers(&headers)?.unwrap();
+        assert_eq!(challenge.sid(), expected.sid());
+        assert_eq!(challenge.data(), expected.data());
+
+        let mut headers = Headers::new();
+        headers.insert(
+            AUTHORIZATION,
+            "SCRAM-SHA-256 sid=AAAABBBBCCCCDDDD, data=Yz1iaXdzLHI9ck9wck5HZndFYmVSV2diTkVrcU8laHZZRHBXVWEyUmFUQ0FmdXhGSWxqKWhObEYkazAscD1kSHpiWmFwV0lrNGpVaE4rVXRlOXl0YWc5empmTUhnc3FtbWl6N0FuZFZRPQo=",
+        )?;
+        let info = server.finish(&ScramAuth::from_headers(&headers)?.unwrap())?;
+        assert!(server.is_authenticated());
+        assert_eq!(
+            info.header_value(),
+            r#"sid=AAAABBBBCCCCDDDD, data="dj02cnJpVFJCaTIzV3BSUi93dHVwK21NaFVaVW4vZEI1bkxUSlJzamw5NUc0PQ==""#
+        );
+
+        let mut headers = Headers::new();
+        headers.insert(
+            AUTHENTICATION_INFO,
+            "sid=AAAABBBBCCCCDDDD, data=dj02cnJpVFJCaTIzV3BSUi93dHVwK21NaFVaVW4vZEI1bkxUSlJzamw5NUc0PQo=",
+        )?;
+        let expected = ScramAuthenticationInfo::from_headers(headers)?.unwrap();
+        assert_eq!(info, expected);
+        Ok(())
+    }
+
+    #[test]
+    fn wrong_password() -> crate::Result<()> {
+        let mut client = crate::auth::ScramClient::new(ScramAlgorithm::Sha256, "user", "crayon");
+        let mut server = ScramServer::start(&client.start()?)?;
+        let challenge = server.challenge(&credentials())?;
+        let err = server.finish(&client.respond(&challenge)?).unwrap_err();
+        assert_eq!(err.status(), 401);
+        assert!(!server.is_authenticated());
+        Ok(())
+    }
+
+    #[test]
+    fn bad_request_on_parse_error() {
+        let auth = |data: &str| ScramAuth {
+            algorithm: ScramAlgorithm::Sha256,
+            realm: None,
+            sid: None,
+            data: data.into(),
+        };
+        for data in [
+            "n=user,r=abc",
+            "p=tls-unique,,n=user,r=abc",
+            "n,a=admin,n=user,r=abc",
+            "n,,r=abc,n=user",
+            "n,,n=us=er,r=abc",
+        ] {
+            let err = ScramServer::start(&auth(data)).unwrap_err();
+            assert_eq!(err.status(), 400, "{} should not parse", data);
+        }
+    }
+}
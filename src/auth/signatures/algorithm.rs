@@ -0,0 +1,68 @@
+use hmac::Hmac;
+use sha2::Sha256;
+
+use crate::utils::{constant_time_eq, hmac};
+
+use std::fmt::{self, Debug};
+
+/// An algorithm used to create and verify message signatures.
+///
+/// Implement this trait to sign with keys held elsewhere, or with asymmetric
+/// algorithms such as `ed25519` or `ecdsa-p256-sha256`.
+///
+/// # Specifications
+///
+/// - [RFC 9421, section 3.3: Signature Algorithms](https://www.rfc-editor.org/rfc/rfc9421#section-3.3)
+pub trait SignatureAlgorithm {
+    /// The name of the algorithm, as used in the `alg` signature parameter.
+    fn name(&self) -> &str;
+
+    /// Sign the signature base.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the algorithm can only verify signatures, or the
+    /// key could not be used.
+    fn sign(&self, base: &[u8]) -> crate::Result<Vec<u8>>;
+
+    /// Returns `true` if `signature` is a valid signature of the signature
+    /// base.
+    fn verify(&self, base: &[u8], signature: &[u8]) -> bool;
+}
+
+/// The `hmac-sha256` signature algorithm, using a shared secret.
+///
+/// # Specifications
+///
+/// - [RFC 9421, section 3.3.3: HMAC Using SHA-256](https://www.rfc-editor.org/rfc/rfc9421#section-3.3.3)
+#[derive(Clone)]
+pub struct HmacSha256 {
+    key: Vec<u8>,
+}
+
+impl HmacSha256 {
+    /// Create a new instance of `HmacSha256` from a shared secret.
+    pub fn new(key: impl Into<Vec<u8>>) -> Self {
+        Self { key: key.into() }
+    }
+}
+
+impl SignatureAlgorithm for HmacSha256 {
+    fn name(&self) -> &str {
+        "hmac-sha256"
+    }
+
+    fn sign(&self, base: &[u8]) -> crate::Result<Vec<u8>> {
+        Ok(hmac::<Hmac<Sha256>>(&self.key, base))
+    }
+
+    fn verify(&self, base: &[u8], signature: &[u8]) -> bool {
+        constant_time_eq(&hmac::<Hmac<Sha256>>(&self.key, base), signature)
+    }
+}
+
+impl Debug for HmacSha256 {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("HmacSha256").finish()
+    }
+}
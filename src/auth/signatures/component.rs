@@ -0,0 +1,209 @@
+use super::Message;
+use crate::headers::HeaderName;
+use crate::sf::{self, Params};
+use crate::{bail_status as bail, ensure_status as ensure};
+
+use std::fmt::{self, Display};
+
+/// A message component covered by a signature.
+///
+/// Components are either derived from the message control data, such as the
+/// method or the target URI, or the value of a header field.
+///
+/// Component parameters such as `;sf`, `;bs` or `;req` are not supported.
+///
+/// # Specifications
+///
+/// - [RFC 9421, section 2: HTTP Message Components](https://www.rfc-editor.org/rfc/rfc9421#section-2)
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum Component {
+    /// `@method`, the method of a request.
+    Method,
+    /// `@target-uri`, the full target URI of a request.
+    TargetUri,
+    /// `@authority`, the host and port of a request's target URI.
+    Authority,
+    /// `@scheme`, the scheme of a request's target URI.
+    Scheme,
+    /// `@request-target`, the path and query of a request's target URI.
+    RequestTarget,
+    /// `@path`, the path of a request's target URI.
+    Path,
+    /// `@query`, the query of a request's target URI, including the leading
+    /// `?`.
+    Query,
+    /// `@status`, the status code of a response.
+    Status,
+    /// The value of a header field.
+    Field(HeaderName),
+}
+
+impl Component {
+    /// Get the component identifier, without quotes.
+    pub fn name(&self) -> &str {
+        match self {
+            Component::Method => "@method",
+            Component::TargetUri => "@target-uri",
+            Component::Authority => "@authority",
+            Component::Scheme => "@scheme",
+            Component::RequestTarget => "@request-target",
+            Component::Path => "@path",
+            Component::Query => "@query",
+            Component::Status => "@status",
+            Component::Field(name) => name.as_str(),
+        }
+    }
+
+    /// Parse a component identifier with its parameters.
+    pub(super) fn parse(name: String, params: Params) -> crate::Result<Self> {
+        ensure!(
+            params.is_empty(),
+            400,
+            "Unsupported parameters for signature component `{}`",
+            name
+        );
+        let component = match name.as_str() {
+            "@method" => Component::Method,
+            "@target-uri" => Component::TargetUri,
+            "@authority" => Component::Authority,
+            "@scheme" => Component::Scheme,
+            "@request-target" => Component::RequestTarget,
+            "@path" => Component::Path,
+            "@query" => Component::Query,
+            "@status" => Component::Status,
+            _ => {
+                ensure!(
+                    !name.is_empty()
+                        && !name.starts_with('@')
+                        && name.bytes().all(|b| b.is_ascii_graphic())
+                        && !name.bytes().any(|b| b.is_ascii_uppercase()),
+                    400,
+                    "Invalid signature component `{}`",
+                    name
+                );
+                Component::Field(HeaderName::from_string(name)?)
+            }
+        };
+        Ok(component)
+    }
+
+    /// Get the value of the component in `message`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the component does not exist in the message.
+    pub fn value<'a>(&self, message: impl Into<Message<'a>>) -> crate::Result<String> {
+        let message = message.into();
+        let value = match (self, message) {
+            (Component::Field(name), message) => {
+                let values = match message.headers().get(name) {
+                    Some(values) => values,
+                    None => bail!(400, "Signature covers missing header `{}`", name),
+                };
+                let values: Vec<_> = values.iter().map(|value| value.as_str().trim()).collect();
+                values.join(", ")
+            }
+            (Component::Status, Message::Response(res)) => format!("{:03}", res.status() as u16),
+            (Component::Status, Message::Request(_)) => {
+                bail!(400, "`@status` can only be used with responses")
+            }
+            (_, Message::Response(_)) => {
+                bail!(400, "`{}` can only be used with requests", self.name())
+            }
+            (Component::Method, Message::Request(req)) => req.method().to_string(),
+            (Component::TargetUri, Message::Request(req)) => req.url().to_string(),
+            (Component::Authority, Message::Request(req)) => {
+                let url = req.url();
+                let host = url.host_str().unwrap_or_default().to_ascii_lowercase();
+                match url.port() {
+                    Some(port) => format!("{}:{}", host, port),
+                    None => host,
+                }
+            }
+            (Component::Scheme, Message::Request(req)) => req.url().scheme().to_string(),
+            (Component::RequestTarget, Message::Request(req)) => match req.url().query() {
+                Some(query) => format!("{}?{}", req.url().path(), query),
+                None => req.url().path().to_string(),
+            },
+            (Component::Path, Message::Request(req)) => match req.url().path() {
+                "" => String::from("/"),
+                path => path.to_string(),
+            },
+            (Component::Query, Message::Request(req)) => {
+                format!("?{}", req.url().query().unwrap_or_default())
+            }
+        };
+        Ok(value)
+    }
+}
+
+impl Display for Component {
+    /// Writes the component identifier as a structured field string.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        sf::write_string(f, self.name())
+    }
+}
+
+impl From<HeaderName> for Component {
+    fn from(name: HeaderName) -> Self {
+        Component::Field(name)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::headers::CONTENT_TYPE;
+    use crate::{Request, Response};
+
+    #[test]
+    fn derived_components() -> crate::Result<()> {
+        let mut req = Request::post("https://www.Example.com:8443/path/to?param=value&foo");
+        req.append_header("X-Empty", "")?;
+        req.append_header("X-Multi", " a ")?;
+        req.append_header("X-Multi", "b")?;
+
+        let values = [
+            (Component::Method, "POST"),
+            (
+                Component::TargetUri,
+                "https://www.example.com:8443/path/to?param=value&foo",
+            ),
+            (Component::Authority, "www.example.com:8443"),
+            (Component::Scheme, "https"),
+            (Component::RequestTarget, "/path/to?param=value&foo"),
+            (Component::Path, "/path/to"),
+            (Component::Query, "?param=value&foo"),
+            (Component::Field("x-empty".into()), ""),
+            (Component::Field("x-multi".into()), "a, b"),
+        ];
+        for (component, value) in values {
+            assert_eq!(component.value(&req)?, value, "{}", component);
+        }
+        assert_eq!(Component::Status.value(&req).unwrap_err().status(), 400);
+        assert_eq!(
+            Component::from(CONTENT_TYPE)
+                .value(&req)
+                .unwrap_err()
+                .status(),
+            400
+        );
+
+        let res = Response::new(200);
+        assert_eq!(Component::Status.value(&res)?, "200");
+        assert_eq!(Component::Method.value(&res).unwrap_err().status(), 400);
+        Ok(())
+    }
+
+    #[test]
+    fn parse() {
+        let component = Component::parse("content-type".into(), vec![]).unwrap();
+        assert_eq!(component, Component::Field(CONTENT_TYPE));
+        assert_eq!(component.to_string(), r#""content-type""#);
+
+        assert!(Component::parse("Content-Type".into(), vec![]).is_err());
+        assert!(Component::parse("@unknown".into(), vec![]).is_err());
+        let params = vec![("sf".into(), sf::Item::Boolean(true))];
+        assert!(Component::parse("x-dictionary".into(), params).is_err());
+    }
+}
@@ -0,0 +1,36 @@
+use crate::headers::Headers;
+use crate::{Request, Response};
+
+/// An HTTP message which can be signed or verified.
+///
+/// This is usually created from a reference to a [`Request`] or
+/// [`Response`] through `Into`.
+#[derive(Debug, Clone, Copy)]
+pub enum Message<'a> {
+    /// A request.
+    Request(&'a Request),
+    /// A response.
+    Response(&'a Response),
+}
+
+impl<'a> Message<'a> {
+    /// Get the headers of the message.
+    pub fn headers(&self) -> &'a Headers {
+        match *self {
+            Message::Request(req) => req.as_ref(),
+            Message::Response(res) => res.as_ref(),
+        }
+    }
+}
+
+impl<'a> From<&'a Request> for Message<'a> {
+    fn from(req: &'a Request) -> Self {
+        Message::Request(req)
+    }
+}
+
+impl<'a> From<&'a Response> for Message<'a> {
+    fn from(res: &'a Response) -> Self {
+        Message::Response(res)
+    }
+}
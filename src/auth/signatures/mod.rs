@@ -0,0 +1,180 @@
+//! HTTP Message Signatures.
+//!
+//! Signatures cover a chosen set of message components, such as the method,
+//! the target URI or header fields, so they survive intermediaries which
+//! reformat the parts of a message they do not cover. A message can carry
+//! several signatures, each identified by a label in the `Signature-Input`
+//! and `Signature` headers.
+//!
+//! # Specifications
+//!
+//! - [RFC 9421: HTTP Message Signatures](https://www.rfc-editor.org/rfc/rfc9421)
+//!
+//! # Examples
+//!
+//! ```
+//! # fn main() -> http_types::Result<()> {
+//! #
+//! use http_types::auth::signatures::{
+//!     self, Component, HmacSha256, Signature, SignatureInput, SignatureParams,
+//! };
+//! use http_types::headers::CONTENT_TYPE;
+//! use http_types::Request;
+//! use std::time::SystemTime;
+//!
+//! let key = HmacSha256::new("shared secret");
+//! let now = SystemTime::now();
+//!
+//! let mut req = Request::post("https://example.com/webhook");
+//! req.insert_header("Content-Type", "application/json");
+//!
+//! let mut params = SignatureParams::new(vec![
+//!     Component::Method,
+//!     Component::TargetUri,
+//!     Component::Field(CONTENT_TYPE),
+//! ]);
+//! params.set_created(Some(now));
+//! params.set_keyid(Some("webhooks".into()))?;
+//!
+//! let mut signature = Signature::new();
+//! signature.insert("sig1", params.sign(&req, &key)?)?;
+//! let mut input = SignatureInput::new();
+//! input.insert("sig1", params)?;
+//! req.insert_header(&input, &input);
+//! req.insert_header(&signature, &signature);
+//!
+//! let params = signatures::verify(&req, "sig1", &key, now)?;
+//! assert_eq!(params.keyid(), Some("webhooks"));
+//! #
+//! # Ok(()) }
+//! ```
+
+mod algorithm;
+mod component;
+mod message;
+mod signature;
+mod signature_input;
+mod signature_params;
+mod verify;
+
+pub use algorithm::{HmacSha256, SignatureAlgorithm};
+pub use component::Component;
+pub use message::Message;
+pub use signature::Signature;
+pub use signature_input::SignatureInput;
+pub use signature_params::SignatureParams;
+pub use verify::verify;
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::headers::{CONTENT_TYPE, DATE};
+    use crate::{Request, Response};
+
+    use std::time::{Duration, UNIX_EPOCH};
+
+    // The test request and shared secret from RFC 9421, appendix B.
+    fn test_request() -> crate::Result<Request> {
+        let mut req = Request::post("https://example.com/foo?param=Value&Pet=dog");
+        req.insert_header(DATE, "Tue, 20 Apr 2021 02:07:55 GMT")?;
+        req.insert_header(CONTENT_TYPE, "application/json")?;
+        Ok(req)
+    }
+
+    fn test_key() -> HmacSha256 {
+        let key = base64::decode(
+            "uzvJfB4u3N0Jy4T7NZ75MDVcr8zSTInedJtkgcu46YW4XByzNJjxBdtjUkdJPBtbmHhIDi6pcl8jsasjlTMtDQ==",
+        )
+        .unwrap();
+        HmacSha256::new(key)
+    }
+
+    #[test]
+    fn hmac_sha256_test_vector() -> crate::Result<()> {
+        let mut req = test_request()?;
+        req.insert_header(
+            "Signature-Input",
+            r#"sig-b25=("date" "@authority" "content-type");created=1618884473;keyid="test-shared-secret""#,
+        )?;
+        req.insert_header(
+            "Signature",
+            "sig-b25=:pxcQw6G3AjtMBQjwo8XzkZf/bws5LelbaMk5rGIGtE8=:",
+        )?;
+
+        let now = UNIX_EPOCH + Duration::from_secs(1618884480);
+        let params = verify(&req, "sig-b25", &test_key(), now)?;
+        assert_eq!(params.keyid(), Some("test-shared-secret"));
+        assert_eq!(params.age(now), Some(Duration::from_secs(7)));
+
+        let (params, _) = SignatureParams::parse(&req["Signature-Input"].as_str()[8..])?;
+        let signature = Signature::from_headers(&req)?.unwrap();
+        assert_eq!(
+            params.sign(&req, &test_key())?,
+            signature.get("sig-b25").unwrap()
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn verification_errors() -> crate::Result<()> {
+        let created = UNIX_EPOCH + Duration::from_secs(1618884473);
+        let mut params = SignatureParams::new(vec![Component::Method, Component::Field(DATE)]);
+        params.set_created(Some(created));
+        params.set_expires(Some(created + Duration::from_secs(60)));
+        params.set_alg(Some("hmac-sha256".into()))?;
+
+        let mut req = test_request()?;
+        let mut signature = Signature::new();
+        signature.insert("sig", params.sign(&req, &test_key())?)?;
+        let mut input = SignatureInput::new();
+        input.insert("sig", params)?;
+        req.insert_header(&input, &input)?;
+        req.insert_header(&signature, &signature)?;
+
+        let now = created + Duration::from_secs(30);
+        assert!(verify(&req, "sig", &test_key(), now).is_ok());
+
+        let status = |req: &Request, label, key: &dyn SignatureAlgorithm, now| {
+            verify(req, label, key, now).unwrap_err().status()
+        };
+        assert_eq!(status(&req, "other", &test_key(), now), 401);
+        assert_eq!(status(&req, "sig", &HmacSha256::new("wrong"), now), 401);
+        assert_eq!(
+            status(&req, "sig", &test_key(), created + Duration::from_secs(60)),
+            401
+        );
+        assert_eq!(
+            status(&req, "sig", &test_key(), created - Duration::from_secs(1)),
+            401
+        );
+
+        let mut tampered = req.clone();
+        tampered.insert_header(DATE, "Wed, 21 Apr 2021 02:07:55 GMT")?;
+        assert_eq!(status(&tampered, "sig", &test_key(), now), 401);
+
+        let mut tampered = req;
+        tampered.remove_header(DATE);
+        assert_eq!(status(&tampered, "sig", &test_key(), now), 400);
+        Ok(())
+    }
+
+    #[test]
+    fn sign_response() -> crate::Result<()> {
+        let mut res = Response::new(503);
+        res.insert_header(CONTENT_TYPE, "text/plain")?;
+        let params = SignatureParams::new(vec![Component::Status, Component::Field(CONTENT_TYPE)]);
+        assert_eq!(
+            params.signature_base(&res)?,
+            "\"@status\": 503\n\"content-type\": text/plain\n\"@signature-params\": (\"@status\" \"content-type\")"
+        );
+
+        let mut signature = Signature::new();
+        signature.insert("res", params.sign(&res, &test_key())?)?;
+        let mut input = SignatureInput::new();
+        input.insert("res", params)?;
+        res.insert_header(&input, &input)?;
+        res.insert_header(&signature, &signature)?;
+        verify(&res, "res", &test_key(), UNIX_EPOCH)?;
+        Ok(())
+    }
+}
@@ -0,0 +1,145 @@
+use crate::headers::{Header, HeaderName, HeaderValue, Headers, SIGNATURE};
+use crate::sf::{self, Item};
+use crate::{bail_status as bail, ensure_status as ensure};
+
+/// The signatures of a message, by label.
+///
+/// # Specifications
+///
+/// - [RFC 9421, section 4.2: The Signature HTTP Field](https://www.rfc-editor.org/rfc/rfc9421#section-4.2)
+///
+/// # Examples
+///
+/// ```
+/// # fn main() -> http_types::Result<()> {
+/// #
+/// use http_types::auth::signatures::Signature;
+/// use http_types::Response;
+///
+/// let mut signature = Signature::new();
+/// signature.insert("sig1", b"hello".to_vec())?;
+///
+/// let mut res = Response::new(200);
+/// res.insert_header(&signature, &signature);
+/// assert_eq!(res["Signature"], "sig1=:aGVsbG8=:");
+///
+/// let signature = Signature::from_headers(res)?.unwrap();
+/// assert_eq!(signature.get("sig1"), Some(&b"hello"[..]));
+/// #
+/// # Ok(()) }
+/// ```
+#[derive(Debug, Clone, Default, Eq, PartialEq)]
+pub struct Signature {
+    entries: Vec<(String, Vec<u8>)>,
+}
+
+impl Signature {
+    /// Create a new instance of `Signature`.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create a new instance from headers.
+    pub fn from_headers(headers: impl AsRef<Headers>) -> crate::Result<Option<Self>> {
+        let headers = match headers.as_ref().get(SIGNATURE) {
+            Some(headers) => headers,
+            None => return Ok(None),
+        };
+        let values: Vec<_> = headers.iter().map(HeaderValue::as_str).collect();
+        let entries = sf::parse_dictionary(&values.join(", "), |input| {
+            let (signature, rest) = match sf::parse_bare_item(input)? {
+                (Item::Bytes(signature), rest) => (signature, rest),
+                _ => bail!(400, "Signatures must be byte sequences"),
+            };
+            // Parameters on signatures have no defined meaning.
+            let (_, rest) = sf::parse_params(rest)?;
+            Ok((signature, rest))
+        })?;
+        Ok(Some(Self { entries }))
+    }
+
+    /// Get the signature labeled `label`.
+    pub fn get(&self, label: &str) -> Option<&[u8]> {
+        self.entries
+            .iter()
+            .find(|(name, _)| name == label)
+            .map(|(_, signature)| signature.as_slice())
+    }
+
+    /// Insert the signature labeled `label`, replacing any existing signature
+    /// with the same label.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the label is not a valid structured field key.
+    pub fn insert(&mut self, label: impl Into<String>, signature: Vec<u8>) -> crate::Result<()> {
+        let label = label.into();
+        ensure!(
+            sf::is_key(&label),
+            400,
+            "Invalid signature label `{}`",
+            label
+        );
+        match self.entries.iter_mut().find(|(name, _)| *name == label) {
+            Some((_, existing)) => *existing = signature,
+            None => self.entries.push((label, signature)),
+        }
+        Ok(())
+    }
+
+    /// Remove the signature labeled `label`.
+    pub fn remove(&mut self, label: &str) -> Option<Vec<u8>> {
+        let index = self.entries.iter().position(|(name, _)| name == label)?;
+        Some(self.entries.remove(index).1)
+    }
+
+    /// An iterator visiting all signature labels and their signatures.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &[u8])> {
+        self.entries
+            .iter()
+            .map(|(label, signature)| (label.as_str(), signature.as_slice()))
+    }
+
+    /// Returns `true` if there are no signatures.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+impl Header for Signature {
+    fn header_name(&self) -> HeaderName {
+        SIGNATURE
+    }
+
+    fn header_value(&self) -> HeaderValue {
+        let entries: Vec<_> = self
+            .entries
+            .iter()
+            .map(|(label, signature)| format!("{}=:{}:", label, base64::encode(signature)))
+            .collect();
+        let output = entries.join(", ");
+
+        // SAFETY: the internal string is validated to be ASCII.
+        unsafe { HeaderValue::from_bytes_unchecked(output.into()) }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn bad_request_on_parse_error() {
+        for input in [
+            "sig1=aGVsbG8=",
+            "sig1=:not base64:",
+            "sig1=:aGVsbG8=",
+            "sig1",
+        ] {
+            let mut headers = Headers::new();
+            headers.insert(SIGNATURE, input).unwrap();
+            let err = Signature::from_headers(headers).unwrap_err();
+            assert_eq!(err.status(), 400, "{} should not parse", input);
+        }
+    }
+}
@@ -0,0 +1,162 @@
+use super::SignatureParams;
+use crate::ensure_status as ensure;
+use crate::headers::{Header, HeaderName, HeaderValue, Headers, SIGNATURE_INPUT};
+use crate::sf;
+
+/// The parameters of the signatures in a message, by label.
+///
+/// # Specifications
+///
+/// - [RFC 9421, section 4.1: The Signature-Input HTTP Field](https://www.rfc-editor.org/rfc/rfc9421#section-4.1)
+///
+/// # Examples
+///
+/// ```
+/// # fn main() -> http_types::Result<()> {
+/// #
+/// use http_types::auth::signatures::{Component, SignatureInput, SignatureParams};
+/// use http_types::Response;
+///
+/// let mut input = SignatureInput::new();
+/// input.insert("sig1", SignatureParams::new(vec![Component::Status]))?;
+///
+/// let mut res = Response::new(200);
+/// res.insert_header(&input, &input);
+/// assert_eq!(res["Signature-Input"], r#"sig1=("@status")"#);
+///
+/// let input = SignatureInput::from_headers(res)?.unwrap();
+/// assert_eq!(input.get("sig1").unwrap().components(), [Component::Status]);
+/// #
+/// # Ok(()) }
+/// ```
+#[derive(Debug, Clone, Default, Eq, PartialEq)]
+pub struct SignatureInput {
+    entries: Vec<(String, SignatureParams)>,
+}
+
+impl SignatureInput {
+    /// Create a new instance of `SignatureInput`.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create a new instance from headers.
+    pub fn from_headers(headers: impl AsRef<Headers>) -> crate::Result<Option<Self>> {
+        let headers = match headers.as_ref().get(SIGNATURE_INPUT) {
+            Some(headers) => headers,
+            None => return Ok(None),
+        };
+        let values: Vec<_> = headers.iter().map(HeaderValue::as_str).collect();
+        let entries = sf::parse_dictionary(&values.join(", "), SignatureParams::parse)?;
+        Ok(Some(Self { entries }))
+    }
+
+    /// Get the parameters of the signature labeled `label`.
+    pub fn get(&self, label: &str) -> Option<&SignatureParams> {
+        self.entries
+            .iter()
+            .find(|(name, _)| name == label)
+            .map(|(_, params)| params)
+    }
+
+    /// Insert the parameters of the signature labeled `label`, replacing any
+    /// existing parameters with the same label.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the label is not a valid structured field key.
+    pub fn insert(
+        &mut self,
+        label: impl Into<String>,
+        params: SignatureParams,
+    ) -> crate::Result<()> {
+        let label = label.into();
+        ensure!(
+            sf::is_key(&label),
+            400,
+            "Invalid signature label `{}`",
+            label
+        );
+        match self.entries.iter_mut().find(|(name, _)| *name == label) {
+            Some((_, existing)) => *existing = params,
+            None => self.entries.push((label, params)),
+        }
+        Ok(())
+    }
+
+    /// Remove the parameters of the signature labeled `label`.
+    pub fn remove(&mut self, label: &str) -> Option<SignatureParams> {
+        let index = self.entries.iter().position(|(name, _)| name == label)?;
+        Some(self.entries.remove(index).1)
+    }
+
+    /// An iterator visiting all signature labels and their parameters.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &SignatureParams)> {
+        self.entries
+            .iter()
+            .map(|(label, params)| (label.as_str(), params))
+    }
+
+    /// Returns `true` if there are no signatures.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+impl Header for SignatureInput {
+    fn header_name(&self) -> HeaderName {
+        SIGNATURE_INPUT
+    }
+
+    fn header_value(&self) -> HeaderValue {
+        let entries: Vec<_> = self
+            .entries
+            .iter()
+            .map(|(label, params)| format!("{}={}", label, params))
+            .collect();
+        let output = entries.join(", ");
+
+        // SAFETY: the internal string is validated to be ASCII.
+        unsafe { HeaderValue::from_bytes_unchecked(output.into()) }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn multiple_signatures() -> crate::Result<()> {
+        let mut headers = Headers::new();
+        headers.append(SIGNATURE_INPUT, r#"sig1=("@method");created=1, sig2=()"#)?;
+        headers.append(SIGNATURE_INPUT, r#"proxy=("@authority" "via");tag="proxy""#)?;
+        let mut input = SignatureInput::from_headers(&headers)?.unwrap();
+
+        let labels: Vec<_> = input.iter().map(|(label, _)| label).collect();
+        assert_eq!(labels, ["sig1", "sig2", "proxy"]);
+        assert_eq!(input.get("proxy").unwrap().tag(), Some("proxy"));
+        assert!(input.remove("sig2").unwrap().components().is_empty());
+        assert_eq!(
+            input.header_value(),
+            r#"sig1=("@method");created=1, proxy=("@authority" "via");tag="proxy""#
+        );
+        assert!(input.insert("Sig", SignatureParams::new(vec![])).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn bad_request_on_parse_error() {
+        for input in [
+            "sig1",
+            r#"sig1=("@method"),"#,
+            r#"sig1=("@method") sig2=()"#,
+            r#"Sig1=()"#,
+            r#"sig1=:aGVsbG8=:"#,
+        ] {
+            let mut headers = Headers::new();
+            headers.insert(SIGNATURE_INPUT, input).unwrap();
+            let err = SignatureInput::from_headers(headers).unwrap_err();
+            assert_eq!(err.status(), 400, "{} should not parse", input);
+        }
+    }
+}
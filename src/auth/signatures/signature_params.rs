@@ -0,0 +1,342 @@
+use super::{Component, Message, SignatureAlgorithm};
+use crate::sf::{self, Item, Params};
+use crate::{bail_status as bail, ensure_status as ensure};
+
+use std::fmt::{self, Display};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// The covered components and metadata of a single message signature.
+///
+/// This is the value of one entry in the `Signature-Input` header. Parameters
+/// are kept in the order they were sent in, since the signature covers their
+/// serialized form.
+///
+/// # Specifications
+///
+/// - [RFC 9421, section 2.3: Signature Parameters](https://www.rfc-editor.org/rfc/rfc9421#section-2.3)
+/// - [RFC 9421, section 2.5: Creating the Signature Base](https://www.rfc-editor.org/rfc/rfc9421#section-2.5)
+///
+/// # Examples
+///
+/// ```
+/// # fn main() -> http_types::Result<()> {
+/// #
+/// use http_types::auth::signatures::{Component, SignatureParams};
+/// use http_types::headers::CONTENT_TYPE;
+/// use http_types::Request;
+/// use std::time::{Duration, UNIX_EPOCH};
+///
+/// let mut req = Request::post("https://example.com/foo?param=Value&Pet=dog");
+/// req.insert_header("Content-Type", "application/json");
+///
+/// let mut params = SignatureParams::new(vec![
+///     Component::Method,
+///     Component::Authority,
+///     Component::Field(CONTENT_TYPE),
+/// ]);
+/// params.set_created(Some(UNIX_EPOCH + Duration::from_secs(1618884473)));
+/// params.set_keyid(Some("test-key".into()))?;
+///
+/// assert_eq!(
+///     params.signature_base(&req)?,
+///     concat!(
+///         "\"@method\": POST\n",
+///         "\"@authority\": example.com\n",
+///         "\"content-type\": application/json\n",
+///         "\"@signature-params\": (\"@method\" \"@authority\" \"content-type\")",
+///         ";created=1618884473;keyid=\"test-key\"",
+///     )
+/// );
+/// #
+/// # Ok(()) }
+/// ```
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct SignatureParams {
+    components: Vec<Component>,
+    params: Params,
+}
+
+impl SignatureParams {
+    /// Create a new instance of `SignatureParams` covering `components`.
+    pub fn new(components: Vec<Component>) -> Self {
+        Self {
+            components,
+            params: vec![],
+        }
+    }
+
+    /// Parse an inner list of components with its parameters.
+    pub(super) fn parse(input: &str) -> crate::Result<(Self, &str)> {
+        let (items, params, rest) = sf::parse_inner_list(input)?;
+        let components = items
+            .into_iter()
+            .map(|(name, params)| Component::parse(name, params))
+            .collect::<crate::Result<_>>()?;
+        let params = Self { components, params };
+
+        for key in ["created", "expires"] {
+            let valid = match params.param(key) {
+                None => true,
+                Some(Item::Integer(secs @ 0..)) => UNIX_EPOCH
+                    .checked_add(Duration::from_secs(*secs as u64))
+                    .is_some(),
+                _ => false,
+            };
+            ensure!(
+                valid,
+                400,
+                "Signature parameter `{}` must be a non-negative integer",
+                key
+            );
+        }
+        for key in ["nonce", "alg", "keyid", "tag"] {
+            ensure!(
+                matches!(params.param(key), None | Some(Item::String(_))),
+                400,
+                "Signature parameter `{}` must be a string",
+                key
+            );
+        }
+        Ok((params, rest))
+    }
+
+    /// Get the covered components.
+    pub fn components(&self) -> &[Component] {
+        &self.components
+    }
+
+    /// Returns `true` if the signature covers `component`.
+    pub fn covers(&self, component: &Component) -> bool {
+        self.components.contains(component)
+    }
+
+    /// Get the time the signature was created.
+    pub fn created(&self) -> Option<SystemTime> {
+        self.time("created")
+    }
+
+    /// Set the time the signature was created.
+    pub fn set_created(&mut self, created: Option<SystemTime>) {
+        self.set_time("created", created);
+    }
+
+    /// Get the time after which the signature should no longer be accepted.
+    pub fn expires(&self) -> Option<SystemTime> {
+        self.time("expires")
+    }
+
+    /// Set the time after which the signature should no longer be accepted.
+    pub fn set_expires(&mut self, expires: Option<SystemTime>) {
+        self.set_time("expires", expires);
+    }
+
+    /// Get the age of the signature at `now`, if it has a creation time.
+    ///
+    /// Signatures created after `now` have an age of zero.
+    pub fn age(&self, now: SystemTime) -> Option<Duration> {
+        let created = self.created()?;
+        Some(now.duration_since(created).unwrap_or_default())
+    }
+
+    /// Get the nonce, which verifiers may use to detect replayed messages.
+    pub fn nonce(&self) -> Option<&str> {
+        self.string("nonce")
+    }
+
+    /// Set the nonce.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the nonce contains control or non-ASCII
+    /// characters.
+    pub fn set_nonce(&mut self, nonce: Option<String>) -> crate::Result<()> {
+        self.set_string("nonce", nonce)
+    }
+
+    /// Get the name of the algorithm used to create the signature.
+    pub fn alg(&self) -> Option<&str> {
+        self.string("alg")
+    }
+
+    /// Set the name of the algorithm used to create the signature.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the name contains control or non-ASCII characters.
+    pub fn set_alg(&mut self, alg: Option<String>) -> crate::Result<()> {
+        self.set_string("alg", alg)
+    }
+
+    /// Get the identifier of the key used to create the signature.
+    pub fn keyid(&self) -> Option<&str> {
+        self.string("keyid")
+    }
+
+    /// Set the identifier of the key used to create the signature.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the identifier contains control or non-ASCII
+    /// characters.
+    pub fn set_keyid(&mut self, keyid: Option<String>) -> crate::Result<()> {
+        self.set_string("keyid", keyid)
+    }
+
+    /// Get the application-specific tag of the signature.
+    pub fn tag(&self) -> Option<&str> {
+        self.string("tag")
+    }
+
+    /// Set the application-specific tag of the signature.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the tag contains control or non-ASCII characters.
+    pub fn set_tag(&mut self, tag: Option<String>) -> crate::Result<()> {
+        self.set_string("tag", tag)
+    }
+
+    /// Create the signature base for `message`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a component is covered more than once, or does not
+    /// exist in the message.
+    pub fn signature_base<'a>(&self, message: impl Into<Message<'a>>) -> crate::Result<String> {
+        let message = message.into();
+        let mut base = String::new();
+        for (i, component) in self.components.iter().enumerate() {
+            ensure!(
+                !self.components[..i].contains(component),
+                400,
+                "Signature covers `{}` more than once",
+                component.name()
+            );
+            let value = component.value(message)?;
+            ensure!(
+                sf::is_printable(&value),
+                400,
+                "Signature component `{}` contains non-ASCII characters",
+                component.name()
+            );
+            base.push_str(&format!("{}: {}\n", component, value));
+        }
+        base.push_str(&format!("\"@signature-params\": {}", self));
+        Ok(base)
+    }
+
+    /// Sign `message` with `algorithm`.
+    ///
+    /// The returned signature should be sent in the `Signature` header, with
+    /// the same label as these parameters in the `Signature-Input` header.
+    pub fn sign<'a>(
+        &self,
+        message: impl Into<Message<'a>>,
+        algorithm: &dyn SignatureAlgorithm,
+    ) -> crate::Result<Vec<u8>> {
+        let base = self.signature_base(message)?;
+        algorithm.sign(base.as_bytes())
+    }
+
+    fn param(&self, key: &str) -> Option<&Item> {
+        self.params
+            .iter()
+            .find(|(name, _)| name == key)
+            .map(|(_, value)| value)
+    }
+
+    /// Replace a parameter in place, or append it if it does not exist yet.
+    fn set_param(&mut self, key: &str, value: Option<Item>) {
+        let index = self.params.iter().position(|(name, _)| name == key);
+        match (index, value) {
+            (Some(index), Some(value)) => self.params[index].1 = value,
+            (Some(index), None) => {
+                self.params.remove(index);
+            }
+            (None, Some(value)) => self.params.push((key.to_string(), value)),
+            (None, None) => {}
+        }
+    }
+
+    fn time(&self, key: &str) -> Option<SystemTime> {
+        match self.param(key) {
+            Some(Item::Integer(secs)) => UNIX_EPOCH.checked_add(Duration::from_secs(*secs as u64)),
+            _ => None,
+        }
+    }
+
+    fn set_time(&mut self, key: &str, time: Option<SystemTime>) {
+        let secs = time.map(|time| match time.duration_since(UNIX_EPOCH) {
+            Ok(duration) => duration.as_secs() as i64,
+            Err(_) => 0,
+        });
+        self.set_param(key, secs.map(Item::Integer));
+    }
+
+    fn string(&self, key: &str) -> Option<&str> {
+        match self.param(key) {
+            Some(Item::String(value)) => Some(value),
+            _ => None,
+        }
+    }
+
+    fn set_string(&mut self, key: &str, value: Option<String>) -> crate::Result<()> {
+        if let Some(value) = &value {
+            if !sf::is_printable(value) {
+                bail!(400, "Invalid signature parameter `{}`", key);
+            }
+        }
+        self.set_param(key, value.map(Item::String));
+        Ok(())
+    }
+}
+
+impl Display for SignatureParams {
+    /// Writes the parameters as they appear in `Signature-Input` and the
+    /// `@signature-params` line of the signature base.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let components: Vec<_> = self.components.iter().map(|c| c.to_string()).collect();
+        write!(f, "({})", components.join(" "))?;
+        sf::write_params(f, &self.params)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn keep_parameter_order() -> crate::Result<()> {
+        let input =
+            r#"();created=1618884473;keyid="test-key-rsa-pss";nonce="b3k2pp5k7z-50gnwp.yemd""#;
+        let (mut params, rest) = SignatureParams::parse(input)?;
+        assert_eq!(rest, "");
+        assert_eq!(params.to_string(), input);
+        assert_eq!(params.nonce(), Some("b3k2pp5k7z-50gnwp.yemd"));
+
+        params.set_keyid(Some("other".into()))?;
+        params.set_nonce(None)?;
+        params.set_tag(Some("app".into()))?;
+        assert_eq!(
+            params.to_string(),
+            r#"();created=1618884473;keyid="other";tag="app""#
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn invalid_params() {
+        for input in [
+            r#"("@method";req)"#,
+            r#"("@method""content-type")"#,
+            r#"("@method");created="yesterday""#,
+            r#"("@method");created=-1"#,
+            r#"("@method");expires=1234567890123456"#,
+            r#"("@method");keyid=key"#,
+            r#""@method""#,
+        ] {
+            let err = SignatureParams::parse(input).unwrap_err();
+            assert_eq!(err.status(), 400, "{} should not parse", input);
+        }
+    }
+}
@@ -0,0 +1,77 @@
+use super::{Message, Signature, SignatureAlgorithm, SignatureInput, SignatureParams};
+use crate::{bail_status as bail, ensure_status as ensure};
+
+use std::time::SystemTime;
+
+/// Verify the signature labeled `label` in `message`.
+///
+/// Besides checking the signature itself, this rejects signatures which
+/// expired or were created after `now`, and signatures with an `alg`
+/// parameter which does not match `algorithm`. On success the signature
+/// parameters are returned, so the caller can check which components are
+/// covered, the `keyid`, the `nonce` or the age of the signature.
+///
+/// # Errors
+///
+/// Returns a `400 Bad Request` error if the signature headers are malformed
+/// or a covered component is missing, and a `401 Unauthorized` error if the
+/// signature is missing, invalid, or not valid at `now`.
+///
+/// # Specifications
+///
+/// - [RFC 9421, section 3.2: Verifying a Signature](https://www.rfc-editor.org/rfc/rfc9421#section-3.2)
+pub fn verify<'a>(
+    message: impl Into<Message<'a>>,
+    label: &str,
+    algorithm: &dyn SignatureAlgorithm,
+    now: SystemTime,
+) -> crate::Result<SignatureParams> {
+    let message = message.into();
+    let params = match SignatureInput::from_headers(message.headers())? {
+        Some(input) => input.get(label).cloned(),
+        None => None,
+    };
+    let params = match params {
+        Some(params) => params,
+        None => bail!(401, "Missing parameters for signature `{}`", label),
+    };
+    let signature = match Signature::from_headers(message.headers())? {
+        Some(signature) => signature.get(label).map(<[u8]>::to_vec),
+        None => None,
+    };
+    let signature = match signature {
+        Some(signature) => signature,
+        None => bail!(401, "Missing signature `{}`", label),
+    };
+
+    if let Some(alg) = params.alg() {
+        ensure!(
+            alg == algorithm.name(),
+            401,
+            "Signature `{}` uses algorithm `{}`, expected `{}`",
+            label,
+            alg,
+            algorithm.name()
+        );
+    }
+    if let Some(expires) = params.expires() {
+        ensure!(expires > now, 401, "Signature `{}` expired", label);
+    }
+    if let Some(created) = params.created() {
+        ensure!(
+            created <= now,
+            401,
+            "Signature `{}` was created in the future",
+            label
+        );
+    }
+
+    let base = params.signature_base(message)?;
+    ensure!(
+        algorithm.verify(base.as_bytes(), &signature),
+        401,
+        "Invalid signature `{}`",
+        label
+    );
+    Ok(params)
+}
@@ -0,0 +1,210 @@
+use crate::auth::params::write_token;
+use crate::auth::{AuthenticationScheme, Challenge, VapidKey};
+use crate::headers::{Header, HeaderName, HeaderValue, Headers, AUTHORIZATION};
+use crate::Url;
+use crate::{bail_status as bail, ensure_status as ensure};
+
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Voluntary Application Server Identification for Web Push.
+///
+/// Application servers identify themselves to push services with a signed
+/// JSON Web Token, and the public key the token can be verified with.
+///
+/// # Specifications
+///
+/// - [RFC 8292: Voluntary Application Server Identification (VAPID) for Web Push](https://tools.ietf.org/html/rfc8292)
+///
+/// # Examples
+///
+/// ```
+/// # fn main() -> http_types::Result<()> {
+/// #
+/// use http_types::auth::{VapidAuth, VapidKey};
+/// use http_types::{Request, Url};
+/// use std::time::{Duration, SystemTime};
+///
+/// let key = VapidKey::generate();
+/// let endpoint = Url::parse("https://push.example.net/push/JzLQ3raZJfFBR0aqvOMsLrt54w4rJUsV")?;
+/// let expires = SystemTime::now() + Duration::from_secs(12 * 60 * 60);
+/// let authz = VapidAuth::new(&key, &endpoint, "mailto:push@example.com", expires)?;
+///
+/// let mut req = Request::post(endpoint);
+/// req.insert_header(&authz, &authz)?;
+///
+/// let authz = VapidAuth::from_headers(req)?.unwrap();
+/// assert_eq!(authz.key(), key.public_key());
+/// #
+/// # Ok(()) }
+/// ```
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct VapidAuth {
+    token: String,
+    key: String,
+}
+
+impl VapidAuth {
+    /// Create a new instance of `VapidAuth` for messages sent to `endpoint`.
+    ///
+    /// `subject` is a `mailto:` or `https:` URI the push service can use to
+    /// contact the operator of the application server. Push services reject
+    /// tokens which expire more than 24 hours in the future.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the endpoint is not an HTTP URL, or the subject is
+    /// not a `mailto:` or `https:` URI.
+    pub fn new(
+        key: &VapidKey,
+        endpoint: &Url,
+        subject: &str,
+        expires: SystemTime,
+    ) -> crate::Result<Self> {
+        crate::ensure!(
+            matches!(endpoint.scheme(), "http" | "https") && endpoint.has_host(),
+            "Push endpoint `{}` is not an HTTP URL",
+            endpoint
+        );
+        crate::ensure!(
+            (subject.starts_with("mailto:") || subject.starts_with("https:"))
+                && subject
+                    .bytes()
+                    .all(|b| b.is_ascii_graphic() && b != b'"' && b != b'\\'),
+            "VAPID subject `{}` must be a mailto: or https: URI",
+            subject
+        );
+        let expires = match expires.duration_since(UNIX_EPOCH) {
+            Ok(expires) => expires.as_secs(),
+            Err(_) => 0,
+        };
+
+        let header = encode(br#"{"typ":"JWT","alg":"ES256"}"#);
+        let claims = format!(
+            r#"{{"aud":"{}","exp":{},"sub":"{}"}}"#,
+            endpoint.origin().ascii_serialization(),
+            expires,
+            subject
+        );
+        let mut token = format!("{}.{}", header, encode(claims.as_bytes()));
+        let signature = key.sign(token.as_bytes());
+        token.push('.');
+        token.push_str(&encode(&signature));
+
+        Ok(Self {
+            token,
+            key: key.public_key(),
+        })
+    }
+
+    /// Create a new instance from headers.
+    pub fn from_headers(headers: impl AsRef<Headers>) -> crate::Result<Option<Self>> {
+        let headers = match headers.as_ref().get(AUTHORIZATION) {
+            Some(headers) => headers,
+            None => return Ok(None),
+        };
+
+        // If we successfully parsed the header then there's always at least one
+        // entry. We want the last entry.
+        let value = headers.iter().last().unwrap();
+        let credentials = match Challenge::parse_list(value.as_str())?.into_iter().next() {
+            Some(credentials) => credentials,
+            None => bail!(400, "Could not find scheme"),
+        };
+        ensure!(
+            credentials.is_scheme(AuthenticationScheme::Vapid),
+            400,
+            "Expected vapid auth scheme found `{}`",
+            credentials.scheme_name()
+        );
+        let (token, key) = match (credentials.param("t"), credentials.param("k")) {
+            (Some(token), Some(key)) => (token.to_string(), key.to_string()),
+            _ => bail!(400, "VAPID credentials must contain a token and a key"),
+        };
+        ensure!(
+            token.split('.').count() == 3,
+            400,
+            "VAPID token is not a JSON Web Token"
+        );
+        Ok(Some(Self { token, key }))
+    }
+
+    /// Get the signed JSON Web Token.
+    pub fn token(&self) -> &str {
+        &self.token
+    }
+
+    /// Get the public key of the application server, as URL-safe base64.
+    pub fn key(&self) -> &str {
+        &self.key
+    }
+}
+
+impl Header for VapidAuth {
+    fn header_name(&self) -> HeaderName {
+        AUTHORIZATION
+    }
+
+    fn header_value(&self) -> HeaderValue {
+        let mut output = String::from("vapid ");
+        write_token(&mut output, "t", &self.token);
+        write_token(&mut output, "k", &self.key);
+
+        // SAFETY: the internal string is validated to be ASCII.
+        unsafe { HeaderValue::from_bytes_unchecked(output.into()) }
+    }
+}
+
+fn encode(bytes: &[u8]) -> String {
+    base64::encode_config(bytes, base64::URL_SAFE_NO_PAD)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use p256::ecdsa::signature::Verifier;
+    use p256::ecdsa::{Signature, VerifyingKey};
+
+    #[test]
+    fn signed_token() -> crate::Result<()> {
+        let key = VapidKey::generate();
+        let endpoint = Url::parse("https://push.example.net:8443/push/abc")?;
+        let expires = UNIX_EPOCH + std::time::Duration::from_secs(1453523768);
+        let authz = VapidAuth::new(&key, &endpoint, "mailto:push@example.com", expires)?;
+
+        let parts: Vec<_> = authz.token().split('.').collect();
+        let decode = |s| base64::decode_config(s, base64::URL_SAFE_NO_PAD).unwrap();
+        assert_eq!(decode(parts[0]), br#"{"typ":"JWT","alg":"ES256"}"#);
+        assert_eq!(
+            String::from_utf8(decode(parts[1]))?,
+            r#"{"aud":"https://push.example.net:8443","exp":1453523768,"sub":"mailto:push@example.com"}"#
+        );
+
+        let verifying_key = VerifyingKey::from_sec1_bytes(&decode(authz.key())).unwrap();
+        let signature = Signature::from_slice(&decode(parts[2])).unwrap();
+        let message = format!("{}.{}", parts[0], parts[1]);
+        assert!(verifying_key.verify(message.as_bytes(), &signature).is_ok());
+
+        let mut headers = Headers::new();
+        authz.apply_header(&mut headers);
+        assert_eq!(VapidAuth::from_headers(headers)?, Some(authz));
+        Ok(())
+    }
+
+    #[test]
+    fn invalid_input() {
+        let key = VapidKey::generate();
+        let endpoint = Url::parse("https://push.example.net/").unwrap();
+        let expires = SystemTime::now();
+        assert!(VapidAuth::new(&key, &endpoint, "push@example.com", expires).is_err());
+        let endpoint = Url::parse("mailto:push@example.net").unwrap();
+        assert!(VapidAuth::new(&key, &endpoint, "mailto:push@example.com", expires).is_err());
+
+        for input in ["vapid t=abc, k=def", "vapid k=def", "Bearer abc"] {
+            let mut headers = Headers::new();
+            headers.insert(AUTHORIZATION, input).unwrap();
+            let err = VapidAuth::from_headers(headers).unwrap_err();
+            assert_eq!(err.status(), 400, "{} should not parse", input);
+        }
+    }
+}
@@ -0,0 +1,96 @@
+use crate::ensure_status as ensure;
+
+use p256::ecdsa::signature::Signer;
+use p256::ecdsa::{Signature, SigningKey};
+use rand_core::OsRng;
+
+use std::fmt::{self, Debug};
+
+/// An application server key, used to identify the sender of push messages.
+///
+/// The public half is handed to user agents as the `applicationServerKey`
+/// when subscribing, and is sent with every push message in the
+/// [`VapidAuth`](crate::auth::VapidAuth) header.
+///
+/// # Specifications
+///
+/// - [RFC 8292, section 3.2: Public Key Parameter ("k")](https://tools.ietf.org/html/rfc8292#section-3.2)
+///
+/// # Examples
+///
+/// ```
+/// # fn main() -> http_types::Result<()> {
+/// #
+/// use http_types::auth::VapidKey;
+///
+/// let key = VapidKey::generate();
+/// let key = VapidKey::from_base64(&key.to_base64())?;
+/// assert_eq!(key.public_key().len(), 87);
+/// #
+/// # Ok(()) }
+/// ```
+#[derive(Clone)]
+pub struct VapidKey {
+    key: SigningKey,
+}
+
+impl VapidKey {
+    /// Generate a new random key.
+    pub fn generate() -> Self {
+        Self {
+            key: SigningKey::random(&mut OsRng),
+        }
+    }
+
+    /// Create a key from the 32 bytes of an ECDSA P-256 private key.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the bytes are not a valid P-256 private key.
+    pub fn from_bytes(bytes: &[u8]) -> crate::Result<Self> {
+        ensure!(bytes.len() == 32, 500, "VAPID keys must be 32 bytes long");
+        match SigningKey::from_slice(bytes) {
+            Ok(key) => Ok(Self { key }),
+            Err(_) => crate::bail!("Invalid VAPID key"),
+        }
+    }
+
+    /// Create a key from its URL-safe base64 encoding, as produced by
+    /// [`VapidKey::to_base64`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the string is not a valid encoded P-256 private
+    /// key.
+    pub fn from_base64(key: &str) -> crate::Result<Self> {
+        match base64::decode_config(key.trim_end_matches('='), base64::URL_SAFE_NO_PAD) {
+            Ok(bytes) => Self::from_bytes(&bytes),
+            Err(_) => crate::bail!("Invalid VAPID key encoding"),
+        }
+    }
+
+    /// Get the private key as URL-safe base64, for storage.
+    pub fn to_base64(&self) -> String {
+        base64::encode_config(self.key.to_bytes(), base64::URL_SAFE_NO_PAD)
+    }
+
+    /// Get the uncompressed public key as URL-safe base64.
+    pub fn public_key(&self) -> String {
+        let point = self.key.verifying_key().to_encoded_point(false);
+        base64::encode_config(point.as_bytes(), base64::URL_SAFE_NO_PAD)
+    }
+
+    /// Sign `data` with ES256, returning the 64 byte `r || s` signature.
+    pub(crate) fn sign(&self, data: &[u8]) -> Vec<u8> {
+        let signature: Signature = self.key.sign(data);
+        signature.to_bytes().to_vec()
+    }
+}
+
+impl Debug for VapidKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("VapidKey")
+            .field("public_key", &self.public_key())
+            .finish()
+    }
+}
@@ -1,18 +1,16 @@
+use crate::auth::{AuthenticationScheme, Challenge};
 use crate::bail_status as bail;
-use crate::headers::{HeaderName, HeaderValue, Headers, WWW_AUTHENTICATE};
-use crate::{auth::AuthenticationScheme, headers::Header};
+use crate::headers::{Header, HeaderName, HeaderValue, HeaderValues, Headers, WWW_AUTHENTICATE};
 
-/// Define the authentication method that should be used to gain access to a
+use std::fmt::{self, Debug, Write};
+use std::slice;
+
+/// Define the authentication methods that should be used to gain access to a
 /// resource.
 ///
 /// # Specifications
 ///
-/// - [RFC 7235, section 4.1: WWW-Authenticate](https://tools.ietf.org/html/rfc7235#section-4.1)
-///
-/// # Implementation Notes
-///
-/// This implementation only encodes and parses a single authentication method,
-/// further authorization methods are ignored. It also always passes the utf-8 encoding flag.
+/// - [RFC 9110, section 11.6.1: WWW-Authenticate](https://www.rfc-editor.org/rfc/rfc9110#section-11.6.1)
 ///
 /// # Examples
 ///
@@ -20,97 +18,86 @@ use crate::{auth::AuthenticationScheme, headers::Header};
 /// # fn main() -> http_types::Result<()> {
 /// #
 /// use http_types::Response;
-/// use http_types::auth::{AuthenticationScheme, WwwAuthenticate};
+/// use http_types::auth::{AuthenticationScheme, Challenge, WwwAuthenticate};
+///
+/// let mut basic = Challenge::new(AuthenticationScheme::Basic);
+/// basic.set_realm("Access to the staging site")?;
+/// basic.insert_param("charset", "UTF-8")?;
+///
+/// let mut bearer = Challenge::new(AuthenticationScheme::Bearer);
+/// bearer.insert_param("error", "invalid_token")?;
 ///
-/// let scheme = AuthenticationScheme::Basic;
-/// let realm = "Access to the staging site";
-/// let authz = WwwAuthenticate::new(scheme, realm.into());
+/// let mut authz = WwwAuthenticate::new();
+/// authz.push(basic);
+/// authz.push(bearer);
 ///
-/// let mut res = Response::new(200);
+/// let mut res = Response::new(401);
 /// res.insert_header(&authz, &authz);
 ///
 /// let authz = WwwAuthenticate::from_headers(res)?.unwrap();
-///
-/// assert_eq!(authz.scheme(), AuthenticationScheme::Basic);
-/// assert_eq!(authz.realm(), realm);
+/// let basic = authz.get(AuthenticationScheme::Basic).unwrap();
+/// assert_eq!(basic.realm(), Some("Access to the staging site"));
+/// assert_eq!(basic.param("charset"), Some("UTF-8"));
+/// let bearer = authz.get(AuthenticationScheme::Bearer).unwrap();
+/// assert_eq!(bearer.param("error"), Some("invalid_token"));
 /// #
 /// # Ok(()) }
 /// ```
-#[derive(Debug)]
+#[derive(Clone, Eq, PartialEq)]
 pub struct WwwAuthenticate {
-    scheme: AuthenticationScheme,
-    realm: String,
+    challenges: Vec<Challenge>,
 }
 
 impl WwwAuthenticate {
     /// Create a new instance of `WwwAuthenticate`.
-    pub fn new(scheme: AuthenticationScheme, realm: String) -> Self {
-        Self { scheme, realm }
+    pub fn new() -> Self {
+        Self { challenges: vec![] }
     }
 
     /// Create a new instance from headers.
     pub fn from_headers(headers: impl AsRef<Headers>) -> crate::Result<Option<Self>> {
-        let headers = match headers.as_ref().get(WWW_AUTHENTICATE) {
-            Some(headers) => headers,
-            None => return Ok(None),
-        };
-
-        // If we successfully parsed the header then there's always at least one
-        // entry. We want the last entry.
-        let value = headers.iter().last().unwrap();
-
-        let mut iter = value.as_str().splitn(2, ' ');
-        let scheme = iter.next();
-        let credential = iter.next();
-        let (scheme, realm) = match (scheme, credential) {
-            (None, _) => bail!(400, "Could not find scheme"),
-            (Some(_), None) => bail!(400, "Could not find realm"),
-            (Some(scheme), Some(realm)) => (scheme.parse()?, realm.to_owned()),
-        };
-
-        let realm = realm.trim_start();
-        let realm = match realm.strip_prefix(r#"realm=""#) {
-            Some(realm) => realm,
-            None => bail!(400, "realm not found"),
-        };
-
-        let mut chars = realm.chars();
-        let mut closing_quote = false;
-        let realm = (&mut chars)
-            .take_while(|c| {
-                if c == &'"' {
-                    closing_quote = true;
-                    false
-                } else {
-                    true
-                }
-            })
-            .collect();
-        if !closing_quote {
-            bail!(400, r"Expected a closing quote");
+        match headers.as_ref().get(WWW_AUTHENTICATE) {
+            Some(values) => Self::from_values(values).map(Some),
+            None => Ok(None),
         }
+    }
 
-        Ok(Some(Self { scheme, realm }))
+    /// Create a new instance from the values of a challenge header.
+    pub(crate) fn from_values(values: &HeaderValues) -> crate::Result<Self> {
+        let mut challenges = vec![];
+        for value in values {
+            challenges.extend(Challenge::parse_list(value.as_str())?);
+        }
+        if challenges.is_empty() {
+            bail!(400, "Could not find scheme");
+        }
+        Ok(Self { challenges })
     }
 
-    /// Get the authorization scheme.
-    pub fn scheme(&self) -> AuthenticationScheme {
-        self.scheme
+    /// Push a challenge into the list of challenges.
+    pub fn push(&mut self, challenge: Challenge) {
+        self.challenges.push(challenge);
     }
 
-    /// Set the authorization scheme.
-    pub fn set_scheme(&mut self, scheme: AuthenticationScheme) {
-        self.scheme = scheme;
+    /// Get the first challenge using `scheme`.
+    pub fn get(&self, scheme: AuthenticationScheme) -> Option<&Challenge> {
+        self.challenges
+            .iter()
+            .find(|challenge| challenge.is_scheme(scheme))
     }
 
-    /// Get the authorization realm.
-    pub fn realm(&self) -> &str {
-        self.realm.as_str()
+    /// An iterator visiting all challenges.
+    pub fn iter(&self) -> Iter<'_> {
+        Iter {
+            inner: self.challenges.iter(),
+        }
     }
 
-    /// Set the authorization realm.
-    pub fn set_realm(&mut self, realm: String) {
-        self.realm = realm;
+    /// An iterator visiting all challenges.
+    pub fn iter_mut(&mut self) -> IterMut<'_> {
+        IterMut {
+            inner: self.challenges.iter_mut(),
+        }
     }
 }
 
@@ -120,13 +107,118 @@ impl Header for WwwAuthenticate {
     }
 
     fn header_value(&self) -> HeaderValue {
-        let output = format!(r#"{} realm="{}", charset="UTF-8""#, self.scheme, self.realm);
+        let mut output = String::new();
+        for (n, challenge) in self.challenges.iter().enumerate() {
+            match n {
+                0 => write!(output, "{}", challenge).unwrap(),
+                _ => write!(output, ", {}", challenge).unwrap(),
+            };
+        }
 
         // SAFETY: the internal string is validated to be ASCII.
         unsafe { HeaderValue::from_bytes_unchecked(output.into()) }
     }
 }
 
+impl IntoIterator for WwwAuthenticate {
+    type Item = Challenge;
+    type IntoIter = IntoIter;
+
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        IntoIter {
+            inner: self.challenges.into_iter(),
+        }
+    }
+}
+
+impl<'a> IntoIterator for &'a WwwAuthenticate {
+    type Item = &'a Challenge;
+    type IntoIter = Iter<'a>;
+
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<'a> IntoIterator for &'a mut WwwAuthenticate {
+    type Item = &'a mut Challenge;
+    type IntoIter = IterMut<'a>;
+
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter_mut()
+    }
+}
+
+/// A borrowing iterator over challenges in `WwwAuthenticate`.
+#[derive(Debug)]
+pub struct IntoIter {
+    inner: std::vec::IntoIter<Challenge>,
+}
+
+impl Iterator for IntoIter {
+    type Item = Challenge;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next()
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+/// A lending iterator over challenges in `WwwAuthenticate`.
+#[derive(Debug)]
+pub struct Iter<'a> {
+    inner: slice::Iter<'a, Challenge>,
+}
+
+impl<'a> Iterator for Iter<'a> {
+    type Item = &'a Challenge;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next()
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+/// A mutable iterator over challenges in `WwwAuthenticate`.
+#[derive(Debug)]
+pub struct IterMut<'a> {
+    inner: slice::IterMut<'a, Challenge>,
+}
+
+impl<'a> Iterator for IterMut<'a> {
+    type Item = &'a mut Challenge;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next()
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl Debug for WwwAuthenticate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut list = f.debug_list();
+        for challenge in &self.challenges {
+            list.entry(challenge);
+        }
+        list.finish()
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -134,9 +226,11 @@ mod test {
 
     #[test]
     fn smoke() -> crate::Result<()> {
-        let scheme = AuthenticationScheme::Basic;
-        let realm = "Access to the staging site";
-        let authz = WwwAuthenticate::new(scheme, realm.into());
+        let mut challenge = Challenge::new(AuthenticationScheme::Basic);
+        challenge.set_realm("Access to the staging site")?;
+        challenge.insert_param("charset", "UTF-8")?;
+        let mut authz = WwwAuthenticate::new();
+        authz.push(challenge);
 
         let mut headers = Headers::new();
         authz.apply_header(&mut headers);
@@ -147,9 +241,41 @@ mod test {
         );
 
         let authz = WwwAuthenticate::from_headers(headers)?.unwrap();
+        let challenge = authz.iter().next().unwrap();
+        assert_eq!(challenge.scheme(), Some(AuthenticationScheme::Basic));
+        assert_eq!(challenge.realm(), Some("Access to the staging site"));
+        Ok(())
+    }
 
-        assert_eq!(authz.scheme(), AuthenticationScheme::Basic);
-        assert_eq!(authz.realm(), realm);
+    #[test]
+    fn multiple_values() -> crate::Result<()> {
+        let mut headers = Headers::new();
+        headers.append(
+            WWW_AUTHENTICATE,
+            r#"Newauth realm="apps", type=1, title="Login to \"apps\"", Basic realm="simple""#,
+        )?;
+        headers.append(
+            WWW_AUTHENTICATE,
+            r#"Bearer realm="example", error="invalid_token", error_description="The access token expired""#,
+        )?;
+        headers.append(WWW_AUTHENTICATE, "Negotiate YII=")?;
+
+        let authz = WwwAuthenticate::from_headers(headers)?.unwrap();
+        let names: Vec<_> = authz.iter().map(Challenge::scheme_name).collect();
+        assert_eq!(names, ["Newauth", "Basic", "Bearer", "Negotiate"]);
+
+        let bearer = authz.get(AuthenticationScheme::Bearer).unwrap();
+        assert_eq!(
+            bearer.param("error_description"),
+            Some("The access token expired")
+        );
+        let negotiate = authz.get(AuthenticationScheme::Negotiate).unwrap();
+        assert_eq!(negotiate.token68(), Some("YII="));
+
+        assert_eq!(
+            authz.header_value(),
+            r#"Newauth realm="apps", type="1", title="Login to \"apps\"", Basic realm="simple", Bearer realm="example", error="invalid_token", error_description="The access token expired", Negotiate YII="#
+        );
         Ok(())
     }
 
@@ -7,6 +7,7 @@ use std::fmt::{self, Debug};
 use std::pin::Pin;
 use std::task::{Context, Poll};
 
+use crate::conditional::ETag;
 use crate::mime::{self, Mime};
 use crate::{Status, StatusCode};
 
@@ -59,6 +60,7 @@ pin_project_lite::pin_project! {
         mime: Option<Mime>,
         length: Option<u64>,
         bytes_read: u64,
+        etag: Option<ETag>,
     }
 }
 
@@ -82,6 +84,7 @@ impl Body {
             mime: Some(mime::BYTE_STREAM),
             length: Some(0),
             bytes_read: 0,
+            etag: None,
         }
     }
 
@@ -113,6 +116,7 @@ impl Body {
             mime: Some(mime::BYTE_STREAM),
             length,
             bytes_read: 0,
+            etag: None,
         }
     }
 
@@ -157,6 +161,7 @@ impl Body {
             length: Some(bytes.len() as u64),
             reader: Box::new(io::Cursor::new(bytes)),
             bytes_read: 0,
+            etag: None,
         }
     }
 
@@ -207,6 +212,7 @@ impl Body {
             length: Some(s.len() as u64),
             reader: Box::new(io::Cursor::new(s.into_bytes())),
             bytes_read: 0,
+            etag: None,
         }
     }
 
@@ -255,6 +261,7 @@ impl Body {
             reader: Box::new(io::Cursor::new(bytes)),
             mime: Some(mime::JSON),
             bytes_read: 0,
+            etag: None,
         };
         Ok(body)
     }
@@ -324,6 +331,7 @@ impl Body {
             reader: Box::new(io::Cursor::new(bytes)),
             mime: Some(mime::FORM),
             bytes_read: 0,
+            etag: None,
         };
         Ok(body)
     }
@@ -434,7 +442,8 @@ impl Body {
         mut file: async_std::fs::File,
         path: &std::path::Path,
     ) -> io::Result<Self> {
-        let len = file.metadata().await?.len();
+        let metadata = file.metadata().await?;
+        let len = metadata.len();
 
         // Look at magic bytes first, look at extension second, fall back to
         // octet stream.
@@ -448,6 +457,7 @@ impl Body {
             length: Some(len),
             reader: Box::new(io::BufReader::new(file)),
             bytes_read: 0,
+            etag: Some(ETag::from_metadata(&metadata)),
         })
     }
 
@@ -496,6 +506,28 @@ impl Body {
         self.mime = mime;
     }
 
+    /// Returns the weak ETag derived from the file metadata, if this Body
+    /// was created from a file.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # fn main() -> http_types::Result<()> { async_std::task::block_on(async {
+    /// use http_types::{Body, Response, StatusCode};
+    /// use http_types::headers::Header;
+    ///
+    /// let body = Body::from_path("/path/to/file").await?;
+    /// let mut res = Response::new(StatusCode::Ok);
+    /// if let Some(etag) = body.etag() {
+    ///     etag.apply_header(&mut res);
+    /// }
+    /// res.set_body(body);
+    /// # Ok(()) }) }
+    /// ```
+    pub fn etag(&self) -> Option<&ETag> {
+        self.etag.as_ref()
+    }
+
     /// Create a Body by chaining another Body after this one, consuming both.
     ///
     /// If both Body instances have a length, and their sum does not overflow,
@@ -532,6 +564,7 @@ impl Body {
             length,
             reader: Box::new(futures_lite::io::AsyncReadExt::chain(self, other)),
             bytes_read: 0,
+            etag: None,
         }
     }
 }
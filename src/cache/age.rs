@@ -63,7 +63,7 @@ impl Age {
         let header = headers.iter().last().unwrap();
 
         let num: u64 = header.as_str().parse::<u64>().status(400)?;
-        let dur = Duration::from_secs_f64(num as f64);
+        let dur = Duration::from_secs(num);
 
         Ok(Some(Self { dur }))
     }
@@ -106,4 +106,13 @@ mod test {
         let err = Age::from_headers(headers).unwrap_err();
         assert_eq!(err.status(), 400);
     }
+
+    #[test]
+    fn huge_age() -> crate::Result<()> {
+        let mut headers = Headers::new();
+        headers.insert(AGE, u64::MAX.to_string())?;
+        let age = Age::from_headers(headers)?.unwrap();
+        assert_eq!(age, Age::from_secs(u64::MAX));
+        Ok(())
+    }
 }
@@ -0,0 +1,629 @@
+use crate::cache::{
+    Age, CacheControl, CacheDirective, CacheEntry, CacheStorage, Freshness, MemoryStorage,
+};
+use crate::conditional::Vary;
+use crate::headers::{
+    Header, Headers, AUTHORIZATION, CONTENT_LENGTH, CONTENT_LOCATION, ETAG, EXPIRES,
+    IF_MODIFIED_SINCE, IF_NONE_MATCH, LAST_MODIFIED, LOCATION,
+};
+use crate::proxies::strip_hop_by_hop;
+use crate::{Body, Method, Request, Response, StatusCode, Url};
+
+use std::time::SystemTime;
+
+/// An embeddable HTTP cache.
+///
+/// `Cache` stores responses along with the request headers nominated by
+/// their `Vary` header, and decides whether a stored response may be reused
+/// using [`Freshness`]. Stale responses can be revalidated with a
+/// conditional request, and a `304 Not Modified` answer merged back into the
+/// stored response.
+///
+/// Responses to unsafe methods such as `POST` invalidate the stored
+/// responses for their target, `Location` and `Content-Location` URLs.
+///
+/// # Specifications
+///
+/// - [RFC 9111: HTTP Caching](https://www.rfc-editor.org/rfc/rfc9111)
+///
+/// # Examples
+///
+/// ```
+/// # fn main() -> http_types::Result<()> { async_std::task::block_on(async {
+/// #
+/// use http_types::cache::Cache;
+/// use http_types::{Request, Response};
+/// use std::time::{Duration, SystemTime};
+///
+/// let mut cache = Cache::new();
+/// let now = SystemTime::now();
+///
+/// let req = Request::get("https://example.com/");
+/// let mut res = Response::new(200);
+/// res.insert_header("Cache-Control", "max-age=60");
+/// res.insert_header("ETag", r#""v1""#);
+/// res.set_body("hello");
+/// assert!(cache.store(&req, &mut res, now, now).await?);
+///
+/// let hit = cache.lookup(&req, now + Duration::from_secs(10))?.unwrap();
+/// assert!(hit.is_fresh());
+/// let mut cached = hit.response();
+/// assert_eq!(cached["Age"], "10");
+/// assert_eq!(cached.body_string().await?, "hello");
+///
+/// // Once stale, revalidate with the origin server.
+/// let later = now + Duration::from_secs(120);
+/// let hit = cache.lookup(&req, later)?.unwrap();
+/// assert!(hit.must_revalidate());
+/// let revalidate = hit.revalidation_request(&req);
+/// assert_eq!(revalidate["If-None-Match"], r#""v1""#);
+///
+/// let not_modified = Response::new(304);
+/// let res = cache.update(&req, &not_modified, later, later)?.unwrap();
+/// assert_eq!(res.status(), 200);
+/// assert!(cache.lookup(&req, later)?.unwrap().is_fresh());
+/// #
+/// # Ok(()) })}
+/// ```
+#[derive(Debug)]
+pub struct Cache<S: CacheStorage = MemoryStorage> {
+    storage: S,
+    shared: bool,
+}
+
+impl Cache<MemoryStorage> {
+    /// Create a new private cache which keeps responses in memory.
+    pub fn new() -> Self {
+        Self::with_storage(MemoryStorage::new())
+    }
+}
+
+impl Default for Cache<MemoryStorage> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<S: CacheStorage> Cache<S> {
+    /// Create a new private cache with the given storage.
+    pub fn with_storage(storage: S) -> Self {
+        Self {
+            storage,
+            shared: false,
+        }
+    }
+
+    /// Get a reference to the storage.
+    pub fn storage(&self) -> &S {
+        &self.storage
+    }
+
+    /// Get a mutable reference to the storage.
+    pub fn storage_mut(&mut self) -> &mut S {
+        &mut self.storage
+    }
+
+    /// Returns `true` if this is a shared cache.
+    pub fn is_shared(&self) -> bool {
+        self.shared
+    }
+
+    /// Set whether this is a shared cache, such as a proxy or CDN.
+    ///
+    /// Shared caches do not store `private` responses, or responses to
+    /// authenticated requests unless explicitly allowed, and honour
+    /// `s-maxage` and `proxy-revalidate`.
+    pub fn set_shared(&mut self, shared: bool) {
+        self.shared = shared;
+    }
+
+    /// Returns `true` if `res` may be stored as a response to `req`.
+    pub fn is_storable(&self, req: &Request, res: &Response) -> bool {
+        if !matches!(req.method(), Method::Get | Method::Head) {
+            return false;
+        }
+        if matches!(res.status() as u16, 100..=199 | 206 | 304) {
+            return false;
+        }
+        let req_directives = directives(req);
+        let res_directives = directives(res);
+        let (req_directives, res_directives) = match (req_directives, res_directives) {
+            (Some(req), Some(res)) => (req, res),
+            _ => return false,
+        };
+        let has = |directives: &[CacheDirective], f: fn(&CacheDirective) -> bool| {
+            directives.iter().any(f)
+        };
+
+        if has(&req_directives, |d| matches!(d, CacheDirective::NoStore))
+            || has(&res_directives, |d| matches!(d, CacheDirective::NoStore))
+        {
+            return false;
+        }
+        if self.shared
+            && has(&res_directives, |d| {
+                matches!(
+                    d,
+                    CacheDirective::Private | CacheDirective::PrivateFields(_)
+                )
+            })
+        {
+            return false;
+        }
+        if self.shared
+            && req.header(AUTHORIZATION).is_some()
+            && !has(&res_directives, |d| {
+                matches!(
+                    d,
+                    CacheDirective::Public
+                        | CacheDirective::MustRevalidate
+                        | CacheDirective::SMaxAge(_)
+                )
+            })
+        {
+            return false;
+        }
+        match Vary::from_headers(res) {
+            Ok(Some(vary)) if vary.wildcard() => return false,
+            Err(_) => return false,
+            _ => {}
+        }
+
+        res.header(EXPIRES).is_some()
+            || has(&res_directives, |d| {
+                matches!(
+                    d,
+                    CacheDirective::Public | CacheDirective::MaxAge(_) | CacheDirective::SMaxAge(_)
+                )
+            })
+            || super::freshness::is_heuristically_cacheable(res.status())
+    }
+
+    /// Store a response.
+    ///
+    /// `request_time` is the time `req` was sent, and `response_time` the
+    /// time `res` was received. If the response is storable its body is
+    /// buffered in memory; `res` keeps an identical copy of the body.
+    /// Hop-by-hop headers are not stored.
+    ///
+    /// Returns `true` if the response was stored.
+    pub async fn store(
+        &mut self,
+        req: &Request,
+        res: &mut Response,
+        request_time: SystemTime,
+        response_time: SystemTime,
+    ) -> crate::Result<bool> {
+        if !self.is_storable(req, res) {
+            return Ok(false);
+        }
+
+        let body = res.take_body().into_bytes().await?;
+        res.set_body(Body::from_bytes(body.clone()));
+
+        let vary = match Vary::from_headers(&*res)? {
+            Some(vary) => vary
+                .iter()
+                .map(|name| (name.clone(), req.header(name).cloned()))
+                .collect(),
+            None => vec![],
+        };
+        let mut headers = AsRef::<Headers>::as_ref(&*res).clone();
+        strip_hop_by_hop(&mut headers);
+        let entry = CacheEntry {
+            status: res.status(),
+            version: res.version(),
+            headers,
+            body: body.into(),
+            request_time,
+            response_time,
+            vary,
+        };
+
+        let key = key(req.method(), req.url());
+        let mut variants = match self.storage.get(&key) {
+            Some(variants) => variants.into_owned(),
+            None => vec![],
+        };
+        variants.retain(|variant| !variant.matches(req.as_ref()));
+        variants.push(entry);
+        self.storage.set(key, variants);
+        Ok(true)
+    }
+
+    /// Look up a stored response for `req` at `now`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the `Cache-Control` header of the request or the
+    /// stored response could not be parsed.
+    pub fn lookup(&self, req: &Request, now: SystemTime) -> crate::Result<Option<CacheHit>> {
+        let variants = match self.storage.get(&key(req.method(), req.url())) {
+            Some(variants) => variants,
+            None => return Ok(None),
+        };
+        let entry = match variants
+            .iter()
+            .rev()
+            .find(|variant| variant.matches(req.as_ref()))
+        {
+            Some(entry) => entry.clone(),
+            None => return Ok(None),
+        };
+
+        let mut freshness = Freshness::new(
+            req,
+            &to_response(&entry),
+            entry.request_time,
+            entry.response_time,
+        )?;
+        freshness.set_shared(self.shared);
+        Ok(Some(CacheHit {
+            entry,
+            freshness,
+            now,
+        }))
+    }
+
+    /// Merge a `304 Not Modified` response to a revalidation request into
+    /// the stored response.
+    ///
+    /// The headers of `res` replace those of the stored response, except for
+    /// `Content-Length` and hop-by-hop headers. Returns the updated response, ready to be served, or
+    /// `None` if `res` is not a `304` or no matching response is stored.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the `Cache-Control` header of the request or the
+    /// updated response could not be parsed.
+    pub fn update(
+        &mut self,
+        req: &Request,
+        res: &Response,
+        request_time: SystemTime,
+        response_time: SystemTime,
+    ) -> crate::Result<Option<Response>> {
+        if res.status() != StatusCode::NotModified {
+            return Ok(None);
+        }
+        let key = key(req.method(), req.url());
+        let mut variants = match self.storage.get(&key) {
+            Some(variants) => variants.into_owned(),
+            None => return Ok(None),
+        };
+        let entry = match variants
+            .iter_mut()
+            .rev()
+            .find(|variant| variant.matches(req.as_ref()))
+        {
+            Some(entry) => entry,
+            None => return Ok(None),
+        };
+
+        // A 304 for a different representation must not be merged.
+        if let Some(etag) = res.header(ETAG) {
+            let stored = entry.headers.get(ETAG).map(|stored| stored.last().as_str());
+            if stored != Some(etag.last().as_str()) {
+                return Ok(None);
+            }
+        }
+
+        let mut headers = AsRef::<Headers>::as_ref(res).clone();
+        strip_hop_by_hop(&mut headers);
+        for (name, values) in headers.iter() {
+            if name != &CONTENT_LENGTH {
+                entry.headers.insert(name, values).unwrap();
+            }
+        }
+        entry.request_time = request_time;
+        entry.response_time = response_time;
+
+        let mut updated = to_response(entry);
+        let mut freshness = Freshness::new(req, &updated, request_time, response_time)?;
+        freshness.set_shared(self.shared);
+        Age::new(freshness.current_age(response_time)).apply_header(&mut updated);
+
+        self.storage.set(key, variants);
+        Ok(Some(updated))
+    }
+
+    /// Invalidate stored responses after `req` was answered with `res`.
+    ///
+    /// Successful responses to unsafe methods invalidate responses stored for
+    /// the request URL, and for the `Location` and `Content-Location` URLs
+    /// if they share its origin.
+    pub fn invalidate(&mut self, req: &Request, res: &Response) {
+        if req.method().is_safe() || !(res.status().is_success() || res.status().is_redirection()) {
+            return;
+        }
+
+        let mut urls = vec![req.url().clone()];
+        for name in [LOCATION, CONTENT_LOCATION] {
+            if let Some(values) = res.header(name) {
+                if let Ok(url) = req.url().join(values.last().as_str()) {
+                    if url.origin() == req.url().origin() {
+                        urls.push(url);
+                    }
+                }
+            }
+        }
+        for url in urls {
+            for method in [Method::Get, Method::Head] {
+                self.storage.remove(&key(method, &url));
+            }
+        }
+    }
+}
+
+/// A stored response found by [`Cache::lookup`].
+#[derive(Debug)]
+pub struct CacheHit {
+    entry: CacheEntry,
+    freshness: Freshness,
+    now: SystemTime,
+}
+
+impl CacheHit {
+    /// Get the stored entry.
+    pub fn entry(&self) -> &CacheEntry {
+        &self.entry
+    }
+
+    /// Get the freshness of the stored response.
+    pub fn freshness(&self) -> &Freshness {
+        &self.freshness
+    }
+
+    /// Returns `true` if the stored response may be served without
+    /// validation.
+    pub fn is_fresh(&self) -> bool {
+        self.freshness.is_fresh(self.now)
+    }
+
+    /// Returns `true` if the stored response must be validated with the
+    /// origin server before it is served.
+    pub fn must_revalidate(&self) -> bool {
+        self.freshness.must_revalidate(self.now)
+    }
+
+    /// Create a response from the stored entry, with an up to date `Age`
+    /// header.
+    pub fn response(&self) -> Response {
+        let mut res = to_response(&self.entry);
+        Age::new(self.freshness.current_age(self.now)).apply_header(&mut res);
+        res
+    }
+
+    /// Create a conditional request to revalidate the stored response.
+    ///
+    /// The request is a copy of `req` with `If-None-Match` and
+    /// `If-Modified-Since` headers taken from the stored `ETag` and
+    /// `Last-Modified` headers.
+    pub fn revalidation_request(&self, req: &Request) -> Request {
+        let mut req = req.clone();
+        if let Some(etag) = self.entry.headers.get(ETAG) {
+            req.insert_header(IF_NONE_MATCH, etag).unwrap();
+        }
+        if let Some(last_modified) = self.entry.headers.get(LAST_MODIFIED) {
+            req.insert_header(IF_MODIFIED_SINCE, last_modified).unwrap();
+        }
+        req
+    }
+}
+
+fn key(method: Method, url: &Url) -> String {
+    let mut url = url.clone();
+    url.set_fragment(None);
+    format!("{} {}", method, url)
+}
+
+fn directives(headers: impl AsRef<Headers>) -> Option<Vec<CacheDirective>> {
+    match CacheControl::from_headers(headers) {
+        Ok(Some(cache_control)) => Some(cache_control.iter().cloned().collect()),
+        Ok(None) => Some(vec![]),
+        Err(_) => None,
+    }
+}
+
+fn to_response(entry: &CacheEntry) -> Response {
+    let mut res = Response::new(entry.status);
+    res.set_version(entry.version);
+    res.set_body(Body::from_bytes(entry.body.to_vec()));
+    *AsMut::<Headers>::as_mut(&mut res) = entry.headers.clone();
+    res
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::time::Duration;
+
+    fn secs(secs: u64) -> Duration {
+        Duration::from_secs(secs)
+    }
+
+    #[async_std::test]
+    async fn vary() -> crate::Result<()> {
+        let mut cache = Cache::new();
+        let now = SystemTime::UNIX_EPOCH + secs(1_000_000);
+
+        for encoding in ["gzip", "br"] {
+            let mut req = Request::get("https://example.com/");
+            req.insert_header("Accept-Encoding", encoding)?;
+            let mut res = Response::new(200);
+            res.insert_header("Cache-Control", "max-age=60")?;
+            res.insert_header("Vary", "Accept-Encoding")?;
+            res.set_body(encoding);
+            assert!(cache.store(&req, &mut res, now, now).await?);
+            assert_eq!(res.body_string().await?, encoding);
+        }
+        assert_eq!(cache.storage().len(), 1);
+
+        let mut req = Request::get("https://example.com/#fragment");
+        req.insert_header("Accept-Encoding", "br")?;
+        let hit = cache.lookup(&req, now)?.unwrap();
+        assert_eq!(hit.response().body_string().await?, "br");
+        assert_eq!(hit.entry().vary().len(), 1);
+
+        req.insert_header("Accept-Encoding", "deflate")?;
+        assert!(cache.lookup(&req, now)?.is_none());
+        req.remove_header("Accept-Encoding");
+        assert!(cache.lookup(&req, now)?.is_none());
+        Ok(())
+    }
+
+    #[async_std::test]
+    async fn storability() -> crate::Result<()> {
+        let mut cache = Cache::new();
+        let req = Request::get("https://example.com/");
+        let mut res = Response::new(200);
+        res.insert_header("Cache-Control", "private, max-age=60")?;
+        assert!(cache.is_storable(&req, &res));
+        cache.set_shared(true);
+        assert!(!cache.is_storable(&req, &res));
+
+        res.insert_header("Cache-Control", "max-age=60")?;
+        assert!(cache.is_storable(&req, &res));
+        let mut authorized = req.clone();
+        authorized.insert_header("Authorization", "Bearer token")?;
+        assert!(!cache.is_storable(&authorized, &res));
+        res.insert_header("Cache-Control", "public, max-age=60")?;
+        assert!(cache.is_storable(&authorized, &res));
+
+        res.insert_header("Vary", "*")?;
+        assert!(!cache.is_storable(&req, &res));
+        res.remove_header("Vary");
+        res.insert_header("Cache-Control", "no-store")?;
+        assert!(!cache.is_storable(&req, &res));
+
+        let res = Response::new(StatusCode::Created);
+        assert!(!cache.is_storable(&req, &res));
+        let post = Request::post("https://example.com/");
+        assert!(!cache.is_storable(&post, &Response::new(200)));
+        Ok(())
+    }
+
+    #[async_std::test]
+    async fn revalidate() -> crate::Result<()> {
+        let mut cache = Cache::new();
+        let now = SystemTime::UNIX_EPOCH + secs(1_000_000);
+
+        let req = Request::get("https://example.com/");
+        let mut res = Response::new(200);
+        res.insert_header("Cache-Control", "no-cache")?;
+        res.insert_header("ETag", r#""v1""#)?;
+        res.insert_header("Last-Modified", "Wed, 21 Oct 2015 07:28:00 GMT")?;
+        res.insert_header("X-Version", "1")?;
+        res.set_body("cached");
+        assert!(cache.store(&req, &mut res, now, now).await?);
+
+        let hit = cache.lookup(&req, now)?.unwrap();
+        assert!(!hit.is_fresh());
+        assert!(hit.must_revalidate());
+        let revalidate = hit.revalidation_request(&req);
+        assert_eq!(revalidate["If-None-Match"], r#""v1""#);
+        assert_eq!(
+            revalidate["If-Modified-Since"],
+            "Wed, 21 Oct 2015 07:28:00 GMT"
+        );
+
+        let mut not_modified = Response::new(304);
+        not_modified.insert_header("ETag", r#""v2""#)?;
+        assert!(cache.update(&req, &not_modified, now, now)?.is_none());
+
+        let later = now + secs(30);
+        let mut not_modified = Response::new(304);
+        not_modified.insert_header("ETag", r#""v1""#)?;
+        not_modified.insert_header("X-Version", "2")?;
+        not_modified.insert_header("Content-Length", "0")?;
+        let mut updated = cache.update(&req, &not_modified, later, later)?.unwrap();
+        assert_eq!(updated.status(), 200);
+        assert_eq!(updated["X-Version"], "2");
+        assert_eq!(updated["Age"], "0");
+        assert!(updated.header("Content-Length").is_none());
+        assert_eq!(updated.body_string().await?, "cached");
+
+        let hit = cache.lookup(&req, later + secs(5))?.unwrap();
+        assert_eq!(hit.response()["X-Version"], "2");
+        assert_eq!(hit.response()["Age"], "5");
+        Ok(())
+    }
+
+    #[async_std::test]
+    async fn hop_by_hop_headers() -> crate::Result<()> {
+        let mut cache = Cache::new();
+        let now = SystemTime::UNIX_EPOCH + secs(1_000_000);
+
+        let req = Request::get("https://example.com/");
+        let mut res = Response::new(200);
+        res.insert_header("Cache-Control", "no-cache")?;
+        res.insert_header("ETag", r#""v1""#)?;
+        res.insert_header("Connection", "close, X-Foo")?;
+        res.insert_header("X-Foo", "bar")?;
+        assert!(cache.store(&req, &mut res, now, now).await?);
+
+        let hit = cache.lookup(&req, now)?.unwrap();
+        assert!(hit.response().header("Connection").is_none());
+        assert!(hit.response().header("X-Foo").is_none());
+
+        let mut not_modified = Response::new(304);
+        not_modified.insert_header("ETag", r#""v1""#)?;
+        not_modified.insert_header("Connection", "close, X-Foo")?;
+        not_modified.insert_header("X-Foo", "bar")?;
+        let updated = cache.update(&req, &not_modified, now, now)?.unwrap();
+        assert!(updated.header("Connection").is_none());
+        assert!(updated.header("X-Foo").is_none());
+
+        let hit = cache.lookup(&req, now)?.unwrap();
+        assert!(hit.response().header("Connection").is_none());
+        assert!(hit.response().header("X-Foo").is_none());
+        Ok(())
+    }
+
+    #[async_std::test]
+    async fn huge_age() -> crate::Result<()> {
+        let mut cache = Cache::new();
+        let now = SystemTime::UNIX_EPOCH + secs(1_000_000);
+
+        let req = Request::get("https://example.com/");
+        let mut res = Response::new(200);
+        res.insert_header("Cache-Control", "max-age=60")?;
+        res.insert_header("Age", "18446744073709549568")?;
+        cache.store(&req, &mut res, now, now + secs(5)).await?;
+
+        let hit = cache.lookup(&req, now + secs(10))?.unwrap();
+        assert!(!hit.is_fresh());
+        assert_eq!(hit.response()["Age"], "18446744073709549578");
+        Ok(())
+    }
+
+    #[async_std::test]
+    async fn invalidation() -> crate::Result<()> {
+        let mut cache = Cache::new();
+        let now = SystemTime::UNIX_EPOCH + secs(1_000_000);
+        for url in [
+            "https://example.com/items",
+            "https://example.com/items/1",
+            "https://other.example/items/1",
+        ] {
+            let req = Request::get(url);
+            let mut res = Response::new(200);
+            res.insert_header("Cache-Control", "max-age=60")?;
+            cache.store(&req, &mut res, now, now).await?;
+        }
+
+        let post = Request::post("https://example.com/items");
+        cache.invalidate(&post, &Response::new(500));
+        assert_eq!(cache.storage().len(), 3);
+
+        let mut res = Response::new(StatusCode::Created);
+        res.insert_header("Location", "/items/1")?;
+        res.insert_header("Content-Location", "https://other.example/items/1")?;
+        cache.invalidate(&post, &res);
+        assert_eq!(cache.storage().len(), 1);
+        assert!(cache
+            .lookup(&Request::get("https://other.example/items/1"), now)?
+            .is_some());
+        Ok(())
+    }
+}
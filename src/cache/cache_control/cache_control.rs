@@ -1,12 +1,12 @@
-use headers::Header;
-
-use crate::headers::{HeaderName, HeaderValue, Headers, CACHE_CONTROL};
-use crate::{cache::CacheDirective, headers};
+use crate::cache::CacheDirective;
+use crate::headers::{Header, HeaderName, HeaderValue, HeaderValues, Headers, CACHE_CONTROL};
+use crate::parse_utils::{parse_quoted_string, parse_token};
+use crate::{bail_status as bail, ensure_status as ensure};
 
 use std::fmt::{self, Debug, Write};
 use std::iter::Iterator;
-
 use std::slice;
+use std::time::Duration;
 
 /// A Cache-Control header.
 ///
@@ -43,29 +43,201 @@ impl CacheControl {
 
     /// Create a new instance from headers.
     pub fn from_headers(headers: impl AsRef<Headers>) -> crate::Result<Option<Self>> {
+        match headers.as_ref().get(CACHE_CONTROL) {
+            Some(values) => Ok(Some(Self::from_values(values)?)),
+            None => Ok(None),
+        }
+    }
+
+    /// Parse directives from header values.
+    ///
+    /// Directives which are not known are returned as
+    /// [`CacheDirective::Extension`].
+    pub(crate) fn from_values(values: &HeaderValues) -> crate::Result<Self> {
         let mut entries = vec![];
-        let headers = match headers.as_ref().get(CACHE_CONTROL) {
-            Some(headers) => headers,
-            None => return Ok(None),
-        };
-
-        for value in headers {
-            for part in value.as_str().trim().split(',') {
-                // Try and parse a directive from a str. If the directive is
-                // unkown we skip it.
-                if let Some(entry) = CacheDirective::from_str(part)? {
-                    entries.push(entry);
+        for value in values {
+            let mut input = value.as_str();
+            loop {
+                input = input.trim_start_matches([',', ' ', '\t']);
+                if input.is_empty() {
+                    break;
                 }
+
+                let (name, rest) = match parse_token(input) {
+                    (Some(name), rest) => (name, rest),
+                    (None, _) => bail!(400, "Invalid cache directive in `{}`", value),
+                };
+                let (arg, rest) = match rest.strip_prefix('=') {
+                    Some(rest) => match parse_token(rest) {
+                        (Some(token), rest) => (Some(token.into()), rest),
+                        (None, _) => match parse_quoted_string(rest) {
+                            (Some(quoted), rest) => (Some(quoted), rest),
+                            (None, _) => bail!(400, "Invalid cache directive in `{}`", value),
+                        },
+                    },
+                    None => (None, rest),
+                };
+                entries.push(CacheDirective::from_parts(name, arg)?);
+
+                input = rest.trim_start_matches([' ', '\t']);
+                ensure!(
+                    input.is_empty() || input.starts_with(','),
+                    400,
+                    "Invalid cache directive in `{}`",
+                    value
+                );
             }
         }
-
-        Ok(Some(Self { entries }))
+        Ok(Self { entries })
     }
+
     /// Push a directive into the list of entries.
     pub fn push(&mut self, directive: CacheDirective) {
         self.entries.push(directive);
     }
 
+    /// Returns the `max-age` directive, if any.
+    pub fn max_age(&self) -> Option<Duration> {
+        self.find(|d| match d {
+            CacheDirective::MaxAge(dur) => Some(*dur),
+            _ => None,
+        })
+    }
+
+    /// Returns the `s-maxage` directive, if any.
+    pub fn s_maxage(&self) -> Option<Duration> {
+        self.find(|d| match d {
+            CacheDirective::SMaxAge(dur) => Some(*dur),
+            _ => None,
+        })
+    }
+
+    /// Returns the `max-stale` directive, if any.
+    ///
+    /// The inner value is `None` if any amount of staleness is accepted.
+    pub fn max_stale(&self) -> Option<Option<Duration>> {
+        self.find(|d| match d {
+            CacheDirective::MaxStale(dur) => Some(*dur),
+            _ => None,
+        })
+    }
+
+    /// Returns the `min-fresh` directive, if any.
+    pub fn min_fresh(&self) -> Option<Duration> {
+        self.find(|d| match d {
+            CacheDirective::MinFresh(dur) => Some(*dur),
+            _ => None,
+        })
+    }
+
+    /// Returns the `stale-while-revalidate` directive, if any.
+    pub fn stale_while_revalidate(&self) -> Option<Duration> {
+        self.find(|d| match d {
+            CacheDirective::StaleWhileRevalidate(dur) => Some(*dur),
+            _ => None,
+        })
+    }
+
+    /// Returns the `stale-if-error` directive, if any.
+    pub fn stale_if_error(&self) -> Option<Duration> {
+        self.find(|d| match d {
+            CacheDirective::StaleIfError(dur) => Some(*dur),
+            _ => None,
+        })
+    }
+
+    /// Returns `true` if the `no-cache` directive is set without field
+    /// names.
+    pub fn is_no_cache(&self) -> bool {
+        self.contains(&CacheDirective::NoCache)
+    }
+
+    /// Returns the field names of the qualified `no-cache` directives.
+    pub fn no_cache_fields(&self) -> Vec<&HeaderName> {
+        self.entries
+            .iter()
+            .filter_map(|d| match d {
+                CacheDirective::NoCacheFields(fields) => Some(fields.iter()),
+                _ => None,
+            })
+            .flatten()
+            .collect()
+    }
+
+    /// Returns `true` if the `no-store` directive is set.
+    pub fn is_no_store(&self) -> bool {
+        self.contains(&CacheDirective::NoStore)
+    }
+
+    /// Returns `true` if the `no-transform` directive is set.
+    pub fn is_no_transform(&self) -> bool {
+        self.contains(&CacheDirective::NoTransform)
+    }
+
+    /// Returns `true` if the `only-if-cached` directive is set.
+    pub fn is_only_if_cached(&self) -> bool {
+        self.contains(&CacheDirective::OnlyIfCached)
+    }
+
+    /// Returns `true` if the `must-revalidate` directive is set.
+    pub fn is_must_revalidate(&self) -> bool {
+        self.contains(&CacheDirective::MustRevalidate)
+    }
+
+    /// Returns `true` if the `proxy-revalidate` directive is set.
+    pub fn is_proxy_revalidate(&self) -> bool {
+        self.contains(&CacheDirective::ProxyRevalidate)
+    }
+
+    /// Returns `true` if the `public` directive is set.
+    pub fn is_public(&self) -> bool {
+        self.contains(&CacheDirective::Public)
+    }
+
+    /// Returns `true` if the `private` directive is set without field names.
+    pub fn is_private(&self) -> bool {
+        self.contains(&CacheDirective::Private)
+    }
+
+    /// Returns the field names of the qualified `private` directives.
+    pub fn private_fields(&self) -> Vec<&HeaderName> {
+        self.entries
+            .iter()
+            .filter_map(|d| match d {
+                CacheDirective::PrivateFields(fields) => Some(fields.iter()),
+                _ => None,
+            })
+            .flatten()
+            .collect()
+    }
+
+    /// Returns `true` if the `immutable` directive is set.
+    pub fn is_immutable(&self) -> bool {
+        self.contains(&CacheDirective::Immutable)
+    }
+
+    /// Returns an extension directive by name.
+    ///
+    /// Names are compared case-insensitively. The inner value is the
+    /// argument of the directive, if any.
+    pub fn extension(&self, name: &str) -> Option<Option<&str>> {
+        self.find(|d| match d {
+            CacheDirective::Extension(extension) if extension.name().eq_ignore_ascii_case(name) => {
+                Some(extension.value())
+            }
+            _ => None,
+        })
+    }
+
+    /// Returns `true` if the directive is set.
+    pub fn contains(&self, directive: &CacheDirective) -> bool {
+        self.entries.contains(directive)
+    }
+
+    fn find<'a, T>(&'a self, f: impl FnMut(&'a CacheDirective) -> Option<T>) -> Option<T> {
+        self.entries.iter().find_map(f)
+    }
+
     /// An iterator visiting all server entries.
     pub fn iter(&self) -> Iter<'_> {
         Iter {
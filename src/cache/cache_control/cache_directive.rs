@@ -1,6 +1,9 @@
-use crate::headers::HeaderValue;
+use crate::cache::CacheExtension;
+use crate::headers::{HeaderName, HeaderValue};
 use crate::Status;
 
+use std::borrow::Cow;
+use std::str::FromStr;
 use std::time::Duration;
 
 /// An HTTP `Cache-Control` directive.
@@ -19,6 +22,9 @@ pub enum CacheDirective {
     MustRevalidate,
     /// The response may be cached, but must always be revalidated before being used.
     NoCache,
+    /// The listed header fields of the response must not be reused without
+    /// revalidation, the rest of the response may be.
+    NoCacheFields(Vec<HeaderName>),
     /// The response may not be cached.
     NoStore,
     /// An intermediate cache or proxy should not edit the response body,
@@ -29,6 +35,9 @@ pub enum CacheDirective {
     /// The response may be stored only by a browser's cache, even if the
     /// response is normally non-cacheable.
     Private,
+    /// The listed header fields of the response may be stored only by a
+    /// browser's cache, the rest of the response may be stored by any cache.
+    PrivateFields(Vec<HeaderName>),
     /// Like must-revalidate, but only for shared caches (e.g., proxies).
     ProxyRevalidate,
     /// The response may be stored by any cache, even if the response is normally
@@ -41,6 +50,10 @@ pub enum CacheDirective {
     /// Indicates the client will accept a stale response, while asynchronously
     /// checking in the background for a fresh one.
     StaleWhileRevalidate(Duration),
+    /// A directive which is not defined by this crate.
+    ///
+    /// Use [`CacheDirective::extension`] to create one.
+    Extension(CacheExtension),
 }
 
 impl CacheDirective {
@@ -49,7 +62,15 @@ impl CacheDirective {
         use CacheDirective::*;
         matches!(
             self,
-            MaxAge(_) | MaxStale(_) | MinFresh(_) | NoCache | NoStore | NoTransform | OnlyIfCached
+            MaxAge(_)
+                | MaxStale(_)
+                | MinFresh(_)
+                | NoCache
+                | NoStore
+                | NoTransform
+                | OnlyIfCached
+                | StaleIfError(_)
+                | Extension(..)
         )
     }
 
@@ -58,70 +79,88 @@ impl CacheDirective {
         use CacheDirective::*;
         matches!(
             self,
-            MustRevalidate
+            Immutable
+                | MustRevalidate
                 | NoCache
+                | NoCacheFields(_)
                 | NoStore
                 | NoTransform
                 | Public
                 | Private
+                | PrivateFields(_)
                 | ProxyRevalidate
                 | MaxAge(_)
                 | SMaxAge(_)
                 | StaleIfError(_)
                 | StaleWhileRevalidate(_)
+                | Extension(..)
         )
     }
 
-    /// Create an instance from a string slice.
-    //
-    // This is a private method rather than a trait because we assume the
-    // input string is a single-value only. This is upheld by the calling
-    // function, but we cannot guarantee this to be true in the general
-    // sense.
-    pub(crate) fn from_str(s: &str) -> crate::Result<Option<Self>> {
-        use CacheDirective::*;
-
-        let s = s.trim();
-
-        // We're dealing with an empty string.
-        if s.is_empty() {
-            return Ok(None);
-        }
+    /// Create a new extension directive.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the name is not a valid token, or the argument
+    /// contains control characters or non-ASCII characters.
+    pub fn extension(name: impl Into<String>, value: Option<String>) -> crate::Result<Self> {
+        let extension = CacheExtension::new(name.into(), value)?;
+        Ok(CacheDirective::Extension(extension))
+    }
 
-        let s = s.to_lowercase();
-        let mut parts = s.split('=');
-        let next = parts.next().unwrap();
+    /// Create an instance from a directive name and its unquoted argument.
+    ///
+    /// Directives which are not known are returned as `Extension`.
+    pub(crate) fn from_parts(name: &str, value: Option<Cow<'_, str>>) -> crate::Result<Self> {
+        use CacheDirective::*;
 
-        let mut get_dur = || -> crate::Result<Duration> {
-            let dur = parts.next().status(400)?;
-            let dur: u64 = dur.parse::<u64>().status(400)?;
-            Ok(Duration::new(dur, 0))
+        let dur = || -> crate::Result<Duration> {
+            let secs = value.as_deref().status(400)?;
+            let secs: u64 = secs.parse::<u64>().status(400)?;
+            Ok(Duration::new(secs, 0))
+        };
+        let fields = |value: &str| -> crate::Result<Vec<HeaderName>> {
+            value
+                .split(',')
+                .map(str::trim)
+                .filter(|name| !name.is_empty())
+                .map(HeaderName::from_str)
+                .collect::<crate::Result<_>>()
+                .map_err(|mut err| {
+                    err.set_status(400);
+                    err
+                })
         };
 
-        // This won't panic because each input string has at least one part.
-        let res = match next {
-            "immutable" => Some(Immutable),
-            "no-cache" => Some(NoCache),
-            "no-store" => Some(NoStore),
-            "no-transform" => Some(NoTransform),
-            "only-if-cached" => Some(OnlyIfCached),
-            "must-revalidate" => Some(MustRevalidate),
-            "public" => Some(Public),
-            "private" => Some(Private),
-            "proxy-revalidate" => Some(ProxyRevalidate),
-            "max-age" => Some(MaxAge(get_dur()?)),
-            "max-stale" => match parts.next() {
-                Some(secs) => {
-                    let dur: u64 = secs.parse::<u64>().status(400)?;
-                    Some(MaxStale(Some(Duration::new(dur, 0))))
-                }
-                None => Some(MaxStale(None)),
+        let res = match name.to_ascii_lowercase().as_str() {
+            "immutable" => Immutable,
+            "no-cache" => match value.as_deref() {
+                Some(value) => NoCacheFields(fields(value)?),
+                None => NoCache,
+            },
+            "no-store" => NoStore,
+            "no-transform" => NoTransform,
+            "only-if-cached" => OnlyIfCached,
+            "must-revalidate" => MustRevalidate,
+            "public" => Public,
+            "private" => match value.as_deref() {
+                Some(value) => PrivateFields(fields(value)?),
+                None => Private,
             },
-            "min-fresh" => Some(MinFresh(get_dur()?)),
-            "s-maxage" => Some(SMaxAge(get_dur()?)),
-            "stale-if-error" => Some(StaleIfError(get_dur()?)),
-            "stale-while-revalidate" => Some(StaleWhileRevalidate(get_dur()?)),
-            _ => None,
+            "proxy-revalidate" => ProxyRevalidate,
+            "max-age" => MaxAge(dur()?),
+            "max-stale" => match value {
+                Some(_) => MaxStale(Some(dur()?)),
+                None => MaxStale(None),
+            },
+            "min-fresh" => MinFresh(dur()?),
+            "s-maxage" => SMaxAge(dur()?),
+            "stale-if-error" => StaleIfError(dur()?),
+            "stale-while-revalidate" => StaleWhileRevalidate(dur()?),
+            _ => Self::extension(name, value.map(Cow::into_owned)).map_err(|mut err| {
+                err.set_status(400);
+                err
+            })?,
         };
         Ok(res)
     }
@@ -142,15 +181,23 @@ impl From<CacheDirective> for HeaderValue {
             MinFresh(dur) => h(format!("min-fresh={}", dur.as_secs())),
             MustRevalidate => h("must-revalidate".to_string()),
             NoCache => h("no-cache".to_string()),
+            NoCacheFields(fields) => h(format!(r#"no-cache="{}""#, join(&fields))),
             NoStore => h("no-store".to_string()),
             NoTransform => h("no-transform".to_string()),
             OnlyIfCached => h("only-if-cached".to_string()),
             Private => h("private".to_string()),
+            PrivateFields(fields) => h(format!(r#"private="{}""#, join(&fields))),
             ProxyRevalidate => h("proxy-revalidate".to_string()),
             Public => h("public".to_string()),
-            SMaxAge(dur) => h(format!("s-max-age={}", dur.as_secs())),
+            SMaxAge(dur) => h(format!("s-maxage={}", dur.as_secs())),
             StaleIfError(dur) => h(format!("stale-if-error={}", dur.as_secs())),
             StaleWhileRevalidate(dur) => h(format!("stale-while-revalidate={}", dur.as_secs())),
+            Extension(extension) => h(extension.to_string()),
         }
     }
 }
+
+fn join(fields: &[HeaderName]) -> String {
+    let fields: Vec<_> = fields.iter().map(HeaderName::as_str).collect();
+    fields.join(", ")
+}
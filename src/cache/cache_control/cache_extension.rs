@@ -0,0 +1,62 @@
+use crate::parse_utils::{is_quotable, is_token, parse_token};
+
+use std::fmt::{self, Display};
+
+/// A `Cache-Control` directive which is not defined by this crate.
+///
+/// The name is a token, and the argument only contains characters which can
+/// be sent in a quoted-string. Create one with [`CacheDirective::extension`].
+///
+/// [`CacheDirective::extension`]: crate::cache::CacheDirective::extension
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CacheExtension {
+    name: String,
+    value: Option<String>,
+}
+
+impl CacheExtension {
+    /// Create a new instance of `CacheExtension`.
+    pub(crate) fn new(name: String, value: Option<String>) -> crate::Result<Self> {
+        let value_valid = match &value {
+            Some(value) => is_quotable(value),
+            None => true,
+        };
+        crate::ensure!(
+            is_token(&name) && value_valid,
+            "Invalid Cache-Control extension directive `{}`",
+            name
+        );
+        Ok(Self { name, value })
+    }
+
+    /// Get the name of the directive.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Get the argument of the directive, if any.
+    pub fn value(&self) -> Option<&str> {
+        self.value.as_deref()
+    }
+}
+
+impl Display for CacheExtension {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.name)?;
+        let value = match &self.value {
+            Some(value) => value,
+            None => return Ok(()),
+        };
+        if let (Some(_), "") = parse_token(value) {
+            return write!(f, "={}", value);
+        }
+        f.write_str("=\"")?;
+        for ch in value.chars() {
+            if let '\\' | '"' = ch {
+                f.write_str("\\")?;
+            }
+            write!(f, "{}", ch)?;
+        }
+        f.write_str("\"")
+    }
+}
@@ -0,0 +1,86 @@
+use crate::cache::CacheControl;
+use crate::headers::{Header, HeaderName, HeaderValue, Headers, CDN_CACHE_CONTROL};
+
+use std::fmt::{self, Debug};
+use std::ops::{Deref, DerefMut};
+
+/// A CDN-Cache-Control header.
+///
+/// Uses the same directives as `Cache-Control`, but is only honoured by CDN
+/// caches, which ignore `Cache-Control` when it is present.
+///
+/// # Specifications
+///
+/// - [RFC 9213: Targeted HTTP Cache Control](https://www.rfc-editor.org/rfc/rfc9213)
+///
+/// # Examples
+///
+/// ```
+/// # fn main() -> http_types::Result<()> {
+/// #
+/// use http_types::Response;
+/// use http_types::cache::{CacheDirective, CdnCacheControl};
+/// use std::time::Duration;
+///
+/// let mut entries = CdnCacheControl::new();
+/// entries.push(CacheDirective::MaxAge(Duration::from_secs(600)));
+///
+/// let mut res = Response::new(200);
+/// res.insert_header(&entries, &entries);
+/// assert_eq!(res["CDN-Cache-Control"], "max-age=600");
+///
+/// let entries = CdnCacheControl::from_headers(res)?.unwrap();
+/// assert_eq!(entries.max_age(), Some(Duration::from_secs(600)));
+/// #
+/// # Ok(()) }
+/// ```
+pub struct CdnCacheControl {
+    inner: CacheControl,
+}
+
+impl CdnCacheControl {
+    /// Create a new instance of `CdnCacheControl`.
+    pub fn new() -> Self {
+        Self {
+            inner: CacheControl::new(),
+        }
+    }
+
+    /// Create a new instance from headers.
+    pub fn from_headers(headers: impl AsRef<Headers>) -> crate::Result<Option<Self>> {
+        match headers.as_ref().get(CDN_CACHE_CONTROL) {
+            Some(values) => Ok(Some(Self {
+                inner: CacheControl::from_values(values)?,
+            })),
+            None => Ok(None),
+        }
+    }
+}
+
+impl Header for CdnCacheControl {
+    fn header_name(&self) -> HeaderName {
+        CDN_CACHE_CONTROL
+    }
+    fn header_value(&self) -> HeaderValue {
+        self.inner.header_value()
+    }
+}
+
+impl Deref for CdnCacheControl {
+    type Target = CacheControl;
+    fn deref(&self) -> &Self::Target {
+        &self.inner
+    }
+}
+
+impl DerefMut for CdnCacheControl {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.inner
+    }
+}
+
+impl Debug for CdnCacheControl {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.inner.fmt(f)
+    }
+}
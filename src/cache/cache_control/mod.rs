@@ -5,18 +5,26 @@
 //! - [RFC 8246: HTTP Immutable Responses](https://tools.ietf.org/html/rfc8246)
 //! - [RFC 7234: Hypertext Transfer Protocol (HTTP/1.1): Caching](https://tools.ietf.org/html/rfc7234)
 //! - [RFC 5861: HTTP Cache-Control Extensions for Stale Content](https://tools.ietf.org/html/rfc5861)
+//! - [RFC 9213: Targeted HTTP Cache Control](https://www.rfc-editor.org/rfc/rfc9213)
 
 #[allow(clippy::module_inception)]
 mod cache_control;
 mod cache_directive;
+mod cache_extension;
+mod cdn_cache_control;
+mod surrogate_control;
 
 pub use cache_control::CacheControl;
 pub use cache_directive::CacheDirective;
+pub use cache_extension::CacheExtension;
+pub use cdn_cache_control::CdnCacheControl;
+pub use surrogate_control::SurrogateControl;
 
 #[cfg(test)]
 mod test {
     use super::*;
     use crate::headers::{Header, Headers, CACHE_CONTROL};
+    use std::time::Duration;
 
     #[test]
     fn smoke() -> crate::Result<()> {
@@ -35,12 +43,80 @@ mod test {
     }
 
     #[test]
-    fn ignore_unkonwn_directives() -> crate::Result<()> {
+    fn keep_unknown_directives() -> crate::Result<()> {
         let mut headers = Headers::new();
-        headers.insert(CACHE_CONTROL, "barrel_roll").unwrap();
+        headers
+            .insert(CACHE_CONTROL, r#"barrel_roll, community="UCI", max-age=5"#)
+            .unwrap();
+        let entries = CacheControl::from_headers(&headers)?.unwrap();
+        assert_eq!(entries.extension("Barrel_Roll"), Some(None));
+        assert_eq!(entries.extension("community"), Some(Some("UCI")));
+        assert_eq!(entries.extension("max-age"), None);
+
+        entries.apply_header(&mut headers);
+        assert_eq!(
+            headers[CACHE_CONTROL],
+            "barrel_roll, community=UCI, max-age=5"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn validate_extensions() -> crate::Result<()> {
+        let directive = CacheDirective::extension("community", Some("a \"b\"".into()))?;
+        let mut entries = CacheControl::new();
+        entries.push(directive);
+        assert_eq!(entries.header_value(), r#"community="a \"b\"""#);
+        assert_eq!(entries.extension("Community"), Some(Some("a \"b\"")));
+
+        assert!(CacheDirective::extension("bad name", None).is_err());
+        assert!(CacheDirective::extension("x\r\nSet-Cookie: a", None).is_err());
+        assert!(CacheDirective::extension("x", Some("a\r\nb".into())).is_err());
+        assert!(CacheDirective::extension("x", Some("caf\u{e9}".into())).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn field_names() -> crate::Result<()> {
+        let mut headers = Headers::new();
+        headers
+            .insert(
+                CACHE_CONTROL,
+                r#"no-cache="Set-Cookie, X-Token", private=Authorization, s-maxage=60"#,
+            )
+            .unwrap();
+        let entries = CacheControl::from_headers(&headers)?.unwrap();
+        assert!(!entries.is_no_cache());
+        assert!(!entries.is_private());
+        assert_eq!(entries.no_cache_fields(), ["set-cookie", "x-token"]);
+        assert_eq!(entries.private_fields(), ["authorization"]);
+        assert_eq!(entries.s_maxage(), Some(Duration::from_secs(60)));
+
+        entries.apply_header(&mut headers);
+        assert_eq!(
+            headers[CACHE_CONTROL],
+            r#"no-cache="set-cookie, x-token", private="authorization", s-maxage=60"#
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn getters() -> crate::Result<()> {
+        let mut headers = Headers::new();
+        headers
+            .insert(
+                CACHE_CONTROL,
+                "No-Store, max-age=\"10\", max-stale, stale-if-error=5, immutable",
+            )
+            .unwrap();
         let entries = CacheControl::from_headers(headers)?.unwrap();
-        let mut entries = entries.iter();
-        assert!(entries.next().is_none());
+        assert!(entries.is_no_store());
+        assert!(entries.is_immutable());
+        assert!(!entries.is_public());
+        assert_eq!(entries.max_age(), Some(Duration::from_secs(10)));
+        assert_eq!(entries.max_stale(), Some(None));
+        assert_eq!(entries.stale_if_error(), Some(Duration::from_secs(5)));
+        assert_eq!(entries.min_fresh(), None);
         Ok(())
     }
 
@@ -50,5 +126,17 @@ mod test {
         headers.insert(CACHE_CONTROL, "min-fresh=0.9").unwrap(); // floats are not supported
         let err = CacheControl::from_headers(headers).unwrap_err();
         assert_eq!(err.status(), 400);
+
+        for input in [
+            "max-age=",
+            "no-cache=\"unterminated",
+            "max-age=5 junk",
+            "=5",
+        ] {
+            let mut headers = Headers::new();
+            headers.insert(CACHE_CONTROL, input).unwrap();
+            let err = CacheControl::from_headers(headers).unwrap_err();
+            assert_eq!(err.status(), 400, "{} should not parse", input);
+        }
     }
 }
@@ -0,0 +1,90 @@
+use crate::cache::CacheControl;
+use crate::headers::{Header, HeaderName, HeaderValue, Headers, SURROGATE_CONTROL};
+
+use std::fmt::{self, Debug};
+use std::ops::{Deref, DerefMut};
+
+/// A Surrogate-Control header.
+///
+/// Uses the same directives as `Cache-Control`, but is only honoured by
+/// surrogates such as reverse proxies and CDNs, which remove it before
+/// forwarding the response. Surrogate-specific directives such as
+/// `no-store-remote` and `content` are available as extensions.
+///
+/// # Specifications
+///
+/// - [Edge Architecture Specification 1.0](https://www.w3.org/TR/edge-arch/)
+///
+/// # Examples
+///
+/// ```
+/// # fn main() -> http_types::Result<()> {
+/// #
+/// use http_types::Response;
+/// use http_types::cache::{CacheDirective, SurrogateControl};
+/// use std::time::Duration;
+///
+/// let mut entries = SurrogateControl::new();
+/// entries.push(CacheDirective::MaxAge(Duration::from_secs(600)));
+/// entries.push(CacheDirective::extension("content", Some("ESI/1.0".into()))?);
+///
+/// let mut res = Response::new(200);
+/// res.insert_header(&entries, &entries);
+/// assert_eq!(res["Surrogate-Control"], r#"max-age=600, content="ESI/1.0""#);
+///
+/// let entries = SurrogateControl::from_headers(res)?.unwrap();
+/// assert_eq!(entries.max_age(), Some(Duration::from_secs(600)));
+/// assert_eq!(entries.extension("content"), Some(Some("ESI/1.0")));
+/// #
+/// # Ok(()) }
+/// ```
+pub struct SurrogateControl {
+    inner: CacheControl,
+}
+
+impl SurrogateControl {
+    /// Create a new instance of `SurrogateControl`.
+    pub fn new() -> Self {
+        Self {
+            inner: CacheControl::new(),
+        }
+    }
+
+    /// Create a new instance from headers.
+    pub fn from_headers(headers: impl AsRef<Headers>) -> crate::Result<Option<Self>> {
+        match headers.as_ref().get(SURROGATE_CONTROL) {
+            Some(values) => Ok(Some(Self {
+                inner: CacheControl::from_values(values)?,
+            })),
+            None => Ok(None),
+        }
+    }
+}
+
+impl Header for SurrogateControl {
+    fn header_name(&self) -> HeaderName {
+        SURROGATE_CONTROL
+    }
+    fn header_value(&self) -> HeaderValue {
+        self.inner.header_value()
+    }
+}
+
+impl Deref for SurrogateControl {
+    type Target = CacheControl;
+    fn deref(&self) -> &Self::Target {
+        &self.inner
+    }
+}
+
+impl DerefMut for SurrogateControl {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.inner
+    }
+}
+
+impl Debug for SurrogateControl {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.inner.fmt(f)
+    }
+}
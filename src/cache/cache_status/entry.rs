@@ -0,0 +1,253 @@
+use crate::cache::ForwardReason;
+use crate::sf::{self, is_printable, write_string, write_token_or_string, Item};
+use crate::{bail_status as bail, ensure_status as ensure};
+
+use std::fmt::{self, Display};
+
+/// A single cache in a `Cache-Status` header.
+///
+/// Each entry names the cache that handled the response and describes how
+/// it did so.
+///
+/// # Specifications
+///
+/// - [RFC 9211, section 2: The Cache-Status HTTP Response Header Field](https://www.rfc-editor.org/rfc/rfc9211#section-2)
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct CacheStatusEntry {
+    cache: String,
+    hit: bool,
+    fwd: Option<ForwardReason>,
+    fwd_status: Option<u16>,
+    ttl: Option<i64>,
+    stored: bool,
+    collapsed: bool,
+    key: Option<String>,
+    detail: Option<String>,
+}
+
+impl CacheStatusEntry {
+    /// Create a new instance of `CacheStatusEntry`.
+    ///
+    /// `cache` identifies the cache, usually by product name or hostname.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `cache` is empty or contains control or non-ASCII
+    /// characters.
+    pub fn new(cache: impl Into<String>) -> crate::Result<Self> {
+        let cache = cache.into();
+        ensure!(!cache.is_empty(), 400, "Cache-Status cache name is empty");
+        ensure!(
+            is_printable(&cache),
+            400,
+            "Invalid Cache-Status cache name `{}`",
+            cache
+        );
+        Ok(Self {
+            cache,
+            hit: false,
+            fwd: None,
+            fwd_status: None,
+            ttl: None,
+            stored: false,
+            collapsed: false,
+            key: None,
+            detail: None,
+        })
+    }
+
+    /// Get the name of the cache.
+    pub fn cache(&self) -> &str {
+        &self.cache
+    }
+
+    /// Returns `true` if the request was satisfied by the cache without
+    /// contacting the next hop.
+    pub fn is_hit(&self) -> bool {
+        self.hit
+    }
+
+    /// Set whether the request was satisfied by the cache.
+    pub fn set_hit(&mut self, hit: bool) {
+        self.hit = hit;
+    }
+
+    /// Get the reason the request was forwarded towards the origin, if it
+    /// was.
+    pub fn fwd(&self) -> Option<ForwardReason> {
+        self.fwd
+    }
+
+    /// Set the reason the request was forwarded towards the origin.
+    pub fn set_fwd(&mut self, fwd: Option<ForwardReason>) {
+        self.fwd = fwd;
+    }
+
+    /// Get the status code the next hop responded with, if the request was
+    /// forwarded.
+    pub fn fwd_status(&self) -> Option<u16> {
+        self.fwd_status
+    }
+
+    /// Set the status code the next hop responded with.
+    pub fn set_fwd_status(&mut self, fwd_status: Option<u16>) {
+        self.fwd_status = fwd_status;
+    }
+
+    /// Get the remaining freshness lifetime of the response, in seconds.
+    ///
+    /// This is negative if the response is stale.
+    pub fn ttl(&self) -> Option<i64> {
+        self.ttl
+    }
+
+    /// Set the remaining freshness lifetime of the response, in seconds.
+    pub fn set_ttl(&mut self, ttl: Option<i64>) {
+        self.ttl = ttl;
+    }
+
+    /// Returns `true` if the cache stored the response it forwarded.
+    pub fn is_stored(&self) -> bool {
+        self.stored
+    }
+
+    /// Set whether the cache stored the response it forwarded.
+    pub fn set_stored(&mut self, stored: bool) {
+        self.stored = stored;
+    }
+
+    /// Returns `true` if the request was collapsed with other requests to the
+    /// next hop.
+    pub fn is_collapsed(&self) -> bool {
+        self.collapsed
+    }
+
+    /// Set whether the request was collapsed with other requests.
+    pub fn set_collapsed(&mut self, collapsed: bool) {
+        self.collapsed = collapsed;
+    }
+
+    /// Get the implementation-specific cache key of the response, if any.
+    pub fn key(&self) -> Option<&str> {
+        self.key.as_deref()
+    }
+
+    /// Set the implementation-specific cache key of the response.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the key contains control or non-ASCII characters.
+    pub fn set_key(&mut self, key: Option<String>) -> crate::Result<()> {
+        if let Some(key) = &key {
+            ensure!(is_printable(key), 400, "Invalid Cache-Status key");
+        }
+        self.key = key;
+        Ok(())
+    }
+
+    /// Get additional implementation-specific details, if any.
+    pub fn detail(&self) -> Option<&str> {
+        self.detail.as_deref()
+    }
+
+    /// Set additional implementation-specific details.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the detail contains control or non-ASCII
+    /// characters.
+    pub fn set_detail(&mut self, detail: Option<String>) -> crate::Result<()> {
+        if let Some(detail) = &detail {
+            ensure!(is_printable(detail), 400, "Invalid Cache-Status detail");
+        }
+        self.detail = detail;
+        Ok(())
+    }
+
+    /// Parse a single list member, returning the remaining input.
+    pub(super) fn parse(input: &str) -> crate::Result<(Self, &str)> {
+        let (cache, rest) = match sf::parse_bare_item(input)? {
+            (Item::String(cache), rest) | (Item::Token(cache), rest) => (cache, rest),
+            _ => bail!(400, "Cache-Status cache name must be a string or token"),
+        };
+        let mut entry = Self::new(cache)?;
+
+        let (params, rest) = sf::parse_params(rest)?;
+        for (key, value) in params {
+            entry.set_param(&key, value)?;
+        }
+
+        Ok((entry, rest))
+    }
+
+    /// Set a parameter from its parsed value.
+    ///
+    /// Unknown parameters are ignored.
+    fn set_param(&mut self, key: &str, value: Item) -> crate::Result<()> {
+        match (key, value) {
+            ("hit", Item::Boolean(hit)) => self.hit = hit,
+            ("fwd", Item::Token(fwd)) => self.fwd = Some(fwd.parse()?),
+            ("fwd-status", Item::Integer(status)) => {
+                ensure!(
+                    (100..=999).contains(&status),
+                    400,
+                    "Invalid Cache-Status fwd-status `{}`",
+                    status
+                );
+                self.fwd_status = Some(status as u16);
+            }
+            ("ttl", Item::Integer(ttl)) => self.ttl = Some(ttl),
+            ("stored", Item::Boolean(stored)) => self.stored = stored,
+            ("collapsed", Item::Boolean(collapsed)) => self.collapsed = collapsed,
+            ("key", Item::String(key)) => self.key = Some(key),
+            ("detail", Item::String(detail)) | ("detail", Item::Token(detail)) => {
+                self.detail = Some(detail)
+            }
+            ("hit", _)
+            | ("fwd", _)
+            | ("fwd-status", _)
+            | ("ttl", _)
+            | ("stored", _)
+            | ("collapsed", _)
+            | ("key", _)
+            | ("detail", _) => {
+                bail!(400, "Invalid value for Cache-Status parameter `{}`", key)
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+}
+
+impl Display for CacheStatusEntry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write_token_or_string(f, &self.cache)?;
+        if self.hit {
+            f.write_str(";hit")?;
+        }
+        if let Some(fwd) = self.fwd {
+            write!(f, ";fwd={}", fwd)?;
+        }
+        if let Some(status) = self.fwd_status {
+            write!(f, ";fwd-status={}", status)?;
+        }
+        if let Some(ttl) = self.ttl {
+            write!(f, ";ttl={}", ttl)?;
+        }
+        if self.stored {
+            f.write_str(";stored")?;
+        }
+        if self.collapsed {
+            f.write_str(";collapsed")?;
+        }
+        if let Some(key) = &self.key {
+            f.write_str(";key=")?;
+            write_string(f, key)?;
+        }
+        if let Some(detail) = &self.detail {
+            f.write_str(";detail=")?;
+            write_token_or_string(f, detail)?;
+        }
+        Ok(())
+    }
+}
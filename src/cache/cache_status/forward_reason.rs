@@ -0,0 +1,81 @@
+use std::fmt::{self, Display};
+use std::str::FromStr;
+
+use crate::bail_status as bail;
+
+/// The reason a cache forwarded a request, as reported by the `fwd`
+/// parameter of a `Cache-Status` entry.
+///
+/// # Specifications
+///
+/// - [RFC 9211, section 2.2: The fwd parameter](https://www.rfc-editor.org/rfc/rfc9211#section-2.2)
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub enum ForwardReason {
+    /// The cache was configured to not handle this request.
+    Bypass,
+    /// The request method's semantics require the request to be forwarded.
+    Method,
+    /// The cache did not contain any responses that matched the request URI.
+    UriMiss,
+    /// The cache contained a response that matched the request URI, but it
+    /// could not select a response based upon this request's headers and
+    /// stored `Vary` headers.
+    VaryMiss,
+    /// The cache did not contain any responses that could be used to satisfy
+    /// this request, for an unspecified reason.
+    Miss,
+    /// The cache was able to select a fresh response for the request, but the
+    /// request's semantics (e.g. `Cache-Control: max-age=0`) did not allow its
+    /// use.
+    Request,
+    /// The cache was able to select a response for the request, but it was
+    /// stale.
+    Stale,
+    /// The cache was able to select a partial response for the request, but
+    /// it did not contain all of the requested ranges.
+    Partial,
+}
+
+impl ForwardReason {
+    /// Get the formatted string.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Bypass => "bypass",
+            Self::Method => "method",
+            Self::UriMiss => "uri-miss",
+            Self::VaryMiss => "vary-miss",
+            Self::Miss => "miss",
+            Self::Request => "request",
+            Self::Stale => "stale",
+            Self::Partial => "partial",
+        }
+    }
+}
+
+impl Display for ForwardReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for ForwardReason {
+    type Err = crate::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "bypass" => Ok(Self::Bypass),
+            "method" => Ok(Self::Method),
+            "uri-miss" => Ok(Self::UriMiss),
+            "vary-miss" => Ok(Self::VaryMiss),
+            "miss" => Ok(Self::Miss),
+            "request" => Ok(Self::Request),
+            "stale" => Ok(Self::Stale),
+            "partial" => Ok(Self::Partial),
+            s => bail!(
+                400,
+                "`{}` is not a recognized Cache-Status forward reason",
+                s
+            ),
+        }
+    }
+}
@@ -0,0 +1,350 @@
+//! Report how caches handled a response.
+
+use crate::headers::{Header, HeaderName, HeaderValue, Headers, CACHE_STATUS};
+use crate::{bail_status as bail, ensure_status as ensure};
+
+use std::fmt::{self, Debug, Write};
+use std::iter::Iterator;
+use std::slice;
+
+mod entry;
+mod forward_reason;
+
+pub use entry::CacheStatusEntry;
+pub use forward_reason::ForwardReason;
+
+/// Report how caches handled a response.
+///
+/// Each cache which handles a response appends an entry describing whether
+/// it was a hit, and if not why the request was forwarded. The first entry is
+/// the cache closest to the origin server, the last one the cache closest to
+/// the user agent.
+///
+/// # Specifications
+///
+/// - [RFC 9211: The Cache-Status HTTP Response Header Field](https://www.rfc-editor.org/rfc/rfc9211)
+///
+/// # Examples
+///
+/// ```
+/// # fn main() -> http_types::Result<()> {
+/// #
+/// use http_types::Response;
+/// use http_types::headers::Header;
+/// use http_types::cache::{CacheStatus, CacheStatusEntry, ForwardReason};
+///
+/// let mut res = Response::new(200);
+///
+/// let mut origin = CacheStatusEntry::new("OriginCache")?;
+/// origin.set_fwd(Some(ForwardReason::UriMiss));
+/// origin.set_stored(true);
+/// CacheStatus::append_entry(&mut res, origin);
+///
+/// let mut cdn = CacheStatusEntry::new("CDN Company Here")?;
+/// cdn.set_hit(true);
+/// cdn.set_ttl(Some(545));
+/// CacheStatus::append_entry(&mut res, cdn);
+///
+/// let status = CacheStatus::from_headers(res)?.unwrap();
+/// let mut entries = status.iter();
+/// assert_eq!(entries.next().unwrap().fwd(), Some(ForwardReason::UriMiss));
+/// assert!(entries.next().unwrap().is_hit());
+/// assert_eq!(
+///     status.header_value(),
+///     r#"OriginCache;fwd=uri-miss;stored, "CDN Company Here";hit;ttl=545"#
+/// );
+/// #
+/// # Ok(()) }
+/// ```
+#[derive(Clone, Eq, PartialEq)]
+pub struct CacheStatus {
+    entries: Vec<CacheStatusEntry>,
+}
+
+impl CacheStatus {
+    /// Create a new instance of `CacheStatus`.
+    pub fn new() -> Self {
+        Self { entries: vec![] }
+    }
+
+    /// Create a new instance from headers.
+    pub fn from_headers(headers: impl AsRef<Headers>) -> crate::Result<Option<Self>> {
+        let headers = match headers.as_ref().get(CACHE_STATUS) {
+            Some(headers) => headers,
+            None => return Ok(None),
+        };
+
+        let mut entries = vec![];
+        for value in headers {
+            let mut input = value.as_str().trim_start_matches([' ', '\t']);
+            while !input.is_empty() {
+                let (entry, rest) = CacheStatusEntry::parse(input)?;
+                entries.push(entry);
+                let rest = rest.trim_start_matches([' ', '\t']);
+                input = match rest.strip_prefix(',') {
+                    Some(rest) => {
+                        let rest = rest.trim_start_matches([' ', '\t']);
+                        ensure!(
+                            !rest.is_empty(),
+                            400,
+                            "Trailing comma in Cache-Status header"
+                        );
+                        rest
+                    }
+                    None if rest.is_empty() => rest,
+                    None => bail!(400, "Unexpected characters after Cache-Status entry"),
+                };
+            }
+        }
+
+        Ok(Some(Self { entries }))
+    }
+
+    /// Append an entry to the `Cache-Status` header of a response.
+    ///
+    /// Existing entries added by caches closer to the origin server are kept.
+    pub fn append_entry(mut headers: impl AsMut<Headers>, entry: CacheStatusEntry) {
+        headers
+            .as_mut()
+            .append(CACHE_STATUS, entry.to_string())
+            .unwrap();
+    }
+
+    /// Push an entry into the list of entries.
+    pub fn push(&mut self, entry: CacheStatusEntry) {
+        self.entries.push(entry);
+    }
+
+    /// Get the entry added by the cache named `cache`, if any.
+    pub fn get(&self, cache: &str) -> Option<&CacheStatusEntry> {
+        self.entries.iter().find(|entry| entry.cache() == cache)
+    }
+
+    /// An iterator visiting all entries.
+    pub fn iter(&self) -> Iter<'_> {
+        Iter {
+            inner: self.entries.iter(),
+        }
+    }
+
+    /// An iterator visiting all entries.
+    pub fn iter_mut(&mut self) -> IterMut<'_> {
+        IterMut {
+            inner: self.entries.iter_mut(),
+        }
+    }
+}
+
+impl Header for CacheStatus {
+    fn header_name(&self) -> HeaderName {
+        CACHE_STATUS
+    }
+
+    fn header_value(&self) -> HeaderValue {
+        let mut output = String::new();
+        for (n, entry) in self.entries.iter().enumerate() {
+            match n {
+                0 => write!(output, "{}", entry).unwrap(),
+                _ => write!(output, ", {}", entry).unwrap(),
+            };
+        }
+
+        // SAFETY: the internal string is validated to be ASCII.
+        unsafe { HeaderValue::from_bytes_unchecked(output.into()) }
+    }
+}
+
+impl IntoIterator for CacheStatus {
+    type Item = CacheStatusEntry;
+    type IntoIter = IntoIter;
+
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        IntoIter {
+            inner: self.entries.into_iter(),
+        }
+    }
+}
+
+impl<'a> IntoIterator for &'a CacheStatus {
+    type Item = &'a CacheStatusEntry;
+    type IntoIter = Iter<'a>;
+
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<'a> IntoIterator for &'a mut CacheStatus {
+    type Item = &'a mut CacheStatusEntry;
+    type IntoIter = IterMut<'a>;
+
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter_mut()
+    }
+}
+
+/// A borrowing iterator over entries in `CacheStatus`.
+#[derive(Debug)]
+pub struct IntoIter {
+    inner: std::vec::IntoIter<CacheStatusEntry>,
+}
+
+impl Iterator for IntoIter {
+    type Item = CacheStatusEntry;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next()
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+/// A lending iterator over entries in `CacheStatus`.
+#[derive(Debug)]
+pub struct Iter<'a> {
+    inner: slice::Iter<'a, CacheStatusEntry>,
+}
+
+impl<'a> Iterator for Iter<'a> {
+    type Item = &'a CacheStatusEntry;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next()
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+/// A mutable iterator over entries in `CacheStatus`.
+#[derive(Debug)]
+pub struct IterMut<'a> {
+    inner: slice::IterMut<'a, CacheStatusEntry>,
+}
+
+impl<'a> Iterator for IterMut<'a> {
+    type Item = &'a mut CacheStatusEntry;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next()
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl Debug for CacheStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut list = f.debug_list();
+        for entry in &self.entries {
+            list.entry(entry);
+        }
+        list.finish()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::Response;
+
+    #[test]
+    fn smoke() -> crate::Result<()> {
+        let mut entry = CacheStatusEntry::new("ExampleCache")?;
+        entry.set_fwd(Some(ForwardReason::Stale));
+        entry.set_fwd_status(Some(304));
+        entry.set_collapsed(true);
+        entry.set_ttl(Some(-412));
+        entry.set_key(Some("GET /a \"b\"".into()))?;
+
+        let mut status = CacheStatus::new();
+        status.push(entry.clone());
+
+        let mut res = Response::new(200);
+        status.apply_header(&mut res);
+        assert_eq!(
+            res[CACHE_STATUS],
+            r#"ExampleCache;fwd=stale;fwd-status=304;ttl=-412;collapsed;key="GET /a \"b\"""#
+        );
+
+        let status = CacheStatus::from_headers(res)?.unwrap();
+        assert_eq!(status.get("ExampleCache"), Some(&entry));
+        Ok(())
+    }
+
+    #[test]
+    fn parse_rfc_examples() -> crate::Result<()> {
+        let mut res = Response::new(200);
+        res.append_header(
+            "Cache-Status",
+            r#"OriginCache; hit; ttl=1100, "CDN Company Here"; hit; ttl=545"#,
+        )
+        .unwrap();
+        res.append_header(
+            "Cache-Status",
+            "ReverseProxyCache; fwd=uri-miss; stored; detail=MEMORY",
+        )
+        .unwrap();
+
+        let status = CacheStatus::from_headers(res)?.unwrap();
+        let entries: Vec<_> = status.iter().collect();
+        assert_eq!(entries.len(), 3);
+        assert_eq!(entries[0].cache(), "OriginCache");
+        assert!(entries[0].is_hit());
+        assert_eq!(entries[0].ttl(), Some(1100));
+        assert_eq!(entries[1].cache(), "CDN Company Here");
+        assert_eq!(entries[1].ttl(), Some(545));
+        assert!(!entries[2].is_hit());
+        assert_eq!(entries[2].fwd(), Some(ForwardReason::UriMiss));
+        assert!(entries[2].is_stored());
+        assert_eq!(entries[2].detail(), Some("MEMORY"));
+        Ok(())
+    }
+
+    #[test]
+    fn ignore_unknown_parameters() -> crate::Result<()> {
+        let mut res = Response::new(200);
+        res.insert_header(
+            "Cache-Status",
+            "ExampleCache; hit=?0; x-shard=3; x-ratio=0.5; x-tag=:AQID:; stored",
+        )
+        .unwrap();
+
+        let status = CacheStatus::from_headers(res)?.unwrap();
+        let entry = status.get("ExampleCache").unwrap();
+        assert!(!entry.is_hit());
+        assert!(entry.is_stored());
+        assert_eq!(status.header_value(), "ExampleCache;stored");
+        Ok(())
+    }
+
+    #[test]
+    fn bad_request_on_parse_error() {
+        let inputs = [
+            "ExampleCache,",
+            "ExampleCache; fwd=unknown",
+            "ExampleCache; fwd=\"stale\"",
+            "ExampleCache; ttl=soon",
+            "ExampleCache; Hit",
+            "\"unterminated",
+            "ExampleCache junk",
+            "42",
+        ];
+        for input in inputs {
+            let mut res = Response::new(200);
+            res.insert_header("Cache-Status", input).unwrap();
+            let err = CacheStatus::from_headers(res).unwrap_err();
+            assert_eq!(err.status(), 400, "{:?} should not parse", input);
+        }
+    }
+}
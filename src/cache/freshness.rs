@@ -0,0 +1,472 @@
+use crate::cache::{Age, CacheControl, CacheDirective, Expires};
+use crate::conditional::LastModified;
+use crate::other::Date;
+use crate::{Request, Response, StatusCode};
+
+use std::time::{Duration, SystemTime};
+
+/// The freshness of a stored response.
+///
+/// `Freshness` combines the `Date`, `Age`, `Expires`, `Last-Modified` and
+/// `Cache-Control` headers of a response with the times at which the
+/// request was sent and the response was received, to decide whether a cache
+/// may reuse the response without contacting the origin server.
+///
+/// The `Cache-Control` directives of the request are taken into account as
+/// well, so pass the request which is about to be served from the cache.
+///
+/// # Specifications
+///
+/// - [RFC 9111, section 4.2: Freshness](https://www.rfc-editor.org/rfc/rfc9111#section-4.2)
+/// - [RFC 5861: HTTP Cache-Control Extensions for Stale Content](https://tools.ietf.org/html/rfc5861)
+///
+/// # Examples
+///
+/// ```
+/// # fn main() -> http_types::Result<()> {
+/// #
+/// use http_types::cache::Freshness;
+/// use http_types::{Request, Response};
+/// use std::time::{Duration, SystemTime};
+///
+/// let sent = SystemTime::now();
+/// let received = sent + Duration::from_secs(1);
+///
+/// let req = Request::get("http://example.com");
+/// let mut res = Response::new(200);
+/// res.insert_header("Cache-Control", "max-age=60, stale-while-revalidate=30");
+/// res.insert_header("Age", "10");
+///
+/// let freshness = Freshness::new(&req, &res, sent, received)?;
+/// assert_eq!(freshness.freshness_lifetime(), Duration::from_secs(60));
+/// assert_eq!(freshness.current_age(received), Duration::from_secs(11));
+/// assert!(freshness.is_fresh(received));
+///
+/// let later = received + Duration::from_secs(60);
+/// assert!(!freshness.is_fresh(later));
+/// assert!(freshness.may_serve_stale_while_revalidate(later));
+/// assert!(freshness.must_revalidate(later));
+/// #
+/// # Ok(()) }
+/// ```
+#[derive(Debug, Clone)]
+pub struct Freshness {
+    shared: bool,
+    status: StatusCode,
+    response_time: SystemTime,
+    corrected_initial_age: Duration,
+    date: SystemTime,
+    expires: Option<Option<SystemTime>>,
+    last_modified: Option<SystemTime>,
+    req_directives: Vec<CacheDirective>,
+    res_directives: Vec<CacheDirective>,
+}
+
+impl Freshness {
+    /// Create a new instance of `Freshness` for a private cache.
+    ///
+    /// `request_time` is the time the request which produced `res` was sent,
+    /// and `response_time` the time at which `res` was received.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the `Cache-Control` header of either message
+    /// could not be parsed. Invalid `Date` and `Age` headers are ignored, and
+    /// an invalid `Expires` header marks the response as already expired.
+    pub fn new(
+        req: &Request,
+        res: &Response,
+        request_time: SystemTime,
+        response_time: SystemTime,
+    ) -> crate::Result<Self> {
+        let req_directives = directives(req)?;
+        let res_directives = directives(res)?;
+
+        let date = match Date::from_headers(res) {
+            Ok(Some(date)) => date.into(),
+            _ => response_time,
+        };
+        let age = match Age::from_headers(res) {
+            Ok(Some(age)) => age.duration(),
+            _ => Duration::ZERO,
+        };
+        let expires = match Expires::from_headers(res) {
+            Ok(Some(expires)) => Some(Some(expires.expiration())),
+            Ok(None) => None,
+            Err(_) => Some(None),
+        };
+        let last_modified = match LastModified::from_headers(res) {
+            Ok(Some(last_modified)) => Some(last_modified.modified()),
+            _ => None,
+        };
+
+        let apparent_age = elapsed(date, response_time);
+        let response_delay = elapsed(request_time, response_time);
+        let corrected_age_value = age.saturating_add(response_delay);
+        let corrected_initial_age = apparent_age.max(corrected_age_value);
+
+        Ok(Self {
+            shared: false,
+            status: res.status(),
+            response_time,
+            corrected_initial_age,
+            date,
+            expires,
+            last_modified,
+            req_directives,
+            res_directives,
+        })
+    }
+
+    /// Returns `true` if freshness is calculated for a shared cache.
+    pub fn is_shared(&self) -> bool {
+        self.shared
+    }
+
+    /// Set whether freshness is calculated for a shared cache, such as a
+    /// proxy or CDN.
+    ///
+    /// Shared caches honour `s-maxage` and `proxy-revalidate`.
+    pub fn set_shared(&mut self, shared: bool) {
+        self.shared = shared;
+    }
+
+    /// Get the age of the response at `now`.
+    pub fn current_age(&self, now: SystemTime) -> Duration {
+        self.corrected_initial_age
+            .saturating_add(elapsed(self.response_time, now))
+    }
+
+    /// Get the amount of time the response is fresh for after it was
+    /// generated.
+    pub fn freshness_lifetime(&self) -> Duration {
+        self.explicit_lifetime()
+            .or_else(|| self.heuristic_lifetime())
+            .unwrap_or(Duration::ZERO)
+    }
+
+    /// Returns `true` if the freshness lifetime was not set by the origin
+    /// server, but estimated from `Last-Modified`.
+    pub fn is_heuristic(&self) -> bool {
+        self.explicit_lifetime().is_none() && self.heuristic_lifetime().is_some()
+    }
+
+    /// Get how long the response has been stale for at `now`.
+    pub fn staleness(&self, now: SystemTime) -> Duration {
+        self.current_age(now)
+            .saturating_sub(self.freshness_lifetime())
+    }
+
+    /// Returns `true` if the response may be served without validation at
+    /// `now`.
+    ///
+    /// This takes the `no-cache`, `max-age` and `min-fresh` request
+    /// directives into account.
+    pub fn is_fresh(&self, now: SystemTime) -> bool {
+        if self.requires_validation() {
+            return false;
+        }
+        let age = self.current_age(now);
+        let lifetime = self.freshness_lifetime();
+        if age >= lifetime {
+            return false;
+        }
+        for directive in &self.req_directives {
+            match directive {
+                CacheDirective::MaxAge(max_age) if age > *max_age => return false,
+                CacheDirective::MinFresh(min_fresh)
+                    if age.saturating_add(*min_fresh) > lifetime =>
+                {
+                    return false
+                }
+                _ => {}
+            }
+        }
+        true
+    }
+
+    /// Returns `true` if the response is stale, but the request's `max-stale`
+    /// directive allows it to be served without validation at `now`.
+    pub fn may_serve_stale(&self, now: SystemTime) -> bool {
+        if self.is_fresh(now) || self.stale_prohibited() {
+            return false;
+        }
+        let staleness = self.staleness(now);
+        self.req_directives.iter().any(|directive| match directive {
+            CacheDirective::MaxStale(None) => true,
+            CacheDirective::MaxStale(Some(max_stale)) => staleness <= *max_stale,
+            _ => false,
+        })
+    }
+
+    /// Returns `true` if the response is stale, but may be served while it is
+    /// revalidated in the background at `now`.
+    pub fn may_serve_stale_while_revalidate(&self, now: SystemTime) -> bool {
+        if self.is_fresh(now) || self.stale_prohibited() {
+            return false;
+        }
+        let staleness = self.staleness(now);
+        self.res_directives.iter().any(|directive| match directive {
+            CacheDirective::StaleWhileRevalidate(window) => staleness <= *window,
+            _ => false,
+        })
+    }
+
+    /// Returns `true` if the response is stale, but may be served at `now`
+    /// because revalidating it failed with an error.
+    ///
+    /// The `stale-if-error` directive is honoured on both the request and the
+    /// response.
+    pub fn may_serve_stale_if_error(&self, now: SystemTime) -> bool {
+        if self.is_fresh(now) || self.stale_prohibited() {
+            return false;
+        }
+        let staleness = self.staleness(now);
+        self.req_directives
+            .iter()
+            .chain(self.res_directives.iter())
+            .any(|directive| match directive {
+                CacheDirective::StaleIfError(window) => staleness <= *window,
+                _ => false,
+            })
+    }
+
+    /// Returns `true` if the response must be validated with the origin
+    /// server before it is served at `now`.
+    ///
+    /// A response which may be served while revalidating in the background
+    /// still returns `true`.
+    pub fn must_revalidate(&self, now: SystemTime) -> bool {
+        !self.is_fresh(now) && !self.may_serve_stale(now)
+    }
+
+    /// Returns `true` if the response or the request forbid serving the
+    /// response without validation, regardless of its age.
+    fn requires_validation(&self) -> bool {
+        // Qualified no-cache directives are treated as unqualified, which RFC
+        // 9111 allows for caches that do not strip the listed fields.
+        let no_cache = |directive: &CacheDirective| {
+            matches!(
+                directive,
+                CacheDirective::NoCache | CacheDirective::NoCacheFields(_)
+            )
+        };
+        self.req_directives.iter().any(no_cache) || self.res_directives.iter().any(no_cache)
+    }
+
+    /// Returns `true` if the response may not be served once stale.
+    fn stale_prohibited(&self) -> bool {
+        self.requires_validation()
+            || self.res_directives.iter().any(|directive| match directive {
+                CacheDirective::MustRevalidate => true,
+                CacheDirective::ProxyRevalidate | CacheDirective::SMaxAge(_) => self.shared,
+                _ => false,
+            })
+    }
+
+    fn explicit_lifetime(&self) -> Option<Duration> {
+        if self.shared {
+            let s_maxage = self.res_directives.iter().find_map(|d| match d {
+                CacheDirective::SMaxAge(dur) => Some(*dur),
+                _ => None,
+            });
+            if s_maxage.is_some() {
+                return s_maxage;
+            }
+        }
+        let max_age = self.res_directives.iter().find_map(|d| match d {
+            CacheDirective::MaxAge(dur) => Some(*dur),
+            _ => None,
+        });
+        if max_age.is_some() {
+            return max_age;
+        }
+        match self.expires {
+            Some(Some(expires)) => Some(elapsed(self.date, expires)),
+            Some(None) => Some(Duration::ZERO),
+            None => None,
+        }
+    }
+
+    /// A tenth of the time since the response was last modified, as
+    /// suggested in RFC 9111, section 4.2.2.
+    fn heuristic_lifetime(&self) -> Option<Duration> {
+        let last_modified = self.last_modified?;
+        let public = self
+            .res_directives
+            .iter()
+            .any(|d| matches!(d, CacheDirective::Public));
+        if !public && !is_heuristically_cacheable(self.status) {
+            return None;
+        }
+        Some(elapsed(last_modified, self.date) / 10)
+    }
+}
+
+fn directives(headers: impl AsRef<crate::headers::Headers>) -> crate::Result<Vec<CacheDirective>> {
+    Ok(match CacheControl::from_headers(headers)? {
+        Some(cache_control) => cache_control.iter().cloned().collect(),
+        None => vec![],
+    })
+}
+
+/// The time between `earlier` and `later`, or zero if `later` comes first.
+fn elapsed(earlier: SystemTime, later: SystemTime) -> Duration {
+    later.duration_since(earlier).unwrap_or(Duration::ZERO)
+}
+
+/// Status codes which are cacheable by default, from RFC 9110, section 15.1.
+pub(super) fn is_heuristically_cacheable(status: StatusCode) -> bool {
+    matches!(
+        status as u16,
+        200 | 203 | 204 | 206 | 300 | 301 | 308 | 404 | 405 | 410 | 414 | 501
+    )
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::cache::Expires;
+    use crate::conditional::LastModified;
+    use crate::headers::Header;
+    use crate::other::Date;
+
+    fn secs(secs: u64) -> Duration {
+        Duration::from_secs(secs)
+    }
+
+    #[test]
+    fn age_calculation() -> crate::Result<()> {
+        let sent = SystemTime::UNIX_EPOCH + secs(1_000_000);
+        let received = sent + secs(2);
+
+        // The response claims to be 5 seconds old, but its date says 10.
+        let req = Request::get("http://example.com");
+        let mut res = Response::new(200);
+        Date::new(sent - secs(8)).apply_header(&mut res);
+        res.insert_header("Age", "5")?;
+
+        let freshness = Freshness::new(&req, &res, sent, received)?;
+        assert_eq!(freshness.current_age(received), secs(10));
+        assert_eq!(freshness.current_age(received + secs(5)), secs(15));
+
+        // Without a date, the reported age plus the response delay is used.
+        res.remove_header("Date");
+        let freshness = Freshness::new(&req, &res, sent, received)?;
+        assert_eq!(freshness.current_age(received), secs(7));
+        assert_eq!(freshness.freshness_lifetime(), Duration::ZERO);
+        assert!(freshness.must_revalidate(received));
+        Ok(())
+    }
+
+    #[test]
+    fn lifetime() -> crate::Result<()> {
+        let now = SystemTime::UNIX_EPOCH + secs(1_000_000);
+        let req = Request::get("http://example.com");
+
+        let mut res = Response::new(200);
+        Date::new(now).apply_header(&mut res);
+        Expires::new_at(now + secs(300)).apply_header(&mut res);
+        LastModified::new(now - secs(1000)).apply_header(&mut res);
+        let freshness = Freshness::new(&req, &res, now, now)?;
+        assert_eq!(freshness.freshness_lifetime(), secs(300));
+
+        res.insert_header("Cache-Control", "max-age=60, s-maxage=120")?;
+        let mut freshness = Freshness::new(&req, &res, now, now)?;
+        assert_eq!(freshness.freshness_lifetime(), secs(60));
+        freshness.set_shared(true);
+        assert_eq!(freshness.freshness_lifetime(), secs(120));
+
+        res.remove_header("Cache-Control");
+        res.insert_header("Expires", "0")?;
+        let freshness = Freshness::new(&req, &res, now, now)?;
+        assert_eq!(freshness.freshness_lifetime(), Duration::ZERO);
+        assert!(!freshness.is_heuristic());
+
+        res.remove_header("Expires");
+        let freshness = Freshness::new(&req, &res, now, now)?;
+        assert_eq!(freshness.freshness_lifetime(), secs(100));
+        assert!(freshness.is_heuristic());
+
+        res.set_status(StatusCode::Created);
+        let freshness = Freshness::new(&req, &res, now, now)?;
+        assert_eq!(freshness.freshness_lifetime(), Duration::ZERO);
+        Ok(())
+    }
+
+    #[test]
+    fn request_directives() -> crate::Result<()> {
+        let now = SystemTime::UNIX_EPOCH + secs(1_000_000);
+        let mut res = Response::new(200);
+        res.insert_header("Cache-Control", "max-age=100")?;
+        res.insert_header("Age", "50")?;
+
+        let mut req = Request::get("http://example.com");
+        let fresh = |req: &Request| Freshness::new(req, &res, now, now).unwrap().is_fresh(now);
+        assert!(fresh(&req));
+        req.insert_header("Cache-Control", "max-age=30")?;
+        assert!(!fresh(&req));
+        req.insert_header("Cache-Control", "min-fresh=60")?;
+        assert!(!fresh(&req));
+        req.insert_header("Cache-Control", "no-cache")?;
+        assert!(!fresh(&req));
+
+        req.insert_header("Cache-Control", "max-stale=10")?;
+        let freshness = Freshness::new(&req, &res, now, now)?;
+        assert!(freshness.may_serve_stale(now + secs(55)));
+        assert!(!freshness.may_serve_stale(now + secs(65)));
+        assert!(freshness.must_revalidate(now + secs(65)));
+        Ok(())
+    }
+
+    #[test]
+    fn huge_values() -> crate::Result<()> {
+        let now = SystemTime::UNIX_EPOCH + secs(1_000_000);
+        let mut req = Request::get("http://example.com");
+        req.insert_header("Cache-Control", format!("min-fresh={}", u64::MAX))?;
+        let mut res = Response::new(200);
+        res.insert_header("Cache-Control", "max-age=100")?;
+        res.insert_header("Age", u64::MAX.to_string())?;
+
+        let freshness = Freshness::new(&req, &res, now, now + secs(5))?;
+        assert_eq!(freshness.current_age(now + secs(10)), Duration::MAX);
+        assert!(!freshness.is_fresh(now + secs(10)));
+
+        res.insert_header("Age", "0")?;
+        let freshness = Freshness::new(&req, &res, now, now)?;
+        assert!(!freshness.is_fresh(now));
+        Ok(())
+    }
+
+    #[test]
+    fn serve_stale() -> crate::Result<()> {
+        let now = SystemTime::UNIX_EPOCH + secs(1_000_000);
+        let req = Request::get("http://example.com");
+        let mut res = Response::new(200);
+        res.insert_header(
+            "Cache-Control",
+            "max-age=10, s-maxage=10, stale-while-revalidate=20, stale-if-error=60",
+        )?;
+
+        let mut freshness = Freshness::new(&req, &res, now, now)?;
+        assert!(freshness.is_fresh(now + secs(5)));
+        assert!(!freshness.may_serve_stale_while_revalidate(now + secs(5)));
+        assert!(freshness.may_serve_stale_while_revalidate(now + secs(25)));
+        assert!(!freshness.may_serve_stale_while_revalidate(now + secs(35)));
+        assert!(freshness.may_serve_stale_if_error(now + secs(35)));
+        assert!(!freshness.may_serve_stale_if_error(now + secs(75)));
+
+        // s-maxage implies proxy-revalidate for shared caches.
+        freshness.set_shared(true);
+        assert!(!freshness.may_serve_stale_while_revalidate(now + secs(25)));
+        assert!(!freshness.may_serve_stale_if_error(now + secs(35)));
+
+        res.insert_header(
+            "Cache-Control",
+            "max-age=10, must-revalidate, stale-if-error=60",
+        )?;
+        let freshness = Freshness::new(&req, &res, now, now)?;
+        assert!(!freshness.may_serve_stale_if_error(now + secs(35)));
+        Ok(())
+    }
+}
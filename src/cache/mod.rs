@@ -9,12 +9,23 @@
 //! - [MDN: HTTP Caching](https://developer.mozilla.org/en-US/docs/Web/HTTP/Caching)
 
 mod age;
+#[allow(clippy::module_inception)]
+mod cache;
 mod cache_control;
+mod cache_status;
 mod clear_site_data;
 mod expires;
+mod freshness;
+mod storage;
 
 pub use age::Age;
+pub use cache::{Cache, CacheHit};
 pub use cache_control::CacheControl;
 pub use cache_control::CacheDirective;
+pub use cache_control::CacheExtension;
+pub use cache_control::{CdnCacheControl, SurrogateControl};
+pub use cache_status::{CacheStatus, CacheStatusEntry, ForwardReason};
 pub use clear_site_data::{ClearDirective, ClearSiteData};
 pub use expires::Expires;
+pub use freshness::Freshness;
+pub use storage::{CacheEntry, CacheStorage, MemoryStorage};
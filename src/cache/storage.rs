@@ -0,0 +1,144 @@
+use crate::headers::{HeaderName, HeaderValues, Headers};
+use crate::{StatusCode, Version};
+
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::SystemTime;
+
+/// Storage backend for a [`Cache`](super::Cache).
+///
+/// Entries are stored under a primary key made up of the request method and
+/// URL. Responses carrying a `Vary` header can have several variants under
+/// the same key; the cache selects between them, so storages only need to
+/// keep the list of variants for each key.
+///
+/// Entry bodies are reference counted, so cloning an entry does not copy its
+/// body.
+pub trait CacheStorage {
+    /// Get the variants stored under `key`.
+    ///
+    /// Storages which keep entries in memory can lend them out, others
+    /// return an owned copy.
+    fn get(&self, key: &str) -> Option<Cow<'_, [CacheEntry]>>;
+
+    /// Replace the variants stored under `key`.
+    fn set(&mut self, key: String, variants: Vec<CacheEntry>);
+
+    /// Remove all variants stored under `key`.
+    fn remove(&mut self, key: &str);
+}
+
+/// A response stored in a cache.
+#[derive(Debug, Clone)]
+pub struct CacheEntry {
+    pub(super) status: StatusCode,
+    pub(super) version: Option<Version>,
+    pub(super) headers: Headers,
+    pub(super) body: Arc<[u8]>,
+    pub(super) request_time: SystemTime,
+    pub(super) response_time: SystemTime,
+    pub(super) vary: Vec<(HeaderName, Option<HeaderValues>)>,
+}
+
+impl CacheEntry {
+    /// Get the status code of the stored response.
+    pub fn status(&self) -> StatusCode {
+        self.status
+    }
+
+    /// Get the HTTP version of the stored response, if one was set.
+    pub fn version(&self) -> Option<Version> {
+        self.version
+    }
+
+    /// Get the headers of the stored response.
+    pub fn headers(&self) -> &Headers {
+        &self.headers
+    }
+
+    /// Get the body of the stored response.
+    pub fn body(&self) -> &[u8] {
+        &self.body
+    }
+
+    /// Get the time at which the request for this response was sent.
+    pub fn request_time(&self) -> SystemTime {
+        self.request_time
+    }
+
+    /// Get the time at which the response was received.
+    pub fn response_time(&self) -> SystemTime {
+        self.response_time
+    }
+
+    /// Get the request headers nominated by the `Vary` header of the
+    /// response, along with their values in the original request.
+    pub fn vary(&self) -> &[(HeaderName, Option<HeaderValues>)] {
+        &self.vary
+    }
+
+    /// Returns `true` if the request headers nominated by `Vary` match.
+    pub(super) fn matches(&self, headers: &Headers) -> bool {
+        self.vary
+            .iter()
+            .all(|(name, values)| normalize(headers.get(name)) == normalize(values.as_ref()))
+    }
+}
+
+/// Combine the values of a header into a single list, ignoring whitespace
+/// around list elements.
+fn normalize(values: Option<&HeaderValues>) -> Option<Vec<&str>> {
+    values.map(|values| {
+        values
+            .iter()
+            .flat_map(|value| value.as_str().split(','))
+            .map(str::trim)
+            .filter(|value| !value.is_empty())
+            .collect()
+    })
+}
+
+/// A [`CacheStorage`] which keeps entries in memory.
+#[derive(Debug, Default)]
+pub struct MemoryStorage {
+    entries: HashMap<String, Vec<CacheEntry>>,
+}
+
+impl MemoryStorage {
+    /// Create a new instance of `MemoryStorage`.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Get the number of keys in the storage.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns `true` if the storage is empty.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Remove all entries from the storage.
+    pub fn clear(&mut self) {
+        self.entries.clear();
+    }
+}
+
+impl CacheStorage for MemoryStorage {
+    fn get(&self, key: &str) -> Option<Cow<'_, [CacheEntry]>> {
+        self.entries
+            .get(key)
+            .map(|variants| Cow::Borrowed(variants.as_slice()))
+    }
+
+    fn set(&mut self, key: String, variants: Vec<CacheEntry>) {
+        self.entries.insert(key, variants);
+    }
+
+    fn remove(&mut self, key: &str) {
+        self.entries.remove(key);
+    }
+}
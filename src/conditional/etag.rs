@@ -1,7 +1,13 @@
+use crate::conditional::ETagReceiver;
 use crate::headers::{Header, HeaderName, HeaderValue, Headers, ETAG};
-use crate::{Error, StatusCode};
+use crate::{Body, Error, StatusCode};
+
+use futures_lite::{io, prelude::*, ready};
 
 use std::fmt::{self, Debug, Display};
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use std::time::UNIX_EPOCH;
 
 /// HTTP Entity Tags.
 ///
@@ -51,6 +57,108 @@ impl ETag {
         Self::Weak(s)
     }
 
+    /// Create a new ETag that uses strong validation from a hash of the
+    /// content.
+    ///
+    /// The hash is stable across processes and platforms, so servers sharing
+    /// the same content generate the same ETag. It is not a cryptographic
+    /// hash.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use http_types::conditional::ETag;
+    ///
+    /// let etag = ETag::from_bytes("hello world");
+    /// assert!(etag.is_strong());
+    /// assert_eq!(etag, ETag::from_bytes(b"hello world"));
+    /// assert_ne!(etag, ETag::from_bytes("hello world!"));
+    /// ```
+    pub fn from_bytes(bytes: impl AsRef<[u8]>) -> Self {
+        let mut hasher = ContentHasher::new();
+        hasher.update(bytes.as_ref());
+        hasher.finish()
+    }
+
+    /// Create a new ETag that uses strong validation from the content of a
+    /// body.
+    ///
+    /// The body is buffered in memory, and returned along with the ETag.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # fn main() -> http_types::Result<()> { async_std::task::block_on(async {
+    /// #
+    /// use http_types::conditional::ETag;
+    /// use http_types::Body;
+    ///
+    /// let (etag, body) = ETag::from_body(Body::from("hello world")).await?;
+    /// assert_eq!(etag, ETag::from_bytes("hello world"));
+    /// assert_eq!(body.into_string().await?, "hello world");
+    /// #
+    /// # Ok(()) })}
+    /// ```
+    pub async fn from_body(body: Body) -> crate::Result<(Self, Body)> {
+        let mime = body.mime().cloned();
+        let bytes = body.into_bytes().await?;
+        let etag = Self::from_bytes(&bytes);
+        let mut body = Body::from_bytes(bytes);
+        body.set_mime(mime);
+        Ok((etag, body))
+    }
+
+    /// Compute a strong ETag while a body is streamed.
+    ///
+    /// Returns a body which hashes its content as it is read, and a receiver
+    /// which resolves to the ETag once the body has been read to the end. This
+    /// is useful to send the ETag in a trailer.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # fn main() -> http_types::Result<()> { async_std::task::block_on(async {
+    /// #
+    /// use http_types::conditional::ETag;
+    /// use http_types::Body;
+    ///
+    /// let (body, receiver) = ETag::from_streaming_body(Body::from("hello world"));
+    /// assert_eq!(body.into_string().await?, "hello world");
+    /// assert_eq!(receiver.await, Some(ETag::from_bytes("hello world")));
+    /// #
+    /// # Ok(()) })}
+    /// ```
+    pub fn from_streaming_body(body: Body) -> (Body, ETagReceiver) {
+        let (sender, receiver) = async_channel::bounded(1);
+        let mime = body.mime().cloned();
+        let len = body.len();
+        let reader = HashingReader {
+            inner: body,
+            hasher: ContentHasher::new(),
+            len,
+            bytes_read: 0,
+            sender: Some(sender),
+        };
+        let mut body = Body::from_reader(io::BufReader::new(reader), len);
+        body.set_mime(mime);
+        (body, ETagReceiver::new(receiver))
+    }
+
+    /// Create a new ETag that uses weak validation from file metadata.
+    ///
+    /// The ETag is derived from the size and modification time of the file.
+    /// [`Body::etag`] returns this ETag for bodies created from a file.
+    pub fn from_metadata(metadata: &std::fs::Metadata) -> Self {
+        let modified = metadata
+            .modified()
+            .ok()
+            .and_then(|modified| modified.duration_since(UNIX_EPOCH).ok());
+        match modified {
+            Some(modified) => Self::Weak(format!("{:x}-{:x}", metadata.len(), modified.as_nanos())),
+            None => Self::Weak(format!("{:x}", metadata.len())),
+        }
+    }
+
     /// Create a new instance from headers.
     ///
     /// Only a single ETag per resource is assumed to exist. If multiple ETag
@@ -76,6 +184,57 @@ impl ETag {
         matches!(self, Self::Weak(_))
     }
 
+    /// Returns the opaque value of the ETag, without quotes.
+    pub fn value(&self) -> &str {
+        match self {
+            Self::Strong(s) | Self::Weak(s) => s,
+        }
+    }
+
+    /// Compare two ETags using strong comparison.
+    ///
+    /// Both ETags must be strong and have the same value. This is the
+    /// comparison used by `If-Match`.
+    ///
+    /// # Specifications
+    ///
+    /// - [RFC 9110, section 8.8.3.2: Comparison](https://www.rfc-editor.org/rfc/rfc9110#section-8.8.3.2)
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use http_types::conditional::ETag;
+    ///
+    /// let etag = ETag::new("1".to_string());
+    /// assert!(etag.strong_eq(&ETag::new("1".to_string())));
+    /// assert!(!etag.strong_eq(&ETag::new_weak("1".to_string())));
+    /// ```
+    pub fn strong_eq(&self, other: &ETag) -> bool {
+        self.is_strong() && other.is_strong() && self.value() == other.value()
+    }
+
+    /// Compare two ETags using weak comparison.
+    ///
+    /// The values must be the same, either ETag may be weak. This is the
+    /// comparison used by `If-None-Match`.
+    ///
+    /// # Specifications
+    ///
+    /// - [RFC 9110, section 8.8.3.2: Comparison](https://www.rfc-editor.org/rfc/rfc9110#section-8.8.3.2)
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use http_types::conditional::ETag;
+    ///
+    /// let etag = ETag::new_weak("1".to_string());
+    /// assert!(etag.weak_eq(&ETag::new("1".to_string())));
+    /// assert!(!etag.weak_eq(&ETag::new_weak("2".to_string())));
+    /// ```
+    pub fn weak_eq(&self, other: &ETag) -> bool {
+        self.value() == other.value()
+    }
+
     /// Create an Etag from a string.
     pub(crate) fn from_str(s: &str) -> crate::Result<Self> {
         let mut weak = false;
@@ -132,6 +291,68 @@ impl Display for ETag {
     }
 }
 
+/// 128-bit FNV-1a, chosen for being simple and stable rather than fast.
+struct ContentHasher {
+    state: u128,
+}
+
+impl ContentHasher {
+    const OFFSET_BASIS: u128 = 0x6c62272e07bb014262b821756295c58d;
+    const PRIME: u128 = 0x0000000001000000000000000000013b;
+
+    fn new() -> Self {
+        Self {
+            state: Self::OFFSET_BASIS,
+        }
+    }
+
+    fn update(&mut self, bytes: &[u8]) {
+        for byte in bytes {
+            self.state ^= *byte as u128;
+            self.state = self.state.wrapping_mul(Self::PRIME);
+        }
+    }
+
+    fn finish(&self) -> ETag {
+        ETag::Strong(format!("{:032x}", self.state))
+    }
+}
+
+/// Hashes a body as it is read, sending the ETag once it reaches the end.
+struct HashingReader {
+    inner: Body,
+    hasher: ContentHasher,
+    len: Option<u64>,
+    bytes_read: u64,
+    sender: Option<async_channel::Sender<ETag>>,
+}
+
+impl AsyncRead for HashingReader {
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut [u8],
+    ) -> Poll<io::Result<usize>> {
+        let this = &mut *self;
+        let bytes = ready!(Pin::new(&mut this.inner).poll_read(cx, buf))?;
+        this.hasher.update(&buf[..bytes]);
+        this.bytes_read += bytes as u64;
+
+        // Bodies with a known length are not read past their end, so the
+        // end is reached either at that length or at EOF.
+        let done = match this.len {
+            Some(len) => this.bytes_read >= len,
+            None => bytes == 0 && !buf.is_empty(),
+        };
+        if done {
+            if let Some(sender) = this.sender.take() {
+                let _ = sender.try_send(this.hasher.finish());
+            }
+        }
+        Poll::Ready(Ok(bytes))
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -184,6 +405,62 @@ mod test {
         assert_eq!(format!("{}", err), msg);
     }
 
+    #[test]
+    fn comparison() {
+        let strong = |s: &str| ETag::new(s.to_string());
+        let weak = |s: &str| ETag::new_weak(s.to_string());
+
+        // The examples from RFC 9110, section 8.8.3.2.
+        assert!(!weak("1").strong_eq(&weak("1")));
+        assert!(weak("1").weak_eq(&weak("1")));
+        assert!(!weak("1").strong_eq(&weak("2")));
+        assert!(!weak("1").weak_eq(&weak("2")));
+        assert!(!weak("1").strong_eq(&strong("1")));
+        assert!(weak("1").weak_eq(&strong("1")));
+        assert!(strong("1").strong_eq(&strong("1")));
+        assert!(strong("1").weak_eq(&strong("1")));
+    }
+
+    #[test]
+    fn content_hash() {
+        assert_eq!(
+            ETag::from_bytes(""),
+            ETag::new("6c62272e07bb014262b821756295c58d".to_string())
+        );
+        assert_eq!(
+            ETag::from_bytes("a"),
+            ETag::new("d228cb696f1a8caf78912b704e4a8964".to_string())
+        );
+    }
+
+    #[async_std::test]
+    async fn streaming_body() -> crate::Result<()> {
+        let body = Body::from_reader(io::Cursor::new(vec![7; 100_000]), None);
+        let (body, receiver) = ETag::from_streaming_body(body);
+        assert_eq!(body.into_bytes().await?.len(), 100_000);
+        assert_eq!(receiver.await, Some(ETag::from_bytes(vec![7; 100_000])));
+
+        // Dropping the body before the end means no ETag is produced.
+        let (body, receiver) = ETag::from_streaming_body(Body::from("hello"));
+        drop(body);
+        assert_eq!(receiver.await, None);
+        Ok(())
+    }
+
+    #[cfg(feature = "fs")]
+    #[async_std::test]
+    async fn file_metadata() -> crate::Result<()> {
+        let body = Body::from_path("Cargo.toml").await?;
+        let etag = body.etag().unwrap();
+        assert!(etag.is_weak());
+        assert_eq!(
+            etag,
+            &ETag::from_metadata(&std::fs::metadata("Cargo.toml")?)
+        );
+        assert!(Body::from("Cargo.toml").etag().is_none());
+        Ok(())
+    }
+
     #[test]
     fn validate_characters() {
         assert_entry_err(r#"""hello""#, "Invalid ETag header");
@@ -0,0 +1,33 @@
+use futures_lite::Stream;
+
+use std::future::Future;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use crate::conditional::ETag;
+
+/// The receiving half of a channel to send an ETag computed while a body is
+/// streamed.
+///
+/// Created by [`ETag::from_streaming_body`]. Resolves to `None` if the body
+/// was dropped before it was read to the end.
+#[must_use = "Futures do nothing unless polled or .awaited"]
+#[derive(Debug)]
+pub struct ETagReceiver {
+    receiver: async_channel::Receiver<ETag>,
+}
+
+impl ETagReceiver {
+    /// Create a new instance of `ETagReceiver`.
+    pub(crate) fn new(receiver: async_channel::Receiver<ETag>) -> Self {
+        Self { receiver }
+    }
+}
+
+impl Future for ETagReceiver {
+    type Output = Option<ETag>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        Pin::new(&mut self.receiver).poll_next(cx)
+    }
+}
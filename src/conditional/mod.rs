@@ -9,15 +9,19 @@
 //! - [MDN: HTTP Conditional Requests](https://developer.mozilla.org/en-US/docs/Web/HTTP/Conditional_requests)
 
 mod etag;
+mod etag_receiver;
 mod if_modified_since;
 mod if_unmodified_since;
 mod last_modified;
+mod preconditions;
 mod vary;
 
 pub mod if_match;
 pub mod if_none_match;
 
 pub use etag::ETag;
+pub use etag_receiver::ETagReceiver;
+pub use preconditions::{Evaluation, Preconditions};
 pub use vary::Vary;
 
 #[doc(inline)]
@@ -0,0 +1,378 @@
+use crate::conditional::{ETag, IfMatch, IfModifiedSince, IfNoneMatch, IfUnmodifiedSince};
+use crate::headers::{
+    Header, HeaderName, Headers, CACHE_CONTROL, CONTENT_LOCATION, DATE, EXPIRES, LAST_MODIFIED,
+    VARY,
+};
+use crate::other::Date;
+use crate::utils::parse_http_date;
+use crate::{Method, Request, Response, StatusCode};
+
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// Headers a `304 Not Modified` response must carry if the `200 OK`
+/// response would have, from RFC 9110, section 15.4.5.
+const NOT_MODIFIED_HEADERS: [HeaderName; 5] =
+    [CACHE_CONTROL, CONTENT_LOCATION, DATE, EXPIRES, VARY];
+
+/// The outcome of evaluating the preconditions of a request.
+#[derive(Debug)]
+pub enum Evaluation {
+    /// All preconditions passed, the request should be handled normally.
+    Proceed,
+    /// The representation has not been modified, respond with this
+    /// `304 Not Modified` response.
+    NotModified(Response),
+    /// A precondition failed, respond with this `412 Precondition Failed`
+    /// response.
+    PreconditionFailed(Response),
+}
+
+/// Evaluate conditional request headers against the current state of a
+/// resource.
+///
+/// `If-Match` and `If-Unmodified-Since` guard state-changing requests against
+/// lost updates, and `If-None-Match` and `If-Modified-Since` allow caches to
+/// revalidate stored responses. `Preconditions` applies them in the order
+/// defined by RFC 9110: entity tags take precedence over dates, `If-Match` is
+/// compared using strong comparison, and `If-None-Match` using weak
+/// comparison.
+///
+/// # Specifications
+///
+/// - [RFC 9110, section 13.2.2: Precedence of Preconditions](https://www.rfc-editor.org/rfc/rfc9110#section-13.2.2)
+///
+/// # Examples
+///
+/// ```
+/// # fn main() -> http_types::Result<()> {
+/// #
+/// use http_types::conditional::{ETag, Evaluation, Preconditions};
+/// use http_types::Request;
+///
+/// let mut preconditions = Preconditions::new();
+/// preconditions.set_etag(Some(ETag::new("v2".to_string())));
+///
+/// let mut req = Request::get("http://example.com");
+/// req.insert_header("If-None-Match", r#"W/"v1", W/"v2""#);
+/// match preconditions.evaluate(&req)? {
+///     Evaluation::NotModified(res) => assert_eq!(res["ETag"], r#""v2""#),
+///     _ => panic!("expected 304"),
+/// }
+///
+/// let mut req = Request::put("http://example.com");
+/// req.insert_header("If-Match", r#""v1""#);
+/// assert!(matches!(
+///     preconditions.evaluate(&req)?,
+///     Evaluation::PreconditionFailed(_)
+/// ));
+/// #
+/// # Ok(()) }
+/// ```
+#[derive(Debug, Clone)]
+pub struct Preconditions {
+    exists: bool,
+    etag: Option<ETag>,
+    last_modified: Option<SystemTime>,
+    headers: Headers,
+}
+
+impl Preconditions {
+    /// Create a new instance of `Preconditions` for an existing resource
+    /// without validators.
+    pub fn new() -> Self {
+        Self {
+            exists: true,
+            etag: None,
+            last_modified: None,
+            headers: Headers::new(),
+        }
+    }
+
+    /// Create a new instance from the response which would be sent if all
+    /// preconditions pass.
+    ///
+    /// The `ETag` and `Last-Modified` headers of the response are used as
+    /// validators, and the headers a `304 Not Modified` response must repeat
+    /// are copied from it.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the `ETag` header of the response could not be
+    /// parsed.
+    pub fn from_response(res: &Response) -> crate::Result<Self> {
+        let mut preconditions = Self::new();
+        preconditions.exists = res.status().is_success();
+        preconditions.etag = ETag::from_headers(res)?;
+        preconditions.last_modified = res
+            .header(LAST_MODIFIED)
+            .and_then(|values| parse_http_date(values.last().as_str()).ok());
+        for name in NOT_MODIFIED_HEADERS.iter() {
+            if let Some(values) = res.header(name) {
+                preconditions.headers.insert(name, values).unwrap();
+            }
+        }
+        Ok(preconditions)
+    }
+
+    /// Returns `true` if the resource has a current representation.
+    pub fn exists(&self) -> bool {
+        self.exists
+    }
+
+    /// Set whether the resource has a current representation.
+    ///
+    /// `If-Match: *` fails and `If-None-Match: *` passes for resources which
+    /// do not exist.
+    pub fn set_exists(&mut self, exists: bool) {
+        self.exists = exists;
+    }
+
+    /// Get the current entity tag of the resource.
+    pub fn etag(&self) -> Option<&ETag> {
+        self.etag.as_ref()
+    }
+
+    /// Set the current entity tag of the resource.
+    pub fn set_etag(&mut self, etag: Option<ETag>) {
+        self.etag = etag;
+    }
+
+    /// Get the time the resource was last modified.
+    pub fn last_modified(&self) -> Option<SystemTime> {
+        self.last_modified
+    }
+
+    /// Set the time the resource was last modified.
+    pub fn set_last_modified(&mut self, last_modified: Option<SystemTime>) {
+        self.last_modified = last_modified;
+    }
+
+    /// Evaluate the preconditions of `req`.
+    ///
+    /// Invalid dates in `If-Modified-Since` and `If-Unmodified-Since` are
+    /// ignored, as required by RFC 9110.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `400 Bad Request` error if the `If-Match` or `If-None-Match`
+    /// headers could not be parsed.
+    pub fn evaluate(&self, req: &Request) -> crate::Result<Evaluation> {
+        let is_get_or_head = matches!(req.method(), Method::Get | Method::Head);
+
+        // Step 1 and 2: If-Match, or else If-Unmodified-Since.
+        if let Some(if_match) = IfMatch::from_headers(req)? {
+            let matches = if if_match.wildcard() {
+                self.exists
+            } else {
+                self.exists
+                    && if_match
+                        .iter()
+                        .any(|tag| self.matches(tag, ETag::strong_eq))
+            };
+            if !matches {
+                return Ok(Evaluation::PreconditionFailed(self.precondition_failed()));
+            }
+        } else if let Ok(Some(since)) = IfUnmodifiedSince::from_headers(req) {
+            if let Some(modified) = self.last_modified {
+                if seconds(modified) > seconds(since.modified()) {
+                    return Ok(Evaluation::PreconditionFailed(self.precondition_failed()));
+                }
+            }
+        }
+
+        // Step 3 and 4: If-None-Match, or else If-Modified-Since.
+        if let Some(if_none_match) = IfNoneMatch::from_headers(req)? {
+            let matches = if if_none_match.wildcard() {
+                self.exists
+            } else {
+                self.exists
+                    && if_none_match
+                        .iter()
+                        .any(|tag| self.matches(tag, ETag::weak_eq))
+            };
+            if matches {
+                return Ok(if is_get_or_head {
+                    Evaluation::NotModified(self.not_modified())
+                } else {
+                    Evaluation::PreconditionFailed(self.precondition_failed())
+                });
+            }
+        } else if is_get_or_head {
+            if let (Ok(Some(since)), Some(modified)) =
+                (IfModifiedSince::from_headers(req), self.last_modified)
+            {
+                if seconds(modified) <= seconds(since.modified()) {
+                    return Ok(Evaluation::NotModified(self.not_modified()));
+                }
+            }
+        }
+
+        Ok(Evaluation::Proceed)
+    }
+
+    fn matches(&self, other: &ETag, eq: fn(&ETag, &ETag) -> bool) -> bool {
+        match &self.etag {
+            Some(etag) => eq(etag, other),
+            None => false,
+        }
+    }
+
+    fn not_modified(&self) -> Response {
+        let mut res = Response::new(StatusCode::NotModified);
+        for (name, values) in self.headers.iter() {
+            res.insert_header(name, values).unwrap();
+        }
+        if res.header(DATE).is_none() {
+            Date::now().apply_header(&mut res);
+        }
+        if let Some(etag) = &self.etag {
+            etag.apply_header(&mut res);
+        }
+        res
+    }
+
+    fn precondition_failed(&self) -> Response {
+        Response::new(StatusCode::PreconditionFailed)
+    }
+}
+
+impl Default for Preconditions {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// HTTP dates have a resolution of one second.
+fn seconds(time: SystemTime) -> Duration {
+    let since_epoch = time.duration_since(UNIX_EPOCH).unwrap_or_default();
+    Duration::from_secs(since_epoch.as_secs())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const LAST_MODIFIED_DATE: &str = "Wed, 21 Oct 2015 07:28:00 GMT";
+
+    fn preconditions() -> Preconditions {
+        let mut preconditions = Preconditions::new();
+        preconditions.set_etag(Some(ETag::new("v1".to_string())));
+        let modified = parse_http_date(LAST_MODIFIED_DATE).unwrap();
+        preconditions.set_last_modified(Some(modified + Duration::from_millis(500)));
+        preconditions
+    }
+
+    fn evaluate(method: Method, headers: &[(&str, &str)]) -> Evaluation {
+        let mut req = Request::new(method, "http://example.com");
+        for (name, value) in headers {
+            req.insert_header(*name, *value).unwrap();
+        }
+        preconditions().evaluate(&req).unwrap()
+    }
+
+    fn status(evaluation: Evaluation) -> u16 {
+        match evaluation {
+            Evaluation::Proceed => 200,
+            Evaluation::NotModified(res) | Evaluation::PreconditionFailed(res) => {
+                res.status() as u16
+            }
+        }
+    }
+
+    #[test]
+    fn if_match() {
+        assert_eq!(
+            status(evaluate(Method::Put, &[("If-Match", r#""v1""#)])),
+            200
+        );
+        assert_eq!(status(evaluate(Method::Put, &[("If-Match", "*")])), 200);
+        assert_eq!(
+            status(evaluate(Method::Put, &[("If-Match", r#"W/"v1""#)])),
+            412
+        );
+        assert_eq!(
+            status(evaluate(Method::Put, &[("If-Match", r#""v2""#)])),
+            412
+        );
+
+        // If-Match takes precedence over If-Unmodified-Since.
+        let headers = [
+            ("If-Match", r#""v1""#),
+            ("If-Unmodified-Since", "Tue, 20 Oct 2015 07:28:00 GMT"),
+        ];
+        assert_eq!(status(evaluate(Method::Put, &headers)), 200);
+
+        let mut req = Request::put("http://example.com");
+        req.insert_header("If-Match", "*").unwrap();
+        let mut preconditions = Preconditions::new();
+        preconditions.set_exists(false);
+        assert_eq!(status(preconditions.evaluate(&req).unwrap()), 412);
+    }
+
+    #[test]
+    fn if_unmodified_since() {
+        let since = |date| evaluate(Method::Delete, &[("If-Unmodified-Since", date)]);
+        assert_eq!(status(since(LAST_MODIFIED_DATE)), 200);
+        assert_eq!(status(since("Tue, 20 Oct 2015 07:28:00 GMT")), 412);
+        assert_eq!(status(since("not a date")), 200);
+    }
+
+    #[test]
+    fn if_none_match() {
+        let get = |value| evaluate(Method::Get, &[("If-None-Match", value)]);
+        assert_eq!(status(get(r#"W/"v1""#)), 304);
+        assert_eq!(status(get(r#""v0", "v1""#)), 304);
+        assert_eq!(status(get("*")), 304);
+        assert_eq!(status(get(r#""v2""#)), 200);
+
+        let post = evaluate(Method::Post, &[("If-None-Match", "*")]);
+        assert_eq!(status(post), 412);
+
+        // If-None-Match takes precedence over If-Modified-Since.
+        let headers = [
+            ("If-None-Match", r#""v2""#),
+            ("If-Modified-Since", LAST_MODIFIED_DATE),
+        ];
+        assert_eq!(status(evaluate(Method::Get, &headers)), 200);
+
+        let mut req = Request::get("http://example.com");
+        req.insert_header("If-None-Match", "v1").unwrap();
+        let err = preconditions().evaluate(&req).unwrap_err();
+        assert_eq!(err.status(), 400);
+    }
+
+    #[test]
+    fn if_modified_since() {
+        let get = |date| evaluate(Method::Get, &[("If-Modified-Since", date)]);
+        assert_eq!(status(get(LAST_MODIFIED_DATE)), 304);
+        assert_eq!(status(get("Tue, 20 Oct 2015 07:28:00 GMT")), 200);
+        assert_eq!(status(get("not a date")), 200);
+
+        let post = evaluate(Method::Post, &[("If-Modified-Since", LAST_MODIFIED_DATE)]);
+        assert_eq!(status(post), 200);
+    }
+
+    #[test]
+    fn not_modified_headers() -> crate::Result<()> {
+        let mut res = Response::new(200);
+        res.insert_header("ETag", r#"W/"v1""#)?;
+        res.insert_header("Last-Modified", LAST_MODIFIED_DATE)?;
+        res.insert_header("Cache-Control", "max-age=60")?;
+        res.insert_header("Vary", "Accept-Encoding")?;
+        res.insert_header("Content-Type", "text/plain")?;
+        let preconditions = Preconditions::from_response(&res)?;
+
+        let mut req = Request::get("http://example.com");
+        req.insert_header("If-None-Match", r#""v1""#)?;
+        let res = match preconditions.evaluate(&req)? {
+            Evaluation::NotModified(res) => res,
+            _ => panic!("expected 304"),
+        };
+        assert_eq!(res["ETag"], r#"W/"v1""#);
+        assert_eq!(res["Cache-Control"], "max-age=60");
+        assert_eq!(res["Vary"], "Accept-Encoding");
+        assert!(res.header("Date").is_some());
+        assert!(res.header("Content-Type").is_none());
+        Ok(())
+    }
+}
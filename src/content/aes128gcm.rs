@@ -0,0 +1,366 @@
+//! Encrypted content coding.
+
+use crate::utils::fill_random;
+use crate::Body;
+use crate::{bail_status as bail, ensure_status as ensure};
+
+use aes_gcm::aead::generic_array::GenericArray;
+use aes_gcm::aead::{Aead, KeyInit};
+use hkdf::Hkdf;
+use sha2::Sha256;
+
+use std::fmt::{self, Debug};
+
+/// The length of the authentication tag added to each record.
+const TAG_LEN: usize = 16;
+
+/// The length of the coding header, without the key identifier.
+const HEADER_LEN: usize = 21;
+
+/// The `aes128gcm` encrypted content coding.
+///
+/// The body is split into records of at most
+/// [`record_size`](Aes128Gcm::record_size) bytes, which are encrypted with
+/// a key and nonce derived from the input keying material and a salt, which
+/// is drawn at random for every message.
+/// The salt, record size and key identifier are sent in a header in front of
+/// the first record, so recipients only need to know the keying material.
+///
+/// # Specifications
+///
+/// - [RFC 8188: Encrypted Content-Encoding for HTTP](https://tools.ietf.org/html/rfc8188)
+///
+/// # Examples
+///
+/// ```
+/// # fn main() -> http_types::Result<()> { async_std::task::block_on(async {
+/// #
+/// use http_types::content::{Aes128Gcm, ContentEncoding, Encoding};
+/// use http_types::Request;
+///
+/// let coding = Aes128Gcm::new(b"sixteen byte key".to_vec());
+///
+/// let mut req = Request::post("https://example.com/");
+/// req.set_body(coding.encrypt_body("I am the walrus".into()).await?);
+/// let encoding = ContentEncoding::new(Encoding::Aes128Gcm);
+/// req.insert_header(&encoding, &encoding)?;
+///
+/// let body = coding.decrypt_body(req.take_body()).await?;
+/// assert_eq!(body.into_string().await?, "I am the walrus");
+/// #
+/// # Ok(()) }) }
+/// ```
+#[derive(Clone)]
+pub struct Aes128Gcm {
+    key: Vec<u8>,
+    salt: Option<[u8; 16]>,
+    key_id: Vec<u8>,
+    record_size: u32,
+}
+
+impl Aes128Gcm {
+    /// Create a new instance of `Aes128Gcm` from input keying material.
+    ///
+    /// The coding uses a fresh random salt for every message, 4096 byte
+    /// records and no key identifier.
+    pub fn new(key: impl Into<Vec<u8>>) -> Self {
+        Self {
+            key: key.into(),
+            salt: None,
+            key_id: vec![],
+            record_size: 4096,
+        }
+    }
+
+    /// Get the fixed salt used to derive the content encryption key.
+    ///
+    /// Returns `None` if a random salt is drawn for every message.
+    pub fn salt(&self) -> Option<[u8; 16]> {
+        self.salt
+    }
+
+    /// Use a fixed salt to derive the content encryption key, instead of a
+    /// random one per message.
+    ///
+    /// This is meant for reproducing test vectors. A salt must never be used
+    /// for more than one message with the same keying material, since the
+    /// messages would then share a key and nonces.
+    pub fn set_salt(&mut self, salt: [u8; 16]) {
+        self.salt = Some(salt);
+    }
+
+    /// Get the key identifier.
+    pub fn key_id(&self) -> &[u8] {
+        &self.key_id
+    }
+
+    /// Set the key identifier, which tells recipients which keying material
+    /// to use.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the identifier is longer than 255 bytes.
+    pub fn set_key_id(&mut self, key_id: Vec<u8>) -> crate::Result<()> {
+        ensure!(
+            key_id.len() <= 255,
+            500,
+            "aes128gcm key identifiers can be at most 255 bytes"
+        );
+        self.key_id = key_id;
+        Ok(())
+    }
+
+    /// Get the size of encrypted records.
+    pub fn record_size(&self) -> u32 {
+        self.record_size
+    }
+
+    /// Set the size of encrypted records.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the record size is smaller than 18 bytes, which
+    /// leaves no room for content.
+    pub fn set_record_size(&mut self, record_size: u32) -> crate::Result<()> {
+        ensure!(
+            record_size as usize > TAG_LEN + 1,
+            500,
+            "aes128gcm records must be at least 18 bytes"
+        );
+        self.record_size = record_size;
+        Ok(())
+    }
+
+    /// Encrypt `plaintext`, including the coding header.
+    ///
+    /// Unless a fixed salt was set, every call uses a new random salt.
+    pub fn encrypt(&self, plaintext: &[u8]) -> Vec<u8> {
+        let salt = self.salt.unwrap_or_else(|| {
+            let mut salt = [0; 16];
+            fill_random(&mut salt);
+            salt
+        });
+        let (cipher, nonce) = derive(&self.key, &salt);
+        let mut output = Vec::with_capacity(HEADER_LEN + self.key_id.len() + plaintext.len());
+        output.extend_from_slice(&salt);
+        output.extend_from_slice(&self.record_size.to_be_bytes());
+        output.push(self.key_id.len() as u8);
+        output.extend_from_slice(&self.key_id);
+
+        // Each record ends with a delimiter, which marks the last record.
+        let chunk_size = self.record_size as usize - TAG_LEN - 1;
+        let mut chunks = plaintext.chunks(chunk_size).peekable();
+        let mut seq = 0;
+        loop {
+            let chunk = chunks.next().unwrap_or_default();
+            let last = chunks.peek().is_none();
+            let mut record = chunk.to_vec();
+            record.push(if last { 2 } else { 1 });
+            let record = cipher
+                .encrypt(&record_nonce(&nonce, seq), record.as_slice())
+                .expect("records fit in a single AES-GCM message");
+            output.extend_from_slice(&record);
+            if last {
+                return output;
+            }
+            seq += 1;
+        }
+    }
+
+    /// Decrypt `data`, which starts with the coding header.
+    ///
+    /// The salt, record size and key identifier are read from the header,
+    /// only the keying material of this instance is used.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `400 Bad Request` error if the header is malformed, or the
+    /// content was modified or encrypted with other keying material.
+    pub fn decrypt(&self, data: &[u8]) -> crate::Result<Vec<u8>> {
+        ensure!(data.len() >= HEADER_LEN, 400, "Truncated aes128gcm header");
+        let mut salt = [0; 16];
+        salt.copy_from_slice(&data[..16]);
+        let record_size = u32::from_be_bytes([data[16], data[17], data[18], data[19]]) as usize;
+        ensure!(
+            record_size > TAG_LEN + 1,
+            400,
+            "Invalid aes128gcm record size `{}`",
+            record_size
+        );
+        let records = match data.get(HEADER_LEN + data[20] as usize..) {
+            Some(records) => records,
+            None => bail!(400, "Truncated aes128gcm header"),
+        };
+
+        let (cipher, nonce) = derive(&self.key, &salt);
+        let mut output = Vec::with_capacity(records.len());
+        let mut records = records.chunks(record_size).peekable();
+        let mut seq = 0;
+        while let Some(record) = records.next() {
+            let record = match cipher.decrypt(&record_nonce(&nonce, seq), record) {
+                Ok(record) => record,
+                Err(_) => bail!(400, "Could not decrypt aes128gcm record"),
+            };
+            let end = match record.iter().rposition(|byte| *byte != 0) {
+                Some(end) => end,
+                None => bail!(400, "Missing aes128gcm record delimiter"),
+            };
+            let last = records.peek().is_none();
+            match (record[end], last) {
+                (1, false) | (2, true) => output.extend_from_slice(&record[..end]),
+                (2, false) => bail!(400, "Unexpected data after the last aes128gcm record"),
+                (1, true) => bail!(400, "Truncated aes128gcm content"),
+                _ => bail!(400, "Invalid aes128gcm record delimiter"),
+            }
+            seq += 1;
+        }
+        ensure!(seq > 0, 400, "Truncated aes128gcm content");
+        Ok(output)
+    }
+
+    /// Encrypt `body`.
+    ///
+    /// The media type of the body is kept, since it describes the decrypted
+    /// content.
+    pub async fn encrypt_body(&self, body: Body) -> crate::Result<Body> {
+        let mime = body.mime().cloned();
+        let mut body = Body::from_bytes(self.encrypt(&body.into_bytes().await?));
+        body.set_mime(mime);
+        Ok(body)
+    }
+
+    /// Decrypt `body`.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `400 Bad Request` error if the content could not be
+    /// decrypted.
+    pub async fn decrypt_body(&self, body: Body) -> crate::Result<Body> {
+        let mime = body.mime().cloned();
+        let mut body = Body::from_bytes(self.decrypt(&body.into_bytes().await?)?);
+        body.set_mime(mime);
+        Ok(body)
+    }
+}
+
+impl Debug for Aes128Gcm {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Aes128Gcm")
+            .field("salt", &self.salt)
+            .field("key_id", &self.key_id)
+            .field("record_size", &self.record_size)
+            .finish()
+    }
+}
+
+/// Derive the content encryption key and the base nonce.
+fn derive(key: &[u8], salt: &[u8; 16]) -> (aes_gcm::Aes128Gcm, [u8; 12]) {
+    let hkdf = Hkdf::<Sha256>::new(Some(salt), key);
+    let mut cek = [0; 16];
+    hkdf.expand(b"Content-Encoding: aes128gcm\0", &mut cek)
+        .expect("16 bytes is a valid HKDF output length");
+    let mut nonce = [0; 12];
+    hkdf.expand(b"Content-Encoding: nonce\0", &mut nonce)
+        .expect("12 bytes is a valid HKDF output length");
+    let cipher = aes_gcm::Aes128Gcm::new(GenericArray::from_slice(&cek));
+    (cipher, nonce)
+}
+
+/// XOR the record sequence number into the last bytes of the base nonce.
+fn record_nonce(nonce: &[u8; 12], seq: u64) -> GenericArray<u8, aes_gcm::aead::consts::U12> {
+    let mut nonce = *nonce;
+    for (byte, seq) in nonce[4..].iter_mut().zip(seq.to_be_bytes()) {
+        *byte ^= seq;
+    }
+    GenericArray::clone_from_slice(&nonce)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn decode(s: &str) -> Vec<u8> {
+        base64::decode_config(s, base64::URL_SAFE_NO_PAD).unwrap()
+    }
+
+    #[test]
+    fn rfc_8188_single_record() -> crate::Result<()> {
+        let mut coding = Aes128Gcm::new(decode("yqdlZ-tYemfogSmv7Ws5PQ"));
+        let mut salt = [0; 16];
+        salt.copy_from_slice(&decode("I1BsxtFttlv3u_Oo94xnmw"));
+        coding.set_salt(salt);
+
+        let encrypted =
+            decode("I1BsxtFttlv3u_Oo94xnmwAAEAAA-NAVub2qFgBEuQKRapoZu-IxkIva3MEB1PD-ly8Thjg");
+        assert_eq!(coding.encrypt(b"I am the walrus"), encrypted);
+        assert_eq!(coding.decrypt(&encrypted)?, b"I am the walrus");
+        Ok(())
+    }
+
+    #[test]
+    fn rfc_8188_multiple_records() -> crate::Result<()> {
+        let coding = Aes128Gcm::new(decode("BO3ZVPxUlnLORbVGMpbT1Q"));
+        let encrypted = decode("uNCkWiNYzKTnBN9ji3-qWAAAABkCYTHOG8chz_gnvgOqdGYovxyjuqRyJFjEDyoF1Fvkj6hQPdPHI51OEUKEpgz3SsLWIqS_uA");
+        assert_eq!(coding.decrypt(&encrypted)?, b"I am the walrus");
+        Ok(())
+    }
+
+    #[test]
+    fn record_sizes() -> crate::Result<()> {
+        let mut coding = Aes128Gcm::new(b"key".to_vec());
+        coding.set_key_id(b"a1".to_vec())?;
+        coding.set_record_size(20)?;
+        for len in [0, 1, 3, 4, 5, 12] {
+            let plaintext = vec![b'x'; len];
+            let encrypted = coding.encrypt(&plaintext);
+            let records = len.max(1).div_ceil(3);
+            assert_eq!(
+                encrypted.len(),
+                HEADER_LEN + 2 + len + records * (TAG_LEN + 1),
+                "{} bytes",
+                len
+            );
+            assert_eq!(coding.decrypt(&encrypted)?, plaintext);
+        }
+        assert!(coding.set_record_size(17).is_err());
+        assert!(coding.set_key_id(vec![0; 256]).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn fresh_salt_per_message() -> crate::Result<()> {
+        let coding = Aes128Gcm::new(b"key".to_vec());
+        let first = coding.encrypt(b"hello");
+        let second = coding.clone().encrypt(b"hello");
+        assert_ne!(first[..16], second[..16]);
+        assert_ne!(first[HEADER_LEN..], second[HEADER_LEN..]);
+        assert_eq!(coding.decrypt(&first)?, coding.decrypt(&second)?);
+        assert_eq!(coding.salt(), None);
+        Ok(())
+    }
+
+    #[test]
+    fn bad_request_on_decrypt_error() {
+        let coding = Aes128Gcm::new(b"key".to_vec());
+        let mut encrypted = coding.encrypt(b"hello");
+        let other = Aes128Gcm::new(b"other key".to_vec());
+        assert_eq!(other.decrypt(&encrypted).unwrap_err().status(), 400);
+        assert_eq!(coding.decrypt(&encrypted[..20]).unwrap_err().status(), 400);
+
+        let last = encrypted.len() - 1;
+        encrypted[last] ^= 1;
+        assert_eq!(coding.decrypt(&encrypted).unwrap_err().status(), 400);
+    }
+
+    #[async_std::test]
+    async fn body() -> crate::Result<()> {
+        let coding = Aes128Gcm::new(b"key".to_vec());
+        let body = coding
+            .encrypt_body(Body::from_string("hello".into()))
+            .await?;
+        assert_eq!(body.mime().unwrap().essence(), "text/plain");
+        let body = coding.decrypt_body(body).await?;
+        assert_eq!(body.into_string().await?, "hello");
+        Ok(())
+    }
+}
@@ -13,6 +13,8 @@ pub enum Encoding {
     Brotli,
     /// The Zstd encoding.
     Zstd,
+    /// The aes128gcm encrypted content coding.
+    Aes128Gcm,
     /// No encoding.
     Identity,
 }
@@ -32,6 +34,7 @@ impl Encoding {
             "deflate" => Some(Encoding::Deflate),
             "br" => Some(Encoding::Brotli),
             "zstd" => Some(Encoding::Zstd),
+            "aes128gcm" => Some(Encoding::Aes128Gcm),
             "identity" => Some(Encoding::Identity),
             _ => None,
         }
@@ -45,6 +48,7 @@ impl Display for Encoding {
             Encoding::Deflate => write!(f, "deflate"),
             Encoding::Brotli => write!(f, "br"),
             Encoding::Zstd => write!(f, "zstd"),
+            Encoding::Aes128Gcm => write!(f, "aes128gcm"),
             Encoding::Identity => write!(f, "identity"),
         }
     }
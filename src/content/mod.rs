@@ -35,6 +35,8 @@ pub mod accept;
 pub mod accept_encoding;
 pub mod content_encoding;
 
+#[cfg(feature = "aes128gcm")]
+mod aes128gcm;
 mod content_length;
 mod content_location;
 mod content_type;
@@ -46,6 +48,9 @@ mod media_type_proposal;
 pub use accept::Accept;
 #[doc(inline)]
 pub use accept_encoding::AcceptEncoding;
+#[cfg(feature = "aes128gcm")]
+#[cfg_attr(feature = "docs", doc(cfg(feature = "aes128gcm")))]
+pub use aes128gcm::Aes128Gcm;
 #[doc(inline)]
 pub use content_encoding::ContentEncoding;
 pub use content_length::ContentLength;
@@ -0,0 +1,434 @@
+use crate::cookies::{Cookie, CookieJar};
+use crate::ensure_status as ensure;
+use crate::headers::{HeaderValue, Headers, COOKIE, SET_COOKIE};
+
+use std::fmt::{self, Debug};
+
+#[cfg(feature = "cookie-secure")]
+use crate::cookies::{Key, PrivateJar, SignedJar};
+
+const SECURE_PREFIX: &str = "__Secure-";
+const HOST_PREFIX: &str = "__Host-";
+
+/// A cookie jar bound to a request-response pair.
+///
+/// A `Jar` is populated from the `Cookie` header of an incoming `Request`.
+/// Handlers can then read, add and remove cookies, optionally through
+/// `signed` or `private` sub-jars. Once done, only the cookies which changed
+/// are written to the `Response` as `Set-Cookie` headers.
+///
+/// # Specifications
+///
+/// - [RFC 6265, section 4.1: Set-Cookie](https://tools.ietf.org/html/rfc6265#section-4.1)
+/// - [RFC 6265, section 4.2: Cookie](https://tools.ietf.org/html/rfc6265#section-4.2)
+///
+/// # Examples
+///
+/// ```
+/// # fn main() -> http_types::Result<()> {
+/// #
+/// use http_types::cookies::{Cookie, Jar};
+/// use http_types::{Request, Response};
+///
+/// let mut req = Request::get("https://example.com");
+/// req.insert_header("Cookie", "theme=dark; lang=en");
+///
+/// let mut jar = Jar::from_headers(&req)?;
+/// assert_eq!(jar.get("theme").unwrap().value(), "dark");
+///
+/// jar.add(Cookie::new("theme", "light"))?;
+///
+/// let mut res = Response::new(200);
+/// jar.apply(&mut res)?;
+/// assert_eq!(res["Set-Cookie"], "theme=light");
+/// #
+/// # Ok(()) }
+/// ```
+#[derive(Clone, Default)]
+pub struct Jar {
+    jar: CookieJar,
+    #[cfg(feature = "cookie-secure")]
+    key: Option<Key>,
+}
+
+impl Jar {
+    /// Create a new, empty instance of `Jar`.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create a new instance from the `Cookie` headers of a request.
+    ///
+    /// Cookies read this way are treated as originals: they are not written
+    /// back to the response unless they are modified or removed. Pairs which
+    /// can't be parsed or percent-decoded are skipped, so one bad cookie
+    /// doesn't fail the whole request.
+    pub fn from_headers(headers: impl AsRef<Headers>) -> crate::Result<Self> {
+        let mut jar = Self::new();
+        let headers = match headers.as_ref().get(COOKIE) {
+            Some(headers) => headers,
+            None => return Ok(jar),
+        };
+
+        for value in headers {
+            for pair in value.as_str().split(';') {
+                let pair = pair.trim();
+                if pair.is_empty() {
+                    continue;
+                }
+                if let Ok(cookie) = Cookie::parse_encoded(pair) {
+                    jar.jar.add_original(cookie.into_owned());
+                }
+            }
+        }
+
+        Ok(jar)
+    }
+
+    /// Set the key used by the `signed` and `private` sub-jars.
+    #[cfg(feature = "cookie-secure")]
+    #[cfg_attr(feature = "docs", doc(cfg(feature = "cookie-secure")))]
+    pub fn set_key(&mut self, key: Key) {
+        self.key = Some(key);
+    }
+
+    /// Get the key used by the `signed` and `private` sub-jars, if any.
+    #[cfg(feature = "cookie-secure")]
+    #[cfg_attr(feature = "docs", doc(cfg(feature = "cookie-secure")))]
+    pub fn key(&self) -> Option<&Key> {
+        self.key.as_ref()
+    }
+
+    /// Get a cookie by name.
+    pub fn get(&self, name: &str) -> Option<&Cookie<'static>> {
+        self.jar.get(name)
+    }
+
+    /// Add a cookie to the jar.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the cookie does not meet the requirements of its
+    /// name prefix. See [`validate_prefix`].
+    pub fn add(&mut self, cookie: Cookie<'static>) -> crate::Result<()> {
+        validate_prefix(&cookie)?;
+        self.jar.add(cookie);
+        Ok(())
+    }
+
+    /// Remove a cookie from the jar.
+    ///
+    /// If the cookie was sent by the client, a removal cookie is written to the
+    /// response. The `path` and `domain` of the removal cookie must match those
+    /// used when the cookie was set. Cookies with a `__Secure-` or `__Host-`
+    /// prefix are marked `Secure` (and for `__Host-`, given a `/` path) so the
+    /// removal is accepted by the client.
+    pub fn remove(&mut self, mut cookie: Cookie<'static>) {
+        if cookie.name().starts_with(HOST_PREFIX) {
+            cookie.set_secure(true);
+            cookie.set_path("/");
+            cookie.unset_domain();
+        } else if cookie.name().starts_with(SECURE_PREFIX) {
+            cookie.set_secure(true);
+        }
+        self.jar.remove(cookie);
+    }
+
+    /// An iterator visiting all cookies in the jar.
+    pub fn iter(&self) -> crate::cookies::Iter<'_> {
+        self.jar.iter()
+    }
+
+    /// An iterator visiting the cookies which changed since the jar was
+    /// created.
+    pub fn delta(&self) -> crate::cookies::Delta<'_> {
+        self.jar.delta()
+    }
+
+    /// Get a reference to the underlying `CookieJar`.
+    pub fn jar(&self) -> &CookieJar {
+        &self.jar
+    }
+
+    /// Get a mutable reference to the underlying `CookieJar`.
+    ///
+    /// Cookies added this way skip prefix validation until they're written by
+    /// [`Jar::apply`].
+    pub fn jar_mut(&mut self) -> &mut CookieJar {
+        &mut self.jar
+    }
+
+    /// Get a sub-jar which verifies the authenticity of its cookies.
+    ///
+    /// # Panics
+    ///
+    /// Panics if no key has been set using [`Jar::set_key`].
+    #[cfg(feature = "cookie-secure")]
+    #[cfg_attr(feature = "docs", doc(cfg(feature = "cookie-secure")))]
+    pub fn signed(&self) -> SignedJar<&CookieJar> {
+        let key = self
+            .key
+            .as_ref()
+            .expect("A key must be set to use signed cookies");
+        self.jar.signed(key)
+    }
+
+    /// Get a mutable sub-jar which signs and verifies its cookies.
+    ///
+    /// # Panics
+    ///
+    /// Panics if no key has been set using [`Jar::set_key`].
+    #[cfg(feature = "cookie-secure")]
+    #[cfg_attr(feature = "docs", doc(cfg(feature = "cookie-secure")))]
+    pub fn signed_mut(&mut self) -> SignedJar<&mut CookieJar> {
+        let key = self
+            .key
+            .as_ref()
+            .expect("A key must be set to use signed cookies");
+        self.jar.signed_mut(key)
+    }
+
+    /// Get a sub-jar which decrypts and authenticates its cookies.
+    ///
+    /// # Panics
+    ///
+    /// Panics if no key has been set using [`Jar::set_key`].
+    #[cfg(feature = "cookie-secure")]
+    #[cfg_attr(feature = "docs", doc(cfg(feature = "cookie-secure")))]
+    pub fn private(&self) -> PrivateJar<&CookieJar> {
+        let key = self
+            .key
+            .as_ref()
+            .expect("A key must be set to use private cookies");
+        self.jar.private(key)
+    }
+
+    /// Get a mutable sub-jar which encrypts, decrypts and authenticates its
+    /// cookies.
+    ///
+    /// # Panics
+    ///
+    /// Panics if no key has been set using [`Jar::set_key`].
+    #[cfg(feature = "cookie-secure")]
+    #[cfg_attr(feature = "docs", doc(cfg(feature = "cookie-secure")))]
+    pub fn private_mut(&mut self) -> PrivateJar<&mut CookieJar> {
+        let key = self
+            .key
+            .as_ref()
+            .expect("A key must be set to use private cookies");
+        self.jar.private_mut(key)
+    }
+
+    /// Write the cookies which changed as `Set-Cookie` headers.
+    ///
+    /// Existing `Set-Cookie` headers are kept.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if any of the changed cookies does not meet the
+    /// requirements of its name prefix, or has a non-ASCII path or domain. No
+    /// headers are written in that case.
+    pub fn apply(&self, mut headers: impl AsMut<Headers>) -> crate::Result<()> {
+        let mut values = vec![];
+        for cookie in self.jar.delta() {
+            validate_prefix(cookie)?;
+            let value = cookie.encoded().to_string();
+            values.push(HeaderValue::from_bytes(value.into_bytes())?);
+        }
+
+        if !values.is_empty() {
+            headers.as_mut().append(SET_COOKIE, &values[..])?;
+        }
+        Ok(())
+    }
+}
+
+impl Debug for Jar {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut s = f.debug_struct("Jar");
+        s.field("jar", &self.jar);
+        #[cfg(feature = "cookie-secure")]
+        s.field("key", &self.key.as_ref().map(|_| "Key"));
+        s.finish()
+    }
+}
+
+/// Validate a cookie against the requirements of its name prefix.
+///
+/// - Cookies named `__Secure-*` must set the `Secure` attribute.
+/// - Cookies named `__Host-*` must set the `Secure` attribute, set `Path=/`,
+///   and must not set a `Domain` attribute.
+///
+/// Violating these requirements is a server error, so the returned error
+/// carries a `500` status code.
+///
+/// # Examples
+///
+/// ```
+/// use http_types::cookies::{validate_prefix, Cookie};
+///
+/// let cookie = Cookie::build("__Host-id", "1").secure(true).path("/").finish();
+/// assert!(validate_prefix(&cookie).is_ok());
+///
+/// let cookie = Cookie::build("__Secure-id", "1").finish();
+/// assert!(validate_prefix(&cookie).is_err());
+/// ```
+pub fn validate_prefix(cookie: &Cookie<'_>) -> crate::Result<()> {
+    let name = cookie.name();
+    let secure = cookie.secure() == Some(true);
+
+    if name.starts_with(SECURE_PREFIX) {
+        ensure!(
+            secure,
+            500,
+            "Cookie `{}` must set the Secure attribute",
+            name
+        );
+    } else if name.starts_with(HOST_PREFIX) {
+        ensure!(
+            secure,
+            500,
+            "Cookie `{}` must set the Secure attribute",
+            name
+        );
+        ensure!(
+            cookie.path() == Some("/"),
+            500,
+            "Cookie `{}` must set `Path=/`",
+            name
+        );
+        ensure!(
+            cookie.domain().is_none(),
+            500,
+            "Cookie `{}` must not set a Domain attribute",
+            name
+        );
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{Request, Response};
+
+    #[test]
+    fn only_writes_delta() -> crate::Result<()> {
+        let mut req = Request::get("https://example.com");
+        req.insert_header(COOKIE, "a=1; b=2")?;
+        let mut jar = Jar::from_headers(&req)?;
+        assert_eq!(jar.get("a").unwrap().value(), "1");
+        assert_eq!(jar.get("b").unwrap().value(), "2");
+
+        let mut res = Response::new(200);
+        jar.apply(&mut res)?;
+        assert!(res.header(SET_COOKIE).is_none());
+
+        jar.add(Cookie::new("c", "hello world"))?;
+        jar.remove(Cookie::named("a"));
+        jar.apply(&mut res)?;
+
+        let values = res.header(SET_COOKIE).unwrap();
+        assert_eq!(values.iter().count(), 2);
+        assert!(values.iter().any(|v| v == "c=hello%20world"));
+        assert!(values.iter().any(|v| v.as_str().starts_with("a=;")));
+        Ok(())
+    }
+
+    #[test]
+    fn skips_invalid_pairs() -> crate::Result<()> {
+        let mut headers = Headers::new();
+        headers.insert(COOKIE, "a=1; =nori; b=%ff; nori; c=3")?;
+        let jar = Jar::from_headers(headers)?;
+        assert_eq!(jar.get("a").unwrap().value(), "1");
+        assert!(jar.get("b").is_none());
+        assert_eq!(jar.get("c").unwrap().value(), "3");
+        assert_eq!(jar.iter().count(), 2);
+        Ok(())
+    }
+
+    #[test]
+    fn prefix_validation() {
+        let mut jar = Jar::new();
+        let err = jar.add(Cookie::new("__Secure-id", "1")).unwrap_err();
+        assert_eq!(err.status(), 500);
+
+        let cookie = Cookie::build("__Host-id", "1")
+            .secure(true)
+            .path("/")
+            .domain("example.com")
+            .finish();
+        assert!(jar.add(cookie).is_err());
+
+        let cookie = Cookie::build("__Host-id", "1").secure(true).finish();
+        assert!(jar.add(cookie).is_err());
+
+        let cookie = Cookie::build("__Host-id", "1")
+            .secure(true)
+            .path("/")
+            .finish();
+        assert!(jar.add(cookie).is_ok());
+
+        jar.jar_mut().add(Cookie::new("__Secure-raw", "1"));
+        let err = jar.apply(Headers::new()).unwrap_err();
+        assert_eq!(err.status(), 500);
+    }
+
+    #[test]
+    fn non_ascii_attributes() -> crate::Result<()> {
+        let mut jar = Jar::new();
+        jar.add(Cookie::build("a", "1").path("/caf\u{e9}").finish())?;
+        let mut res = Response::new(200);
+        assert!(jar.apply(&mut res).is_err());
+        assert!(res.header(SET_COOKIE).is_none());
+        Ok(())
+    }
+
+    #[test]
+    fn remove_prefixed_cookie() -> crate::Result<()> {
+        let mut headers = Headers::new();
+        headers.insert(COOKIE, "__Host-id=1")?;
+        let mut jar = Jar::from_headers(&headers)?;
+        jar.remove(Cookie::named("__Host-id"));
+
+        let mut res = Response::new(200);
+        jar.apply(&mut res)?;
+        let value = res[SET_COOKIE].as_str();
+        assert!(value.contains("Secure"));
+        assert!(value.contains("Path=/"));
+        Ok(())
+    }
+
+    #[cfg(feature = "cookie-secure")]
+    #[test]
+    fn signed_and_private() -> crate::Result<()> {
+        let key = Key::generate();
+
+        let mut jar = Jar::new();
+        jar.set_key(key.clone());
+        jar.signed_mut().add(Cookie::new("signed", "nori"));
+        jar.private_mut().add(Cookie::new("private", "chashu"));
+
+        let mut res = Response::new(200);
+        jar.apply(&mut res)?;
+
+        // Send the cookies back as a client would.
+        let mut req = Request::get("https://example.com");
+        for value in res.header(SET_COOKIE).unwrap() {
+            let cookie = Cookie::parse_encoded(value.as_str())?;
+            let (name, value) = cookie.name_value();
+            req.append_header(COOKIE, Cookie::new(name, value).encoded().to_string())?;
+        }
+
+        let mut jar = Jar::from_headers(&req)?;
+        jar.set_key(key);
+        assert_eq!(jar.signed().get("signed").unwrap().value(), "nori");
+        assert_eq!(jar.private().get("private").unwrap().value(), "chashu");
+        assert_ne!(jar.get("private").unwrap().value(), "chashu");
+
+        jar.set_key(Key::generate());
+        assert!(jar.signed().get("signed").is_none());
+        assert!(jar.private().get("private").is_none());
+        Ok(())
+    }
+}
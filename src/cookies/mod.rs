@@ -0,0 +1,17 @@
+//! HTTP cookies.
+//!
+//! This module re-exports the [`cookie`](https://docs.rs/cookie) crate, and
+//! adds types to connect cookies to `Request` and `Response`: a server-side
+//! [`Jar`], and a client-side [`CookieStore`].
+//!
+//! # Specifications
+//!
+//! - [RFC 6265: HTTP State Management Mechanism](https://tools.ietf.org/html/rfc6265)
+//! - [Cookie Prefixes](https://tools.ietf.org/html/draft-ietf-httpbis-rfc6265bis-10#section-4.1.3)
+
+mod jar;
+mod store;
+
+pub use cookie::*;
+pub use jar::{validate_prefix, Jar};
+pub use store::{CookieStore, StoredCookie};
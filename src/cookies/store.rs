@@ -0,0 +1,737 @@
+use crate::cookies::{validate_prefix, Cookie, SameSite};
+use crate::headers::{HeaderValue, Headers, COOKIE, SET_COOKIE};
+use crate::parse_utils::is_quotable;
+use crate::utils::parse_http_date;
+use crate::{Method, Request, Url};
+
+use std::net::IpAddr;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+#[cfg(feature = "serde")]
+use serde_crate::{Deserialize, Serialize};
+
+/// Cookie lifetimes are capped at 400 days, per RFC 6265bis.
+const MAX_AGE_LIMIT: u64 = 400 * 24 * 60 * 60;
+
+/// A client-side cookie store.
+///
+/// Cookies are ingested from the `Set-Cookie` headers of a response, and the
+/// matching cookies are attached to outgoing requests as a `Cookie` header.
+///
+/// # Specifications
+///
+/// - [RFC 6265, section 5.3: Storage Model](https://tools.ietf.org/html/rfc6265#section-5.3)
+/// - [RFC 6265, section 5.4: The Cookie Header](https://tools.ietf.org/html/rfc6265#section-5.4)
+/// - [RFC 6265bis, section 5.6.7: The SameSite Attribute](https://tools.ietf.org/html/draft-ietf-httpbis-rfc6265bis-10#section-5.6.7)
+///
+/// # Implementation Notes
+///
+/// No public suffix list is consulted. As a safeguard, `Domain` attributes
+/// naming a single label (e.g. `com`) are rejected unless they equal the
+/// request host.
+///
+/// # Examples
+///
+/// ```
+/// # fn main() -> http_types::Result<()> {
+/// #
+/// use http_types::cookies::CookieStore;
+/// use http_types::{Request, Response};
+///
+/// let req = Request::get("https://example.com/login");
+/// let mut res = Response::new(200);
+/// res.append_header("Set-Cookie", "session=nori; Path=/; Secure; HttpOnly")?;
+///
+/// let mut store = CookieStore::new();
+/// store.store(req.url(), &res);
+///
+/// let mut req = Request::get("https://example.com/profile");
+/// store.apply(&mut req);
+/// assert_eq!(req["Cookie"], "session=nori");
+/// #
+/// # Ok(()) }
+/// ```
+#[derive(Debug, Clone, Default)]
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(crate = "serde_crate")
+)]
+pub struct CookieStore {
+    cookies: Vec<StoredCookie>,
+}
+
+/// A cookie held by a [`CookieStore`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(crate = "serde_crate")
+)]
+pub struct StoredCookie {
+    name: String,
+    value: String,
+    domain: String,
+    host_only: bool,
+    path: String,
+    secure: bool,
+    http_only: bool,
+    #[cfg_attr(feature = "serde", serde(with = "same_site"))]
+    same_site: Option<SameSite>,
+    expires: Option<SystemTime>,
+    created: SystemTime,
+}
+
+impl StoredCookie {
+    /// Get the cookie name.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Get the cookie value.
+    pub fn value(&self) -> &str {
+        &self.value
+    }
+
+    /// Get the domain the cookie applies to.
+    pub fn domain(&self) -> &str {
+        &self.domain
+    }
+
+    /// Returns `true` if the cookie is only sent to the exact host which set it.
+    pub fn host_only(&self) -> bool {
+        self.host_only
+    }
+
+    /// Get the path the cookie applies to.
+    pub fn path(&self) -> &str {
+        &self.path
+    }
+
+    /// Returns `true` if the cookie is only sent over secure channels.
+    pub fn secure(&self) -> bool {
+        self.secure
+    }
+
+    /// Returns `true` if the cookie was set with the `HttpOnly` attribute.
+    pub fn http_only(&self) -> bool {
+        self.http_only
+    }
+
+    /// Get the `SameSite` policy of the cookie, if any.
+    pub fn same_site(&self) -> Option<SameSite> {
+        self.same_site
+    }
+
+    /// Get the expiry time of the cookie. Session cookies return `None`.
+    pub fn expires(&self) -> Option<SystemTime> {
+        self.expires
+    }
+
+    /// Returns `true` if the cookie does not expire.
+    pub fn is_session(&self) -> bool {
+        self.expires.is_none()
+    }
+
+    /// Returns `true` if the cookie has expired at the given time.
+    pub fn is_expired(&self, now: SystemTime) -> bool {
+        matches!(self.expires, Some(expires) if expires <= now)
+    }
+
+    /// Parse a `Set-Cookie` string received from `url`, applying the rules of
+    /// the storage model. Returns `None` if the cookie must be ignored.
+    fn parse(s: &str, url: &Url, now: SystemTime) -> Option<Self> {
+        let mut parts = s.split(';');
+        let (name, value) = parts.next()?.split_once('=')?;
+        let name = name.trim();
+        if name.is_empty() {
+            return None;
+        }
+        // The value is stored verbatim, including any surrounding DQUOTEs.
+        let value = value.trim();
+        if !is_cookie_text(name) || !is_cookie_text(value) {
+            return None;
+        }
+
+        let mut max_age = None;
+        let mut expires = None;
+        let mut domain = None;
+        let mut path = None;
+        let mut secure = false;
+        let mut http_only = false;
+        let mut same_site = None;
+
+        // Unknown or malformed attributes are ignored, and the last attribute
+        // with a given name wins.
+        for attr in parts {
+            let (key, val) = match attr.split_once('=') {
+                Some((key, val)) => (key.trim(), val.trim()),
+                None => (attr.trim(), ""),
+            };
+            match key.to_ascii_lowercase().as_str() {
+                "max-age" => {
+                    if let Ok(secs) = val.parse::<i64>() {
+                        max_age = Some(secs);
+                    }
+                }
+                "expires" => {
+                    if let Ok(time) = parse_http_date(val) {
+                        expires = Some(time);
+                    }
+                }
+                "domain" if !val.is_empty() => {
+                    domain = Some(val.trim_start_matches('.').to_ascii_lowercase());
+                }
+                "path" => path = Some(val),
+                "secure" => secure = true,
+                "httponly" => http_only = true,
+                "samesite" => {
+                    same_site = match val.to_ascii_lowercase().as_str() {
+                        "strict" => Some(SameSite::Strict),
+                        "lax" => Some(SameSite::Lax),
+                        "none" => Some(SameSite::None),
+                        _ => None,
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        // Max-Age takes precedence over Expires.
+        let expires = match max_age {
+            Some(secs) if secs <= 0 => Some(UNIX_EPOCH),
+            Some(secs) => Some(now + Duration::from_secs((secs as u64).min(MAX_AGE_LIMIT))),
+            None => expires.map(|time| time.min(now + Duration::from_secs(MAX_AGE_LIMIT))),
+        };
+
+        let host = url.host_str()?.to_ascii_lowercase();
+        let (domain, host_only) = match domain {
+            Some(domain) if domain == host => (domain, false),
+            Some(domain) => {
+                if !domain.contains('.') || !domain_match(&host, &domain) {
+                    return None;
+                }
+                (domain, false)
+            }
+            None => (host, true),
+        };
+
+        let path_attr = path.filter(|path| path.starts_with('/'));
+        let path = match path_attr {
+            Some(path) => path.to_owned(),
+            None => default_path(url.path()).to_owned(),
+        };
+
+        // Secure cookies can only be set from secure origins.
+        if secure && !is_secure(url) {
+            return None;
+        }
+
+        // A `SameSite=None` cookie must also be `Secure`.
+        if same_site == Some(SameSite::None) && !secure {
+            return None;
+        }
+
+        let cookie = Self {
+            name: name.to_owned(),
+            value: value.to_owned(),
+            domain,
+            host_only,
+            path,
+            secure,
+            http_only,
+            same_site,
+            expires,
+            created: now,
+        };
+
+        // Cookie prefixes are validated against the stored cookie, so
+        // `__Host-` cookies must be host-only and set an explicit `Path=/`.
+        let mut check = Cookie::new(cookie.name.as_str(), "");
+        check.set_secure(cookie.secure);
+        if let Some(path) = path_attr {
+            check.set_path(path);
+        }
+        if !cookie.host_only {
+            check.set_domain(cookie.domain.as_str());
+        }
+        validate_prefix(&check).ok()?;
+
+        Some(cookie)
+    }
+
+    /// Check whether the cookie should be sent along with a request to `url`.
+    fn matches(&self, url: &Url, now: SystemTime) -> bool {
+        let host = match url.host_str() {
+            Some(host) => host.to_ascii_lowercase(),
+            None => return false,
+        };
+
+        let domain_ok = if self.host_only {
+            host == self.domain
+        } else {
+            domain_match(&host, &self.domain)
+        };
+
+        domain_ok
+            && path_match(url.path(), &self.path)
+            && (!self.secure || is_secure(url))
+            && !self.is_expired(now)
+    }
+}
+
+impl CookieStore {
+    /// Create a new, empty instance of `CookieStore`.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Store the cookies from the `Set-Cookie` headers of a response.
+    ///
+    /// `url` is the URL of the request which produced the response. Cookies
+    /// which are invalid, or which the response is not allowed to set, are
+    /// ignored.
+    pub fn store(&mut self, url: &Url, headers: impl AsRef<Headers>) {
+        self.store_at(url, headers, SystemTime::now());
+    }
+
+    fn store_at(&mut self, url: &Url, headers: impl AsRef<Headers>, now: SystemTime) {
+        let headers = match headers.as_ref().get(SET_COOKIE) {
+            Some(headers) => headers,
+            None => return,
+        };
+
+        for value in headers {
+            if let Some(cookie) = StoredCookie::parse(value.as_str(), url, now) {
+                self.insert(cookie, now);
+            }
+        }
+    }
+
+    /// Insert a cookie, replacing a cookie with the same name, domain and
+    /// path. Expired cookies remove the existing entry instead.
+    fn insert(&mut self, mut cookie: StoredCookie, now: SystemTime) {
+        let existing = self.cookies.iter().position(|c| {
+            c.name == cookie.name && c.domain == cookie.domain && c.path == cookie.path
+        });
+
+        if let Some(index) = existing {
+            let old = self.cookies.remove(index);
+            cookie.created = old.created;
+        }
+
+        if !cookie.is_expired(now) {
+            self.cookies.push(cookie);
+        }
+    }
+
+    /// Get the cookies to send along with a same-site request to `url`.
+    ///
+    /// Cookies are sorted with longer paths first, and then by creation time.
+    pub fn matches(&self, url: &Url) -> Vec<&StoredCookie> {
+        self.matches_at(url, SystemTime::now())
+    }
+
+    fn matches_at(&self, url: &Url, now: SystemTime) -> Vec<&StoredCookie> {
+        let mut cookies: Vec<_> = self
+            .cookies
+            .iter()
+            .filter(|c| c.matches(url, now))
+            .collect();
+        cookies.sort_by(|a, b| {
+            b.path
+                .len()
+                .cmp(&a.path.len())
+                .then(a.created.cmp(&b.created))
+        });
+        cookies
+    }
+
+    /// Attach the matching cookies to a request as a `Cookie` header.
+    ///
+    /// The request is treated as same-site, which is the right choice for
+    /// clients that aren't acting on behalf of a document from another site.
+    /// Existing `Cookie` headers are replaced.
+    pub fn apply(&self, req: &mut Request) {
+        let cookies = self.matches(req.url());
+        set_cookie_header(req, &cookies);
+    }
+
+    /// Attach the matching cookies to a cross-site request as a `Cookie`
+    /// header.
+    ///
+    /// `SameSite=Strict` cookies are never sent, and `SameSite=Lax` cookies
+    /// are only sent along with safe methods. Existing `Cookie` headers are
+    /// replaced.
+    pub fn apply_cross_site(&self, req: &mut Request) {
+        let method: Method = req.method();
+        let cookies: Vec<_> = self
+            .matches(req.url())
+            .into_iter()
+            .filter(|c| match c.same_site {
+                Some(SameSite::Strict) => false,
+                Some(SameSite::Lax) => method.is_safe(),
+                _ => true,
+            })
+            .collect();
+        set_cookie_header(req, &cookies);
+    }
+
+    /// Remove a cookie by domain, path and name.
+    pub fn remove(&mut self, domain: &str, path: &str, name: &str) -> Option<StoredCookie> {
+        let index = self
+            .cookies
+            .iter()
+            .position(|c| c.domain == domain && c.path == path && c.name == name)?;
+        Some(self.cookies.remove(index))
+    }
+
+    /// Remove all expired cookies.
+    pub fn remove_expired(&mut self) {
+        let now = SystemTime::now();
+        self.cookies.retain(|c| !c.is_expired(now));
+    }
+
+    /// Remove all session cookies, as is done when a session ends.
+    pub fn remove_session_cookies(&mut self) {
+        self.cookies.retain(|c| !c.is_session());
+    }
+
+    /// Remove all cookies.
+    pub fn clear(&mut self) {
+        self.cookies.clear();
+    }
+
+    /// An iterator visiting all stored cookies.
+    pub fn iter(&self) -> std::slice::Iter<'_, StoredCookie> {
+        self.cookies.iter()
+    }
+
+    /// Get the number of stored cookies.
+    pub fn len(&self) -> usize {
+        self.cookies.len()
+    }
+
+    /// Returns `true` if the store holds no cookies.
+    pub fn is_empty(&self) -> bool {
+        self.cookies.is_empty()
+    }
+}
+
+fn set_cookie_header(req: &mut Request, cookies: &[&StoredCookie]) {
+    req.remove_header(COOKIE);
+    if cookies.is_empty() {
+        return;
+    }
+    // Cookies which can't be sent, which can only come from deserialized
+    // stores, are skipped.
+    let pairs: Vec<_> = cookies
+        .iter()
+        .filter(|c| is_cookie_text(&c.name) && is_cookie_text(&c.value))
+        .map(|c| format!("{}={}", c.name, c.value))
+        .collect();
+    if pairs.is_empty() {
+        return;
+    }
+    let value = HeaderValue::from_bytes(pairs.join("; ").into_bytes())
+        .expect("cookie names and values are ASCII");
+    req.insert_header(COOKIE, value).unwrap();
+}
+
+/// Returns `true` if a cookie name or value only contains ASCII characters
+/// other than controls, which is what user agents accept.
+///
+/// https://httpwg.org/http-extensions/draft-ietf-httpbis-rfc6265bis.html#section-5.6
+fn is_cookie_text(s: &str) -> bool {
+    is_quotable(s)
+}
+
+fn is_secure(url: &Url) -> bool {
+    matches!(url.scheme(), "https" | "wss")
+}
+
+/// https://tools.ietf.org/html/rfc6265#section-5.1.3
+fn domain_match(host: &str, domain: &str) -> bool {
+    if host == domain {
+        return true;
+    }
+    let host_ip = host.trim_start_matches('[').trim_end_matches(']');
+    if host_ip.parse::<IpAddr>().is_ok() {
+        return false;
+    }
+    host.len() > domain.len()
+        && host.ends_with(domain)
+        && host.as_bytes()[host.len() - domain.len() - 1] == b'.'
+}
+
+/// https://tools.ietf.org/html/rfc6265#section-5.1.4
+fn default_path(path: &str) -> &str {
+    if !path.starts_with('/') {
+        return "/";
+    }
+    match path.rfind('/') {
+        Some(0) | None => "/",
+        Some(index) => &path[..index],
+    }
+}
+
+/// https://tools.ietf.org/html/rfc6265#section-5.1.4
+fn path_match(request_path: &str, cookie_path: &str) -> bool {
+    match request_path.strip_prefix(cookie_path) {
+        Some("") => true,
+        Some(rest) => cookie_path.ends_with('/') || rest.starts_with('/'),
+        None => false,
+    }
+}
+
+#[cfg(feature = "serde")]
+mod same_site {
+    use crate::cookies::SameSite;
+    use serde_crate::{Deserialize, Deserializer, Serialize, Serializer};
+
+    pub(super) fn serialize<S>(same_site: &Option<SameSite>, s: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        same_site.map(|v| v.to_string()).serialize(s)
+    }
+
+    pub(super) fn deserialize<'de, D>(d: D) -> Result<Option<SameSite>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let s: Option<String> = Option::deserialize(d)?;
+        Ok(match s.as_deref() {
+            Some("Strict") => Some(SameSite::Strict),
+            Some("Lax") => Some(SameSite::Lax),
+            Some("None") => Some(SameSite::None),
+            _ => None,
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::Response;
+
+    fn url(s: &str) -> Url {
+        Url::parse(s).unwrap()
+    }
+
+    fn store(store: &mut CookieStore, from: &str, set_cookie: &str, now: SystemTime) {
+        let mut res = Response::new(200);
+        res.insert_header(SET_COOKIE, set_cookie).unwrap();
+        store.store_at(&url(from), &res, now);
+    }
+
+    fn names(store: &CookieStore, to: &str, now: SystemTime) -> Vec<String> {
+        store
+            .matches_at(&url(to), now)
+            .iter()
+            .map(|c| c.name().to_owned())
+            .collect()
+    }
+
+    #[test]
+    fn domain_and_host_only() {
+        let now = SystemTime::now();
+        let mut jar = CookieStore::new();
+        store(&mut jar, "https://www.example.com/", "a=1", now);
+        store(
+            &mut jar,
+            "https://www.example.com/",
+            "b=2; Domain=.example.com",
+            now,
+        );
+        store(
+            &mut jar,
+            "https://www.example.com/",
+            "c=3; Domain=other.com",
+            now,
+        );
+        store(&mut jar, "https://www.example.com/", "d=4; Domain=com", now);
+
+        assert_eq!(jar.len(), 2);
+        assert_eq!(names(&jar, "https://www.example.com/", now), ["a", "b"]);
+        assert_eq!(names(&jar, "https://api.example.com/", now), ["b"]);
+        assert!(names(&jar, "https://example.org/", now).is_empty());
+    }
+
+    #[test]
+    fn rejects_control_characters() {
+        let now = SystemTime::now();
+        let mut jar = CookieStore::new();
+        store(&mut jar, "https://example.com/", "a=1\u{1}", now);
+        store(&mut jar, "https://example.com/", "b\u{7f}=2", now);
+        store(&mut jar, "https://example.com/", "c=3", now);
+        assert_eq!(names(&jar, "https://example.com/", now), ["c"]);
+    }
+
+    #[test]
+    fn skips_unsendable_cookies() {
+        let now = SystemTime::now();
+        let mut jar = CookieStore::new();
+        store(&mut jar, "https://example.com/", "a=1", now);
+        store(&mut jar, "https://example.com/", "b=2", now);
+        jar.cookies[0].value = "caf\u{e9}".into();
+
+        let mut req = Request::get("https://example.com/");
+        jar.apply(&mut req);
+        assert_eq!(req[COOKIE], "b=2");
+    }
+
+    #[test]
+    fn keeps_quotes() {
+        let now = SystemTime::now();
+        let mut jar = CookieStore::new();
+        store(&mut jar, "https://example.com/", r#"a="x y""#, now);
+        assert_eq!(jar.iter().next().unwrap().value(), r#""x y""#);
+
+        let mut req = Request::get("https://example.com/");
+        jar.apply(&mut req);
+        assert_eq!(req[COOKIE], r#"a="x y""#);
+    }
+
+    #[test]
+    fn path_and_secure() {
+        let now = SystemTime::now();
+        let mut jar = CookieStore::new();
+        store(&mut jar, "https://example.com/docs/index.html", "a=1", now);
+        store(&mut jar, "https://example.com/", "b=2; Path=/docs/api", now);
+        store(&mut jar, "https://example.com/", "c=3; Secure", now);
+        store(&mut jar, "http://example.com/", "d=4; Secure", now);
+
+        assert_eq!(jar.iter().next().unwrap().path(), "/docs");
+        assert_eq!(
+            names(&jar, "https://example.com/docs/api/v1", now),
+            ["b", "a", "c"]
+        );
+        assert_eq!(names(&jar, "https://example.com/docsearch", now), ["c"]);
+        assert!(names(&jar, "http://example.com/docs/", now).contains(&"a".to_owned()));
+        assert!(!names(&jar, "http://example.com/", now).contains(&"c".to_owned()));
+    }
+
+    #[test]
+    fn expiry() {
+        let now = SystemTime::now();
+        let mut jar = CookieStore::new();
+        store(&mut jar, "https://example.com/", "a=1; Max-Age=60", now);
+        store(
+            &mut jar,
+            "https://example.com/",
+            "b=2; Expires=Wed, 21 Oct 2015 07:28:00 GMT",
+            now,
+        );
+        store(
+            &mut jar,
+            "https://example.com/",
+            "c=3; Max-Age=60; Expires=Wed, 21 Oct 2015 07:28:00 GMT",
+            now,
+        );
+        assert_eq!(names(&jar, "https://example.com/", now), ["a", "c"]);
+
+        let later = now + Duration::from_secs(120);
+        assert!(names(&jar, "https://example.com/", later).is_empty());
+
+        store(&mut jar, "https://example.com/", "a=1; Max-Age=0", now);
+        assert_eq!(names(&jar, "https://example.com/", now), ["c"]);
+    }
+
+    #[test]
+    fn replaces_and_keeps_creation_time() {
+        let now = SystemTime::now();
+        let mut jar = CookieStore::new();
+        store(&mut jar, "https://example.com/", "a=1", now);
+        store(
+            &mut jar,
+            "https://example.com/",
+            "b=2",
+            now + Duration::from_secs(1),
+        );
+        store(
+            &mut jar,
+            "https://example.com/",
+            "a=3",
+            now + Duration::from_secs(2),
+        );
+
+        let cookies = jar.matches_at(&url("https://example.com/"), now);
+        assert_eq!(cookies[0].name(), "a");
+        assert_eq!(cookies[0].value(), "3");
+    }
+
+    #[test]
+    fn prefixes() {
+        let now = SystemTime::now();
+        let mut jar = CookieStore::new();
+        store(&mut jar, "https://example.com/", "__Secure-a=1", now);
+        store(
+            &mut jar,
+            "https://example.com/",
+            "__Secure-b=1; Secure",
+            now,
+        );
+        store(&mut jar, "https://example.com/", "__Host-c=1; Secure", now);
+        store(
+            &mut jar,
+            "https://example.com/",
+            "__Host-d=1; Secure; Path=/",
+            now,
+        );
+        store(
+            &mut jar,
+            "https://example.com/",
+            "__Host-e=1; Secure; Path=/; Domain=example.com",
+            now,
+        );
+        assert_eq!(
+            names(&jar, "https://example.com/", now),
+            ["__Secure-b", "__Host-d"]
+        );
+    }
+
+    #[test]
+    fn same_site() {
+        let mut jar = CookieStore::new();
+        let mut res = Response::new(200);
+        res.append_header(SET_COOKIE, "strict=1; SameSite=Strict")
+            .unwrap();
+        res.append_header(SET_COOKIE, "lax=1; SameSite=Lax")
+            .unwrap();
+        res.append_header(SET_COOKIE, "none=1; SameSite=None; Secure")
+            .unwrap();
+        res.append_header(SET_COOKIE, "insecure=1; SameSite=None")
+            .unwrap();
+        jar.store(&url("https://example.com/"), &res);
+        assert_eq!(jar.len(), 3);
+
+        let mut req = Request::get("https://example.com/");
+        jar.apply(&mut req);
+        assert_eq!(req[COOKIE], "strict=1; lax=1; none=1");
+
+        jar.apply_cross_site(&mut req);
+        assert_eq!(req[COOKIE], "lax=1; none=1");
+
+        let mut req = Request::post("https://example.com/");
+        jar.apply_cross_site(&mut req);
+        assert_eq!(req[COOKIE], "none=1");
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde_round_trip() -> crate::Result<()> {
+        let mut jar = CookieStore::new();
+        store(
+            &mut jar,
+            "https://example.com/",
+            "a=1; Max-Age=60; SameSite=Lax",
+            SystemTime::now(),
+        );
+        let json = serde_json::to_string(&jar)?;
+        let jar2: CookieStore = serde_json::from_str(&json)?;
+        assert_eq!(jar.cookies, jar2.cookies);
+        assert_eq!(jar2.iter().next().unwrap().same_site(), Some(SameSite::Lax));
+        Ok(())
+    }
+}
@@ -12,6 +12,12 @@ pub const CONTENT_LOCATION: HeaderName = HeaderName::from_lowercase_str("content
 pub const CONTENT_MD5: HeaderName = HeaderName::from_lowercase_str("content-md5");
 /// The `Content-Range` Header
 pub const CONTENT_RANGE: HeaderName = HeaderName::from_lowercase_str("content-range");
+/// The `Content-Security-Policy` Header
+pub const CONTENT_SECURITY_POLICY: HeaderName =
+    HeaderName::from_lowercase_str("content-security-policy");
+/// The `Content-Security-Policy-Report-Only` Header
+pub const CONTENT_SECURITY_POLICY_REPORT_ONLY: HeaderName =
+    HeaderName::from_lowercase_str("content-security-policy-report-only");
 /// The `Content-Type` Header
 pub const CONTENT_TYPE: HeaderName = HeaderName::from_lowercase_str("content-type");
 
@@ -75,12 +81,21 @@ pub const AGE: HeaderName = HeaderName::from_lowercase_str("age");
 ///  The `Allow` Header
 pub const ALLOW: HeaderName = HeaderName::from_lowercase_str("allow");
 
+///  The `Authentication-Info` Header
+pub const AUTHENTICATION_INFO: HeaderName = HeaderName::from_lowercase_str("authentication-info");
+
 ///  The `Authorization` Header
 pub const AUTHORIZATION: HeaderName = HeaderName::from_lowercase_str("authorization");
 
 ///  The `Cache-Control` Header
 pub const CACHE_CONTROL: HeaderName = HeaderName::from_lowercase_str("cache-control");
 
+///  The `Cache-Status` Header
+pub const CACHE_STATUS: HeaderName = HeaderName::from_lowercase_str("cache-status");
+
+///  The `CDN-Cache-Control` Header
+pub const CDN_CACHE_CONTROL: HeaderName = HeaderName::from_lowercase_str("cdn-cache-control");
+
 ///  The `Clear-Site-Data` Header
 pub const CLEAR_SITE_DATA: HeaderName = HeaderName::from_lowercase_str("clear-site-data");
 
@@ -117,6 +132,9 @@ pub const IF_RANGE: HeaderName = HeaderName::from_lowercase_str("if-range");
 ///  The `If-Unmodified-Since` Header
 pub const IF_UNMODIFIED_SINCE: HeaderName = HeaderName::from_lowercase_str("if-unmodified-since");
 
+///  The `Keep-Alive` Header
+pub const KEEP_ALIVE: HeaderName = HeaderName::from_lowercase_str("keep-alive");
+
 ///  The `Last-Modified` Header
 pub const LAST_MODIFIED: HeaderName = HeaderName::from_lowercase_str("last-modified");
 
@@ -144,12 +162,36 @@ pub const REFERER: HeaderName = HeaderName::from_lowercase_str("referer");
 ///  The `Retry-After` Header
 pub const RETRY_AFTER: HeaderName = HeaderName::from_lowercase_str("retry-after");
 
+///  The `Sec-WebSocket-Accept` Header
+pub const SEC_WEBSOCKET_ACCEPT: HeaderName = HeaderName::from_lowercase_str("sec-websocket-accept");
+
+///  The `Sec-WebSocket-Extensions` Header
+pub const SEC_WEBSOCKET_EXTENSIONS: HeaderName =
+    HeaderName::from_lowercase_str("sec-websocket-extensions");
+
+///  The `Sec-WebSocket-Key` Header
+pub const SEC_WEBSOCKET_KEY: HeaderName = HeaderName::from_lowercase_str("sec-websocket-key");
+
+///  The `Sec-WebSocket-Protocol` Header
+pub const SEC_WEBSOCKET_PROTOCOL: HeaderName =
+    HeaderName::from_lowercase_str("sec-websocket-protocol");
+
+///  The `Sec-WebSocket-Version` Header
+pub const SEC_WEBSOCKET_VERSION: HeaderName =
+    HeaderName::from_lowercase_str("sec-websocket-version");
+
 ///  The `Server` Header
 pub const SERVER: HeaderName = HeaderName::from_lowercase_str("server");
 
 ///  The `Server` Header
 pub const SERVER_TIMING: HeaderName = HeaderName::from_lowercase_str("server-timing");
 
+///  The `Signature` Header
+pub const SIGNATURE: HeaderName = HeaderName::from_lowercase_str("signature");
+
+///  The `Signature-Input` Header
+pub const SIGNATURE_INPUT: HeaderName = HeaderName::from_lowercase_str("signature-input");
+
 ///  The `SourceMap` Header
 pub const SOURCE_MAP: HeaderName = HeaderName::from_lowercase_str("sourcemap");
 
@@ -157,6 +199,9 @@ pub const SOURCE_MAP: HeaderName = HeaderName::from_lowercase_str("sourcemap");
 pub const STRICT_TRANSPORT_SECURITY: HeaderName =
     HeaderName::from_lowercase_str("strict-transport-security");
 
+///  The `Surrogate-Control` Header
+pub const SURROGATE_CONTROL: HeaderName = HeaderName::from_lowercase_str("surrogate-control");
+
 ///  The `Te` Header
 pub const TE: HeaderName = HeaderName::from_lowercase_str("te");
 
@@ -169,6 +214,9 @@ pub const TRACEPARENT: HeaderName = HeaderName::from_lowercase_str("traceparent"
 ///  The `Trailer` Header
 pub const TRAILER: HeaderName = HeaderName::from_lowercase_str("trailer");
 
+///  The `TTL` Header
+pub const TTL: HeaderName = HeaderName::from_lowercase_str("ttl");
+
 ///  The `Upgrade` Header
 pub const UPGRADE: HeaderName = HeaderName::from_lowercase_str("upgrade");
 
@@ -101,11 +101,8 @@
 #![doc(html_favicon_url = "https://yoshuawuyts.com/assets/http-rs/favicon.ico")]
 #![doc(html_logo_url = "https://yoshuawuyts.com/assets/http-rs/logo-rounded.png")]
 
-/// HTTP cookies.
 #[cfg(feature = "cookies")]
-pub mod cookies {
-    pub use cookie::*;
-}
+pub mod cookies;
 
 /// URL records.
 pub mod url {
@@ -139,6 +136,8 @@ mod method;
 mod parse_utils;
 mod request;
 mod response;
+#[cfg_attr(not(feature = "http-signatures"), allow(dead_code))]
+mod sf;
 mod status;
 mod status_code;
 mod version;
@@ -157,6 +156,9 @@ pub use crate::url::Url;
 
 pub mod security;
 pub mod trailers;
+#[cfg(feature = "web-push")]
+#[cfg_attr(feature = "docs", doc(cfg(feature = "web-push")))]
+pub mod web_push;
 
 #[cfg(feature = "hyperium_http")]
 mod hyperium_http;
@@ -0,0 +1,288 @@
+use crate::bail_status as bail;
+use crate::headers::{Header, HeaderName, HeaderValue, Headers, CONNECTION};
+use crate::parse_utils::parse_token;
+
+use std::fmt::{self, Debug, Write};
+use std::iter::Iterator;
+use std::slice;
+
+const CLOSE: &str = "close";
+const KEEP_ALIVE: &str = "keep-alive";
+const UPGRADE: &str = "upgrade";
+
+/// Control options for the current connection.
+///
+/// The `Connection` header lists the connection options `close`,
+/// `keep-alive` and `upgrade`, as well as the names of any headers which only
+/// apply to the current hop and must be removed by proxies before forwarding
+/// the message. See [`proxies::strip_hop_by_hop`](crate::proxies::strip_hop_by_hop).
+///
+/// [MDN Documentation](https://developer.mozilla.org/en-US/docs/Web/HTTP/Headers/Connection)
+///
+/// # Specifications
+///
+/// - [RFC 7230, section 6.1: Connection](https://tools.ietf.org/html/rfc7230#section-6.1)
+///
+/// # Examples
+///
+/// ```
+/// # fn main() -> http_types::Result<()> {
+/// #
+/// use http_types::Response;
+/// use http_types::other::Connection;
+///
+/// let mut connection = Connection::new();
+/// connection.push("keep-alive");
+/// connection.push("X-Session");
+///
+/// let mut res = Response::new(200);
+/// res.insert_header(&connection, &connection);
+///
+/// let connection = Connection::from_headers(res)?.unwrap();
+/// assert!(connection.is_keep_alive());
+/// assert!(connection.contains("x-session"));
+/// #
+/// # Ok(()) }
+/// ```
+#[derive(Clone, Eq, PartialEq)]
+pub struct Connection {
+    entries: Vec<HeaderName>,
+}
+
+impl Connection {
+    /// Create a new instance of `Connection`.
+    pub fn new() -> Self {
+        Self { entries: vec![] }
+    }
+
+    /// Create a new instance from headers.
+    pub fn from_headers(headers: impl AsRef<Headers>) -> crate::Result<Option<Self>> {
+        let headers = match headers.as_ref().get(CONNECTION) {
+            Some(headers) => headers,
+            None => return Ok(None),
+        };
+
+        let mut entries = vec![];
+        for value in headers {
+            for part in value.as_str().split(',') {
+                let part = part.trim();
+                if part.is_empty() {
+                    continue;
+                }
+                match parse_token(part) {
+                    (Some(token), "") => entries.push(token.parse()?),
+                    _ => bail!(400, "Invalid `Connection` option `{}`", part),
+                }
+            }
+        }
+
+        Ok(Some(Self { entries }))
+    }
+
+    /// Push an option or header name into the list of entries.
+    pub fn push(&mut self, entry: impl Into<HeaderName>) {
+        self.entries.push(entry.into());
+    }
+
+    /// Returns `true` if the list contains the option or header name.
+    ///
+    /// Entries are compared case-insensitively.
+    pub fn contains(&self, entry: impl Into<HeaderName>) -> bool {
+        let entry = entry.into();
+        self.entries.contains(&entry)
+    }
+
+    /// Returns `true` if the connection will be closed after the current
+    /// message.
+    pub fn is_close(&self) -> bool {
+        self.contains(CLOSE)
+    }
+
+    /// Returns `true` if a persistent connection was requested.
+    pub fn is_keep_alive(&self) -> bool {
+        self.contains(KEEP_ALIVE)
+    }
+
+    /// Returns `true` if the connection is being upgraded to another protocol.
+    ///
+    /// See [`upgrade::Upgrade`](crate::upgrade::Upgrade) for the requested protocols.
+    pub fn is_upgrade(&self) -> bool {
+        self.contains(UPGRADE)
+    }
+
+    /// An iterator visiting all entries.
+    pub fn iter(&self) -> Iter<'_> {
+        Iter {
+            inner: self.entries.iter(),
+        }
+    }
+
+    /// An iterator visiting all entries.
+    pub fn iter_mut(&mut self) -> IterMut<'_> {
+        IterMut {
+            inner: self.entries.iter_mut(),
+        }
+    }
+}
+
+impl Header for Connection {
+    fn header_name(&self) -> HeaderName {
+        CONNECTION
+    }
+
+    fn header_value(&self) -> HeaderValue {
+        let mut output = String::new();
+        for (n, name) in self.entries.iter().enumerate() {
+            match n {
+                0 => write!(output, "{}", name).unwrap(),
+                _ => write!(output, ", {}", name).unwrap(),
+            };
+        }
+
+        // SAFETY: the internal string is validated to be ASCII.
+        unsafe { HeaderValue::from_bytes_unchecked(output.into()) }
+    }
+}
+
+impl IntoIterator for Connection {
+    type Item = HeaderName;
+    type IntoIter = IntoIter;
+
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        IntoIter {
+            inner: self.entries.into_iter(),
+        }
+    }
+}
+
+impl<'a> IntoIterator for &'a Connection {
+    type Item = &'a HeaderName;
+    type IntoIter = Iter<'a>;
+
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<'a> IntoIterator for &'a mut Connection {
+    type Item = &'a mut HeaderName;
+    type IntoIter = IterMut<'a>;
+
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter_mut()
+    }
+}
+
+/// A borrowing iterator over entries in `Connection`.
+#[derive(Debug)]
+pub struct IntoIter {
+    inner: std::vec::IntoIter<HeaderName>,
+}
+
+impl Iterator for IntoIter {
+    type Item = HeaderName;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next()
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+/// A lending iterator over entries in `Connection`.
+#[derive(Debug)]
+pub struct Iter<'a> {
+    inner: slice::Iter<'a, HeaderName>,
+}
+
+impl<'a> Iterator for Iter<'a> {
+    type Item = &'a HeaderName;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next()
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+/// A mutable iterator over entries in `Connection`.
+#[derive(Debug)]
+pub struct IterMut<'a> {
+    inner: slice::IterMut<'a, HeaderName>,
+}
+
+impl<'a> Iterator for IterMut<'a> {
+    type Item = &'a mut HeaderName;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next()
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl Debug for Connection {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut list = f.debug_list();
+        for entry in &self.entries {
+            list.entry(entry);
+        }
+        list.finish()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::Response;
+
+    #[test]
+    fn smoke() -> crate::Result<()> {
+        let mut connection = Connection::new();
+        connection.push("Upgrade");
+        connection.push("X-Session");
+
+        let mut res = Response::new(200);
+        connection.apply_header(&mut res);
+        assert_eq!(res["Connection"], "upgrade, x-session");
+
+        let connection = Connection::from_headers(res)?.unwrap();
+        assert!(connection.is_upgrade());
+        assert!(!connection.is_close());
+        assert!(connection.contains("X-SESSION"));
+        Ok(())
+    }
+
+    #[test]
+    fn multiple_values() -> crate::Result<()> {
+        let mut res = Response::new(200);
+        res.append_header("Connection", "close, ").unwrap();
+        res.append_header("Connection", "Keep-Alive").unwrap();
+
+        let connection = Connection::from_headers(res)?.unwrap();
+        let entries: Vec<_> = connection.iter().map(|e| e.as_str()).collect();
+        assert_eq!(entries, vec!["close", "keep-alive"]);
+        Ok(())
+    }
+
+    #[test]
+    fn bad_request_on_parse_error() {
+        let mut res = Response::new(200);
+        res.insert_header("Connection", "close; keep-alive")
+            .unwrap();
+        let err = Connection::from_headers(res).unwrap_err();
+        assert_eq!(err.status(), 400);
+    }
+}
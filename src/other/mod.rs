@@ -1,11 +1,13 @@
 //! Miscellaneous HTTP headers.
 
+mod connection;
 mod date;
 mod expect;
 mod referer;
 mod retry_after;
 mod source_map;
 
+pub use connection::Connection;
 pub use date::Date;
 pub use expect::Expect;
 pub use referer::Referer;
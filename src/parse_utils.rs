@@ -18,6 +18,19 @@ pub(crate) fn parse_token(input: &str) -> (Option<&str>, &str) {
     }
 }
 
+/// Returns `true` if the whole input is a token.
+pub(crate) fn is_token(input: &str) -> bool {
+    matches!(parse_token(input), (Some(_), ""))
+}
+
+/// Returns `true` if the value can be sent as a quoted-string: visible ASCII
+/// characters, spaces and tabs.
+pub(crate) fn is_quotable(input: &str) -> bool {
+    input
+        .bytes()
+        .all(|b| b == b'\t' || (b' '..=b'~').contains(&b))
+}
+
 /// https://tools.ietf.org/html/rfc7230#section-3.2.6
 fn tchar(c: char) -> bool {
     matches!(
@@ -145,6 +158,15 @@ mod test {
         }
     }
 
+    #[test]
+    fn whole_tokens() {
+        assert!(is_token("gzip"));
+        assert!(is_token("!#$%&'*+-.^_`|~"));
+        assert!(!is_token(""));
+        assert!(!is_token("no-cache, private"));
+        assert!(!is_token("caf\u{e9}"));
+    }
+
     #[test]
     fn qstring_successful_parses() {
         assert_eq!(
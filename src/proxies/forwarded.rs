@@ -1,8 +1,9 @@
 use crate::{
     headers::{Header, HeaderName, HeaderValue, Headers, FORWARDED},
     parse_utils::{parse_quoted_string, parse_token},
+    proxies::ForwardedElement,
 };
-use std::{borrow::Cow, convert::TryFrom, fmt::Write, net::IpAddr};
+use std::{borrow::Cow, convert::TryFrom, net::IpAddr};
 
 // these constants are private because they are nonstandard
 const X_FORWARDED_FOR: HeaderName = HeaderName::from_lowercase_str("x-forwarded-for");
@@ -12,12 +13,13 @@ const X_FORWARDED_HOST: HeaderName = HeaderName::from_lowercase_str("x-forwarded
 
 /// A rust representation of the [forwarded
 /// header](https://developer.mozilla.org/en-US/docs/Web/HTTP/Headers/Forwarded).
+///
+/// The header is a list of [`ForwardedElement`]s, one for each proxy the
+/// message passed through. The `by`, `host`, `proto` and `forwarded_for`
+/// accessors on `Forwarded` flatten all elements into a single view.
 #[derive(Debug, Clone, Default, PartialEq, Eq)]
 pub struct Forwarded<'a> {
-    by: Option<Cow<'a, str>>,
-    forwarded_for: Vec<Cow<'a, str>>,
-    host: Option<Cow<'a, str>>,
-    proto: Option<Cow<'a, str>>,
+    elements: Vec<ForwardedElement<'a>>,
 }
 
 impl<'a> Forwarded<'a> {
@@ -112,7 +114,13 @@ impl<'a> Forwarded<'a> {
         headers: &'a impl AsRef<Headers>,
     ) -> Result<Option<Self>, ParseError> {
         if let Some(headers) = headers.as_ref().get(FORWARDED) {
-            Ok(Some(Self::parse(headers.as_ref().as_str())?))
+            let mut forwarded = Forwarded::new();
+            for value in headers {
+                forwarded
+                    .elements
+                    .extend(Self::parse(value.as_str())?.elements);
+            }
+            Ok(Some(forwarded))
         } else {
             Ok(None)
         }
@@ -122,6 +130,9 @@ impl<'a> Forwarded<'a> {
     /// non-standardized x-forwarded-{for,by,proto} headers, without
     /// support for the Forwarded header.
     ///
+    /// Each `X-Forwarded-For` entry becomes an element. The `by`, `host`
+    /// and `proto` values are set on the last element.
+    ///
     /// # Examples
     /// ```rust
     /// # use http_types::{proxies::Forwarded, Method::Get, Request, Url, Result};
@@ -146,46 +157,104 @@ impl<'a> Forwarded<'a> {
     pub fn from_x_headers(headers: &'a impl AsRef<Headers>) -> Result<Option<Self>, ParseError> {
         let headers = headers.as_ref();
 
-        let forwarded_for: Vec<Cow<'a, str>> = headers
+        let mut elements: Vec<ForwardedElement<'a>> = headers
             .get(X_FORWARDED_FOR)
             .map(|hv| {
-                hv.as_str()
-                    .split(',')
+                hv.iter()
+                    .flat_map(|v| v.as_str().split(','))
+                    .map(str::trim)
+                    .filter(|v| !v.is_empty())
                     .map(|v| {
-                        let v = v.trim();
+                        let mut element = ForwardedElement::new();
                         match v.parse::<IpAddr>().ok() {
-                            Some(IpAddr::V6(v6)) => Cow::Owned(format!(r#"[{}]"#, v6)),
-                            _ => Cow::Borrowed(v),
+                            Some(IpAddr::V6(v6)) => element.set_for(format!(r#"[{}]"#, v6)),
+                            _ => element.set_for(v),
                         }
+                        element
                     })
                     .collect()
             })
             .unwrap_or_default();
 
-        let by = headers
-            .get(X_FORWARDED_BY)
-            .map(|hv| Cow::Borrowed(hv.as_str()));
-
-        let proto = headers
-            .get(X_FORWARDED_PROTO)
-            .map(|p| Cow::Borrowed(p.as_str()));
-
-        let host = headers
-            .get(X_FORWARDED_HOST)
-            .map(|h| Cow::Borrowed(h.as_str()));
-
-        if !forwarded_for.is_empty() || by.is_some() || proto.is_some() || host.is_some() {
-            Ok(Some(Self {
-                forwarded_for,
-                by,
-                proto,
-                host,
-            }))
-        } else {
-            Ok(None)
+        // Proxies append their own field lines, so the last one wins.
+        let by = headers.get(X_FORWARDED_BY).map(|hv| hv.last().as_str());
+        let proto = headers.get(X_FORWARDED_PROTO).map(|p| p.last().as_str());
+        let host = headers.get(X_FORWARDED_HOST).map(|h| h.last().as_str());
+
+        if elements.is_empty() && (by.is_some() || proto.is_some() || host.is_some()) {
+            elements.push(ForwardedElement::new());
+        }
+
+        match elements.last_mut() {
+            Some(last) => {
+                if let Some(by) = by {
+                    last.set_by(by);
+                }
+                if let Some(host) = host {
+                    last.set_host(host);
+                }
+                if let Some(proto) = proto {
+                    last.set_proto(proto);
+                }
+                Ok(Some(Self { elements }))
+            }
+            None => Ok(None),
         }
     }
 
+    /// Append a proxy's own element to the forwarding information of a
+    /// message.
+    ///
+    /// The existing `Forwarded` header, or the legacy `X-Forwarded-*`
+    /// headers if there is none, are parsed and the element is appended.
+    /// The `X-Forwarded-*` headers are then removed, and replaced by a
+    /// standards-compliant `Forwarded` header.
+    ///
+    /// # Examples
+    /// ```rust
+    /// # fn main() -> http_types::Result<()> {
+    /// use http_types::proxies::{Forwarded, ForwardedElement};
+    /// use http_types::Request;
+    ///
+    /// let mut request = Request::get("http://_/");
+    /// request.insert_header("X-Forwarded-For", "192.0.2.43");
+    /// request.insert_header("X-Forwarded-Proto", "https");
+    ///
+    /// let mut element = ForwardedElement::new();
+    /// element.set_for("198.51.100.17");
+    /// element.set_by("_proxy");
+    /// Forwarded::append_hop(&mut request, element)?;
+    ///
+    /// assert!(request.header("X-Forwarded-For").is_none());
+    /// assert_eq!(
+    ///     request["Forwarded"],
+    ///     "for=192.0.2.43;proto=https, by=_proxy;for=198.51.100.17"
+    /// );
+    /// # Ok(()) }
+    /// ```
+    pub fn append_hop(
+        mut headers: impl AsMut<Headers>,
+        element: ForwardedElement<'_>,
+    ) -> Result<(), ParseError> {
+        let headers = headers.as_mut();
+        let mut forwarded = match Forwarded::from_headers(&*headers)? {
+            Some(forwarded) => forwarded.into_owned(),
+            None => Forwarded::new(),
+        };
+        forwarded.push(element.into_owned());
+
+        for name in &[
+            X_FORWARDED_FOR,
+            X_FORWARDED_BY,
+            X_FORWARDED_HOST,
+            X_FORWARDED_PROTO,
+        ] {
+            headers.remove(name);
+        }
+        forwarded.apply_header(headers);
+        Ok(())
+    }
+
     /// parse a &str into a borrowed Forwarded
     ///
     /// # Examples
@@ -205,95 +274,19 @@ impl<'a> Forwarded<'a> {
     /// # Ok(()) }
     /// ```
     pub fn parse(input: &'a str) -> Result<Self, ParseError> {
-        let mut input = input;
+        let mut input = input.trim();
         let mut forwarded = Forwarded::new();
 
-        while !input.is_empty() {
-            input = if starts_with_ignore_case("for=", input) {
-                forwarded.parse_for(input)?
-            } else {
-                forwarded.parse_forwarded_pair(input)?
-            }
-        }
-
-        Ok(forwarded)
-    }
-
-    fn parse_forwarded_pair(&mut self, input: &'a str) -> Result<&'a str, ParseError> {
-        let (key, value, rest) = match parse_token(input) {
-            (Some(key), rest) if rest.starts_with('=') => match parse_value(&rest[1..]) {
-                (Some(value), rest) => Some((key, value, rest)),
-                (None, _) => None,
-            },
-            _ => None,
-        }
-        .ok_or_else(|| ParseError::new("parse error in forwarded-pair"))?;
-
-        match key {
-            "by" => {
-                if self.by.is_some() {
-                    return Err(ParseError::new("parse error, duplicate `by` key"));
-                }
-                self.by = Some(value);
-            }
-
-            "host" => {
-                if self.host.is_some() {
-                    return Err(ParseError::new("parse error, duplicate `host` key"));
-                }
-                self.host = Some(value);
-            }
-
-            "proto" => {
-                if self.proto.is_some() {
-                    return Err(ParseError::new("parse error, duplicate `proto` key"));
-                }
-                self.proto = Some(value);
-            }
-
-            _ => { /* extensions are allowed in the spec */ }
-        }
-
-        match rest.strip_prefix(';') {
-            Some(rest) => Ok(rest),
-            None => Ok(rest),
-        }
-    }
-
-    fn parse_for(&mut self, input: &'a str) -> Result<&'a str, ParseError> {
-        let mut rest = input;
-
         loop {
-            rest = match match_ignore_case("for=", rest) {
-                (true, rest) => rest,
-                (false, _) => return Err(ParseError::new("http list must start with for=")),
-            };
-
-            let (value, rest_) = parse_value(rest);
-            rest = rest_;
-
-            if let Some(value) = value {
-                // add a successful for= value
-                self.forwarded_for.push(value);
-            } else {
-                return Err(ParseError::new("for= without valid value"));
+            // empty list elements are allowed by the list syntax
+            input = input.trim_start_matches([',', ' ', '\t']);
+            if input.is_empty() {
+                return Ok(forwarded);
             }
 
-            match rest.chars().next() {
-                // we have another for=
-                Some(',') => {
-                    rest = rest[1..].trim_start();
-                }
-
-                // we have reached the end of the for= section
-                Some(';') => return Ok(&rest[1..]),
-
-                // reached the end of the input
-                None => return Ok(rest),
-
-                // bail
-                _ => return Err(ParseError::new("unexpected character after for= section")),
-            }
+            let (element, rest) = parse_element(input)?;
+            forwarded.elements.push(element);
+            input = rest;
         }
     }
 
@@ -301,14 +294,11 @@ impl<'a> Forwarded<'a> {
     /// Forwarded. This is a noop if the Forwarded is already owned.
     pub fn into_owned(self) -> Forwarded<'static> {
         Forwarded {
-            by: self.by.map(|by| Cow::Owned(by.into_owned())),
-            forwarded_for: self
-                .forwarded_for
+            elements: self
+                .elements
                 .into_iter()
-                .map(|ff| Cow::Owned(ff.into_owned()))
+                .map(ForwardedElement::into_owned)
                 .collect(),
-            host: self.host.map(|h| Cow::Owned(h.into_owned())),
-            proto: self.proto.map(|p| Cow::Owned(p.into_owned())),
         }
     }
 
@@ -317,44 +307,81 @@ impl<'a> Forwarded<'a> {
         Self::default()
     }
 
+    /// Returns the elements of this header, from the element added by the
+    /// proxy closest to the client to the element added by the most recent
+    /// proxy.
+    pub fn elements(&self) -> &[ForwardedElement<'a>] {
+        &self.elements
+    }
+
+    /// Returns the elements of this header mutably.
+    pub fn elements_mut(&mut self) -> &mut [ForwardedElement<'a>] {
+        &mut self.elements
+    }
+
+    /// Appends an element to this header.
+    pub fn push(&mut self, element: ForwardedElement<'a>) {
+        self.elements.push(element);
+    }
+
     /// Adds a `for` section to this header
+    ///
+    /// The value is set on the last element if it does not have a `for`
+    /// section yet, and on a new element otherwise.
     pub fn add_for(&mut self, forwarded_for: impl Into<Cow<'a, str>>) {
-        self.forwarded_for.push(forwarded_for.into());
+        match self.elements.last_mut() {
+            Some(last) if last.forwarded_for().is_none() => last.set_for(forwarded_for),
+            _ => {
+                let mut element = ForwardedElement::new();
+                element.set_for(forwarded_for);
+                self.elements.push(element);
+            }
+        }
     }
 
-    /// Returns the `for` field of this header
+    /// Returns the `for` field of all elements of this header
     pub fn forwarded_for(&self) -> Vec<&str> {
-        self.forwarded_for.iter().map(|x| x.as_ref()).collect()
+        self.elements
+            .iter()
+            .filter_map(|element| element.forwarded_for())
+            .collect()
     }
 
-    /// Sets the `host` field of this header
+    /// Sets the `host` field of the last element of this header
     pub fn set_host(&mut self, host: impl Into<Cow<'a, str>>) {
-        self.host = Some(host.into());
+        self.last_element_mut().set_host(host);
     }
 
-    /// Returns the `host` field of this header
+    /// Returns the first `host` field of this header
     pub fn host(&self) -> Option<&str> {
-        self.host.as_deref()
+        self.elements.iter().find_map(|element| element.host())
     }
 
-    /// Sets the `proto` field of this header
+    /// Sets the `proto` field of the last element of this header
     pub fn set_proto(&mut self, proto: impl Into<Cow<'a, str>>) {
-        self.proto = Some(proto.into())
+        self.last_element_mut().set_proto(proto);
     }
 
-    /// Returns the `proto` field of this header
+    /// Returns the first `proto` field of this header
     pub fn proto(&self) -> Option<&str> {
-        self.proto.as_deref()
+        self.elements.iter().find_map(|element| element.proto())
     }
 
-    /// Sets the `by` field of this header
+    /// Sets the `by` field of the last element of this header
     pub fn set_by(&mut self, by: impl Into<Cow<'a, str>>) {
-        self.by = Some(by.into());
+        self.last_element_mut().set_by(by);
     }
 
-    /// Returns the `by` field of this header
+    /// Returns the first `by` field of this header
     pub fn by(&self) -> Option<&str> {
-        self.by.as_deref()
+        self.elements.iter().find_map(|element| element.by())
+    }
+
+    fn last_element_mut(&mut self) -> &mut ForwardedElement<'a> {
+        if self.elements.is_empty() {
+            self.elements.push(ForwardedElement::new());
+        }
+        self.elements.last_mut().unwrap()
     }
 }
 
@@ -363,68 +390,58 @@ impl<'a> Header for Forwarded<'a> {
         FORWARDED
     }
     fn header_value(&self) -> HeaderValue {
-        let mut output = String::new();
-        if let Some(by) = self.by() {
-            write!(&mut output, "by={};", by).unwrap();
-        }
-
-        output.push_str(
-            &self
-                .forwarded_for
-                .iter()
-                .map(|f| format!("for={}", format_value(f)))
-                .collect::<Vec<_>>()
-                .join(", "),
-        );
-
-        output.push(';');
-
-        if let Some(host) = self.host() {
-            write!(&mut output, "host={};", host).unwrap();
-        }
-
-        if let Some(proto) = self.proto() {
-            write!(&mut output, "proto={};", proto).unwrap();
-        }
-
-        // remove a trailing semicolon
-        output.pop();
+        let output = self
+            .elements
+            .iter()
+            .map(|element| element.to_string())
+            .collect::<Vec<_>>()
+            .join(", ");
 
         // SAFETY: the internal string is validated to be ASCII.
         unsafe { HeaderValue::from_bytes_unchecked(output.into()) }
     }
 }
 
-fn parse_value(input: &str) -> (Option<Cow<'_, str>>, &str) {
-    match parse_token(input) {
-        (Some(token), rest) => (Some(Cow::Borrowed(token)), rest),
-        (None, rest) => parse_quoted_string(rest),
-    }
-}
+/// Parse a single forwarded-element, returning the unparsed remainder of the
+/// list.
+fn parse_element(input: &str) -> Result<(ForwardedElement<'_>, &str), ParseError> {
+    let mut element = ForwardedElement::new();
+    let mut rest = input;
 
-fn format_value(input: &str) -> Cow<'_, str> {
-    match parse_token(input) {
-        (_, "") => input.into(),
-        _ => {
-            let mut string = String::from("\"");
-            for ch in input.chars() {
-                if let '\\' | '"' = ch {
-                    string.push('\\');
+    loop {
+        let is_for = starts_with_ignore_case("for=", rest);
+        let (key, value, rest_) = match parse_token(rest) {
+            (Some(key), rest) if rest.starts_with('=') => match parse_value(&rest[1..]) {
+                (Some(value), rest) => (key, value, rest),
+                (None, _) if is_for => return Err(ParseError::new("for= without valid value")),
+                (None, _) => return Err(ParseError::new("parse error in forwarded-pair")),
+            },
+            _ => return Err(ParseError::new("parse error in forwarded-pair")),
+        };
+        element.push_pair(key, value)?;
+        rest = rest_;
+
+        match rest.strip_prefix(';') {
+            // a trailing semicolon ends the element
+            Some(rest_) if rest_.is_empty() || rest_.starts_with(',') => {
+                return Ok((element, rest_))
+            }
+            Some(rest_) => rest = rest_,
+            None => {
+                let trimmed = rest.trim_start();
+                if trimmed.is_empty() || trimmed.starts_with(',') {
+                    return Ok((element, trimmed));
                 }
-                string.push(ch);
+                return Err(ParseError::new("unexpected character after forwarded-pair"));
             }
-            string.push('"');
-            string.into()
         }
     }
 }
 
-fn match_ignore_case<'a>(start: &'static str, input: &'a str) -> (bool, &'a str) {
-    let len = start.len();
-    if input[..len].eq_ignore_ascii_case(start) {
-        (true, &input[len..])
-    } else {
-        (false, input)
+fn parse_value(input: &str) -> (Option<Cow<'_, str>>, &str) {
+    match parse_token(input) {
+        (Some(token), rest) => (Some(Cow::Borrowed(token)), rest),
+        (None, rest) => parse_quoted_string(rest),
     }
 }
 
@@ -468,6 +485,7 @@ impl<'a> TryFrom<&'a str> for Forwarded<'a> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::proxies::{Node, NodeName};
     use crate::{Method::Get, Request, Response, Result};
     use url::Url;
 
@@ -545,10 +563,16 @@ mod tests {
             "unable to parse forwarded header: parse error in forwarded-pair"
         );
 
-        let err = Forwarded::parse("for=for, key=value").unwrap_err();
+        let err = Forwarded::parse("for=a;by=b;for=c").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "unable to parse forwarded header: parse error, duplicate `for` key"
+        );
+
+        let err = Forwarded::parse("for=a by=b").unwrap_err();
         assert_eq!(
             err.to_string(),
-            "unable to parse forwarded header: http list must start with for="
+            "unable to parse forwarded header: unexpected character after forwarded-pair"
         );
 
         let err = Forwarded::parse(r#"for="unterminated string"#).unwrap_err();
@@ -564,6 +588,71 @@ mod tests {
         );
     }
 
+    #[test]
+    fn parse_elements() -> Result<()> {
+        let forwarded = Forwarded::parse(
+            r#"for=192.0.2.43;proto=http;by="[2001:db8::1]:80", for=_proxy;Secret=x, ,"#,
+        )?;
+        let elements = forwarded.elements();
+        assert_eq!(elements.len(), 2);
+
+        assert_eq!(elements[0].forwarded_for(), Some("192.0.2.43"));
+        assert_eq!(elements[0].proto(), Some("http"));
+        let by = elements[0].by_node()?.unwrap();
+        assert_eq!(by.socket_addr(), Some("[2001:db8::1]:80".parse()?));
+
+        assert_eq!(
+            elements[1].for_node()?.unwrap().name(),
+            &NodeName::Obfuscated("_proxy".into())
+        );
+        assert_eq!(elements[1].extension("secret"), Some("x"));
+        assert!(elements[1].proto().is_none());
+
+        // an element does not need to start with `for`
+        let forwarded = Forwarded::parse("for=for, key=value")?;
+        assert_eq!(forwarded.elements().len(), 2);
+        assert_eq!(forwarded.elements()[1].extension("key"), Some("value"));
+        Ok(())
+    }
+
+    #[test]
+    fn multiple_header_values() -> Result<()> {
+        let mut request = Request::new(Get, Url::parse("http://_/")?);
+        request
+            .append_header("Forwarded", "for=a;proto=https")
+            .unwrap();
+        request.append_header("Forwarded", "for=b").unwrap();
+
+        let forwarded = Forwarded::from_headers(&request)?.unwrap();
+        assert_eq!(forwarded.forwarded_for(), vec!["a", "b"]);
+        assert_eq!(forwarded.to_string(), "for=a;proto=https, for=b");
+        Ok(())
+    }
+
+    #[test]
+    fn append_hop() -> Result<()> {
+        let mut request = Request::new(Get, Url::parse("http://_/")?);
+        request
+            .append_header("Forwarded", r#"for="[2001:db8:cafe::17]:4711""#)
+            .unwrap();
+        request.append_header(X_FORWARDED_FOR, "ignored").unwrap();
+
+        let mut element = ForwardedElement::new();
+        element.set_for(Node::from(
+            "192.0.2.60:1234".parse::<std::net::SocketAddr>()?,
+        ));
+        element.set_by(Node::unknown());
+        element.set_extension("via", "test")?;
+        Forwarded::append_hop(&mut request, element)?;
+
+        assert!(request.header(X_FORWARDED_FOR).is_none());
+        assert_eq!(
+            request["Forwarded"],
+            r#"for="[2001:db8:cafe::17]:4711", by=unknown;for="192.0.2.60:1234";via=test"#
+        );
+        Ok(())
+    }
+
     #[test]
     fn bad_parse_from_headers() -> Result<()> {
         let mut response = Response::new(200);
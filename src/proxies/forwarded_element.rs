@@ -0,0 +1,245 @@
+use super::forwarded::ParseError;
+use crate::parse_utils::{is_token, parse_token};
+use crate::proxies::Node;
+
+use std::borrow::Cow;
+use std::fmt::{self, Display};
+
+/// A single hop in a `Forwarded` header.
+///
+/// Each proxy which forwards a request appends one element, describing the
+/// request as the proxy received it.
+///
+/// # Specifications
+///
+/// - [RFC 7239, section 4: Forwarded HTTP Header Field](https://tools.ietf.org/html/rfc7239#section-4)
+///
+/// # Examples
+///
+/// ```
+/// # fn main() -> http_types::Result<()> {
+/// #
+/// use http_types::proxies::{ForwardedElement, Node};
+///
+/// let mut element = ForwardedElement::new();
+/// element.set_for(Node::from("[2001:db8:cafe::17]:4711".parse::<std::net::SocketAddr>()?));
+/// element.set_proto("https");
+///
+/// assert_eq!(element.to_string(), r#"for="[2001:db8:cafe::17]:4711";proto=https"#);
+/// let node = element.for_node()?.unwrap();
+/// assert_eq!(node.socket_addr(), Some("[2001:db8:cafe::17]:4711".parse()?));
+/// #
+/// # Ok(()) }
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ForwardedElement<'a> {
+    by: Option<Cow<'a, str>>,
+    forwarded_for: Option<Cow<'a, str>>,
+    host: Option<Cow<'a, str>>,
+    proto: Option<Cow<'a, str>>,
+    extensions: Vec<(Cow<'a, str>, Cow<'a, str>)>,
+}
+
+impl<'a> ForwardedElement<'a> {
+    /// Create a new, empty `ForwardedElement`.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the `by` field of this element.
+    pub fn by(&self) -> Option<&str> {
+        self.by.as_deref()
+    }
+
+    /// Parse the `by` field of this element as a node identifier.
+    pub fn by_node(&self) -> Result<Option<Node<'_>>, ParseError> {
+        self.by().map(Node::parse).transpose()
+    }
+
+    /// Sets the `by` field of this element.
+    pub fn set_by(&mut self, by: impl Into<Cow<'a, str>>) {
+        self.by = Some(by.into());
+    }
+
+    /// Returns the `for` field of this element.
+    pub fn forwarded_for(&self) -> Option<&str> {
+        self.forwarded_for.as_deref()
+    }
+
+    /// Parse the `for` field of this element as a node identifier.
+    pub fn for_node(&self) -> Result<Option<Node<'_>>, ParseError> {
+        self.forwarded_for().map(Node::parse).transpose()
+    }
+
+    /// Sets the `for` field of this element.
+    pub fn set_for(&mut self, forwarded_for: impl Into<Cow<'a, str>>) {
+        self.forwarded_for = Some(forwarded_for.into());
+    }
+
+    /// Returns the `host` field of this element.
+    pub fn host(&self) -> Option<&str> {
+        self.host.as_deref()
+    }
+
+    /// Sets the `host` field of this element.
+    pub fn set_host(&mut self, host: impl Into<Cow<'a, str>>) {
+        self.host = Some(host.into());
+    }
+
+    /// Returns the `proto` field of this element.
+    pub fn proto(&self) -> Option<&str> {
+        self.proto.as_deref()
+    }
+
+    /// Sets the `proto` field of this element.
+    pub fn set_proto(&mut self, proto: impl Into<Cow<'a, str>>) {
+        self.proto = Some(proto.into());
+    }
+
+    /// Returns the value of an extension parameter.
+    ///
+    /// Parameter names are compared case-insensitively.
+    pub fn extension(&self, name: &str) -> Option<&str> {
+        self.extensions
+            .iter()
+            .find(|(key, _)| key.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_ref())
+    }
+
+    /// Sets an extension parameter, replacing any previous value.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the name is not a valid token, or is one of the
+    /// parameters defined by RFC 7239.
+    pub fn set_extension(
+        &mut self,
+        name: impl Into<Cow<'a, str>>,
+        value: impl Into<Cow<'a, str>>,
+    ) -> Result<(), ParseError> {
+        let name = name.into();
+        if !is_token(&name) {
+            return Err(ParseError::new("invalid extension parameter name"));
+        }
+        if ["by", "for", "host", "proto"]
+            .iter()
+            .any(|key| key.eq_ignore_ascii_case(&name))
+        {
+            return Err(ParseError::new("extension shadows a standard parameter"));
+        }
+
+        let value = value.into();
+        match self
+            .extensions
+            .iter_mut()
+            .find(|(key, _)| key.eq_ignore_ascii_case(&name))
+        {
+            Some((_, old)) => *old = value,
+            None => self.extensions.push((name, value)),
+        }
+        Ok(())
+    }
+
+    /// Returns `true` if no parameters are set.
+    pub fn is_empty(&self) -> bool {
+        self.by.is_none()
+            && self.forwarded_for.is_none()
+            && self.host.is_none()
+            && self.proto.is_none()
+            && self.extensions.is_empty()
+    }
+
+    /// Transform a borrowed `ForwardedElement` into an owned
+    /// `ForwardedElement`.
+    pub fn into_owned(self) -> ForwardedElement<'static> {
+        fn owned(value: Cow<'_, str>) -> Cow<'static, str> {
+            Cow::Owned(value.into_owned())
+        }
+        ForwardedElement {
+            by: self.by.map(owned),
+            forwarded_for: self.forwarded_for.map(owned),
+            host: self.host.map(owned),
+            proto: self.proto.map(owned),
+            extensions: self
+                .extensions
+                .into_iter()
+                .map(|(key, value)| (owned(key), owned(value)))
+                .collect(),
+        }
+    }
+
+    /// Add a parsed parameter, rejecting duplicates.
+    pub(super) fn push_pair(
+        &mut self,
+        key: &'a str,
+        value: Cow<'a, str>,
+    ) -> Result<(), ParseError> {
+        let slot = match key.to_ascii_lowercase().as_str() {
+            "by" => &mut self.by,
+            "for" => &mut self.forwarded_for,
+            "host" => &mut self.host,
+            "proto" => &mut self.proto,
+            _ => {
+                if self.extension(key).is_some() {
+                    return Err(ParseError::new("parse error, duplicate extension key"));
+                }
+                self.extensions.push((Cow::Borrowed(key), value));
+                return Ok(());
+            }
+        };
+        if slot.is_some() {
+            return Err(match key.to_ascii_lowercase().as_str() {
+                "by" => ParseError::new("parse error, duplicate `by` key"),
+                "for" => ParseError::new("parse error, duplicate `for` key"),
+                "host" => ParseError::new("parse error, duplicate `host` key"),
+                _ => ParseError::new("parse error, duplicate `proto` key"),
+            });
+        }
+        *slot = Some(value);
+        Ok(())
+    }
+}
+
+impl Display for ForwardedElement<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let pairs = [
+            ("by", self.by()),
+            ("for", self.forwarded_for()),
+            ("host", self.host()),
+            ("proto", self.proto()),
+        ];
+        let pairs = pairs
+            .iter()
+            .filter_map(|(key, value)| value.map(|value| (*key, value)))
+            .chain(
+                self.extensions
+                    .iter()
+                    .map(|(k, v)| (k.as_ref(), v.as_ref())),
+            );
+
+        for (n, (key, value)) in pairs.enumerate() {
+            if n > 0 {
+                f.write_str(";")?;
+            }
+            write!(f, "{}={}", key, format_value(value))?;
+        }
+        Ok(())
+    }
+}
+
+fn format_value(input: &str) -> Cow<'_, str> {
+    match parse_token(input) {
+        (_, "") => input.into(),
+        _ => {
+            let mut string = String::from("\"");
+            for ch in input.chars() {
+                if let '\\' | '"' = ch {
+                    string.push('\\');
+                }
+                string.push(ch);
+            }
+            string.push('"');
+            string.into()
+        }
+    }
+}
@@ -0,0 +1,94 @@
+use crate::headers::{
+    HeaderName, Headers, CONNECTION, KEEP_ALIVE, PROXY_AUTHENTICATE, PROXY_AUTHORIZATION,
+    PROXY_CONNECTION, TE, TRAILER, TRANSFER_ENCODING, UPGRADE,
+};
+
+use std::str::FromStr;
+
+/// Headers which only apply to a single transport-level connection.
+const HOP_BY_HOP: [HeaderName; 9] = [
+    CONNECTION,
+    KEEP_ALIVE,
+    PROXY_AUTHENTICATE,
+    PROXY_AUTHORIZATION,
+    PROXY_CONNECTION,
+    TE,
+    TRAILER,
+    TRANSFER_ENCODING,
+    UPGRADE,
+];
+
+/// Remove all hop-by-hop headers from a message.
+///
+/// This removes the standard hop-by-hop headers, as well as any header named
+/// in the `Connection` header. Proxies must do this before forwarding a
+/// request or response.
+///
+/// # Specifications
+///
+/// - [RFC 7230, section 6.1: Connection](https://tools.ietf.org/html/rfc7230#section-6.1)
+///
+/// # Examples
+///
+/// ```
+/// use http_types::proxies::strip_hop_by_hop;
+/// use http_types::Request;
+///
+/// let mut req = Request::get("http://example.com");
+/// req.insert_header("Connection", "keep-alive, X-Session");
+/// req.insert_header("Keep-Alive", "timeout=5");
+/// req.insert_header("X-Session", "abc");
+/// req.insert_header("Accept", "*/*");
+///
+/// strip_hop_by_hop(&mut req);
+/// assert!(req.header("Connection").is_none());
+/// assert!(req.header("Keep-Alive").is_none());
+/// assert!(req.header("X-Session").is_none());
+/// assert_eq!(req["Accept"], "*/*");
+/// ```
+pub fn strip_hop_by_hop(mut headers: impl AsMut<Headers>) {
+    let headers = headers.as_mut();
+
+    // Parse leniently here: a malformed `Connection` header must not cause
+    // the headers it does name to be forwarded.
+    if let Some(values) = headers.get(CONNECTION) {
+        let listed: Vec<HeaderName> = values
+            .iter()
+            .flat_map(|value| value.as_str().split(','))
+            .map(str::trim)
+            .filter(|name| !name.is_empty())
+            .filter_map(|name| HeaderName::from_str(name).ok())
+            .collect();
+        for name in listed {
+            headers.remove(name);
+        }
+    }
+
+    for name in &HOP_BY_HOP {
+        headers.remove(name);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::Response;
+
+    #[test]
+    fn strips_standard_and_listed_headers() {
+        let mut res = Response::new(200);
+        res.append_header("Connection", "close").unwrap();
+        res.append_header("Connection", "X-A, ,x-b").unwrap();
+        res.insert_header("X-A", "1").unwrap();
+        res.insert_header("X-B", "2").unwrap();
+        res.insert_header("Transfer-Encoding", "chunked").unwrap();
+        res.insert_header("Upgrade", "websocket").unwrap();
+        res.insert_header("Proxy-Authenticate", "Basic").unwrap();
+        res.insert_header("Content-Type", "text/plain").unwrap();
+
+        strip_hop_by_hop(res.as_mut());
+
+        let names: Vec<_> = res.header_names().map(|n| n.as_str()).collect();
+        assert_eq!(names, vec!["content-type"]);
+    }
+}
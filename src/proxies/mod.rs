@@ -1,3 +1,16 @@
 //! Headers that are set by proxies
 mod forwarded;
+mod forwarded_element;
+mod hop_by_hop;
+mod node;
+mod proxy_config;
+pub mod proxy_protocol;
+mod trusted_proxies;
+mod via;
 pub use forwarded::Forwarded;
+pub use forwarded_element::ForwardedElement;
+pub use hop_by_hop::strip_hop_by_hop;
+pub use node::{Node, NodeName, NodePort};
+pub use proxy_config::{Proxy, ProxyConfig};
+pub use trusted_proxies::{Cidr, ForwardingHeader, TrustedProxies};
+pub use via::{Via, ViaEntry};
@@ -0,0 +1,252 @@
+use super::forwarded::ParseError;
+
+use std::borrow::Cow;
+use std::fmt::{self, Display};
+use std::net::{IpAddr, SocketAddr};
+
+/// A node identifier in a `Forwarded` header.
+///
+/// Nodes identify the client or proxy on either side of a hop, by IP address
+/// or by an obfuscated identifier, optionally followed by a port.
+///
+/// # Specifications
+///
+/// - [RFC 7239, section 6: Node Identifiers](https://tools.ietf.org/html/rfc7239#section-6)
+///
+/// # Examples
+///
+/// ```
+/// # fn main() -> http_types::Result<()> {
+/// #
+/// use http_types::proxies::{Node, NodeName, NodePort};
+///
+/// let node = Node::parse("[2001:db8:cafe::17]:4711")?;
+/// assert_eq!(node.name(), &NodeName::Ip("2001:db8:cafe::17".parse()?));
+/// assert_eq!(node.port(), Some(&NodePort::Real(4711)));
+/// assert_eq!(node.socket_addr(), Some("[2001:db8:cafe::17]:4711".parse()?));
+///
+/// let node = Node::parse("_hidden")?;
+/// assert_eq!(node.name(), &NodeName::Obfuscated("_hidden".into()));
+/// assert_eq!(node.ip_addr(), None);
+/// #
+/// # Ok(()) }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Node<'a> {
+    name: NodeName<'a>,
+    port: Option<NodePort<'a>>,
+}
+
+/// The name part of a [`Node`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum NodeName<'a> {
+    /// An IPv4 or IPv6 address.
+    Ip(IpAddr),
+    /// The node is not known.
+    Unknown,
+    /// An obfuscated identifier, starting with an underscore.
+    Obfuscated(Cow<'a, str>),
+}
+
+/// The port part of a [`Node`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum NodePort<'a> {
+    /// A port number.
+    Real(u16),
+    /// An obfuscated port, starting with an underscore.
+    Obfuscated(Cow<'a, str>),
+}
+
+impl<'a> Node<'a> {
+    /// Create a new instance of `Node`.
+    pub fn new(name: NodeName<'a>, port: Option<NodePort<'a>>) -> Self {
+        Self { name, port }
+    }
+
+    /// Create a node for an unknown client or proxy.
+    pub fn unknown() -> Self {
+        Self::new(NodeName::Unknown, None)
+    }
+
+    /// Parse a node identifier.
+    pub fn parse(input: &'a str) -> Result<Self, ParseError> {
+        let (name, port) = if let Some(rest) = input.strip_prefix('[') {
+            let (addr, rest) = rest
+                .split_once(']')
+                .ok_or_else(|| ParseError::new("unterminated IPv6 address in node"))?;
+            let addr = addr
+                .parse()
+                .map_err(|_| ParseError::new("invalid IPv6 address in node"))?;
+            let port = match rest {
+                "" => None,
+                _ => match rest.strip_prefix(':') {
+                    Some(port) => Some(port),
+                    None => return Err(ParseError::new("unexpected character after node name")),
+                },
+            };
+            (NodeName::Ip(IpAddr::V6(addr)), port)
+        } else {
+            let (name, port) = match input.split_once(':') {
+                Some((name, port)) => (name, Some(port)),
+                None => (input, None),
+            };
+            let name = if name.eq_ignore_ascii_case("unknown") {
+                NodeName::Unknown
+            } else if is_obfuscated(name) {
+                NodeName::Obfuscated(Cow::Borrowed(name))
+            } else {
+                match name.parse() {
+                    Ok(addr) => NodeName::Ip(IpAddr::V4(addr)),
+                    Err(_) => return Err(ParseError::new("invalid node name")),
+                }
+            };
+            (name, port)
+        };
+
+        let port = match port {
+            None => None,
+            Some(port) if is_obfuscated(port) => Some(NodePort::Obfuscated(Cow::Borrowed(port))),
+            Some(port) if port.len() <= 5 && port.bytes().all(|b| b.is_ascii_digit()) => {
+                match port.parse() {
+                    Ok(port) => Some(NodePort::Real(port)),
+                    Err(_) => return Err(ParseError::new("invalid node port")),
+                }
+            }
+            Some(_) => return Err(ParseError::new("invalid node port")),
+        };
+
+        Ok(Self { name, port })
+    }
+
+    /// Get the name of the node.
+    pub fn name(&self) -> &NodeName<'a> {
+        &self.name
+    }
+
+    /// Get the port of the node, if any.
+    pub fn port(&self) -> Option<&NodePort<'a>> {
+        self.port.as_ref()
+    }
+
+    /// Get the IP address of the node, if it is not unknown or obfuscated.
+    pub fn ip_addr(&self) -> Option<IpAddr> {
+        match self.name {
+            NodeName::Ip(addr) => Some(addr),
+            _ => None,
+        }
+    }
+
+    /// Get the socket address of the node, if both the IP address and the
+    /// port are known.
+    pub fn socket_addr(&self) -> Option<SocketAddr> {
+        match (self.ip_addr(), &self.port) {
+            (Some(addr), Some(NodePort::Real(port))) => Some(SocketAddr::new(addr, *port)),
+            _ => None,
+        }
+    }
+
+    /// Transform a borrowed `Node` into an owned `Node`.
+    pub fn into_owned(self) -> Node<'static> {
+        Node {
+            name: match self.name {
+                NodeName::Ip(addr) => NodeName::Ip(addr),
+                NodeName::Unknown => NodeName::Unknown,
+                NodeName::Obfuscated(name) => NodeName::Obfuscated(Cow::Owned(name.into_owned())),
+            },
+            port: self.port.map(|port| match port {
+                NodePort::Real(port) => NodePort::Real(port),
+                NodePort::Obfuscated(port) => NodePort::Obfuscated(Cow::Owned(port.into_owned())),
+            }),
+        }
+    }
+}
+
+impl From<IpAddr> for Node<'_> {
+    fn from(addr: IpAddr) -> Self {
+        Self::new(NodeName::Ip(addr), None)
+    }
+}
+
+impl From<SocketAddr> for Node<'_> {
+    fn from(addr: SocketAddr) -> Self {
+        Self::new(NodeName::Ip(addr.ip()), Some(NodePort::Real(addr.port())))
+    }
+}
+
+impl Display for Node<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.name {
+            NodeName::Ip(IpAddr::V4(addr)) => write!(f, "{}", addr)?,
+            NodeName::Ip(IpAddr::V6(addr)) => write!(f, "[{}]", addr)?,
+            NodeName::Unknown => f.write_str("unknown")?,
+            NodeName::Obfuscated(name) => f.write_str(name)?,
+        }
+        match &self.port {
+            Some(NodePort::Real(port)) => write!(f, ":{}", port),
+            Some(NodePort::Obfuscated(port)) => write!(f, ":{}", port),
+            None => Ok(()),
+        }
+    }
+}
+
+impl<'a> From<Node<'a>> for Cow<'a, str> {
+    fn from(node: Node<'a>) -> Self {
+        Cow::Owned(node.to_string())
+    }
+}
+
+/// obfnode = "_" 1*( ALPHA / DIGIT / "." / "_" / "-")
+fn is_obfuscated(s: &str) -> bool {
+    match s.strip_prefix('_') {
+        Some(rest) => {
+            !rest.is_empty()
+                && rest
+                    .bytes()
+                    .all(|b| b.is_ascii_alphanumeric() || matches!(b, b'.' | b'_' | b'-'))
+        }
+        None => false,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn parse_and_format() -> crate::Result<()> {
+        let inputs = [
+            "192.0.2.43",
+            "192.0.2.43:47011",
+            "[2001:db8:cafe::17]",
+            "[2001:db8:cafe::17]:_port-1",
+            "unknown",
+            "unknown:8080",
+            "_gazonk",
+            "_SEVKISEK:_a.b",
+        ];
+        for input in inputs {
+            assert_eq!(Node::parse(input)?.to_string(), input);
+        }
+        assert_eq!(Node::parse("UNKNOWN")?.name(), &NodeName::Unknown);
+        Ok(())
+    }
+
+    #[test]
+    fn bad_parse() {
+        let inputs = [
+            "",
+            "client.com",
+            "2001:db8:cafe::17",
+            "[2001:db8:cafe::17",
+            "[2001:db8:cafe::17]4711",
+            "192.0.2.43:",
+            "192.0.2.43:65536",
+            "192.0.2.43:-1",
+            "_",
+            "_bad!",
+        ];
+        for input in inputs {
+            assert!(Node::parse(input).is_err(), "{} should not parse", input);
+        }
+    }
+}
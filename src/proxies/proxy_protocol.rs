@@ -0,0 +1,697 @@
+//! The HAProxy PROXY protocol.
+//!
+//! Layer 4 load balancers can't add headers to the HTTP requests they relay.
+//! Instead they send a preamble at the start of each connection which
+//! describes the original client connection. This module parses both the
+//! human-readable version 1 and the binary version 2 of the preamble, and
+//! applies the result to a [`Request`].
+//!
+//! # Specifications
+//!
+//! - [The PROXY protocol, versions 1 & 2](https://www.haproxy.org/download/2.8/doc/proxy-protocol.txt)
+//!
+//! # Examples
+//!
+//! ```
+//! # fn main() -> http_types::Result<()> { async_std::task::block_on(async {
+//! #
+//! use http_types::proxies::proxy_protocol::{self, Command};
+//! use http_types::Request;
+//!
+//! let mut stream = &b"PROXY TCP4 192.0.2.1 198.51.100.1 56324 443\r\nGET / HTTP/1.1\r\n"[..];
+//! let header = proxy_protocol::read(&mut stream).await?;
+//! assert_eq!(header.command(), Command::Proxy);
+//! assert_eq!(stream, &b"GET / HTTP/1.1\r\n"[..]);
+//!
+//! let mut req = Request::get("http://example.com");
+//! header.apply(&mut req);
+//! assert_eq!(req.peer_addr(), Some("192.0.2.1:56324"));
+//! assert_eq!(req.local_addr(), Some("198.51.100.1:443"));
+//! #
+//! # Ok(()) })}
+//! ```
+
+use futures_lite::io::{AsyncRead, AsyncReadExt};
+
+use crate::{bail_status as bail, ensure_status as ensure, Request};
+
+use std::convert::TryInto;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
+
+/// The signature which starts a version 2 header.
+const SIGNATURE: [u8; 12] = *b"\r\n\r\n\0\r\nQUIT\n";
+
+/// The maximum length of a version 1 header, including the trailing CRLF.
+const V1_MAX_LEN: usize = 107;
+
+/// The length of the fixed part of a version 2 header.
+const V2_HEADER_LEN: usize = 16;
+
+const PP2_TYPE_ALPN: u8 = 0x01;
+const PP2_TYPE_AUTHORITY: u8 = 0x02;
+const PP2_TYPE_CRC32C: u8 = 0x03;
+const PP2_TYPE_UNIQUE_ID: u8 = 0x05;
+const PP2_TYPE_SSL: u8 = 0x20;
+const PP2_SUBTYPE_SSL_VERSION: u8 = 0x21;
+const PP2_SUBTYPE_SSL_CN: u8 = 0x22;
+const PP2_SUBTYPE_SSL_CIPHER: u8 = 0x23;
+const PP2_SUBTYPE_SSL_SIG_ALG: u8 = 0x24;
+const PP2_SUBTYPE_SSL_KEY_ALG: u8 = 0x25;
+const PP2_TYPE_NETNS: u8 = 0x30;
+
+/// Read a PROXY protocol header from the start of a connection.
+///
+/// Only the bytes of the header are consumed, so the reader can be used to
+/// read the HTTP request afterwards.
+///
+/// # Errors
+///
+/// Returns a `400` error if the connection does not start with a valid
+/// header.
+pub async fn read<R>(reader: &mut R) -> crate::Result<ProxyHeader>
+where
+    R: AsyncRead + Unpin + ?Sized,
+{
+    // The shortest valid header, `PROXY UNKNOWN\r\n`, is longer than the v2
+    // signature, so this never reads past the end of the header.
+    let mut buf = vec![0; SIGNATURE.len()];
+    reader.read_exact(&mut buf).await?;
+
+    if buf[..] == SIGNATURE[..] {
+        buf.resize(V2_HEADER_LEN, 0);
+        reader.read_exact(&mut buf[SIGNATURE.len()..]).await?;
+        let len = u16::from_be_bytes([buf[14], buf[15]]) as usize;
+        buf.resize(V2_HEADER_LEN + len, 0);
+        reader.read_exact(&mut buf[V2_HEADER_LEN..]).await?;
+    } else {
+        ensure!(
+            buf.starts_with(b"PROXY "),
+            400,
+            "Missing PROXY protocol header"
+        );
+        while !buf.ends_with(b"\r\n") {
+            ensure!(
+                buf.len() < V1_MAX_LEN,
+                400,
+                "PROXY protocol header is too long"
+            );
+            let mut byte = [0];
+            reader.read_exact(&mut byte).await?;
+            buf.push(byte[0]);
+        }
+    }
+
+    let (header, _) = ProxyHeader::parse(&buf)?;
+    Ok(header)
+}
+
+/// A parsed PROXY protocol header.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProxyHeader {
+    version: u8,
+    command: Command,
+    addresses: Addresses,
+    tlvs: Vec<Tlv>,
+}
+
+impl ProxyHeader {
+    /// Parse a PROXY protocol header from the start of a buffer.
+    ///
+    /// Returns the header and the number of bytes it occupied.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `400` error if the buffer does not start with a complete,
+    /// valid header.
+    pub fn parse(buf: &[u8]) -> crate::Result<(Self, usize)> {
+        if buf.starts_with(&SIGNATURE) {
+            parse_v2(buf)
+        } else if buf.starts_with(b"PROXY ") {
+            parse_v1(buf)
+        } else {
+            bail!(400, "Missing PROXY protocol header")
+        }
+    }
+
+    /// Get the version of the protocol, either `1` or `2`.
+    pub fn version(&self) -> u8 {
+        self.version
+    }
+
+    /// Get the command.
+    pub fn command(&self) -> Command {
+        self.command
+    }
+
+    /// Get the addresses of the original connection.
+    pub fn addresses(&self) -> &Addresses {
+        &self.addresses
+    }
+
+    /// Get the address of the client which opened the original connection.
+    pub fn source(&self) -> Option<SocketAddr> {
+        match self.addresses {
+            Addresses::Tcp { source, .. } | Addresses::Udp { source, .. } => Some(source),
+            _ => None,
+        }
+    }
+
+    /// Get the address the original connection was made to.
+    pub fn destination(&self) -> Option<SocketAddr> {
+        match self.addresses {
+            Addresses::Tcp { destination, .. } | Addresses::Udp { destination, .. } => {
+                Some(destination)
+            }
+            _ => None,
+        }
+    }
+
+    /// Get all TLVs sent with a version 2 header.
+    pub fn tlvs(&self) -> &[Tlv] {
+        &self.tlvs
+    }
+
+    /// Get the value of the first TLV of the given type.
+    pub fn tlv(&self, kind: u8) -> Option<&[u8]> {
+        self.tlvs
+            .iter()
+            .find(|tlv| tlv.kind == kind)
+            .map(|tlv| tlv.value.as_slice())
+    }
+
+    /// Get the application protocol negotiated with the client, e.g.
+    /// `b"h2"`.
+    pub fn alpn(&self) -> Option<&[u8]> {
+        self.tlv(PP2_TYPE_ALPN)
+    }
+
+    /// Get the host name the client requested, usually through TLS SNI.
+    pub fn authority(&self) -> Option<&str> {
+        self.tlv(PP2_TYPE_AUTHORITY)
+            .and_then(|value| std::str::from_utf8(value).ok())
+    }
+
+    /// Get the opaque identifier of the connection.
+    pub fn unique_id(&self) -> Option<&[u8]> {
+        self.tlv(PP2_TYPE_UNIQUE_ID)
+    }
+
+    /// Get information about the TLS connection with the client.
+    pub fn ssl(&self) -> Option<SslInfo> {
+        self.tlv(PP2_TYPE_SSL)
+            .and_then(|value| SslInfo::parse(value).ok())
+    }
+
+    /// Get the name of the network namespace the connection was received in.
+    pub fn netns(&self) -> Option<&str> {
+        self.tlv(PP2_TYPE_NETNS)
+            .and_then(|value| std::str::from_utf8(value).ok())
+    }
+
+    /// Apply the header to a request.
+    ///
+    /// For proxied TCP and UDP connections this sets the peer address to the
+    /// source, and the local address to the destination of the original
+    /// connection. The header itself is inserted into the request extensions.
+    pub fn apply(&self, req: &mut Request) {
+        if self.command == Command::Proxy {
+            if let (Some(source), Some(destination)) = (self.source(), self.destination()) {
+                req.set_peer_addr(Some(source));
+                req.set_local_addr(Some(destination));
+            }
+        }
+        req.ext_mut().insert(self.clone());
+    }
+}
+
+/// The command of a PROXY protocol header.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Command {
+    /// The connection was established by the proxy itself, e.g. for a health
+    /// check. The real connection endpoints should be used.
+    Local,
+    /// The connection was relayed on behalf of another node.
+    Proxy,
+}
+
+/// The addresses of the original connection.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Addresses {
+    /// The addresses are not known or not supported.
+    Unknown,
+    /// A TCP connection over IPv4 or IPv6.
+    Tcp {
+        /// The address of the client.
+        source: SocketAddr,
+        /// The address the client connected to.
+        destination: SocketAddr,
+    },
+    /// A UDP connection over IPv4 or IPv6.
+    Udp {
+        /// The address of the client.
+        source: SocketAddr,
+        /// The address the client connected to.
+        destination: SocketAddr,
+    },
+    /// A Unix socket connection.
+    Unix {
+        /// The path of the client socket.
+        source: Vec<u8>,
+        /// The path of the socket the client connected to.
+        destination: Vec<u8>,
+    },
+}
+
+/// A type-length-value field of a version 2 header.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Tlv {
+    kind: u8,
+    value: Vec<u8>,
+}
+
+impl Tlv {
+    /// Get the type of the field.
+    pub fn kind(&self) -> u8 {
+        self.kind
+    }
+
+    /// Get the value of the field.
+    pub fn value(&self) -> &[u8] {
+        &self.value
+    }
+}
+
+/// Information about the TLS connection between the client and the proxy.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SslInfo {
+    client: u8,
+    verify: u32,
+    version: Option<String>,
+    cn: Option<String>,
+    cipher: Option<String>,
+    sig_alg: Option<String>,
+    key_alg: Option<String>,
+}
+
+impl SslInfo {
+    fn parse(value: &[u8]) -> crate::Result<Self> {
+        ensure!(value.len() >= 5, 400, "Invalid PROXY protocol SSL field");
+        let mut info = SslInfo {
+            client: value[0],
+            verify: u32::from_be_bytes(value[1..5].try_into().unwrap()),
+            ..Default::default()
+        };
+        for tlv in parse_tlvs(&value[5..])? {
+            let text = String::from_utf8(tlv.value).ok();
+            match tlv.kind {
+                PP2_SUBTYPE_SSL_VERSION => info.version = text,
+                PP2_SUBTYPE_SSL_CN => info.cn = text,
+                PP2_SUBTYPE_SSL_CIPHER => info.cipher = text,
+                PP2_SUBTYPE_SSL_SIG_ALG => info.sig_alg = text,
+                PP2_SUBTYPE_SSL_KEY_ALG => info.key_alg = text,
+                _ => {}
+            }
+        }
+        Ok(info)
+    }
+
+    /// Returns `true` if the client connected over TLS.
+    pub fn is_ssl(&self) -> bool {
+        self.client & 0x01 != 0
+    }
+
+    /// Returns `true` if the client presented a certificate on this
+    /// connection.
+    pub fn has_client_cert(&self) -> bool {
+        self.client & 0x02 != 0
+    }
+
+    /// Returns `true` if the client presented a certificate at least once
+    /// during the TLS session.
+    pub fn has_session_cert(&self) -> bool {
+        self.client & 0x04 != 0
+    }
+
+    /// Returns `true` if the client certificate was verified successfully.
+    pub fn is_verified(&self) -> bool {
+        self.verify == 0
+    }
+
+    /// Get the TLS version, e.g. `TLSv1.3`.
+    pub fn version(&self) -> Option<&str> {
+        self.version.as_deref()
+    }
+
+    /// Get the common name of the client certificate.
+    pub fn cn(&self) -> Option<&str> {
+        self.cn.as_deref()
+    }
+
+    /// Get the name of the cipher used.
+    pub fn cipher(&self) -> Option<&str> {
+        self.cipher.as_deref()
+    }
+
+    /// Get the signature algorithm of the certificate presented by the
+    /// frontend.
+    pub fn sig_alg(&self) -> Option<&str> {
+        self.sig_alg.as_deref()
+    }
+
+    /// Get the key algorithm of the certificate presented by the frontend.
+    pub fn key_alg(&self) -> Option<&str> {
+        self.key_alg.as_deref()
+    }
+}
+
+fn parse_v1(buf: &[u8]) -> crate::Result<(ProxyHeader, usize)> {
+    let window = &buf[..buf.len().min(V1_MAX_LEN)];
+    let end = match window.windows(2).position(|w| w == b"\r\n") {
+        Some(end) => end,
+        None => bail!(400, "Incomplete PROXY protocol header"),
+    };
+    let line = match std::str::from_utf8(&buf[..end]) {
+        Ok(line) => line,
+        Err(_) => bail!(400, "Invalid PROXY protocol header"),
+    };
+
+    let mut parts = line.split(' ').skip(1);
+    let addresses = match parts.next() {
+        // Anything after `UNKNOWN` must be ignored.
+        Some("UNKNOWN") => Addresses::Unknown,
+        Some(family @ "TCP4") | Some(family @ "TCP6") => {
+            let fields: Vec<&str> = parts.collect();
+            ensure!(fields.len() == 4, 400, "Invalid PROXY protocol header");
+            let source: IpAddr = parse_field(fields[0])?;
+            let destination: IpAddr = parse_field(fields[1])?;
+            let ipv4 = family == "TCP4";
+            ensure!(
+                source.is_ipv4() == ipv4 && destination.is_ipv4() == ipv4,
+                400,
+                "PROXY protocol address does not match the protocol family"
+            );
+            Addresses::Tcp {
+                source: SocketAddr::new(source, parse_port(fields[2])?),
+                destination: SocketAddr::new(destination, parse_port(fields[3])?),
+            }
+        }
+        _ => bail!(400, "Unsupported PROXY protocol family"),
+    };
+
+    let header = ProxyHeader {
+        version: 1,
+        command: Command::Proxy,
+        addresses,
+        tlvs: vec![],
+    };
+    Ok((header, end + 2))
+}
+
+fn parse_field<T: std::str::FromStr>(field: &str) -> crate::Result<T> {
+    match field.parse() {
+        Ok(value) => Ok(value),
+        Err(_) => bail!(400, "Invalid PROXY protocol field `{}`", field),
+    }
+}
+
+fn parse_port(field: &str) -> crate::Result<u16> {
+    ensure!(
+        field == "0" || !field.starts_with('0'),
+        400,
+        "Invalid PROXY protocol port `{}`",
+        field
+    );
+    parse_field(field)
+}
+
+fn parse_v2(buf: &[u8]) -> crate::Result<(ProxyHeader, usize)> {
+    ensure!(
+        buf.len() >= V2_HEADER_LEN,
+        400,
+        "Incomplete PROXY protocol header"
+    );
+    let len = u16::from_be_bytes([buf[14], buf[15]]) as usize;
+    let total = V2_HEADER_LEN + len;
+    ensure!(buf.len() >= total, 400, "Incomplete PROXY protocol header");
+    let payload = &buf[V2_HEADER_LEN..total];
+
+    ensure!(buf[12] >> 4 == 2, 400, "Unsupported PROXY protocol version");
+    let command = match buf[12] & 0x0f {
+        0x0 => Command::Local,
+        0x1 => Command::Proxy,
+        _ => bail!(400, "Unsupported PROXY protocol command"),
+    };
+
+    let (addresses, addr_len) = match (buf[13] >> 4, buf[13] & 0x0f) {
+        (0x1, transport @ 0x1..=0x2) => {
+            ensure!(payload.len() >= 12, 400, "Invalid PROXY protocol addresses");
+            let ip = |b: &[u8]| IpAddr::V4(Ipv4Addr::new(b[0], b[1], b[2], b[3]));
+            let source = SocketAddr::new(ip(&payload[0..4]), port(&payload[8..10]));
+            let destination = SocketAddr::new(ip(&payload[4..8]), port(&payload[10..12]));
+            (inet(transport, source, destination), 12)
+        }
+        (0x2, transport @ 0x1..=0x2) => {
+            ensure!(payload.len() >= 36, 400, "Invalid PROXY protocol addresses");
+            let ip = |b: &[u8]| {
+                let octets: [u8; 16] = b.try_into().unwrap();
+                IpAddr::V6(Ipv6Addr::from(octets))
+            };
+            let source = SocketAddr::new(ip(&payload[0..16]), port(&payload[32..34]));
+            let destination = SocketAddr::new(ip(&payload[16..32]), port(&payload[34..36]));
+            (inet(transport, source, destination), 36)
+        }
+        (0x3, 0x1..=0x2) => {
+            ensure!(
+                payload.len() >= 216,
+                400,
+                "Invalid PROXY protocol addresses"
+            );
+            let path = |b: &[u8]| b.split(|&c| c == 0).next().unwrap_or_default().to_vec();
+            let addresses = Addresses::Unix {
+                source: path(&payload[0..108]),
+                destination: path(&payload[108..216]),
+            };
+            (addresses, 216)
+        }
+        // Unspecified or unsupported families: the address block is skipped.
+        _ => (Addresses::Unknown, payload.len()),
+    };
+
+    let tlvs = parse_tlvs(&payload[addr_len..])?;
+    if let Some(tlv) = tlvs.iter().find(|tlv| tlv.kind == PP2_TYPE_CRC32C) {
+        ensure!(tlv.value.len() == 4, 400, "Invalid PROXY protocol checksum");
+        let expected = u32::from_be_bytes(tlv.value[..].try_into().unwrap());
+        // The checksum is computed with the checksum field set to zero.
+        let mut header = buf[..total].to_vec();
+        let offset = crc_offset(&header[V2_HEADER_LEN + addr_len..])
+            .expect("checksum TLV should be present")
+            + V2_HEADER_LEN
+            + addr_len;
+        header[offset..offset + 4].copy_from_slice(&[0; 4]);
+        ensure!(
+            crc32c(&header) == expected,
+            400,
+            "PROXY protocol checksum mismatch"
+        );
+    }
+
+    let header = ProxyHeader {
+        version: 2,
+        command,
+        addresses,
+        tlvs,
+    };
+    Ok((header, total))
+}
+
+fn port(b: &[u8]) -> u16 {
+    u16::from_be_bytes([b[0], b[1]])
+}
+
+fn inet(transport: u8, source: SocketAddr, destination: SocketAddr) -> Addresses {
+    match transport {
+        0x1 => Addresses::Tcp {
+            source,
+            destination,
+        },
+        _ => Addresses::Udp {
+            source,
+            destination,
+        },
+    }
+}
+
+fn parse_tlvs(mut buf: &[u8]) -> crate::Result<Vec<Tlv>> {
+    let mut tlvs = vec![];
+    while !buf.is_empty() {
+        ensure!(buf.len() >= 3, 400, "Invalid PROXY protocol TLV");
+        let len = u16::from_be_bytes([buf[1], buf[2]]) as usize;
+        ensure!(buf.len() >= 3 + len, 400, "Invalid PROXY protocol TLV");
+        tlvs.push(Tlv {
+            kind: buf[0],
+            value: buf[3..3 + len].to_vec(),
+        });
+        buf = &buf[3 + len..];
+    }
+    Ok(tlvs)
+}
+
+/// Find the offset of the checksum value within a TLV block.
+fn crc_offset(mut buf: &[u8]) -> Option<usize> {
+    let mut offset = 0;
+    while buf.len() >= 3 {
+        let len = u16::from_be_bytes([buf[1], buf[2]]) as usize;
+        if buf[0] == PP2_TYPE_CRC32C {
+            return Some(offset + 3);
+        }
+        offset += 3 + len;
+        buf = buf.get(3 + len..)?;
+    }
+    None
+}
+
+/// CRC-32C (Castagnoli), as used by the checksum TLV.
+fn crc32c(buf: &[u8]) -> u32 {
+    let mut crc = !0u32;
+    for &byte in buf {
+        crc ^= byte as u32;
+        for _ in 0..8 {
+            crc = if crc & 1 == 1 {
+                (crc >> 1) ^ 0x82f6_3b78
+            } else {
+                crc >> 1
+            };
+        }
+    }
+    !crc
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn v2(command: u8, family: u8, addresses: &[u8], tlvs: &[u8]) -> Vec<u8> {
+        let mut buf = SIGNATURE.to_vec();
+        buf.push(0x20 | command);
+        buf.push(family);
+        let len = (addresses.len() + tlvs.len()) as u16;
+        buf.extend_from_slice(&len.to_be_bytes());
+        buf.extend_from_slice(addresses);
+        buf.extend_from_slice(tlvs);
+        buf
+    }
+
+    fn tlv(kind: u8, value: &[u8]) -> Vec<u8> {
+        let mut buf = vec![kind];
+        buf.extend_from_slice(&(value.len() as u16).to_be_bytes());
+        buf.extend_from_slice(value);
+        buf
+    }
+
+    #[test]
+    fn v1() -> crate::Result<()> {
+        let (header, len) =
+            ProxyHeader::parse(b"PROXY TCP6 2001:db8::1 2001:db8::2 1234 80\r\nrest")?;
+        assert_eq!(len, 44);
+        assert_eq!(header.version(), 1);
+        assert_eq!(header.source(), Some("[2001:db8::1]:1234".parse()?));
+        assert_eq!(header.destination(), Some("[2001:db8::2]:80".parse()?));
+
+        let (header, _) = ProxyHeader::parse(b"PROXY UNKNOWN ignored\r\n")?;
+        assert_eq!(header.addresses(), &Addresses::Unknown);
+        Ok(())
+    }
+
+    #[test]
+    fn v1_errors() {
+        let inputs: [&[u8]; 6] = [
+            b"PROXY TCP4 192.0.2.1 198.51.100.1 56324\r\n",
+            b"PROXY TCP4 2001:db8::1 198.51.100.1 1 2\r\n",
+            b"PROXY TCP4 192.0.2.1 198.51.100.1 01 2\r\n",
+            b"PROXY TCP4 192.0.2.1 198.51.100.1 65536 2\r\n",
+            b"PROXY TCP5 192.0.2.1 198.51.100.1 1 2\r\n",
+            b"PROXY TCP4 192.0.2.1 198.51.100.1 1 2",
+        ];
+        for input in &inputs {
+            let err = ProxyHeader::parse(input).unwrap_err();
+            assert_eq!(err.status(), 400);
+        }
+    }
+
+    #[test]
+    fn v2_with_tlvs() -> crate::Result<()> {
+        let mut ssl = vec![0x07, 0, 0, 0, 0];
+        ssl.extend(tlv(PP2_SUBTYPE_SSL_VERSION, b"TLSv1.3"));
+        ssl.extend(tlv(PP2_SUBTYPE_SSL_CN, b"client.example"));
+
+        let mut tlvs = tlv(PP2_TYPE_ALPN, b"h2");
+        tlvs.extend(tlv(PP2_TYPE_AUTHORITY, b"example.com"));
+        tlvs.extend(tlv(PP2_TYPE_SSL, &ssl));
+        tlvs.extend(tlv(PP2_TYPE_CRC32C, &[0; 4]));
+
+        let addresses = [192, 0, 2, 1, 198, 51, 100, 1, 0xdc, 0x04, 0x01, 0xbb];
+        let mut buf = v2(0x1, 0x11, &addresses, &tlvs);
+        let crc = crc32c(&buf).to_be_bytes();
+        let len = buf.len();
+        buf[len - 4..].copy_from_slice(&crc);
+
+        let (header, used) = ProxyHeader::parse(&buf)?;
+        assert_eq!(used, buf.len());
+        assert_eq!(header.command(), Command::Proxy);
+        assert_eq!(header.source(), Some("192.0.2.1:56324".parse()?));
+        assert_eq!(header.destination(), Some("198.51.100.1:443".parse()?));
+        assert_eq!(header.alpn(), Some(&b"h2"[..]));
+        assert_eq!(header.authority(), Some("example.com"));
+        let ssl = header.ssl().unwrap();
+        assert!(ssl.is_ssl() && ssl.has_client_cert() && ssl.is_verified());
+        assert_eq!(ssl.version(), Some("TLSv1.3"));
+        assert_eq!(ssl.cn(), Some("client.example"));
+
+        buf[len - 1] ^= 0xff;
+        assert_eq!(ProxyHeader::parse(&buf).unwrap_err().status(), 400);
+        Ok(())
+    }
+
+    #[test]
+    fn v2_local_is_not_applied() -> crate::Result<()> {
+        let buf = v2(0x0, 0x00, &[], &[]);
+        let (header, _) = ProxyHeader::parse(&buf)?;
+        assert_eq!(header.command(), Command::Local);
+
+        let mut req = Request::get("http://example.com");
+        req.set_peer_addr(Some("10.0.0.1:1234"));
+        header.apply(&mut req);
+        assert_eq!(req.peer_addr(), Some("10.0.0.1:1234"));
+        assert!(req.ext().get::<ProxyHeader>().is_some());
+        Ok(())
+    }
+
+    #[async_std::test]
+    async fn read_v2_from_stream() -> crate::Result<()> {
+        let mut addresses = vec![0; 36];
+        addresses[15] = 1;
+        addresses[31] = 2;
+        addresses[32..36].copy_from_slice(&[0x04, 0xd2, 0x00, 0x50]);
+        let mut buf = v2(0x1, 0x21, &addresses, &tlv(PP2_TYPE_UNIQUE_ID, b"abc"));
+        buf.extend_from_slice(b"GET / HTTP/1.1\r\n");
+
+        let mut stream = &buf[..];
+        let header = read(&mut stream).await?;
+        assert_eq!(
+            header.addresses(),
+            &Addresses::Tcp {
+                source: "[::1]:1234".parse()?,
+                destination: "[::2]:80".parse()?,
+            }
+        );
+        assert_eq!(header.unique_id(), Some(&b"abc"[..]));
+        assert_eq!(stream, &b"GET / HTTP/1.1\r\n"[..]);
+        Ok(())
+    }
+
+    #[test]
+    fn crc32c_check_value() {
+        assert_eq!(crc32c(b"123456789"), 0xe306_9283);
+    }
+}
@@ -0,0 +1,492 @@
+use crate::proxies::{Forwarded, ForwardedElement, NodePort};
+use crate::{ensure, format_err, Request};
+
+use std::fmt::{self, Display};
+use std::net::{IpAddr, SocketAddr};
+use std::str::FromStr;
+
+/// A range of IP addresses in CIDR notation.
+///
+/// # Examples
+///
+/// ```
+/// # fn main() -> http_types::Result<()> {
+/// #
+/// use http_types::proxies::Cidr;
+///
+/// let cidr: Cidr = "10.0.0.0/8".parse()?;
+/// assert!(cidr.contains("10.1.2.3".parse()?));
+/// assert!(!cidr.contains("192.168.0.1".parse()?));
+///
+/// // A single address is a network with the full prefix length.
+/// let cidr: Cidr = "::1".parse()?;
+/// assert_eq!(cidr.to_string(), "::1/128");
+/// #
+/// # Ok(()) }
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Cidr {
+    addr: IpAddr,
+    prefix_len: u8,
+}
+
+impl Cidr {
+    /// Create a new instance of `Cidr`.
+    ///
+    /// Any bits of `addr` beyond the prefix are ignored.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the prefix length is longer than the address.
+    pub fn new(addr: IpAddr, prefix_len: u8) -> crate::Result<Self> {
+        let max = match addr {
+            IpAddr::V4(_) => 32,
+            IpAddr::V6(_) => 128,
+        };
+        ensure!(
+            prefix_len <= max,
+            "Prefix length {} is too long for {}",
+            prefix_len,
+            addr
+        );
+        Ok(Self { addr, prefix_len })
+    }
+
+    /// Get the network address.
+    pub fn addr(&self) -> IpAddr {
+        self.addr
+    }
+
+    /// Get the prefix length.
+    pub fn prefix_len(&self) -> u8 {
+        self.prefix_len
+    }
+
+    /// Returns `true` if the address is part of this network.
+    ///
+    /// IPv4-mapped IPv6 addresses are matched against IPv4 networks.
+    pub fn contains(&self, addr: IpAddr) -> bool {
+        match (self.addr, canonical(addr)) {
+            (IpAddr::V4(net), IpAddr::V4(addr)) => {
+                let mask = mask(self.prefix_len, 32) as u32;
+                u32::from(net) & mask == u32::from(addr) & mask
+            }
+            (IpAddr::V6(net), IpAddr::V6(addr)) => {
+                let mask = mask(self.prefix_len, 128);
+                u128::from(net) & mask == u128::from(addr) & mask
+            }
+            _ => false,
+        }
+    }
+}
+
+impl FromStr for Cidr {
+    type Err = crate::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        let (addr, prefix_len) = match s.split_once('/') {
+            Some((addr, prefix_len)) => (addr, Some(prefix_len)),
+            None => (s, None),
+        };
+        let addr: IpAddr = addr
+            .parse()
+            .map_err(|_| format_err!("Invalid network address `{}`", s))?;
+        let prefix_len = match prefix_len {
+            Some(prefix_len) => prefix_len
+                .parse()
+                .map_err(|_| format_err!("Invalid prefix length `{}`", s))?,
+            None if addr.is_ipv4() => 32,
+            None => 128,
+        };
+        Self::new(addr, prefix_len)
+    }
+}
+
+impl Display for Cidr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}/{}", self.addr, self.prefix_len)
+    }
+}
+
+/// The header trusted proxies append forwarding information to.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ForwardingHeader {
+    /// The `Forwarded` header.
+    #[default]
+    Forwarded,
+    /// The `X-Forwarded-For`, `X-Forwarded-Proto`, `X-Forwarded-Host` and
+    /// `X-Forwarded-By` headers.
+    XForwarded,
+}
+
+/// Resolve client information from requests which passed through trusted
+/// proxies.
+///
+/// [`Request::remote`] returns the first `Forwarded` or `X-Forwarded-For`
+/// entry, which any client can set. `TrustedProxies` only consults the
+/// forwarding headers if the request was received from a trusted proxy. It
+/// then walks the forwarding chain from the right, skipping trusted hops, and
+/// treats the first untrusted hop as the client.
+///
+/// Only the header set by the proxies is read, which is `Forwarded` unless
+/// changed with [`TrustedProxies::set_forwarding_header`]. Clients can send
+/// the other header as well, so it is never used as a fallback.
+///
+/// # Examples
+///
+/// ```
+/// # fn main() -> http_types::Result<()> {
+/// #
+/// use http_types::proxies::{ForwardingHeader, TrustedProxies};
+/// use http_types::Request;
+///
+/// let mut proxies = TrustedProxies::new();
+/// proxies.push("10.0.0.0/8".parse()?);
+/// proxies.set_forwarding_header(ForwardingHeader::XForwarded);
+///
+/// let mut req = Request::get("http://internal/");
+/// req.set_peer_addr(Some("10.0.0.2:443"));
+/// req.insert_header("X-Forwarded-For", "203.0.113.7, 198.51.100.1, 10.0.0.1");
+/// req.insert_header("X-Forwarded-Proto", "https");
+/// req.insert_header("X-Forwarded-Host", "example.com");
+///
+/// assert_eq!(proxies.client_addr(&req), Some("198.51.100.1".parse()?));
+/// assert_eq!(proxies.scheme(&req).as_deref(), Some("https"));
+/// assert_eq!(proxies.host(&req).as_deref(), Some("example.com"));
+/// #
+/// # Ok(()) }
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct TrustedProxies {
+    networks: Vec<Cidr>,
+    header: ForwardingHeader,
+}
+
+impl TrustedProxies {
+    /// Create a new instance of `TrustedProxies` which trusts no proxies.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Trust all proxies in a network.
+    pub fn push(&mut self, network: Cidr) {
+        self.networks.push(network);
+    }
+
+    /// Get the header trusted proxies append forwarding information to.
+    pub fn forwarding_header(&self) -> ForwardingHeader {
+        self.header
+    }
+
+    /// Set the header trusted proxies append forwarding information to.
+    pub fn set_forwarding_header(&mut self, header: ForwardingHeader) {
+        self.header = header;
+    }
+
+    /// Returns `true` if the address belongs to a trusted proxy.
+    pub fn is_trusted(&self, addr: IpAddr) -> bool {
+        self.networks.iter().any(|network| network.contains(addr))
+    }
+
+    /// Get the IP address of the client.
+    ///
+    /// Returns `None` if the address is not known, or if the closest
+    /// untrusted hop used an obfuscated identifier.
+    pub fn client_addr(&self, req: &Request) -> Option<IpAddr> {
+        self.walk(req).0.map(|(addr, _)| addr)
+    }
+
+    /// Get the socket address of the client.
+    ///
+    /// Returns `None` if either the address or the port of the client is not
+    /// known.
+    pub fn client_socket_addr(&self, req: &Request) -> Option<SocketAddr> {
+        match self.walk(req).0? {
+            (addr, Some(port)) => Some(SocketAddr::new(addr, port)),
+            (_, None) => None,
+        }
+    }
+
+    /// Get the scheme the client used to make the request.
+    ///
+    /// The `proto` forwarding parameter is only used if it was set by a
+    /// trusted proxy. Otherwise the scheme of the request URL is returned.
+    pub fn scheme(&self, req: &Request) -> Option<String> {
+        let (_, trusted) = self.walk(req);
+        trusted
+            .iter()
+            .find_map(|element| element.proto())
+            .and_then(first_value)
+            .or_else(|| Some(req.url().scheme().to_owned()))
+    }
+
+    /// Get the host the client made the request to.
+    ///
+    /// The `host` forwarding parameter is only used if it was set by a
+    /// trusted proxy. Otherwise the `Host` header or the host of the request
+    /// URL is returned.
+    pub fn host(&self, req: &Request) -> Option<String> {
+        let (_, trusted) = self.walk(req);
+        trusted
+            .iter()
+            .find_map(|element| element.host())
+            .and_then(first_value)
+            .or_else(|| {
+                req.header(crate::headers::HOST)
+                    .map(|host| host.as_str().to_owned())
+                    .or_else(|| req.url().host_str().map(|host| host.to_owned()))
+            })
+    }
+
+    /// Walk the forwarding chain from the right.
+    ///
+    /// Returns the client, and the forwarded elements which were added by
+    /// trusted proxies, starting with the element describing the client.
+    fn walk<'a>(&self, req: &'a Request) -> (Option<Client>, Vec<ForwardedElement<'a>>) {
+        let peer = match req.peer_addr().and_then(parse_peer) {
+            Some(peer) => peer,
+            None => return (None, vec![]),
+        };
+        if !self.is_trusted(peer.0) {
+            return (Some(peer), vec![]);
+        }
+
+        let forwarded = match self.header {
+            ForwardingHeader::Forwarded => Forwarded::from_forwarded_header(req),
+            ForwardingHeader::XForwarded => Forwarded::from_x_headers(req),
+        };
+        let mut elements = match forwarded {
+            Ok(Some(forwarded)) if forwarded.elements().is_empty() => return (Some(peer), vec![]),
+            Ok(Some(forwarded)) => forwarded.elements().to_vec(),
+            Ok(None) => return (Some(peer), vec![]),
+            Err(_) => return (None, vec![]),
+        };
+
+        // Without any `for` parameters only the peer's own element can be
+        // attributed to a trusted proxy.
+        if elements
+            .iter()
+            .all(|element| element.forwarded_for().is_none())
+        {
+            let last = elements.split_off(elements.len() - 1);
+            return (Some(peer), last);
+        }
+
+        let mut client = None;
+        for i in (0..elements.len()).rev() {
+            let node = match elements[i].for_node() {
+                Ok(Some(node)) => node.ip_addr().map(|addr| {
+                    let port = match node.port() {
+                        Some(NodePort::Real(port)) => Some(*port),
+                        _ => None,
+                    };
+                    (canonical(addr), port)
+                }),
+                _ => None,
+            };
+            client = node;
+            match client {
+                Some((addr, _)) if self.is_trusted(addr) => continue,
+                _ => return (client, elements.split_off(i)),
+            }
+        }
+        (client, elements)
+    }
+}
+
+/// A client IP address and port, if known.
+type Client = (IpAddr, Option<u16>);
+
+/// Parse the peer address of a request.
+fn parse_peer(peer: &str) -> Option<Client> {
+    match peer.parse::<SocketAddr>() {
+        Ok(addr) => Some((canonical(addr.ip()), Some(addr.port()))),
+        Err(_) => peer.parse().ok().map(|addr| (canonical(addr), None)),
+    }
+}
+
+/// Get the first element of a comma-separated forwarding value.
+fn first_value(value: &str) -> Option<String> {
+    match value.split(',').next().map(str::trim) {
+        Some(value) if !value.is_empty() => Some(value.to_owned()),
+        _ => None,
+    }
+}
+
+/// Convert IPv4-mapped IPv6 addresses into IPv4 addresses.
+fn canonical(addr: IpAddr) -> IpAddr {
+    match addr {
+        IpAddr::V6(v6) => match v6.to_ipv4_mapped() {
+            Some(v4) => IpAddr::V4(v4),
+            None => addr,
+        },
+        addr => addr,
+    }
+}
+
+/// Build a mask with the upper `prefix_len` bits of a `bits`-wide integer set.
+fn mask(prefix_len: u8, bits: u32) -> u128 {
+    match prefix_len {
+        0 => 0,
+        len => (u128::MAX << (128 - len as u32)) >> (128 - bits),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn proxies() -> TrustedProxies {
+        let mut proxies = TrustedProxies::new();
+        proxies.push("10.0.0.0/8".parse().unwrap());
+        proxies.push("fd00::/8".parse().unwrap());
+        proxies
+    }
+
+    #[test]
+    fn cidr() -> crate::Result<()> {
+        let cidr: Cidr = "192.168.0.0/16".parse()?;
+        assert!(cidr.contains("192.168.255.1".parse()?));
+        assert!(!cidr.contains("192.169.0.1".parse()?));
+        assert!(cidr.contains("::ffff:192.168.1.1".parse()?));
+
+        let cidr: Cidr = "2001:db8::/32".parse()?;
+        assert!(cidr.contains("2001:db8:cafe::17".parse()?));
+        assert!(!cidr.contains("2001:db9::1".parse()?));
+
+        let cidr: Cidr = "0.0.0.0/0".parse()?;
+        assert!(cidr.contains("8.8.8.8".parse()?));
+
+        assert!("10.0.0.0/33".parse::<Cidr>().is_err());
+        assert!("10.0.0/8".parse::<Cidr>().is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn untrusted_peer_ignores_headers() -> crate::Result<()> {
+        let mut req = Request::get("http://example.com/");
+        req.set_peer_addr(Some("203.0.113.9:5000"));
+        req.insert_header("X-Forwarded-For", "1.2.3.4")?;
+        req.insert_header("X-Forwarded-Proto", "https")?;
+
+        let proxies = proxies();
+        assert_eq!(proxies.client_addr(&req), Some("203.0.113.9".parse()?));
+        assert_eq!(
+            proxies.client_socket_addr(&req),
+            Some("203.0.113.9:5000".parse()?)
+        );
+        assert_eq!(proxies.scheme(&req).as_deref(), Some("http"));
+        Ok(())
+    }
+
+    #[test]
+    fn walks_forwarded_chain_from_the_right() -> crate::Result<()> {
+        let mut req = Request::get("http://example.com/");
+        req.set_peer_addr(Some("[fd00::2]:443"));
+        req.insert_header(
+            "Forwarded",
+            r#"for=1.2.3.4, for="[2001:db8:cafe::17]:4711", for=10.1.1.1;proto=https"#,
+        )?;
+
+        let proxies = proxies();
+        assert_eq!(
+            proxies.client_socket_addr(&req),
+            Some("[2001:db8:cafe::17]:4711".parse()?)
+        );
+        assert_eq!(proxies.scheme(&req).as_deref(), Some("https"));
+        Ok(())
+    }
+
+    #[test]
+    fn obfuscated_and_unknown_nodes() -> crate::Result<()> {
+        let proxies = proxies();
+
+        let mut req = Request::get("http://example.com/");
+        req.set_peer_addr(Some("10.0.0.1:80"));
+        req.insert_header("Forwarded", "for=1.2.3.4, for=_hidden")?;
+        assert_eq!(proxies.client_addr(&req), None);
+
+        req.insert_header("Forwarded", r#"for="1.2.3.4:_port""#)?;
+        assert_eq!(proxies.client_addr(&req), Some("1.2.3.4".parse()?));
+        assert_eq!(proxies.client_socket_addr(&req), None);
+
+        req.insert_header("Forwarded", "for=unknown")?;
+        assert_eq!(proxies.client_addr(&req), None);
+        Ok(())
+    }
+
+    #[test]
+    fn ignores_parameters_from_untrusted_hops() -> crate::Result<()> {
+        let mut req = Request::get("http://example.com/");
+        req.set_peer_addr(Some("10.0.0.1:80"));
+        req.insert_header(
+            "Forwarded",
+            "for=1.2.3.4;host=evil.com;proto=https, for=5.6.7.8;host=example.org",
+        )?;
+
+        let proxies = proxies();
+        assert_eq!(proxies.client_addr(&req), Some("5.6.7.8".parse()?));
+        assert_eq!(proxies.host(&req).as_deref(), Some("example.org"));
+        assert_eq!(proxies.scheme(&req).as_deref(), Some("http"));
+        Ok(())
+    }
+
+    #[test]
+    fn empty_forwarded_header() -> crate::Result<()> {
+        let mut req = Request::get("http://example.com/");
+        req.set_peer_addr(Some("10.0.0.1:80"));
+        req.insert_header("Forwarded", ",")?;
+
+        let proxies = proxies();
+        assert_eq!(proxies.client_addr(&req), Some("10.0.0.1".parse()?));
+        assert_eq!(proxies.scheme(&req).as_deref(), Some("http"));
+        Ok(())
+    }
+
+    #[test]
+    fn all_hops_trusted() -> crate::Result<()> {
+        let mut req = Request::get("http://example.com/");
+        req.set_peer_addr(Some("10.0.0.1:80"));
+        req.insert_header("X-Forwarded-For", "10.0.0.3, 10.0.0.2")?;
+        let mut proxies = proxies();
+        proxies.set_forwarding_header(ForwardingHeader::XForwarded);
+        assert_eq!(proxies.client_addr(&req), Some("10.0.0.3".parse()?));
+        Ok(())
+    }
+
+    #[test]
+    fn reads_every_x_forwarded_for_line() -> crate::Result<()> {
+        let mut req = Request::get("http://example.com/");
+        req.set_peer_addr(Some("10.0.0.1:80"));
+        req.append_header("X-Forwarded-For", "1.2.3.4")?;
+        req.append_header("X-Forwarded-For", "203.0.113.7")?;
+        req.append_header("X-Forwarded-Proto", "http")?;
+        req.append_header("X-Forwarded-Proto", "https")?;
+
+        let mut proxies = proxies();
+        proxies.set_forwarding_header(ForwardingHeader::XForwarded);
+        assert_eq!(proxies.client_addr(&req), Some("203.0.113.7".parse()?));
+        assert_eq!(proxies.scheme(&req).as_deref(), Some("https"));
+        Ok(())
+    }
+
+    #[test]
+    fn ignores_header_not_set_by_proxies() -> crate::Result<()> {
+        let mut req = Request::get("http://example.com/");
+        req.set_peer_addr(Some("10.0.0.1:80"));
+        req.insert_header("Forwarded", "for=1.2.3.4;proto=https")?;
+        req.insert_header("X-Forwarded-For", "1.2.3.4, 203.0.113.7")?;
+
+        let mut proxies = proxies();
+        proxies.set_forwarding_header(ForwardingHeader::XForwarded);
+        assert_eq!(proxies.client_addr(&req), Some("203.0.113.7".parse()?));
+        assert_eq!(proxies.scheme(&req).as_deref(), Some("http"));
+
+        // Proxies which set `Forwarded` ignore `X-Forwarded-For`.
+        req.remove_header("Forwarded");
+        proxies.set_forwarding_header(ForwardingHeader::Forwarded);
+        assert_eq!(proxies.client_addr(&req), Some("10.0.0.1".parse()?));
+        Ok(())
+    }
+}
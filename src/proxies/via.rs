@@ -0,0 +1,517 @@
+use crate::headers::{Header, HeaderName, HeaderValue, Headers, VIA};
+use crate::parse_utils::{is_quotable, is_token};
+use crate::{bail_status as bail, ensure_status as ensure};
+
+use std::fmt::{self, Debug, Display, Write};
+use std::iter::Iterator;
+use std::slice;
+
+/// The proxies a message was forwarded through.
+///
+/// Each intermediary appends an entry with the protocol the message was
+/// received with, and the host or pseudonym of the intermediary itself.
+/// Because every proxy in the chain is listed, the header can be used to
+/// detect forwarding loops.
+///
+/// [MDN Documentation](https://developer.mozilla.org/en-US/docs/Web/HTTP/Headers/Via)
+///
+/// # Specifications
+///
+/// - [RFC 7230, section 5.7.1: Via](https://tools.ietf.org/html/rfc7230#section-5.7.1)
+///
+/// # Examples
+///
+/// ```
+/// # fn main() -> http_types::Result<()> {
+/// #
+/// use http_types::proxies::{Via, ViaEntry};
+/// use http_types::{Request, StatusCode};
+///
+/// let mut req = Request::get("http://example.com");
+/// req.insert_header("Via", "1.0 fred, 1.1 p.example.net (Apache/1.1)");
+///
+/// let via = Via::from_headers(&req)?.unwrap();
+/// let entry = via.iter().nth(1).unwrap();
+/// assert_eq!(entry.version(), "1.1");
+/// assert_eq!(entry.received_by(), "p.example.net");
+/// assert_eq!(entry.comment(), Some("Apache/1.1"));
+///
+/// Via::check_loop(&req, "gateway")?;
+/// Via::append_hop(&mut req, ViaEntry::new("1.1", "gateway")?);
+/// let err = Via::check_loop(&req, "gateway").unwrap_err();
+/// assert_eq!(err.status(), StatusCode::LoopDetected);
+/// #
+/// # Ok(()) }
+/// ```
+#[derive(Clone, Eq, PartialEq)]
+pub struct Via {
+    entries: Vec<ViaEntry>,
+}
+
+impl Via {
+    /// Create a new instance of `Via`.
+    pub fn new() -> Self {
+        Self { entries: vec![] }
+    }
+
+    /// Create a new instance from headers.
+    pub fn from_headers(headers: impl AsRef<Headers>) -> crate::Result<Option<Self>> {
+        let headers = match headers.as_ref().get(VIA) {
+            Some(headers) => headers,
+            None => return Ok(None),
+        };
+
+        let mut entries = vec![];
+        for value in headers {
+            let mut input = value.as_str();
+            loop {
+                input = input.trim_start_matches([',', ' ', '\t']);
+                if input.is_empty() {
+                    break;
+                }
+                let (entry, rest) = ViaEntry::parse(input)?;
+                entries.push(entry);
+                input = rest;
+            }
+        }
+
+        Ok(Some(Self { entries }))
+    }
+
+    /// Append an entry for this intermediary to the `Via` header.
+    pub fn append_hop(mut headers: impl AsMut<Headers>, entry: ViaEntry) {
+        headers.as_mut().append(VIA, entry.to_string()).unwrap();
+    }
+
+    /// Check that a message has not been forwarded by `pseudonym` before.
+    ///
+    /// A proxy should call this before forwarding a message, passing the
+    /// name it uses in its own `Via` entries.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `508 Loop Detected` error if one of the entries was received
+    /// by `pseudonym`, or a `400 Bad Request` error if the header could not be
+    /// parsed.
+    pub fn check_loop(headers: impl AsRef<Headers>, pseudonym: &str) -> crate::Result<()> {
+        if let Some(via) = Self::from_headers(headers)? {
+            ensure!(
+                !via.contains_received_by(pseudonym),
+                508,
+                "Forwarding loop detected, `{}` already appears in the Via header",
+                pseudonym
+            );
+        }
+        Ok(())
+    }
+
+    /// Push an entry into the list of entries.
+    pub fn push(&mut self, entry: ViaEntry) {
+        self.entries.push(entry);
+    }
+
+    /// Returns `true` if an entry was received by `name`.
+    ///
+    /// Names are compared case-insensitively. If `name` has no port, the
+    /// port of the entries is ignored.
+    pub fn contains_received_by(&self, name: &str) -> bool {
+        self.entries.iter().any(|entry| {
+            let received_by = entry.received_by();
+            if received_by.eq_ignore_ascii_case(name) {
+                return true;
+            }
+            match received_by.rsplit_once(':') {
+                Some((host, _)) if !name.contains(':') => host.eq_ignore_ascii_case(name),
+                _ => false,
+            }
+        })
+    }
+
+    /// An iterator visiting all entries.
+    pub fn iter(&self) -> Iter<'_> {
+        Iter {
+            inner: self.entries.iter(),
+        }
+    }
+
+    /// An iterator visiting all entries.
+    pub fn iter_mut(&mut self) -> IterMut<'_> {
+        IterMut {
+            inner: self.entries.iter_mut(),
+        }
+    }
+}
+
+impl Header for Via {
+    fn header_name(&self) -> HeaderName {
+        VIA
+    }
+
+    fn header_value(&self) -> HeaderValue {
+        let mut output = String::new();
+        for (n, entry) in self.entries.iter().enumerate() {
+            match n {
+                0 => write!(output, "{}", entry).unwrap(),
+                _ => write!(output, ", {}", entry).unwrap(),
+            };
+        }
+
+        // SAFETY: the internal string is validated to be ASCII.
+        unsafe { HeaderValue::from_bytes_unchecked(output.into()) }
+    }
+}
+
+/// A single intermediary in a `Via` header.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct ViaEntry {
+    protocol: Option<String>,
+    version: String,
+    received_by: String,
+    comment: Option<String>,
+}
+
+impl ViaEntry {
+    /// Create a new instance of `ViaEntry`.
+    ///
+    /// `version` is the version of the protocol the message was received
+    /// with, and `received_by` the host or pseudonym of the intermediary.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `version` is not a token, or `received_by` is not a
+    /// valid host or pseudonym.
+    pub fn new(version: impl Into<String>, received_by: impl Into<String>) -> crate::Result<Self> {
+        let version = version.into();
+        let received_by = received_by.into();
+        ensure!(is_token(&version), 400, "Invalid Via protocol version");
+        ensure!(
+            is_received_by(&received_by),
+            400,
+            "Invalid Via received-by `{}`",
+            received_by
+        );
+        Ok(Self {
+            protocol: None,
+            version,
+            received_by,
+            comment: None,
+        })
+    }
+
+    /// Get the name of the protocol, if any.
+    ///
+    /// The name is usually omitted for HTTP.
+    pub fn protocol(&self) -> Option<&str> {
+        self.protocol.as_deref()
+    }
+
+    /// Set the name of the protocol.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the name is not a token.
+    pub fn set_protocol(&mut self, protocol: Option<String>) -> crate::Result<()> {
+        if let Some(protocol) = &protocol {
+            ensure!(is_token(protocol), 400, "Invalid Via protocol name");
+        }
+        self.protocol = protocol;
+        Ok(())
+    }
+
+    /// Get the version of the protocol.
+    pub fn version(&self) -> &str {
+        &self.version
+    }
+
+    /// Get the host or pseudonym of the intermediary, including the port if
+    /// one was given.
+    pub fn received_by(&self) -> &str {
+        &self.received_by
+    }
+
+    /// Get the comment, usually identifying the proxy software.
+    pub fn comment(&self) -> Option<&str> {
+        self.comment.as_deref()
+    }
+
+    /// Set the comment.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the comment contains control or non-ASCII
+    /// characters.
+    pub fn set_comment(&mut self, comment: Option<String>) -> crate::Result<()> {
+        if let Some(comment) = &comment {
+            ensure!(is_quotable(comment), 400, "Invalid Via comment");
+        }
+        self.comment = comment;
+        Ok(())
+    }
+
+    /// Parse a single entry, returning the remaining input.
+    fn parse(input: &str) -> crate::Result<(Self, &str)> {
+        let end = input.find([' ', '\t']).unwrap_or(input.len());
+        let (received_protocol, rest) = input.split_at(end);
+        let (protocol, version) = match received_protocol.split_once('/') {
+            Some((protocol, version)) => (Some(protocol), version),
+            None => (None, received_protocol),
+        };
+
+        let rest = rest.trim_start_matches([' ', '\t']);
+        let end = rest.find([' ', '\t', ',', '(']).unwrap_or(rest.len());
+        let (received_by, rest) = rest.split_at(end);
+
+        let mut entry = Self::new(version, received_by)?;
+        entry.set_protocol(protocol.map(String::from))?;
+
+        let mut rest = rest.trim_start_matches([' ', '\t']);
+        if rest.starts_with('(') {
+            let (comment, tail) = parse_comment(rest)?;
+            entry.comment = Some(comment);
+            rest = tail.trim_start_matches([' ', '\t']);
+        }
+        ensure!(
+            rest.is_empty() || rest.starts_with(','),
+            400,
+            "Unexpected characters after Via entry"
+        );
+        Ok((entry, rest))
+    }
+}
+
+impl Display for ViaEntry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(protocol) = &self.protocol {
+            write!(f, "{}/", protocol)?;
+        }
+        write!(f, "{} {}", self.version, self.received_by)?;
+        if let Some(comment) = &self.comment {
+            f.write_str(" (")?;
+            for ch in comment.chars() {
+                if let '(' | ')' | '\\' = ch {
+                    f.write_char('\\')?;
+                }
+                f.write_char(ch)?;
+            }
+            f.write_char(')')?;
+        }
+        Ok(())
+    }
+}
+
+/// Parse a comment, which may contain nested comments and quoted pairs.
+///
+/// Nested parentheses are kept as-is, quoted pairs are unescaped.
+fn parse_comment(input: &str) -> crate::Result<(String, &str)> {
+    let mut comment = String::new();
+    let mut depth = 0;
+    let mut chars = input.char_indices();
+    while let Some((i, ch)) = chars.next() {
+        match ch {
+            '(' => {
+                if depth > 0 {
+                    comment.push(ch);
+                }
+                depth += 1;
+            }
+            ')' => {
+                depth -= 1;
+                if depth == 0 {
+                    return Ok((comment, &input[i + 1..]));
+                }
+                comment.push(ch);
+            }
+            '\\' => match chars.next() {
+                Some((_, ch)) => comment.push(ch),
+                None => break,
+            },
+            _ => comment.push(ch),
+        }
+    }
+    bail!(400, "Unterminated comment in Via header")
+}
+
+/// received-by = ( uri-host [ ":" port ] ) / pseudonym
+fn is_received_by(s: &str) -> bool {
+    !s.is_empty()
+        && s.bytes()
+            .all(|b| b.is_ascii_alphanumeric() || b"!$&'*+-.:;=[]^_`|~".contains(&b) || b == b'%')
+}
+
+impl IntoIterator for Via {
+    type Item = ViaEntry;
+    type IntoIter = IntoIter;
+
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        IntoIter {
+            inner: self.entries.into_iter(),
+        }
+    }
+}
+
+impl<'a> IntoIterator for &'a Via {
+    type Item = &'a ViaEntry;
+    type IntoIter = Iter<'a>;
+
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<'a> IntoIterator for &'a mut Via {
+    type Item = &'a mut ViaEntry;
+    type IntoIter = IterMut<'a>;
+
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter_mut()
+    }
+}
+
+/// A borrowing iterator over entries in `Via`.
+#[derive(Debug)]
+pub struct IntoIter {
+    inner: std::vec::IntoIter<ViaEntry>,
+}
+
+impl Iterator for IntoIter {
+    type Item = ViaEntry;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next()
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+/// A lending iterator over entries in `Via`.
+#[derive(Debug)]
+pub struct Iter<'a> {
+    inner: slice::Iter<'a, ViaEntry>,
+}
+
+impl<'a> Iterator for Iter<'a> {
+    type Item = &'a ViaEntry;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next()
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+/// A mutable iterator over entries in `Via`.
+#[derive(Debug)]
+pub struct IterMut<'a> {
+    inner: slice::IterMut<'a, ViaEntry>,
+}
+
+impl<'a> Iterator for IterMut<'a> {
+    type Item = &'a mut ViaEntry;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next()
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl Debug for Via {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut list = f.debug_list();
+        for entry in &self.entries {
+            list.entry(entry);
+        }
+        list.finish()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::Response;
+
+    #[test]
+    fn smoke() -> crate::Result<()> {
+        let mut res = Response::new(200);
+        res.insert_header(
+            "Via",
+            "1.0 fred, HTTP/1.1 p.example.net:8080 (Apache/1.1 (Unix), \\(x\\)),2 _hidden",
+        )?;
+        res.append_header("Via", "HTTP/2.0 edge")?;
+
+        let via = Via::from_headers(&res)?.unwrap();
+        let entries: Vec<_> = via
+            .iter()
+            .map(|e| (e.protocol(), e.version(), e.received_by(), e.comment()))
+            .collect();
+        assert_eq!(
+            entries,
+            vec![
+                (None, "1.0", "fred", None),
+                (
+                    Some("HTTP"),
+                    "1.1",
+                    "p.example.net:8080",
+                    Some("Apache/1.1 (Unix), (x)")
+                ),
+                (None, "2", "_hidden", None),
+                (Some("HTTP"), "2.0", "edge", None),
+            ]
+        );
+
+        via.apply_header(&mut res);
+        assert_eq!(
+            res["Via"],
+            "1.0 fred, HTTP/1.1 p.example.net:8080 (Apache/1.1 \\(Unix\\), \\(x\\)), 2 _hidden, HTTP/2.0 edge"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn loop_detection() -> crate::Result<()> {
+        let mut res = Response::new(200);
+        Via::check_loop(&res, "p.example.net")?;
+
+        let mut entry = ViaEntry::new("1.1", "p.example.net:8080")?;
+        entry.set_comment(Some("gateway".into()))?;
+        Via::append_hop(&mut res, entry);
+        assert_eq!(res["Via"], "1.1 p.example.net:8080 (gateway)");
+
+        Via::check_loop(&res, "example.net")?;
+        Via::check_loop(&res, "p.example.net:80")?;
+        let err = Via::check_loop(&res, "P.example.net").unwrap_err();
+        assert_eq!(err.status(), 508);
+        let err = Via::check_loop(&res, "p.example.net:8080").unwrap_err();
+        assert_eq!(err.status(), 508);
+        Ok(())
+    }
+
+    #[test]
+    fn bad_request_on_parse_error() {
+        let inputs = [
+            "1.1",
+            "1.1 fred (unterminated",
+            "1.1 fred junk",
+            "/1.1 fred",
+        ];
+        for input in inputs {
+            let mut res = Response::new(200);
+            res.insert_header("Via", input).unwrap();
+            let err = Via::from_headers(res).unwrap_err();
+            assert_eq!(err.status(), 400, "{} should not parse", input);
+        }
+        assert!(ViaEntry::new("1.1", "bad host").is_err());
+    }
+}
@@ -14,6 +14,7 @@ use crate::headers::{
 };
 use crate::mime::Mime;
 use crate::trailers::{self, Trailers};
+use crate::upgrade;
 use crate::{Body, Extensions, Method, Url, Version};
 
 pin_project_lite::pin_project! {
@@ -41,6 +42,10 @@ pin_project_lite::pin_project! {
         trailers_sender: Option<async_channel::Sender<Trailers>>,
         trailers_receiver: Option<async_channel::Receiver<Trailers>>,
         has_trailers: bool,
+        upgrade_sender: Option<async_channel::Sender<upgrade::Connection>>,
+        upgrade_receiver: Option<async_channel::Receiver<upgrade::Connection>>,
+        has_upgrade: bool,
+        protocol: Option<upgrade::Protocol>,
     }
 }
 
@@ -53,6 +58,7 @@ impl Request {
     {
         let url = url.try_into().expect("Could not convert into a valid url");
         let (trailers_sender, trailers_receiver) = async_channel::bounded(1);
+        let (upgrade_sender, upgrade_receiver) = async_channel::bounded(1);
         Self {
             method,
             url,
@@ -65,6 +71,10 @@ impl Request {
             trailers_receiver: Some(trailers_receiver),
             trailers_sender: Some(trailers_sender),
             has_trailers: false,
+            upgrade_sender: Some(upgrade_sender),
+            upgrade_receiver: Some(upgrade_receiver),
+            has_upgrade: false,
+            protocol: None,
         }
     }
 
@@ -100,6 +110,10 @@ impl Request {
     /// 1. `Forwarded` header `for` key
     /// 2. The first `X-Forwarded-For` header
     /// 3. Peer address of the transport
+    ///
+    /// The forwarding headers are trusted unconditionally and can be set by
+    /// any client. Use [`TrustedProxies`](crate::proxies::TrustedProxies) to
+    /// only trust headers set by known proxies.
     pub fn remote(&self) -> Option<&str> {
         self.forwarded_for().or_else(|| self.peer_addr())
     }
@@ -572,6 +586,68 @@ impl Request {
         self.has_trailers
     }
 
+    /// Sends an upgrade connection to the a receiver.
+    #[cfg_attr(feature = "docs", doc(cfg(unstable)))]
+    pub fn send_upgrade(&mut self) -> upgrade::Sender {
+        self.has_upgrade = true;
+        let sender = self
+            .upgrade_sender
+            .take()
+            .expect("Upgrade sender can only be constructed once");
+        upgrade::Sender::new(sender)
+    }
+
+    /// Receive an upgraded connection from a sender.
+    #[cfg_attr(feature = "docs", doc(cfg(unstable)))]
+    pub async fn recv_upgrade(&mut self) -> upgrade::Receiver {
+        self.has_upgrade = true;
+        let receiver = self
+            .upgrade_receiver
+            .take()
+            .expect("Upgrade receiver can only be constructed once");
+        upgrade::Receiver::new(receiver)
+    }
+
+    /// Returns `true` if a protocol upgrade is in progress.
+    #[cfg_attr(feature = "docs", doc(cfg(unstable)))]
+    pub fn has_upgrade(&self) -> bool {
+        self.has_upgrade
+    }
+
+    /// Get the protocol of an extended `CONNECT` request, if one has been set.
+    ///
+    /// This is the value of the HTTP/2 `:protocol` pseudo-header, which
+    /// bootstraps protocols such as WebSockets over a single HTTP/2 stream.
+    ///
+    /// # Specifications
+    ///
+    /// - [RFC 8441, section 4: The Extended CONNECT Method](https://tools.ietf.org/html/rfc8441#section-4)
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # fn main() -> Result<(), http_types::Error> {
+    /// #
+    /// use http_types::{Method, Request, Version};
+    ///
+    /// let mut req = Request::new(Method::Connect, "https://example.com/chat");
+    /// req.set_version(Some(Version::Http2_0));
+    /// assert!(req.protocol().is_none());
+    ///
+    /// req.set_protocol(Some("websocket".parse()?));
+    /// assert!(req.protocol().unwrap().is("websocket"));
+    /// #
+    /// # Ok(()) }
+    /// ```
+    pub fn protocol(&self) -> Option<&upgrade::Protocol> {
+        self.protocol.as_ref()
+    }
+
+    /// Set the protocol of an extended `CONNECT` request.
+    pub fn set_protocol(&mut self, protocol: Option<upgrade::Protocol>) {
+        self.protocol = protocol;
+    }
+
     /// An iterator visiting all header pairs in arbitrary order.
     pub fn iter(&self) -> headers::Iter<'_> {
         self.headers.iter()
@@ -900,6 +976,10 @@ impl Clone for Request {
             peer_addr: self.peer_addr.clone(),
             local_addr: self.local_addr.clone(),
             has_trailers: false,
+            upgrade_sender: self.upgrade_sender.clone(),
+            upgrade_receiver: self.upgrade_receiver.clone(),
+            has_upgrade: false,
+            protocol: self.protocol.clone(),
         }
     }
 }
@@ -1188,6 +1268,58 @@ mod tests {
         }
     }
 
+    mod upgrades {
+        use super::*;
+        use futures_lite::io::Cursor;
+
+        #[async_std::test]
+        async fn send_and_recv_upgrade() -> crate::Result<()> {
+            let mut request = build_test_request();
+            assert!(!request.has_upgrade());
+
+            let sender = request.send_upgrade();
+            assert!(request.has_upgrade());
+            let receiver = request.recv_upgrade().await;
+
+            sender
+                .send(upgrade::Connection::new(Cursor::new(b"hello".to_vec())))
+                .await;
+            let mut conn = receiver.await.unwrap();
+            let mut buf = String::new();
+            conn.read_to_string(&mut buf).await?;
+            assert_eq!(buf, "hello");
+            Ok(())
+        }
+
+        #[async_std::test]
+        async fn clone_shares_upgrade_channel() -> crate::Result<()> {
+            let mut request = build_test_request();
+            let mut cloned = request.clone();
+
+            let sender = request.send_upgrade();
+            let receiver = cloned.recv_upgrade().await;
+            sender
+                .send(upgrade::Connection::new(Cursor::new(b"hello".to_vec())))
+                .await;
+            let mut conn = receiver.await.unwrap();
+            let mut buf = String::new();
+            conn.read_to_string(&mut buf).await?;
+            assert_eq!(buf, "hello");
+            Ok(())
+        }
+
+        #[test]
+        fn extended_connect_protocol() -> crate::Result<()> {
+            let mut request = Request::new(Method::Connect, "https://async.rs/chat");
+            request.set_protocol(Some("websocket".parse()?));
+
+            let cloned = request.clone();
+            assert_eq!(cloned.protocol().unwrap().name(), "websocket");
+            assert!(!cloned.has_upgrade());
+            Ok(())
+        }
+    }
+
     fn build_test_request() -> Request {
         let url = Url::parse("http://async.rs/").unwrap();
         Request::new(Method::Get, url)
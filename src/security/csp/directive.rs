@@ -0,0 +1,219 @@
+use crate::bail_status as bail;
+
+use std::fmt::{self, Display};
+use std::str::FromStr;
+
+/// A `Content-Security-Policy` directive.
+///
+/// # Specifications
+///
+/// - [Content Security Policy Level 3, section 6: Content Security Policy Directives](https://www.w3.org/TR/CSP3/#csp-directives)
+#[non_exhaustive]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum CspDirective {
+    /// `base-uri`
+    BaseUri,
+    /// `block-all-mixed-content`
+    BlockAllMixedContent,
+    /// `child-src`
+    ChildSrc,
+    /// `connect-src`
+    ConnectSrc,
+    /// `default-src`
+    DefaultSrc,
+    /// `font-src`
+    FontSrc,
+    /// `form-action`
+    FormAction,
+    /// `frame-ancestors`
+    FrameAncestors,
+    /// `frame-src`
+    FrameSrc,
+    /// `img-src`
+    ImgSrc,
+    /// `manifest-src`
+    ManifestSrc,
+    /// `media-src`
+    MediaSrc,
+    /// `navigate-to`
+    NavigateTo,
+    /// `object-src`
+    ObjectSrc,
+    /// `plugin-types`
+    PluginTypes,
+    /// `prefetch-src`
+    PrefetchSrc,
+    /// `report-to`
+    ReportTo,
+    /// `report-uri`
+    ReportUri,
+    /// `require-sri-for`
+    RequireSriFor,
+    /// `require-trusted-types-for`
+    RequireTrustedTypesFor,
+    /// `sandbox`
+    Sandbox,
+    /// `script-src`
+    ScriptSrc,
+    /// `script-src-attr`
+    ScriptSrcAttr,
+    /// `script-src-elem`
+    ScriptSrcElem,
+    /// `style-src`
+    StyleSrc,
+    /// `style-src-attr`
+    StyleSrcAttr,
+    /// `style-src-elem`
+    StyleSrcElem,
+    /// `trusted-types`
+    TrustedTypes,
+    /// `upgrade-insecure-requests`
+    UpgradeInsecureRequests,
+    /// `webrtc`
+    Webrtc,
+    /// `worker-src`
+    WorkerSrc,
+}
+
+impl CspDirective {
+    /// Get the formatted string.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::BaseUri => "base-uri",
+            Self::BlockAllMixedContent => "block-all-mixed-content",
+            Self::ChildSrc => "child-src",
+            Self::ConnectSrc => "connect-src",
+            Self::DefaultSrc => "default-src",
+            Self::FontSrc => "font-src",
+            Self::FormAction => "form-action",
+            Self::FrameAncestors => "frame-ancestors",
+            Self::FrameSrc => "frame-src",
+            Self::ImgSrc => "img-src",
+            Self::ManifestSrc => "manifest-src",
+            Self::MediaSrc => "media-src",
+            Self::NavigateTo => "navigate-to",
+            Self::ObjectSrc => "object-src",
+            Self::PluginTypes => "plugin-types",
+            Self::PrefetchSrc => "prefetch-src",
+            Self::ReportTo => "report-to",
+            Self::ReportUri => "report-uri",
+            Self::RequireSriFor => "require-sri-for",
+            Self::RequireTrustedTypesFor => "require-trusted-types-for",
+            Self::Sandbox => "sandbox",
+            Self::ScriptSrc => "script-src",
+            Self::ScriptSrcAttr => "script-src-attr",
+            Self::ScriptSrcElem => "script-src-elem",
+            Self::StyleSrc => "style-src",
+            Self::StyleSrcAttr => "style-src-attr",
+            Self::StyleSrcElem => "style-src-elem",
+            Self::TrustedTypes => "trusted-types",
+            Self::UpgradeInsecureRequests => "upgrade-insecure-requests",
+            Self::Webrtc => "webrtc",
+            Self::WorkerSrc => "worker-src",
+        }
+    }
+
+    /// Returns `true` if the directive's value is a source list.
+    pub fn is_source_list(&self) -> bool {
+        !matches!(
+            self,
+            Self::BlockAllMixedContent
+                | Self::PluginTypes
+                | Self::ReportTo
+                | Self::ReportUri
+                | Self::RequireSriFor
+                | Self::RequireTrustedTypesFor
+                | Self::Sandbox
+                | Self::TrustedTypes
+                | Self::UpgradeInsecureRequests
+                | Self::Webrtc
+        )
+    }
+
+    /// The directives whose source list applies to this one, most specific
+    /// first.
+    ///
+    /// Directives which don't fall back to `default-src` only list
+    /// themselves.
+    pub(crate) fn fallbacks(&self) -> &'static [CspDirective] {
+        use CspDirective::*;
+        match self {
+            ScriptSrc | ScriptSrcElem => &[ScriptSrcElem, ScriptSrc, DefaultSrc],
+            ScriptSrcAttr => &[ScriptSrcAttr, ScriptSrc, DefaultSrc],
+            StyleSrc | StyleSrcElem => &[StyleSrcElem, StyleSrc, DefaultSrc],
+            StyleSrcAttr => &[StyleSrcAttr, StyleSrc, DefaultSrc],
+            WorkerSrc => &[WorkerSrc, ChildSrc, ScriptSrc, DefaultSrc],
+            FrameSrc => &[FrameSrc, ChildSrc, DefaultSrc],
+            ChildSrc => &[ChildSrc, DefaultSrc],
+            ConnectSrc => &[ConnectSrc, DefaultSrc],
+            FontSrc => &[FontSrc, DefaultSrc],
+            ImgSrc => &[ImgSrc, DefaultSrc],
+            ManifestSrc => &[ManifestSrc, DefaultSrc],
+            MediaSrc => &[MediaSrc, DefaultSrc],
+            ObjectSrc => &[ObjectSrc, DefaultSrc],
+            PrefetchSrc => &[PrefetchSrc, DefaultSrc],
+            DefaultSrc => &[DefaultSrc],
+            BaseUri => &[BaseUri],
+            FormAction => &[FormAction],
+            FrameAncestors => &[FrameAncestors],
+            NavigateTo => &[NavigateTo],
+            _ => &[],
+        }
+    }
+
+    /// Returns `true` for directives which govern scripts.
+    pub(crate) fn is_script(&self) -> bool {
+        matches!(
+            self,
+            Self::ScriptSrc | Self::ScriptSrcAttr | Self::ScriptSrcElem | Self::WorkerSrc
+        )
+    }
+}
+
+impl Display for CspDirective {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for CspDirective {
+    type Err = crate::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let directive = match s.to_ascii_lowercase().as_str() {
+            "base-uri" => Self::BaseUri,
+            "block-all-mixed-content" => Self::BlockAllMixedContent,
+            "child-src" => Self::ChildSrc,
+            "connect-src" => Self::ConnectSrc,
+            "default-src" => Self::DefaultSrc,
+            "font-src" => Self::FontSrc,
+            "form-action" => Self::FormAction,
+            "frame-ancestors" => Self::FrameAncestors,
+            "frame-src" => Self::FrameSrc,
+            "img-src" => Self::ImgSrc,
+            "manifest-src" => Self::ManifestSrc,
+            "media-src" => Self::MediaSrc,
+            "navigate-to" => Self::NavigateTo,
+            "object-src" => Self::ObjectSrc,
+            "plugin-types" => Self::PluginTypes,
+            "prefetch-src" => Self::PrefetchSrc,
+            "report-to" => Self::ReportTo,
+            "report-uri" => Self::ReportUri,
+            "require-sri-for" => Self::RequireSriFor,
+            "require-trusted-types-for" => Self::RequireTrustedTypesFor,
+            "sandbox" => Self::Sandbox,
+            "script-src" => Self::ScriptSrc,
+            "script-src-attr" => Self::ScriptSrcAttr,
+            "script-src-elem" => Self::ScriptSrcElem,
+            "style-src" => Self::StyleSrc,
+            "style-src-attr" => Self::StyleSrcAttr,
+            "style-src-elem" => Self::StyleSrcElem,
+            "trusted-types" => Self::TrustedTypes,
+            "upgrade-insecure-requests" => Self::UpgradeInsecureRequests,
+            "webrtc" => Self::Webrtc,
+            "worker-src" => Self::WorkerSrc,
+            _ => bail!(400, "`{}` is not a known CSP directive", s),
+        };
+        Ok(directive)
+    }
+}
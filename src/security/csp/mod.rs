@@ -0,0 +1,870 @@
+use crate::headers::{
+    Header, HeaderName, HeaderValue, Headers, CONTENT_SECURITY_POLICY,
+    CONTENT_SECURITY_POLICY_REPORT_ONLY,
+};
+use crate::Url;
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::str::FromStr;
+
+#[cfg(feature = "serde")]
+use serde_crate::{Deserialize, Serialize};
+
+mod directive;
+mod source;
+
+pub use directive::CspDirective;
+pub use source::Source;
+
+use source::is_scheme;
+
+/// Define `report-to` directive value
+///
+/// [MDN | report-to](https://developer.mozilla.org/en-US/docs/Web/HTTP/Headers/Content-Security-Policy/report-to)
+#[cfg(feature = "serde")]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(crate = "serde_crate")]
+pub struct ReportTo {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    group: Option<String>,
+    max_age: i32,
+    endpoints: Vec<ReportToEndpoint>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    include_subdomains: Option<bool>,
+}
+
+/// Define `endpoints` for `report-to` directive value
+///
+/// [MDN | report-to](https://developer.mozilla.org/en-US/docs/Web/HTTP/Headers/Content-Security-Policy/report-to)
+#[cfg(feature = "serde")]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(crate = "serde_crate")]
+pub struct ReportToEndpoint {
+    url: String,
+}
+
+/// Build a `Content-Security-Policy` header.
+///
+/// `Content-Security-Policy` (CSP) HTTP headers are used to prevent cross-site
+/// injections. [Read more](https://helmetjs.github.io/docs/csp/)
+///
+/// Policies can also be parsed from headers, and checked against the URLs
+/// and inline content a page would load, which makes it possible to test
+/// them without a browser.
+///
+/// [Mozilla Developer Network](https://developer.mozilla.org/en-US/docs/Web/HTTP/Headers/Content-Security-Policy)
+///
+/// # Specifications
+///
+/// - [Content Security Policy Level 3](https://www.w3.org/TR/CSP3/)
+///
+/// # Examples
+///
+/// ```
+/// use http_types::{headers, security, Response, StatusCode};
+///
+/// let mut policy = security::ContentSecurityPolicy::new();
+/// policy
+///     .default_src(security::Source::SameOrigin)
+///     .default_src("areweasyncyet.rs")
+///     .script_src(security::Source::SameOrigin)
+///     .script_src(security::Source::UnsafeInline)
+///     .object_src(security::Source::None)
+///     .base_uri(security::Source::None)
+///     .upgrade_insecure_requests();
+///
+/// let mut res = Response::new(StatusCode::Ok);
+/// res.set_body("Hello, Chashu!");
+///
+/// security::default(&mut res);
+/// policy.apply(&mut res);
+///
+/// assert_eq!(res["content-security-policy"], "base-uri 'none'; default-src 'self' areweasyncyet.rs; object-src 'none'; script-src 'self' 'unsafe-inline'; upgrade-insecure-requests");
+/// ```
+///
+/// Checking what a policy allows:
+///
+/// ```
+/// # fn main() -> http_types::Result<()> {
+/// #
+/// use http_types::security::{ContentSecurityPolicy, CspDirective};
+/// use http_types::{Response, StatusCode, Url};
+///
+/// let mut res = Response::new(StatusCode::Ok);
+/// res.insert_header(
+///     "Content-Security-Policy",
+///     "default-src 'self'; img-src *; script-src 'nonce-r4nd0m' https://cdn.example.com/js/",
+/// )?;
+/// let policy = ContentSecurityPolicy::from_headers(&res)?.unwrap();
+///
+/// let origin = Url::parse("https://example.com/")?;
+/// let script = Url::parse("https://cdn.example.com/js/app.js")?;
+/// let font = Url::parse("https://fonts.example.net/font.woff2")?;
+/// assert!(policy.allows_url(CspDirective::ScriptSrcElem, &script, &origin));
+/// assert!(!policy.allows_url(CspDirective::FontSrc, &font, &origin));
+/// assert!(policy.allows_nonce(CspDirective::ScriptSrcElem, "r4nd0m"));
+/// assert!(!policy.allows_inline(CspDirective::ScriptSrcElem));
+/// #
+/// # Ok(()) }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ContentSecurityPolicy {
+    report_only: bool,
+    directives: BTreeMap<CspDirective, Value>,
+}
+
+/// The value of a directive.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Value {
+    Sources(Vec<Source>),
+    Tokens(Vec<String>),
+}
+
+impl Default for ContentSecurityPolicy {
+    /// Sets the Content-Security-Policy default to "object-src 'self'; script-src 'self'"
+    fn default() -> Self {
+        let mut policy = Self::new();
+        policy
+            .script_src(Source::SameOrigin)
+            .object_src(Source::SameOrigin);
+        policy
+    }
+}
+
+impl ContentSecurityPolicy {
+    /// Create a new instance.
+    pub fn new() -> Self {
+        Self {
+            report_only: false,
+            directives: BTreeMap::new(),
+        }
+    }
+
+    /// Create a new instance from a `Content-Security-Policy` header.
+    ///
+    /// A header can carry several policies which all have to be satisfied.
+    /// Only the first one is returned.
+    pub fn from_headers(headers: impl AsRef<Headers>) -> crate::Result<Option<Self>> {
+        Ok(Self::parse_header(
+            headers.as_ref(),
+            CONTENT_SECURITY_POLICY,
+        ))
+    }
+
+    /// Create a new instance from a `Content-Security-Policy-Report-Only`
+    /// header.
+    ///
+    /// A header can carry several policies which all have to be satisfied.
+    /// Only the first one is returned.
+    pub fn from_report_only_headers(headers: impl AsRef<Headers>) -> crate::Result<Option<Self>> {
+        let policy = Self::parse_header(headers.as_ref(), CONTENT_SECURITY_POLICY_REPORT_ONLY);
+        Ok(policy.map(|mut policy| {
+            policy.report_only = true;
+            policy
+        }))
+    }
+
+    fn parse_header(headers: &Headers, name: HeaderName) -> Option<Self> {
+        let value = headers.get(name)?.iter().next()?;
+        let policy = value.as_str().split(',').next().unwrap_or_default();
+        Some(Self::parse(policy))
+    }
+
+    /// Parse a serialized policy.
+    ///
+    /// Unknown and repeated directives are ignored, like browsers do.
+    fn parse(input: &str) -> Self {
+        let mut policy = Self::new();
+        for directive in input.split(';') {
+            let mut tokens = directive.split_ascii_whitespace();
+            let directive = match tokens.next().map(CspDirective::from_str) {
+                Some(Ok(directive)) => directive,
+                _ => continue,
+            };
+            if policy.directives.contains_key(&directive) {
+                continue;
+            }
+            let value = if directive.is_source_list() {
+                Value::Sources(tokens.map(Source::from).collect())
+            } else {
+                Value::Tokens(tokens.map(String::from).collect())
+            };
+            policy.directives.insert(directive, value);
+        }
+        policy
+    }
+
+    fn insert_source<T: Into<Source>>(&mut self, directive: CspDirective, source: T) {
+        let value = self
+            .directives
+            .entry(directive)
+            .or_insert_with(|| Value::Sources(Vec::new()));
+        if let Value::Sources(sources) = value {
+            sources.push(source.into());
+        }
+    }
+
+    fn insert_token<T: AsRef<str>>(&mut self, directive: CspDirective, token: T) {
+        let value = self
+            .directives
+            .entry(directive)
+            .or_insert_with(|| Value::Tokens(Vec::new()));
+        if let Value::Tokens(tokens) = value {
+            tokens.push(token.as_ref().to_string());
+        }
+    }
+
+    fn insert_flag(&mut self, directive: CspDirective) {
+        self.directives
+            .entry(directive)
+            .or_insert_with(|| Value::Tokens(Vec::new()));
+    }
+
+    /// Defines the Content-Security-Policy `base-uri` directive
+    ///
+    /// [MDN | base-uri](https://developer.mozilla.org/en-US/docs/Web/HTTP/Headers/Content-Security-Policy/base-uri)
+    pub fn base_uri<T: Into<Source>>(&mut self, source: T) -> &mut Self {
+        self.insert_source(CspDirective::BaseUri, source);
+        self
+    }
+
+    /// Defines the Content-Security-Policy `block-all-mixed-content` directive
+    ///
+    /// [MDN | block-all-mixed-content](https://developer.mozilla.org/en-US/docs/Web/HTTP/Headers/Content-Security-Policy/block-all-mixed-content)
+    pub fn block_all_mixed_content(&mut self) -> &mut Self {
+        self.insert_flag(CspDirective::BlockAllMixedContent);
+        self
+    }
+
+    /// Defines the Content-Security-Policy `child-src` directive
+    ///
+    /// [MDN | child-src](https://developer.mozilla.org/en-US/docs/Web/HTTP/Headers/Content-Security-Policy/child-src)
+    pub fn child_src<T: Into<Source>>(&mut self, source: T) -> &mut Self {
+        self.insert_source(CspDirective::ChildSrc, source);
+        self
+    }
+
+    /// Defines the Content-Security-Policy `connect-src` directive
+    ///
+    /// [MDN | connect-src](https://developer.mozilla.org/en-US/docs/Web/HTTP/Headers/Content-Security-Policy/connect-src)
+    pub fn connect_src<T: Into<Source>>(&mut self, source: T) -> &mut Self {
+        self.insert_source(CspDirective::ConnectSrc, source);
+        self
+    }
+
+    /// Defines the Content-Security-Policy `default-src` directive
+    ///
+    /// [MDN | default-src](https://developer.mozilla.org/en-US/docs/Web/HTTP/Headers/Content-Security-Policy/default-src)
+    pub fn default_src<T: Into<Source>>(&mut self, source: T) -> &mut Self {
+        self.insert_source(CspDirective::DefaultSrc, source);
+        self
+    }
+
+    /// Defines the Content-Security-Policy `font-src` directive
+    ///
+    /// [MDN | font-src](https://developer.mozilla.org/en-US/docs/Web/HTTP/Headers/Content-Security-Policy/font-src)
+    pub fn font_src<T: Into<Source>>(&mut self, source: T) -> &mut Self {
+        self.insert_source(CspDirective::FontSrc, source);
+        self
+    }
+
+    /// Defines the Content-Security-Policy `form-action` directive
+    ///
+    /// [MDN | form-action](https://developer.mozilla.org/en-US/docs/Web/HTTP/Headers/Content-Security-Policy/form-action)
+    pub fn form_action<T: Into<Source>>(&mut self, source: T) -> &mut Self {
+        self.insert_source(CspDirective::FormAction, source);
+        self
+    }
+
+    /// Defines the Content-Security-Policy `frame-ancestors` directive
+    ///
+    /// [MDN | frame-ancestors](https://developer.mozilla.org/en-US/docs/Web/HTTP/Headers/Content-Security-Policy/frame-ancestors)
+    pub fn frame_ancestors<T: Into<Source>>(&mut self, source: T) -> &mut Self {
+        self.insert_source(CspDirective::FrameAncestors, source);
+        self
+    }
+
+    /// Defines the Content-Security-Policy `frame-src` directive
+    ///
+    /// [MDN | frame-src](https://developer.mozilla.org/en-US/docs/Web/HTTP/Headers/Content-Security-Policy/frame-src)
+    pub fn frame_src<T: Into<Source>>(&mut self, source: T) -> &mut Self {
+        self.insert_source(CspDirective::FrameSrc, source);
+        self
+    }
+
+    /// Defines the Content-Security-Policy `img-src` directive
+    ///
+    /// [MDN | img-src](https://developer.mozilla.org/en-US/docs/Web/HTTP/Headers/Content-Security-Policy/img-src)
+    pub fn img_src<T: Into<Source>>(&mut self, source: T) -> &mut Self {
+        self.insert_source(CspDirective::ImgSrc, source);
+        self
+    }
+
+    /// Defines the Content-Security-Policy `manifest-src` directive
+    ///
+    /// [MDN | manifest-src](https://developer.mozilla.org/en-US/docs/Web/HTTP/Headers/Content-Security-Policy/manifest-src)
+    pub fn manifest_src<T: Into<Source>>(&mut self, source: T) -> &mut Self {
+        self.insert_source(CspDirective::ManifestSrc, source);
+        self
+    }
+
+    /// Defines the Content-Security-Policy `media-src` directive
+    ///
+    /// [MDN | media-src](https://developer.mozilla.org/en-US/docs/Web/HTTP/Headers/Content-Security-Policy/media-src)
+    pub fn media_src<T: Into<Source>>(&mut self, source: T) -> &mut Self {
+        self.insert_source(CspDirective::MediaSrc, source);
+        self
+    }
+
+    /// Defines the Content-Security-Policy `navigate-to` directive
+    ///
+    /// [MDN | navigate-to](https://developer.mozilla.org/en-US/docs/Web/HTTP/Headers/Content-Security-Policy/navigate-to)
+    pub fn navigate_to<T: Into<Source>>(&mut self, source: T) -> &mut Self {
+        self.insert_source(CspDirective::NavigateTo, source);
+        self
+    }
+
+    /// Defines the Content-Security-Policy `object-src` directive
+    ///
+    /// [MDN | object-src](https://developer.mozilla.org/en-US/docs/Web/HTTP/Headers/Content-Security-Policy/object-src)
+    pub fn object_src<T: Into<Source>>(&mut self, source: T) -> &mut Self {
+        self.insert_source(CspDirective::ObjectSrc, source);
+        self
+    }
+
+    /// Defines the Content-Security-Policy `plugin-types` directive
+    ///
+    /// [MDN | plugin-types](https://developer.mozilla.org/en-US/docs/Web/HTTP/Headers/Content-Security-Policy/plugin-types)
+    pub fn plugin_types<T: AsRef<str>>(&mut self, source: T) -> &mut Self {
+        self.insert_token(CspDirective::PluginTypes, source);
+        self
+    }
+
+    /// Defines the Content-Security-Policy `prefetch-src` directive
+    ///
+    /// [MDN | prefetch-src](https://developer.mozilla.org/en-US/docs/Web/HTTP/Headers/Content-Security-Policy/prefetch-src)
+    pub fn prefetch_src<T: Into<Source>>(&mut self, source: T) -> &mut Self {
+        self.insert_source(CspDirective::PrefetchSrc, source);
+        self
+    }
+
+    /// Defines the Content-Security-Policy `require-sri-for` directive
+    ///
+    /// [MDN | require-sri-for](https://developer.mozilla.org/en-US/docs/Web/HTTP/Headers/Content-Security-Policy/require-sri-for)
+    pub fn require_sri_for<T: AsRef<str>>(&mut self, source: T) -> &mut Self {
+        self.insert_token(CspDirective::RequireSriFor, source);
+        self
+    }
+
+    /// Defines the Content-Security-Policy `require-trusted-types-for` directive
+    ///
+    /// [MDN | require-trusted-types-for](https://developer.mozilla.org/en-US/docs/Web/HTTP/Headers/Content-Security-Policy/require-trusted-types-for)
+    pub fn require_trusted_types_for<T: AsRef<str>>(&mut self, sink_group: T) -> &mut Self {
+        self.insert_token(CspDirective::RequireTrustedTypesFor, sink_group);
+        self
+    }
+
+    /// Defines the Content-Security-Policy `report-uri` directive
+    ///
+    /// [MDN | report-uri](https://developer.mozilla.org/en-US/docs/Web/HTTP/Headers/Content-Security-Policy/report-uri)
+    pub fn report_uri<T: AsRef<str>>(&mut self, uri: T) -> &mut Self {
+        self.insert_token(CspDirective::ReportUri, uri);
+        self
+    }
+
+    /// Defines the Content-Security-Policy `report-to` directive
+    ///
+    /// Endpoints which fail to serialize are skipped.
+    ///
+    /// [MDN | report-to](https://developer.mozilla.org/en-US/docs/Web/HTTP/Headers/Content-Security-Policy/report-to)
+    #[cfg(feature = "serde")]
+    pub fn report_to(&mut self, endpoints: Vec<ReportTo>) -> &mut Self {
+        for endpoint in endpoints.iter() {
+            if let Ok(json) = serde_json::to_string(&endpoint) {
+                self.insert_token(CspDirective::ReportTo, json);
+            }
+        }
+        self
+    }
+
+    /// Defines the Content-Security-Policy `sandbox` directive
+    ///
+    /// [MDN | sandbox](https://developer.mozilla.org/en-US/docs/Web/HTTP/Headers/Content-Security-Policy/sandbox)
+    pub fn sandbox<T: AsRef<str>>(&mut self, source: T) -> &mut Self {
+        self.insert_token(CspDirective::Sandbox, source);
+        self
+    }
+
+    /// Defines the Content-Security-Policy `script-src` directive
+    ///
+    /// [MDN | script-src](https://developer.mozilla.org/en-US/docs/Web/HTTP/Headers/Content-Security-Policy/script-src)
+    pub fn script_src<T: Into<Source>>(&mut self, source: T) -> &mut Self {
+        self.insert_source(CspDirective::ScriptSrc, source);
+        self
+    }
+
+    /// Defines the Content-Security-Policy `script-src-attr` directive
+    ///
+    /// [MDN | script-src-attr](https://developer.mozilla.org/en-US/docs/Web/HTTP/Headers/Content-Security-Policy/script-src-attr)
+    pub fn script_src_attr<T: Into<Source>>(&mut self, source: T) -> &mut Self {
+        self.insert_source(CspDirective::ScriptSrcAttr, source);
+        self
+    }
+
+    /// Defines the Content-Security-Policy `script-src-elem` directive
+    ///
+    /// [MDN | script-src-elem](https://developer.mozilla.org/en-US/docs/Web/HTTP/Headers/Content-Security-Policy/script-src-elem)
+    pub fn script_src_elem<T: Into<Source>>(&mut self, source: T) -> &mut Self {
+        self.insert_source(CspDirective::ScriptSrcElem, source);
+        self
+    }
+
+    /// Defines the Content-Security-Policy `style-src` directive
+    ///
+    /// [MDN | style-src](https://developer.mozilla.org/en-US/docs/Web/HTTP/Headers/Content-Security-Policy/style-src)
+    pub fn style_src<T: Into<Source>>(&mut self, source: T) -> &mut Self {
+        self.insert_source(CspDirective::StyleSrc, source);
+        self
+    }
+
+    /// Defines the Content-Security-Policy `style-src-attr` directive
+    ///
+    /// [MDN | style-src-attr](https://developer.mozilla.org/en-US/docs/Web/HTTP/Headers/Content-Security-Policy/style-src-attr)
+    pub fn style_src_attr<T: Into<Source>>(&mut self, source: T) -> &mut Self {
+        self.insert_source(CspDirective::StyleSrcAttr, source);
+        self
+    }
+
+    /// Defines the Content-Security-Policy `style-src-elem` directive
+    ///
+    /// [MDN | style-src-elem](https://developer.mozilla.org/en-US/docs/Web/HTTP/Headers/Content-Security-Policy/style-src-elem)
+    pub fn style_src_elem<T: Into<Source>>(&mut self, source: T) -> &mut Self {
+        self.insert_source(CspDirective::StyleSrcElem, source);
+        self
+    }
+
+    /// Defines the Content-Security-Policy `trusted-types` directive
+    ///
+    /// [MDN | trusted-types](https://developer.mozilla.org/en-US/docs/Web/HTTP/Headers/Content-Security-Policy/trusted-types)
+    pub fn trusted_types<T: AsRef<str>>(&mut self, policy_name: T) -> &mut Self {
+        self.insert_token(CspDirective::TrustedTypes, policy_name);
+        self
+    }
+
+    /// Defines the Content-Security-Policy `upgrade-insecure-requests` directive
+    ///
+    /// [MDN | upgrade-insecure-requests](https://developer.mozilla.org/en-US/docs/Web/HTTP/Headers/Content-Security-Policy/upgrade-insecure-requests)
+    pub fn upgrade_insecure_requests(&mut self) -> &mut Self {
+        self.insert_flag(CspDirective::UpgradeInsecureRequests);
+        self
+    }
+
+    /// Defines the Content-Security-Policy `worker-src` directive
+    ///
+    /// [MDN | worker-src](https://developer.mozilla.org/en-US/docs/Web/HTTP/Headers/Content-Security-Policy/worker-src)
+    pub fn worker_src<T: Into<Source>>(&mut self, source: T) -> &mut Self {
+        self.insert_source(CspDirective::WorkerSrc, source);
+        self
+    }
+
+    /// Change the header to `Content-Security-Policy-Report-Only`
+    pub fn report_only(&mut self) -> &mut Self {
+        self.report_only = true;
+        self
+    }
+
+    /// Returns `true` if the policy is sent as
+    /// `Content-Security-Policy-Report-Only`.
+    pub fn is_report_only(&self) -> bool {
+        self.report_only
+    }
+
+    /// Returns `true` if the policy contains `directive`.
+    pub fn contains(&self, directive: CspDirective) -> bool {
+        self.directives.contains_key(&directive)
+    }
+
+    /// Iterate over the directives in the policy.
+    pub fn directives(&self) -> impl Iterator<Item = CspDirective> + '_ {
+        self.directives.keys().copied()
+    }
+
+    /// Get the source list of a directive.
+    ///
+    /// Returns `None` if the directive isn't set, or doesn't take a source
+    /// list.
+    pub fn sources(&self, directive: CspDirective) -> Option<&[Source]> {
+        match self.directives.get(&directive) {
+            Some(Value::Sources(sources)) => Some(sources),
+            _ => None,
+        }
+    }
+
+    /// Get the values of a directive which doesn't take a source list, such
+    /// as `sandbox` or `report-uri`.
+    ///
+    /// Returns `None` if the directive isn't set, or takes a source list.
+    pub fn values(&self, directive: CspDirective) -> Option<&[String]> {
+        match self.directives.get(&directive) {
+            Some(Value::Tokens(tokens)) => Some(tokens),
+            _ => None,
+        }
+    }
+
+    /// Remove a directive from the policy, returning `true` if it was set.
+    pub fn remove(&mut self, directive: CspDirective) -> bool {
+        self.directives.remove(&directive).is_some()
+    }
+
+    /// Returns `true` if the policy allows a document at `origin` to load
+    /// `url` for `directive`.
+    ///
+    /// The source list is looked up the way browsers do: `script-src-elem`
+    /// falls back to `script-src`, which falls back to `default-src`, and so
+    /// on. Requests governed by a directive without any applicable source
+    /// list are allowed. `script-src` and `style-src` are treated as loading
+    /// an element.
+    ///
+    /// When `'strict-dynamic'` applies to a script no URL is allowed on its
+    /// own, and [`allows_nonce`](Self::allows_nonce) should be used instead.
+    pub fn allows_url(&self, directive: CspDirective, url: &Url, origin: &Url) -> bool {
+        let sources = match self.effective_sources(directive) {
+            Some(sources) => sources,
+            None => return true,
+        };
+        if directive.is_script() && sources.contains(&Source::StrictDynamic) {
+            return false;
+        }
+        sources
+            .iter()
+            .any(|source| matches_url(source, url, origin))
+    }
+
+    /// Returns `true` if the policy allows an element with a `nonce`
+    /// attribute of `nonce` for `directive`.
+    pub fn allows_nonce(&self, directive: CspDirective, nonce: &str) -> bool {
+        match self.effective_sources(directive) {
+            Some(sources) => sources
+                .iter()
+                .any(|source| matches!(source, Source::Nonce(value) if value == nonce)),
+            None => true,
+        }
+    }
+
+    /// Returns `true` if the policy allows inline content without a nonce
+    /// for `directive`.
+    ///
+    /// `'unsafe-inline'` has no effect once the source list contains a
+    /// nonce or a hash, or `'strict-dynamic'` for scripts. Hashes of inline
+    /// content are not checked.
+    pub fn allows_inline(&self, directive: CspDirective) -> bool {
+        match self.effective_sources(directive) {
+            Some(sources) => {
+                sources.contains(&Source::UnsafeInline)
+                    && !sources.iter().any(Source::is_nonce_or_hash)
+                    && !(directive.is_script() && sources.contains(&Source::StrictDynamic))
+            }
+            None => true,
+        }
+    }
+
+    /// The source list which applies to `directive`, if any.
+    fn effective_sources(&self, directive: CspDirective) -> Option<&[Source]> {
+        directive
+            .fallbacks()
+            .iter()
+            .find_map(|directive| self.sources(*directive))
+    }
+
+    /// Sets the `Content-Security-Policy` (CSP) HTTP header to prevent cross-site injections
+    pub fn apply(&self, headers: impl AsMut<Headers>) {
+        self.apply_header(headers);
+    }
+}
+
+impl fmt::Display for ContentSecurityPolicy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (n, (directive, value)) in self.directives.iter().enumerate() {
+            if n > 0 {
+                write!(f, "; ")?;
+            }
+            write!(f, "{}", directive)?;
+            match value {
+                Value::Sources(sources) => {
+                    for source in sources {
+                        write!(f, " {}", source)?;
+                    }
+                }
+                Value::Tokens(tokens) => {
+                    for token in tokens {
+                        write!(f, " {}", token)?;
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for ContentSecurityPolicy {
+    type Err = crate::Error;
+
+    /// Parse a single serialized policy, such as
+    /// `default-src 'self'; img-src *`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(Self::parse(s))
+    }
+}
+
+impl Header for ContentSecurityPolicy {
+    fn header_name(&self) -> HeaderName {
+        if self.report_only {
+            CONTENT_SECURITY_POLICY_REPORT_ONLY
+        } else {
+            CONTENT_SECURITY_POLICY
+        }
+    }
+
+    fn header_value(&self) -> HeaderValue {
+        HeaderValue::from_bytes(self.to_string().into())
+            .expect("Content-Security-Policy sources must be ASCII")
+    }
+}
+
+/// Does `url` match a source expression?
+///
+/// https://www.w3.org/TR/CSP3/#match-url-to-source-expression
+fn matches_url(source: &Source, url: &Url, origin: &Url) -> bool {
+    match source {
+        Source::Wildcard => {
+            matches!(url.scheme(), "http" | "https" | "ws" | "wss")
+                || url.scheme() == origin.scheme()
+        }
+        Source::SameOrigin => {
+            url.host_str().is_some()
+                && url.host_str() == origin.host_str()
+                && url.port() == origin.port()
+                && (matches!(url.scheme(), "https" | "wss")
+                    || url.scheme() == origin.scheme()
+                    || (origin.scheme() == "http" && url.scheme() == "ws"))
+        }
+        Source::Host(expression) => matches_host(expression, url, origin),
+        source => match source.scheme() {
+            Some(scheme) => scheme_part_match(scheme, url.scheme()),
+            None => false,
+        },
+    }
+}
+
+/// https://www.w3.org/TR/CSP3/#match-schemes
+fn scheme_part_match(expression: &str, scheme: &str) -> bool {
+    let expression = expression.to_ascii_lowercase();
+    match expression.as_str() {
+        _ if expression == scheme => true,
+        "http" => scheme == "https",
+        "ws" => matches!(scheme, "wss" | "http" | "https"),
+        "wss" => scheme == "https",
+        _ => false,
+    }
+}
+
+/// host-source = [ scheme-part "://" ] host-part [ ":" port-part ] [ path-part ]
+fn matches_host(expression: &str, url: &Url, origin: &Url) -> bool {
+    let host = match url.host_str() {
+        Some(host) => host,
+        None => return false,
+    };
+
+    let (scheme, rest) = match expression.find("://") {
+        Some(i) => (&expression[..i], &expression[i + 3..]),
+        None => (origin.scheme(), expression),
+    };
+    if !is_scheme(scheme) || !scheme_part_match(scheme, url.scheme()) {
+        return false;
+    }
+
+    let (authority, path) = rest.split_at(rest.find('/').unwrap_or(rest.len()));
+    let (pattern, port) = match authority.rfind(':') {
+        Some(i) => (&authority[..i], Some(&authority[i + 1..])),
+        None => (authority, None),
+    };
+
+    let pattern = pattern.to_ascii_lowercase();
+    let host_matches = match pattern.strip_prefix('*') {
+        Some(suffix) => host.ends_with(suffix),
+        None => host == pattern,
+    };
+
+    let url_port = url.port_or_known_default();
+    let port_matches = match port {
+        Some("*") => true,
+        Some(port) => match port.parse::<u16>() {
+            Ok(80) => url_port == Some(80) || (url_port == Some(443) && url.port().is_none()),
+            Ok(port) => url_port == Some(port),
+            Err(_) => false,
+        },
+        None => url.port().is_none(),
+    };
+
+    let path_matches = match path {
+        "" | "/" => true,
+        path if path.ends_with('/') => url.path().starts_with(path),
+        path => url.path() == path,
+    };
+
+    host_matches && port_matches && path_matches
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::Response;
+
+    fn url(s: &str) -> Url {
+        Url::parse(s).unwrap()
+    }
+
+    #[test]
+    fn parse_round_trip() -> crate::Result<()> {
+        let input = "default-src 'self'; img-src * data:; script-src 'nonce-abc' 'strict-dynamic'; trusted-types default dompurify; upgrade-insecure-requests";
+        let mut res = Response::new(200);
+        res.insert_header(CONTENT_SECURITY_POLICY, input)?;
+
+        let policy = ContentSecurityPolicy::from_headers(&res)?.unwrap();
+        assert!(!policy.is_report_only());
+        assert_eq!(
+            policy.sources(CspDirective::ImgSrc),
+            Some(&[Source::Wildcard, Source::Data][..])
+        );
+        assert_eq!(
+            policy.values(CspDirective::TrustedTypes),
+            Some(&["default".to_string(), "dompurify".to_string()][..])
+        );
+        assert!(policy.contains(CspDirective::UpgradeInsecureRequests));
+        assert_eq!(policy.to_string(), input);
+        assert_eq!(ContentSecurityPolicy::from_report_only_headers(&res)?, None);
+        Ok(())
+    }
+
+    #[test]
+    fn parse_lenient() -> crate::Result<()> {
+        let mut res = Response::new(200);
+        res.insert_header(
+            CONTENT_SECURITY_POLICY_REPORT_ONLY,
+            "Script-Src a.com;  ; frobnicate x; script-src b.com, img-src 'none'",
+        )?;
+        let policy = ContentSecurityPolicy::from_report_only_headers(&res)?.unwrap();
+        assert!(policy.is_report_only());
+        assert_eq!(
+            policy.directives().collect::<Vec<_>>(),
+            [CspDirective::ScriptSrc]
+        );
+        assert_eq!(
+            policy.sources(CspDirective::ScriptSrc),
+            Some(&[Source::Host("a.com".into())][..])
+        );
+
+        let mut headers = Headers::new();
+        policy.apply_header(&mut headers);
+        assert_eq!(
+            headers[CONTENT_SECURITY_POLICY_REPORT_ONLY],
+            "script-src a.com"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn default_policy() {
+        let policy = ContentSecurityPolicy::default();
+        assert_eq!(policy.to_string(), "object-src 'self'; script-src 'self'");
+    }
+
+    #[test]
+    fn fallback() -> crate::Result<()> {
+        let policy: ContentSecurityPolicy =
+            "default-src 'none'; script-src a.com; worker-src b.com; child-src c.com".parse()?;
+        let origin = url("https://example.com/");
+        let allows = |directive, target| policy.allows_url(directive, &url(target), &origin);
+
+        assert!(allows(CspDirective::ScriptSrcElem, "https://a.com/app.js"));
+        assert!(allows(CspDirective::ScriptSrcAttr, "https://a.com/app.js"));
+        assert!(allows(CspDirective::WorkerSrc, "https://b.com/worker.js"));
+        assert!(!allows(CspDirective::WorkerSrc, "https://a.com/worker.js"));
+        assert!(allows(CspDirective::FrameSrc, "https://c.com/"));
+        assert!(!allows(CspDirective::ImgSrc, "https://a.com/cat.png"));
+        assert!(!allows(CspDirective::ImgSrc, "https://example.com/cat.png"));
+        assert!(allows(CspDirective::FormAction, "https://evil.com/"));
+        Ok(())
+    }
+
+    #[test]
+    fn url_matching() -> crate::Result<()> {
+        let policy: ContentSecurityPolicy =
+            "img-src 'self' *.example.net:* http://a.com/img/ b.com/logo.png blob:; connect-src *"
+                .parse()?;
+        let origin = url("https://example.com/");
+        let allows = |directive, target| policy.allows_url(directive, &url(target), &origin);
+
+        assert!(allows(CspDirective::ImgSrc, "https://example.com/x.png"));
+        assert!(allows(CspDirective::ImgSrc, "wss://example.com/"));
+        assert!(!allows(CspDirective::ImgSrc, "http://example.com/x.png"));
+        assert!(!allows(
+            CspDirective::ImgSrc,
+            "https://example.com:8443/x.png"
+        ));
+
+        assert!(allows(
+            CspDirective::ImgSrc,
+            "https://cdn.example.net:8443/x.png"
+        ));
+        assert!(!allows(CspDirective::ImgSrc, "https://example.net/x.png"));
+        assert!(!allows(
+            CspDirective::ImgSrc,
+            "http://cdn.example.net/x.png"
+        ));
+
+        assert!(allows(CspDirective::ImgSrc, "https://a.com/img/x.png"));
+        assert!(allows(CspDirective::ImgSrc, "http://a.com/img/x.png"));
+        assert!(!allows(CspDirective::ImgSrc, "https://a.com/x.png"));
+        assert!(allows(CspDirective::ImgSrc, "https://b.com/logo.png"));
+        assert!(!allows(CspDirective::ImgSrc, "https://b.com/logo.png.exe"));
+
+        assert!(allows(
+            CspDirective::ImgSrc,
+            "blob:https://example.com/uuid"
+        ));
+        assert!(!allows(CspDirective::ImgSrc, "data:image/png,"));
+
+        assert!(allows(CspDirective::ConnectSrc, "wss://anything.org/"));
+        assert!(!allows(CspDirective::ConnectSrc, "data:text/plain,"));
+        Ok(())
+    }
+
+    #[test]
+    fn nonces_and_inline() -> crate::Result<()> {
+        let nonce = Source::random_nonce();
+        let value = match &nonce {
+            Source::Nonce(value) => value.clone(),
+            _ => unreachable!(),
+        };
+        let mut policy = ContentSecurityPolicy::new();
+        policy
+            .script_src(nonce)
+            .script_src(Source::StrictDynamic)
+            .script_src(Source::UnsafeInline)
+            .style_src(Source::UnsafeInline)
+            .style_src_attr(Source::Sha256("abc=".into()));
+
+        let origin = url("https://example.com/");
+        assert!(policy.allows_nonce(CspDirective::ScriptSrcElem, &value));
+        assert!(!policy.allows_nonce(CspDirective::ScriptSrcElem, "guess"));
+        assert!(!policy.allows_inline(CspDirective::ScriptSrcElem));
+        assert!(!policy.allows_url(CspDirective::ScriptSrc, &origin, &origin));
+
+        assert!(policy.allows_inline(CspDirective::StyleSrcElem));
+        assert!(!policy.allows_inline(CspDirective::StyleSrcAttr));
+        assert!(policy.allows_inline(CspDirective::ImgSrc));
+        Ok(())
+    }
+}
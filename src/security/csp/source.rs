@@ -0,0 +1,260 @@
+use crate::utils::fill_random;
+
+use std::fmt;
+
+/// Define source value
+///
+/// [read more](https://content-security-policy.com)
+///
+/// # Specifications
+///
+/// - [Content Security Policy Level 3, section 2.3.1: Source Lists](https://www.w3.org/TR/CSP3/#framework-directive-source-list)
+#[non_exhaustive]
+#[derive(Debug, PartialEq, Eq, Clone, Hash)]
+pub enum Source {
+    /// Set source `'self'`
+    SameOrigin,
+    /// Set source `'src'`
+    Src,
+    /// Set source `'none'`
+    None,
+    /// Set source `'unsafe-inline'`
+    UnsafeInline,
+    /// Set source `data:`
+    Data,
+    /// Set source `mediastream:`
+    Mediastream,
+    /// Set source `https:`
+    Https,
+    /// Set source `blob:`
+    Blob,
+    /// Set source `filesystem:`
+    Filesystem,
+    /// Set source `'strict-dynamic'`
+    StrictDynamic,
+    /// Set source `'unsafe-eval'`
+    UnsafeEval,
+    /// Set source `*`
+    Wildcard,
+    /// Set source `'unsafe-hashes'`
+    UnsafeHashes,
+    /// Set source `'wasm-unsafe-eval'`
+    WasmUnsafeEval,
+    /// Set source `'report-sample'`
+    ReportSample,
+    /// Set source `'nonce-<value>'`, with the base64 nonce as the value.
+    Nonce(String),
+    /// Set source `'sha256-<value>'`, with the base64 digest as the value.
+    Sha256(String),
+    /// Set source `'sha384-<value>'`, with the base64 digest as the value.
+    Sha384(String),
+    /// Set source `'sha512-<value>'`, with the base64 digest as the value.
+    Sha512(String),
+    /// Set source `<scheme>:` for schemes without a variant of their own.
+    ///
+    /// The scheme is stored lowercased and without the trailing colon.
+    Scheme(String),
+    /// Set a host source such as `example.com`, `*.example.com:443` or
+    /// `https://example.com/path/`.
+    ///
+    /// Values which aren't recognized as any other source end up here too.
+    Host(String),
+}
+
+impl Source {
+    /// Create a `'nonce-...'` source with a fresh random value.
+    ///
+    /// The same value has to be set as the `nonce` attribute of the
+    /// `<script>` or `<style>` elements it allows, and a new one should be
+    /// generated for every response.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use http_types::security::Source;
+    ///
+    /// let nonce = Source::random_nonce();
+    /// assert!(nonce.to_string().starts_with("'nonce-"));
+    /// ```
+    pub fn random_nonce() -> Self {
+        let mut bytes = [0; 16];
+        fill_random(&mut bytes);
+        Source::Nonce(base64::encode(bytes))
+    }
+
+    /// Returns the scheme this source matches, if it's a scheme source.
+    pub(crate) fn scheme(&self) -> Option<&str> {
+        match self {
+            Source::Data => Some("data"),
+            Source::Mediastream => Some("mediastream"),
+            Source::Https => Some("https"),
+            Source::Blob => Some("blob"),
+            Source::Filesystem => Some("filesystem"),
+            Source::Scheme(scheme) => Some(scheme),
+            _ => None,
+        }
+    }
+
+    /// Returns `true` for nonce and hash sources.
+    pub(crate) fn is_nonce_or_hash(&self) -> bool {
+        matches!(
+            self,
+            Source::Nonce(_) | Source::Sha256(_) | Source::Sha384(_) | Source::Sha512(_)
+        )
+    }
+}
+
+impl fmt::Display for Source {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Source::SameOrigin => write!(f, "'self'"),
+            Source::Src => write!(f, "'src'"),
+            Source::None => write!(f, "'none'"),
+            Source::UnsafeInline => write!(f, "'unsafe-inline'"),
+            Source::Data => write!(f, "data:"),
+            Source::Mediastream => write!(f, "mediastream:"),
+            Source::Https => write!(f, "https:"),
+            Source::Blob => write!(f, "blob:"),
+            Source::Filesystem => write!(f, "filesystem:"),
+            Source::StrictDynamic => write!(f, "'strict-dynamic'"),
+            Source::UnsafeEval => write!(f, "'unsafe-eval'"),
+            Source::Wildcard => write!(f, "*"),
+            Source::UnsafeHashes => write!(f, "'unsafe-hashes'"),
+            Source::WasmUnsafeEval => write!(f, "'wasm-unsafe-eval'"),
+            Source::ReportSample => write!(f, "'report-sample'"),
+            Source::Nonce(value) => write!(f, "'nonce-{}'", value),
+            Source::Sha256(value) => write!(f, "'sha256-{}'", value),
+            Source::Sha384(value) => write!(f, "'sha384-{}'", value),
+            Source::Sha512(value) => write!(f, "'sha512-{}'", value),
+            Source::Scheme(scheme) => write!(f, "{}:", scheme),
+            Source::Host(host) => write!(f, "{}", host),
+        }
+    }
+}
+
+impl From<&str> for Source {
+    fn from(s: &str) -> Self {
+        if s == "*" {
+            return Source::Wildcard;
+        }
+
+        if let Some(keyword) = s.strip_prefix('\'').and_then(|s| s.strip_suffix('\'')) {
+            let lower = keyword.to_ascii_lowercase();
+            let prefixed = |prefix: &str| {
+                lower
+                    .starts_with(prefix)
+                    .then(|| keyword[prefix.len()..].to_string())
+            };
+            return match lower.as_str() {
+                "self" => Source::SameOrigin,
+                "src" => Source::Src,
+                "none" => Source::None,
+                "unsafe-inline" => Source::UnsafeInline,
+                "strict-dynamic" => Source::StrictDynamic,
+                "unsafe-eval" => Source::UnsafeEval,
+                "unsafe-hashes" => Source::UnsafeHashes,
+                "wasm-unsafe-eval" => Source::WasmUnsafeEval,
+                "report-sample" => Source::ReportSample,
+                _ => {
+                    if let Some(value) = prefixed("nonce-") {
+                        Source::Nonce(value)
+                    } else if let Some(value) = prefixed("sha256-") {
+                        Source::Sha256(value)
+                    } else if let Some(value) = prefixed("sha384-") {
+                        Source::Sha384(value)
+                    } else if let Some(value) = prefixed("sha512-") {
+                        Source::Sha512(value)
+                    } else {
+                        Source::Host(s.to_string())
+                    }
+                }
+            };
+        }
+
+        match s.strip_suffix(':') {
+            Some(scheme) if is_scheme(scheme) => match scheme.to_ascii_lowercase().as_str() {
+                "data" => Source::Data,
+                "mediastream" => Source::Mediastream,
+                "https" => Source::Https,
+                "blob" => Source::Blob,
+                "filesystem" => Source::Filesystem,
+                scheme => Source::Scheme(scheme.to_string()),
+            },
+            _ => Source::Host(s.to_string()),
+        }
+    }
+}
+
+impl From<String> for Source {
+    fn from(s: String) -> Self {
+        Source::from(s.as_str())
+    }
+}
+
+impl From<&String> for Source {
+    fn from(s: &String) -> Self {
+        Source::from(s.as_str())
+    }
+}
+
+/// scheme = ALPHA *( ALPHA / DIGIT / "+" / "-" / "." )
+pub(crate) fn is_scheme(s: &str) -> bool {
+    let mut chars = s.chars();
+    match chars.next() {
+        Some(c) if c.is_ascii_alphabetic() => {
+            chars.all(|c| c.is_ascii_alphanumeric() || "+-.".contains(c))
+        }
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn parse() {
+        let sources = [
+            ("'self'", Source::SameOrigin),
+            ("'SELF'", Source::SameOrigin),
+            ("*", Source::Wildcard),
+            ("data:", Source::Data),
+            ("wss:", Source::Scheme("wss".into())),
+            ("'nonce-AbC+/='", Source::Nonce("AbC+/=".into())),
+            ("'sha384-xyz'", Source::Sha384("xyz".into())),
+            ("'unsafe-hashes'", Source::UnsafeHashes),
+            (
+                "*.example.com:443",
+                Source::Host("*.example.com:443".into()),
+            ),
+            ("'unknown'", Source::Host("'unknown'".into())),
+        ];
+        for (input, source) in sources {
+            assert_eq!(Source::from(input), source);
+        }
+    }
+
+    #[test]
+    fn round_trip() {
+        for input in [
+            "'self'",
+            "'nonce-abc'",
+            "'sha512-abc'",
+            "ws:",
+            "https://a.b/c/",
+        ] {
+            assert_eq!(Source::from(input).to_string(), input);
+        }
+    }
+
+    #[test]
+    fn random_nonce() {
+        match (Source::random_nonce(), Source::random_nonce()) {
+            (Source::Nonce(a), Source::Nonce(b)) => {
+                assert_eq!(base64::decode(&a).unwrap().len(), 16);
+                assert_ne!(a, b);
+            }
+            _ => panic!("expected nonce sources"),
+        }
+    }
+}
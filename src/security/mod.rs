@@ -21,7 +21,7 @@ mod csp;
 mod strict_transport_security;
 mod timing_allow_origin;
 
-pub use csp::{ContentSecurityPolicy, Source};
+pub use csp::{ContentSecurityPolicy, CspDirective, Source};
 pub use strict_transport_security::StrictTransportSecurity;
 
 #[cfg(feature = "serde")]
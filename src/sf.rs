@@ -0,0 +1,259 @@
+//! Structured Field Values, as used by `Cache-Status` and message signatures.
+//!
+//! - [RFC 8941: Structured Field Values for HTTP](https://www.rfc-editor.org/rfc/rfc8941)
+
+use crate::{bail_status as bail, ensure_status as ensure};
+
+use std::fmt::{self, Display, Write};
+
+/// A bare item.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub(crate) enum Item {
+    Integer(i64),
+    Decimal(String),
+    String(String),
+    Token(String),
+    Bytes(Vec<u8>),
+    Boolean(bool),
+}
+
+impl Display for Item {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Item::Integer(n) => write!(f, "{}", n),
+            Item::Decimal(n) | Item::Token(n) => f.write_str(n),
+            Item::String(s) => write_string(f, s),
+            Item::Bytes(bytes) => write!(f, ":{}:", base64::encode(bytes)),
+            Item::Boolean(true) => f.write_str("?1"),
+            Item::Boolean(false) => f.write_str("?0"),
+        }
+    }
+}
+
+/// A list of parameters, in the order they were sent.
+pub(crate) type Params = Vec<(String, Item)>;
+
+/// The members of an inner list, each with its own parameters.
+pub(crate) type InnerList = Vec<(String, Params)>;
+
+/// Write parameters, omitting the value of `true` booleans.
+pub(crate) fn write_params(f: &mut fmt::Formatter<'_>, params: &[(String, Item)]) -> fmt::Result {
+    for (key, value) in params {
+        write!(f, ";{}", key)?;
+        if *value != Item::Boolean(true) {
+            write!(f, "={}", value)?;
+        }
+    }
+    Ok(())
+}
+
+/// Parse a dictionary, using `member` to parse the value of each entry.
+///
+/// Later entries with the same key overwrite earlier ones, but keep their
+/// position.
+pub(crate) fn parse_dictionary<T>(
+    mut input: &str,
+    member: impl Fn(&str) -> crate::Result<(T, &str)>,
+) -> crate::Result<Vec<(String, T)>> {
+    let mut entries: Vec<(String, T)> = vec![];
+    loop {
+        input = input.trim_start_matches([' ', '\t']);
+        if input.is_empty() {
+            return Ok(entries);
+        }
+        let (key, rest) = parse_key(input)?;
+        let rest = match rest.strip_prefix('=') {
+            Some(rest) => rest,
+            None => bail!(400, "Expected a value for dictionary key `{}`", key),
+        };
+        let (value, rest) = member(rest)?;
+        match entries.iter_mut().find(|(existing, _)| existing == key) {
+            Some((_, existing)) => *existing = value,
+            None => entries.push((key.to_string(), value)),
+        }
+
+        input = rest.trim_start_matches([' ', '\t']);
+        match input.strip_prefix(',') {
+            Some(rest) => {
+                ensure!(
+                    !rest.trim_start_matches([' ', '\t']).is_empty(),
+                    400,
+                    "Trailing comma in dictionary"
+                );
+                input = rest;
+            }
+            None if input.is_empty() => return Ok(entries),
+            None => bail!(400, "Expected a comma between dictionary members"),
+        }
+    }
+}
+
+/// Parse an inner list of strings, followed by its parameters.
+///
+/// Each string is returned together with its own parameters.
+pub(crate) fn parse_inner_list(input: &str) -> crate::Result<(InnerList, Params, &str)> {
+    let mut rest = match input.strip_prefix('(') {
+        Some(rest) => rest,
+        None => bail!(400, "Expected an inner list"),
+    };
+    let mut items = vec![];
+    loop {
+        rest = rest.trim_start_matches(' ');
+        if let Some(tail) = rest.strip_prefix(')') {
+            let (params, tail) = parse_params(tail)?;
+            return Ok((items, params, tail));
+        }
+        let (item, tail) = match parse_bare_item(rest)? {
+            (Item::String(item), tail) => (item, tail),
+            _ => bail!(400, "Expected a string in inner list"),
+        };
+        let (params, tail) = parse_params(tail)?;
+        items.push((item, params));
+        ensure!(
+            tail.starts_with([' ', ')']),
+            400,
+            "Expected a space between inner list members"
+        );
+        rest = tail;
+    }
+}
+
+/// Parse a list of parameters.
+pub(crate) fn parse_params(mut input: &str) -> crate::Result<(Params, &str)> {
+    let mut params: Params = vec![];
+    while let Some(rest) = input.strip_prefix(';') {
+        let (key, rest) = parse_key(rest.trim_start_matches(' '))?;
+        let (value, rest) = match rest.strip_prefix('=') {
+            Some(rest) => parse_bare_item(rest)?,
+            None => (Item::Boolean(true), rest),
+        };
+        match params.iter_mut().find(|(existing, _)| existing == key) {
+            Some((_, existing)) => *existing = value,
+            None => params.push((key.to_string(), value)),
+        }
+        input = rest;
+    }
+    Ok((params, input))
+}
+
+/// key = ( lcalpha / "*" ) *( lcalpha / DIGIT / "_" / "-" / "." / "*" )
+pub(crate) fn parse_key(input: &str) -> crate::Result<(&str, &str)> {
+    ensure!(
+        input.starts_with(|c: char| c.is_ascii_lowercase() || c == '*'),
+        400,
+        "Invalid structured field key"
+    );
+    let end = input.find(|c: char| !is_key_char(c)).unwrap_or(input.len());
+    Ok(input.split_at(end))
+}
+
+/// Returns `true` if `s` is a valid structured field key.
+pub(crate) fn is_key(s: &str) -> bool {
+    matches!(parse_key(s), Ok((_, "")))
+}
+
+pub(crate) fn parse_bare_item(input: &str) -> crate::Result<(Item, &str)> {
+    match input.chars().next() {
+        Some('"') => {
+            let mut output = String::new();
+            let mut chars = input.char_indices().skip(1);
+            while let Some((i, c)) = chars.next() {
+                match c {
+                    '"' => return Ok((Item::String(output), &input[i + 1..])),
+                    '\\' => match chars.next() {
+                        Some((_, c @ '"')) | Some((_, c @ '\\')) => output.push(c),
+                        _ => break,
+                    },
+                    ' '..='~' => output.push(c),
+                    _ => break,
+                }
+            }
+            bail!(400, "Invalid string in structured field")
+        }
+        Some('?') => match input.get(..2) {
+            Some("?0") => Ok((Item::Boolean(false), &input[2..])),
+            Some("?1") => Ok((Item::Boolean(true), &input[2..])),
+            _ => bail!(400, "Invalid boolean in structured field"),
+        },
+        Some(':') => {
+            let end = match input[1..].find(':') {
+                Some(end) => end + 1,
+                None => bail!(400, "Invalid byte sequence in structured field"),
+            };
+            match base64::decode(&input[1..end]) {
+                Ok(bytes) => Ok((Item::Bytes(bytes), &input[end + 1..])),
+                Err(_) => bail!(400, "Invalid byte sequence in structured field"),
+            }
+        }
+        Some(c) if c.is_ascii_alphabetic() || c == '*' => {
+            let end = input
+                .find(|c: char| !is_token_char(c))
+                .unwrap_or(input.len());
+            Ok((Item::Token(input[..end].to_string()), &input[end..]))
+        }
+        Some(c) if c.is_ascii_digit() || c == '-' => {
+            let end = input
+                .char_indices()
+                .skip(1)
+                .find(|(_, c)| !c.is_ascii_digit() && *c != '.')
+                .map(|(i, _)| i)
+                .unwrap_or(input.len());
+            let (number, rest) = input.split_at(end);
+            if number.contains('.') {
+                ensure!(
+                    number.parse::<f64>().is_ok(),
+                    400,
+                    "Invalid decimal in structured field"
+                );
+                return Ok((Item::Decimal(number.to_string()), rest));
+            }
+            ensure!(
+                number.trim_start_matches('-').len() <= 15,
+                400,
+                "Integer out of range in structured field"
+            );
+            match number.parse() {
+                Ok(number) => Ok((Item::Integer(number), rest)),
+                Err(_) => bail!(400, "Invalid integer in structured field"),
+            }
+        }
+        _ => bail!(400, "Invalid item in structured field"),
+    }
+}
+
+pub(crate) fn write_string(f: &mut impl Write, s: &str) -> fmt::Result {
+    f.write_char('"')?;
+    for c in s.chars() {
+        if let '"' | '\\' = c {
+            f.write_char('\\')?;
+        }
+        f.write_char(c)?;
+    }
+    f.write_char('"')
+}
+
+/// Write `s` as a token if it is one, and as a string otherwise.
+pub(crate) fn write_token_or_string(f: &mut impl Write, s: &str) -> fmt::Result {
+    let is_token = s.starts_with(|c: char| c.is_ascii_alphabetic() || c == '*')
+        && s.chars().all(is_token_char);
+    if is_token {
+        f.write_str(s)
+    } else {
+        write_string(f, s)
+    }
+}
+
+/// sf-token = ( ALPHA / "*" ) *( tchar / ":" / "/" )
+fn is_token_char(c: char) -> bool {
+    c.is_ascii_alphanumeric() || "!#$%&'*+-.^_`|~:/".contains(c)
+}
+
+/// key = ( lcalpha / "*" ) *( lcalpha / DIGIT / "_" / "-" / "." / "*" )
+fn is_key_char(c: char) -> bool {
+    c.is_ascii_lowercase() || c.is_ascii_digit() || "_-.*".contains(c)
+}
+
+/// Returns `true` if `s` only contains printable ASCII.
+pub(crate) fn is_printable(s: &str) -> bool {
+    s.bytes().all(|b| (b' '..=b'~').contains(&b))
+}
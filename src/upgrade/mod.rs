@@ -9,11 +9,26 @@
 //! after which it is turned into a stream of bytes. This module provides
 //! primitives for upgrading from HTTP request-response pairs to alternate
 //! protocols.
+//!
+//! Both [`Request`](crate::Request) and [`Response`](crate::Response) carry an
+//! upgrade channel, so an upgraded [`Connection`] can be handed to either side
+//! of an exchange. For HTTP/2 extended `CONNECT` requests the protocol being
+//! bootstrapped is available through
+//! [`Request::protocol`](crate::Request::protocol).
 
 mod connection;
+mod protocol;
 mod receiver;
 mod sender;
+#[allow(clippy::module_inception)]
+mod upgrade;
+
+#[cfg(feature = "websocket")]
+#[cfg_attr(feature = "docs", doc(cfg(feature = "websocket")))]
+pub mod websocket;
 
 pub use connection::Connection;
+pub use protocol::Protocol;
 pub use receiver::Receiver;
 pub use sender::Sender;
+pub use upgrade::Upgrade;
@@ -0,0 +1,94 @@
+use crate::parse_utils::is_token;
+use crate::{bail_status as bail, ensure_status as ensure};
+
+use std::fmt::{self, Display};
+use std::str::FromStr;
+
+/// A protocol a connection can be upgraded to, optionally with a version.
+///
+/// # Specifications
+///
+/// - [RFC 7230, section 6.7: Upgrade](https://tools.ietf.org/html/rfc7230#section-6.7)
+///
+/// # Examples
+///
+/// ```
+/// # fn main() -> http_types::Result<()> {
+/// #
+/// use http_types::upgrade::Protocol;
+///
+/// let protocol: Protocol = "HTTP/2.0".parse()?;
+/// assert_eq!(protocol.name(), "HTTP");
+/// assert_eq!(protocol.version(), Some("2.0"));
+/// assert_eq!(protocol.to_string(), "HTTP/2.0");
+/// #
+/// # Ok(()) }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Protocol {
+    name: String,
+    version: Option<String>,
+}
+
+impl Protocol {
+    /// Create a new instance of `Protocol`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the name or the version is not a valid token.
+    pub fn new(name: impl Into<String>, version: Option<String>) -> crate::Result<Self> {
+        let name = name.into();
+        ensure!(is_token(&name), 400, "Invalid protocol name `{}`", name);
+        if let Some(version) = &version {
+            ensure!(
+                is_token(version),
+                400,
+                "Invalid protocol version `{}`",
+                version
+            );
+        }
+        Ok(Self { name, version })
+    }
+
+    /// Get the protocol name.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Get the protocol version, if any.
+    pub fn version(&self) -> Option<&str> {
+        self.version.as_deref()
+    }
+
+    /// Returns `true` if the protocol has the given name.
+    ///
+    /// Protocol names are compared case-insensitively.
+    pub fn is(&self, name: &str) -> bool {
+        self.name.eq_ignore_ascii_case(name)
+    }
+}
+
+impl FromStr for Protocol {
+    type Err = crate::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        let (name, version) = match s.split_once('/') {
+            Some((name, version)) => (name, Some(version.to_owned())),
+            None => (s, None),
+        };
+        if name.is_empty() {
+            bail!(400, "Invalid protocol `{}`", s);
+        }
+        Self::new(name, version)
+    }
+}
+
+impl Display for Protocol {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.version {
+            Some(version) => write!(f, "{}/{}", self.name, version),
+            None => write!(f, "{}", self.name),
+        }
+    }
+}
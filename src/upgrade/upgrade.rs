@@ -0,0 +1,253 @@
+use crate::headers::{Header, HeaderName, HeaderValue, Headers, UPGRADE};
+use crate::upgrade::Protocol;
+
+use std::fmt::{self, Debug, Write};
+use std::iter::Iterator;
+use std::slice;
+
+/// The protocols a connection may be upgraded to.
+///
+/// Clients list the protocols they would like to switch to in order of
+/// preference. A server switching protocols sends back the protocol it
+/// selected along with a `101 Switching Protocols` response.
+///
+/// [MDN Documentation](https://developer.mozilla.org/en-US/docs/Web/HTTP/Headers/Upgrade)
+///
+/// # Specifications
+///
+/// - [RFC 7230, section 6.7: Upgrade](https://tools.ietf.org/html/rfc7230#section-6.7)
+///
+/// # Examples
+///
+/// ```
+/// # fn main() -> http_types::Result<()> {
+/// #
+/// use http_types::Request;
+/// use http_types::upgrade::{Protocol, Upgrade};
+///
+/// let mut upgrade = Upgrade::new();
+/// upgrade.push(Protocol::new("HTTP", Some("2.0".into()))?);
+/// upgrade.push("websocket".parse()?);
+///
+/// let mut req = Request::get("http://example.com");
+/// req.insert_header(&upgrade, &upgrade);
+///
+/// let upgrade = Upgrade::from_headers(req)?.unwrap();
+/// assert!(upgrade.contains("WebSocket"));
+/// let mut protocols = upgrade.iter();
+/// assert_eq!(protocols.next().unwrap().to_string(), "HTTP/2.0");
+/// assert_eq!(protocols.next().unwrap().to_string(), "websocket");
+/// #
+/// # Ok(()) }
+/// ```
+#[derive(Clone, Eq, PartialEq)]
+pub struct Upgrade {
+    entries: Vec<Protocol>,
+}
+
+impl Upgrade {
+    /// Create a new instance of `Upgrade`.
+    pub fn new() -> Self {
+        Self { entries: vec![] }
+    }
+
+    /// Create a new instance from headers.
+    pub fn from_headers(headers: impl AsRef<Headers>) -> crate::Result<Option<Self>> {
+        let headers = match headers.as_ref().get(UPGRADE) {
+            Some(headers) => headers,
+            None => return Ok(None),
+        };
+
+        let mut entries = vec![];
+        for value in headers {
+            for part in value.as_str().split(',') {
+                let part = part.trim();
+                if part.is_empty() {
+                    continue;
+                }
+                entries.push(part.parse()?);
+            }
+        }
+
+        Ok(Some(Self { entries }))
+    }
+
+    /// Push a protocol into the list of entries.
+    pub fn push(&mut self, protocol: Protocol) {
+        self.entries.push(protocol);
+    }
+
+    /// Returns `true` if a protocol with the given name was listed.
+    ///
+    /// Protocol names are compared case-insensitively.
+    pub fn contains(&self, name: &str) -> bool {
+        self.entries.iter().any(|p| p.is(name))
+    }
+
+    /// An iterator visiting all entries.
+    pub fn iter(&self) -> Iter<'_> {
+        Iter {
+            inner: self.entries.iter(),
+        }
+    }
+
+    /// An iterator visiting all entries.
+    pub fn iter_mut(&mut self) -> IterMut<'_> {
+        IterMut {
+            inner: self.entries.iter_mut(),
+        }
+    }
+}
+
+impl Header for Upgrade {
+    fn header_name(&self) -> HeaderName {
+        UPGRADE
+    }
+
+    fn header_value(&self) -> HeaderValue {
+        let mut output = String::new();
+        for (n, protocol) in self.entries.iter().enumerate() {
+            match n {
+                0 => write!(output, "{}", protocol).unwrap(),
+                _ => write!(output, ", {}", protocol).unwrap(),
+            };
+        }
+
+        // SAFETY: the internal string is validated to be ASCII.
+        unsafe { HeaderValue::from_bytes_unchecked(output.into()) }
+    }
+}
+
+impl IntoIterator for Upgrade {
+    type Item = Protocol;
+    type IntoIter = IntoIter;
+
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        IntoIter {
+            inner: self.entries.into_iter(),
+        }
+    }
+}
+
+impl<'a> IntoIterator for &'a Upgrade {
+    type Item = &'a Protocol;
+    type IntoIter = Iter<'a>;
+
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<'a> IntoIterator for &'a mut Upgrade {
+    type Item = &'a mut Protocol;
+    type IntoIter = IterMut<'a>;
+
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter_mut()
+    }
+}
+
+/// A borrowing iterator over entries in `Upgrade`.
+#[derive(Debug)]
+pub struct IntoIter {
+    inner: std::vec::IntoIter<Protocol>,
+}
+
+impl Iterator for IntoIter {
+    type Item = Protocol;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next()
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+/// A lending iterator over entries in `Upgrade`.
+#[derive(Debug)]
+pub struct Iter<'a> {
+    inner: slice::Iter<'a, Protocol>,
+}
+
+impl<'a> Iterator for Iter<'a> {
+    type Item = &'a Protocol;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next()
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+/// A mutable iterator over entries in `Upgrade`.
+#[derive(Debug)]
+pub struct IterMut<'a> {
+    inner: slice::IterMut<'a, Protocol>,
+}
+
+impl<'a> Iterator for IterMut<'a> {
+    type Item = &'a mut Protocol;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next()
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl Debug for Upgrade {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut list = f.debug_list();
+        for protocol in &self.entries {
+            list.entry(protocol);
+        }
+        list.finish()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::Request;
+
+    #[test]
+    fn smoke() -> crate::Result<()> {
+        let mut upgrade = Upgrade::new();
+        upgrade.push(Protocol::new("h2c", None)?);
+        upgrade.push(Protocol::new("IRC", Some("6.9".into()))?);
+
+        let mut req = Request::get("http://example.com");
+        upgrade.apply_header(&mut req);
+        assert_eq!(req["Upgrade"], "h2c, IRC/6.9");
+
+        let upgrade = Upgrade::from_headers(req)?.unwrap();
+        assert!(upgrade.contains("irc"));
+        assert!(!upgrade.contains("websocket"));
+        let protocols: Vec<_> = upgrade.iter().map(|p| (p.name(), p.version())).collect();
+        assert_eq!(protocols, vec![("h2c", None), ("IRC", Some("6.9"))]);
+        Ok(())
+    }
+
+    #[test]
+    fn bad_request_on_parse_error() {
+        let mut req = Request::get("http://example.com");
+        req.insert_header("Upgrade", "HTTP/2.0/1").unwrap();
+        let err = Upgrade::from_headers(req).unwrap_err();
+        assert_eq!(err.status(), 400);
+
+        assert!(Protocol::new("web socket", None).is_err());
+        assert!("/1.0".parse::<Protocol>().is_err());
+    }
+}
@@ -0,0 +1,435 @@
+use std::fmt::{self, Display};
+
+/// The largest payload a control frame may carry.
+pub(crate) const MAX_CONTROL_PAYLOAD: usize = 125;
+
+/// A WebSocket frame opcode.
+///
+/// # Specifications
+///
+/// - [RFC 6455, section 5.2: Base Framing Protocol](https://tools.ietf.org/html/rfc6455#section-5.2)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum OpCode {
+    Continuation,
+    Text,
+    Binary,
+    Close,
+    Ping,
+    Pong,
+}
+
+impl OpCode {
+    fn from_u8(n: u8) -> Option<Self> {
+        match n {
+            0x0 => Some(Self::Continuation),
+            0x1 => Some(Self::Text),
+            0x2 => Some(Self::Binary),
+            0x8 => Some(Self::Close),
+            0x9 => Some(Self::Ping),
+            0xA => Some(Self::Pong),
+            _ => None,
+        }
+    }
+
+    fn as_u8(self) -> u8 {
+        match self {
+            Self::Continuation => 0x0,
+            Self::Text => 0x1,
+            Self::Binary => 0x2,
+            Self::Close => 0x8,
+            Self::Ping => 0x9,
+            Self::Pong => 0xA,
+        }
+    }
+
+    pub(crate) fn is_control(self) -> bool {
+        matches!(self, Self::Close | Self::Ping | Self::Pong)
+    }
+}
+
+/// A single WebSocket frame, with its payload unmasked.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Frame {
+    pub(crate) fin: bool,
+    pub(crate) opcode: OpCode,
+    pub(crate) payload: Vec<u8>,
+}
+
+impl Frame {
+    pub(crate) fn new(fin: bool, opcode: OpCode, payload: Vec<u8>) -> Self {
+        Self {
+            fin,
+            opcode,
+            payload,
+        }
+    }
+
+    /// Encode the frame, masking the payload with `mask` if one is passed.
+    pub(crate) fn encode(&self, mask: Option<[u8; 4]>, buf: &mut Vec<u8>) {
+        let first = (self.fin as u8) << 7 | self.opcode.as_u8();
+        buf.push(first);
+
+        let mask_bit = if mask.is_some() { 0x80 } else { 0x00 };
+        let len = self.payload.len();
+        if len < 126 {
+            buf.push(mask_bit | len as u8);
+        } else if len <= u16::MAX as usize {
+            buf.push(mask_bit | 126);
+            buf.extend_from_slice(&(len as u16).to_be_bytes());
+        } else {
+            buf.push(mask_bit | 127);
+            buf.extend_from_slice(&(len as u64).to_be_bytes());
+        }
+
+        match mask {
+            Some(mask) => {
+                buf.extend_from_slice(&mask);
+                let start = buf.len();
+                buf.extend_from_slice(&self.payload);
+                apply_mask(&mut buf[start..], mask);
+            }
+            None => buf.extend_from_slice(&self.payload),
+        }
+    }
+
+    /// Decode a frame from the start of `buf`.
+    ///
+    /// Returns `Ok(None)` if `buf` doesn't contain a full frame yet, and
+    /// otherwise the frame along with the number of bytes it occupied.
+    pub(crate) fn decode(
+        buf: &[u8],
+        max_payload: usize,
+    ) -> Result<Option<(Self, bool, usize)>, ProtocolError> {
+        if buf.len() < 2 {
+            return Ok(None);
+        }
+
+        let fin = buf[0] & 0x80 != 0;
+        if buf[0] & 0x70 != 0 {
+            return Err(ProtocolError::new(
+                CloseCode::Protocol,
+                "Reserved bits must not be set",
+            ));
+        }
+        let opcode = OpCode::from_u8(buf[0] & 0x0F)
+            .ok_or_else(|| ProtocolError::new(CloseCode::Protocol, "Unknown opcode"))?;
+        let masked = buf[1] & 0x80 != 0;
+
+        let (len, mut offset) = match buf[1] & 0x7F {
+            126 => {
+                if buf.len() < 4 {
+                    return Ok(None);
+                }
+                (u16::from_be_bytes([buf[2], buf[3]]) as u64, 4)
+            }
+            127 => {
+                if buf.len() < 10 {
+                    return Ok(None);
+                }
+                let mut bytes = [0; 8];
+                bytes.copy_from_slice(&buf[2..10]);
+                (u64::from_be_bytes(bytes), 10)
+            }
+            n => (n as u64, 2),
+        };
+
+        if opcode.is_control() && (!fin || len > MAX_CONTROL_PAYLOAD as u64) {
+            return Err(ProtocolError::new(
+                CloseCode::Protocol,
+                "Control frames must not be fragmented or exceed 125 bytes",
+            ));
+        }
+        if len > max_payload as u64 {
+            return Err(ProtocolError::new(CloseCode::Size, "Frame is too large"));
+        }
+        let len = len as usize;
+
+        let mask = if masked {
+            if buf.len() < offset + 4 {
+                return Ok(None);
+            }
+            let mut mask = [0; 4];
+            mask.copy_from_slice(&buf[offset..offset + 4]);
+            offset += 4;
+            Some(mask)
+        } else {
+            None
+        };
+
+        if buf.len() < offset + len {
+            return Ok(None);
+        }
+        let mut payload = buf[offset..offset + len].to_vec();
+        if let Some(mask) = mask {
+            apply_mask(&mut payload, mask);
+        }
+
+        let frame = Self::new(fin, opcode, payload);
+        Ok(Some((frame, masked, offset + len)))
+    }
+}
+
+/// XOR the payload with the masking key.
+pub(crate) fn apply_mask(payload: &mut [u8], mask: [u8; 4]) {
+    for (i, byte) in payload.iter_mut().enumerate() {
+        *byte ^= mask[i % 4];
+    }
+}
+
+/// A WebSocket close status code.
+///
+/// # Specifications
+///
+/// - [RFC 6455, section 7.4: Status Codes](https://tools.ietf.org/html/rfc6455#section-7.4)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum CloseCode {
+    /// 1000: The purpose for which the connection was established has been fulfilled.
+    Normal,
+    /// 1001: An endpoint is going away.
+    Away,
+    /// 1002: An endpoint received a malformed frame.
+    Protocol,
+    /// 1003: An endpoint received a type of data it cannot accept.
+    Unsupported,
+    /// 1005: No status code was present. Must not be sent.
+    Status,
+    /// 1006: The connection was closed abnormally. Must not be sent.
+    Abnormal,
+    /// 1007: An endpoint received data inconsistent with the message type.
+    Invalid,
+    /// 1008: An endpoint received a message which violates its policy.
+    Policy,
+    /// 1009: An endpoint received a message which is too big to process.
+    Size,
+    /// 1010: The client expected the server to negotiate an extension.
+    Extension,
+    /// 1011: The server encountered an unexpected condition.
+    Error,
+    /// 1012: The server is restarting.
+    Restart,
+    /// 1013: The server is overloaded, try again later.
+    Again,
+    /// 1015: The TLS handshake failed. Must not be sent.
+    Tls,
+    /// Any other close code, e.g. in the application range 4000-4999.
+    Other(u16),
+}
+
+impl CloseCode {
+    /// Returns `true` if the code may be sent in a close frame.
+    pub fn is_allowed(self) -> bool {
+        match u16::from(self) {
+            1005 | 1006 | 1015 => false,
+            1000..=1003 | 1007..=1014 => true,
+            3000..=4999 => true,
+            _ => false,
+        }
+    }
+}
+
+impl From<u16> for CloseCode {
+    fn from(code: u16) -> Self {
+        match code {
+            1000 => Self::Normal,
+            1001 => Self::Away,
+            1002 => Self::Protocol,
+            1003 => Self::Unsupported,
+            1005 => Self::Status,
+            1006 => Self::Abnormal,
+            1007 => Self::Invalid,
+            1008 => Self::Policy,
+            1009 => Self::Size,
+            1010 => Self::Extension,
+            1011 => Self::Error,
+            1012 => Self::Restart,
+            1013 => Self::Again,
+            1015 => Self::Tls,
+            code => Self::Other(code),
+        }
+    }
+}
+
+impl From<CloseCode> for u16 {
+    fn from(code: CloseCode) -> Self {
+        match code {
+            CloseCode::Normal => 1000,
+            CloseCode::Away => 1001,
+            CloseCode::Protocol => 1002,
+            CloseCode::Unsupported => 1003,
+            CloseCode::Status => 1005,
+            CloseCode::Abnormal => 1006,
+            CloseCode::Invalid => 1007,
+            CloseCode::Policy => 1008,
+            CloseCode::Size => 1009,
+            CloseCode::Extension => 1010,
+            CloseCode::Error => 1011,
+            CloseCode::Restart => 1012,
+            CloseCode::Again => 1013,
+            CloseCode::Tls => 1015,
+            CloseCode::Other(code) => code,
+        }
+    }
+}
+
+impl Display for CloseCode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", u16::from(*self))
+    }
+}
+
+/// The payload of a close frame.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CloseFrame {
+    code: CloseCode,
+    reason: String,
+}
+
+impl CloseFrame {
+    /// Create a new instance of `CloseFrame`.
+    pub fn new(code: CloseCode, reason: impl Into<String>) -> Self {
+        Self {
+            code,
+            reason: reason.into(),
+        }
+    }
+
+    /// Get the close code.
+    pub fn code(&self) -> CloseCode {
+        self.code
+    }
+
+    /// Get the reason the connection was closed.
+    pub fn reason(&self) -> &str {
+        &self.reason
+    }
+
+    /// Encode the payload, cutting the reason short at a character boundary
+    /// if it doesn't fit in a control frame.
+    pub(crate) fn encode(&self) -> Vec<u8> {
+        let mut end = self.reason.len().min(MAX_CONTROL_PAYLOAD - 2);
+        while !self.reason.is_char_boundary(end) {
+            end -= 1;
+        }
+        let mut buf = u16::from(self.code).to_be_bytes().to_vec();
+        buf.extend_from_slice(&self.reason.as_bytes()[..end]);
+        buf
+    }
+
+    pub(crate) fn decode(payload: &[u8]) -> Result<Option<Self>, ProtocolError> {
+        match payload.len() {
+            0 => Ok(None),
+            1 => Err(ProtocolError::new(
+                CloseCode::Protocol,
+                "Close frame payload must contain a status code",
+            )),
+            _ => {
+                let code = CloseCode::from(u16::from_be_bytes([payload[0], payload[1]]));
+                if !code.is_allowed() {
+                    return Err(ProtocolError::new(
+                        CloseCode::Protocol,
+                        "Invalid close code",
+                    ));
+                }
+                let reason = std::str::from_utf8(&payload[2..]).map_err(|_| {
+                    ProtocolError::new(CloseCode::Invalid, "Close reason must be valid UTF-8")
+                })?;
+                Ok(Some(Self::new(code, reason)))
+            }
+        }
+    }
+}
+
+/// A violation of the WebSocket protocol by the peer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct ProtocolError {
+    pub(crate) code: CloseCode,
+    pub(crate) reason: &'static str,
+}
+
+impl ProtocolError {
+    pub(crate) fn new(code: CloseCode, reason: &'static str) -> Self {
+        Self { code, reason }
+    }
+}
+
+impl From<ProtocolError> for crate::Error {
+    fn from(err: ProtocolError) -> Self {
+        crate::Error::from_str(crate::StatusCode::BadRequest, err.reason)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn round_trip() {
+        for len in [0, 5, 125, 126, 65535, 65536] {
+            let frame = Frame::new(true, OpCode::Binary, vec![7; len]);
+            for mask in [None, Some([1, 2, 3, 4])] {
+                let mut buf = vec![];
+                frame.encode(mask, &mut buf);
+                let (decoded, masked, used) = Frame::decode(&buf, usize::MAX).unwrap().unwrap();
+                assert_eq!(decoded, frame);
+                assert_eq!(masked, mask.is_some());
+                assert_eq!(used, buf.len());
+                assert!(Frame::decode(&buf[..buf.len() - 1], usize::MAX)
+                    .unwrap()
+                    .is_none());
+            }
+        }
+    }
+
+    #[test]
+    fn rfc_examples() {
+        // A single-frame unmasked text message.
+        let buf = [0x81, 0x05, 0x48, 0x65, 0x6c, 0x6c, 0x6f];
+        let (frame, masked, _) = Frame::decode(&buf, usize::MAX).unwrap().unwrap();
+        assert!(!masked);
+        assert_eq!(frame.payload, b"Hello");
+
+        // A single-frame masked text message.
+        let buf = [
+            0x81, 0x85, 0x37, 0xfa, 0x21, 0x3d, 0x7f, 0x9f, 0x4d, 0x51, 0x58,
+        ];
+        let (frame, masked, _) = Frame::decode(&buf, usize::MAX).unwrap().unwrap();
+        assert!(masked);
+        assert_eq!(frame.opcode, OpCode::Text);
+        assert_eq!(frame.payload, b"Hello");
+    }
+
+    #[test]
+    fn invalid_frames() {
+        let err = Frame::decode(&[0xC1, 0x00], usize::MAX).unwrap_err();
+        assert_eq!(err.code, CloseCode::Protocol);
+
+        let err = Frame::decode(&[0x83, 0x00], usize::MAX).unwrap_err();
+        assert_eq!(err.code, CloseCode::Protocol);
+
+        let err = Frame::decode(&[0x09, 0x00], usize::MAX).unwrap_err();
+        assert_eq!(err.code, CloseCode::Protocol);
+
+        let err = Frame::decode(&[0x82, 0x7E, 0x01, 0x00], 255).unwrap_err();
+        assert_eq!(err.code, CloseCode::Size);
+    }
+
+    #[test]
+    fn close_frames() {
+        let close = CloseFrame::new(CloseCode::Away, "bye");
+        let decoded = CloseFrame::decode(&close.encode()).unwrap().unwrap();
+        assert_eq!(decoded, close);
+
+        assert_eq!(CloseFrame::decode(&[]).unwrap(), None);
+        assert!(CloseFrame::decode(&[0x03]).is_err());
+        assert!(CloseFrame::decode(&1005u16.to_be_bytes()).is_err());
+        assert!(CloseFrame::decode(&[0x03, 0xE8, 0xFF]).is_err());
+
+        // 62 two-byte characters don't fit in the 123 bytes left for the reason.
+        let close = CloseFrame::new(CloseCode::Normal, "\u{e9}".repeat(62));
+        let payload = close.encode();
+        assert_eq!(payload.len(), 2 + 122);
+        let decoded = CloseFrame::decode(&payload).unwrap().unwrap();
+        assert_eq!(decoded.reason(), "\u{e9}".repeat(61));
+    }
+}
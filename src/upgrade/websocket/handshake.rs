@@ -0,0 +1,417 @@
+use sha1::{Digest, Sha1};
+
+use crate::headers::{
+    HeaderValues, Headers, CONNECTION, SEC_WEBSOCKET_ACCEPT, SEC_WEBSOCKET_KEY,
+    SEC_WEBSOCKET_PROTOCOL, SEC_WEBSOCKET_VERSION, UPGRADE,
+};
+use crate::utils::fill_random;
+use crate::{bail_status as bail, ensure_status as ensure};
+use crate::{Method, Request, Response, StatusCode};
+
+/// The GUID appended to the key when computing `Sec-WebSocket-Accept`.
+const GUID: &str = "258EAFA5-E914-47DA-95CA-C5AB0DC85B11";
+
+/// The only WebSocket protocol version defined by RFC 6455.
+const VERSION: &str = "13";
+
+/// Compute the `Sec-WebSocket-Accept` value for a `Sec-WebSocket-Key`.
+///
+/// # Examples
+///
+/// ```
+/// use http_types::upgrade::websocket::accept_key;
+///
+/// let accept = accept_key("dGhlIHNhbXBsZSBub25jZQ==");
+/// assert_eq!(accept, "s3pPLMBiTxaQ9kYGzzhZRbK+xOo=");
+/// ```
+pub fn accept_key(key: &str) -> String {
+    let mut hasher = Sha1::new();
+    hasher.update(key.as_bytes());
+    hasher.update(GUID.as_bytes());
+    base64::encode(hasher.finalize())
+}
+
+/// The server side of a WebSocket opening handshake.
+///
+/// # Specifications
+///
+/// - [RFC 6455, section 4.2: Server-Side Requirements](https://tools.ietf.org/html/rfc6455#section-4.2)
+///
+/// # Examples
+///
+/// ```
+/// # fn main() -> http_types::Result<()> {
+/// #
+/// use http_types::upgrade::websocket::ServerHandshake;
+/// use http_types::{Request, StatusCode};
+///
+/// let mut req = Request::get("http://example.com/chat");
+/// req.insert_header("Upgrade", "websocket");
+/// req.insert_header("Connection", "Upgrade");
+/// req.insert_header("Sec-WebSocket-Key", "dGhlIHNhbXBsZSBub25jZQ==");
+/// req.insert_header("Sec-WebSocket-Version", "13");
+/// req.insert_header("Sec-WebSocket-Protocol", "chat, superchat");
+///
+/// let handshake = ServerHandshake::from_request(&req)?;
+/// let protocol = handshake.negotiate(&["superchat"]);
+/// assert_eq!(protocol, Some("superchat"));
+///
+/// let res = handshake.response(protocol);
+/// assert_eq!(res.status(), StatusCode::SwitchingProtocols);
+/// assert_eq!(res["Sec-WebSocket-Accept"], "s3pPLMBiTxaQ9kYGzzhZRbK+xOo=");
+/// assert_eq!(res["Sec-WebSocket-Protocol"], "superchat");
+/// #
+/// # Ok(()) }
+/// ```
+#[derive(Debug, Clone)]
+pub struct ServerHandshake {
+    key: String,
+    protocols: Vec<String>,
+}
+
+impl ServerHandshake {
+    /// Validate a client's opening handshake.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `400` error if the request is not a valid WebSocket
+    /// handshake, or a `426` error if the requested WebSocket version is not
+    /// supported. Use [`ServerHandshake::version_mismatch`] to build the
+    /// response for the latter.
+    pub fn from_request(req: &Request) -> crate::Result<Self> {
+        ensure!(
+            req.method() == Method::Get,
+            400,
+            "WebSocket handshake must use the GET method"
+        );
+        ensure!(
+            has_token(req.header(UPGRADE), "websocket"),
+            400,
+            "Expected `Upgrade: websocket` header"
+        );
+        ensure!(
+            has_token(req.header(CONNECTION), "upgrade"),
+            400,
+            "Expected `Connection: Upgrade` header"
+        );
+
+        match req.header(SEC_WEBSOCKET_VERSION) {
+            Some(version) if version.as_str().trim() == VERSION => {}
+            Some(_) => bail!(426, "Unsupported WebSocket version"),
+            None => bail!(400, "Missing `Sec-WebSocket-Version` header"),
+        }
+
+        let key = match req.header(SEC_WEBSOCKET_KEY) {
+            Some(key) => key.as_str().trim().to_owned(),
+            None => bail!(400, "Missing `Sec-WebSocket-Key` header"),
+        };
+        let decoded = base64::decode(&key).ok();
+        ensure!(
+            matches!(decoded, Some(bytes) if bytes.len() == 16),
+            400,
+            "`Sec-WebSocket-Key` must be a base64-encoded 16-byte value"
+        );
+
+        let protocols = list(req.header(SEC_WEBSOCKET_PROTOCOL));
+        Ok(Self { key, protocols })
+    }
+
+    /// Get the key sent by the client.
+    pub fn key(&self) -> &str {
+        &self.key
+    }
+
+    /// Get the subprotocols requested by the client, in order of preference.
+    pub fn protocols(&self) -> &[String] {
+        &self.protocols
+    }
+
+    /// Pick the first subprotocol requested by the client which the server
+    /// supports.
+    ///
+    /// Subprotocol names are case-sensitive.
+    pub fn negotiate<'a>(&self, supported: &[&'a str]) -> Option<&'a str> {
+        self.protocols
+            .iter()
+            .find_map(|requested| supported.iter().find(|s| **s == requested).copied())
+    }
+
+    /// Build the `101 Switching Protocols` response which completes the
+    /// handshake.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `protocol` is not a valid header value.
+    pub fn response(&self, protocol: Option<&str>) -> Response {
+        let mut res = Response::new(StatusCode::SwitchingProtocols);
+        res.insert_header(UPGRADE, "websocket").unwrap();
+        res.insert_header(CONNECTION, "Upgrade").unwrap();
+        res.insert_header(SEC_WEBSOCKET_ACCEPT, accept_key(&self.key))
+            .unwrap();
+        if let Some(protocol) = protocol {
+            res.insert_header(SEC_WEBSOCKET_PROTOCOL, protocol)
+                .expect("Subprotocol should be a valid header value");
+        }
+        res
+    }
+
+    /// Build the `426 Upgrade Required` response sent when the client
+    /// requested an unsupported WebSocket version.
+    pub fn version_mismatch() -> Response {
+        let mut res = Response::new(StatusCode::UpgradeRequired);
+        res.insert_header(SEC_WEBSOCKET_VERSION, VERSION).unwrap();
+        res
+    }
+}
+
+/// The client side of a WebSocket opening handshake.
+///
+/// # Specifications
+///
+/// - [RFC 6455, section 4.1: Client Requirements](https://tools.ietf.org/html/rfc6455#section-4.1)
+///
+/// # Examples
+///
+/// ```
+/// # fn main() -> http_types::Result<()> {
+/// #
+/// use http_types::upgrade::websocket::{ClientHandshake, ServerHandshake};
+/// use http_types::Request;
+///
+/// let mut handshake = ClientHandshake::new();
+/// handshake.push_protocol("chat");
+///
+/// let mut req = Request::get("http://example.com/chat");
+/// handshake.apply(&mut req);
+///
+/// let res = ServerHandshake::from_request(&req)?.response(Some("chat"));
+/// assert_eq!(handshake.verify(&res)?, Some("chat".to_owned()));
+/// #
+/// # Ok(()) }
+/// ```
+#[derive(Debug, Clone)]
+pub struct ClientHandshake {
+    key: String,
+    protocols: Vec<String>,
+}
+
+impl ClientHandshake {
+    /// Create a new handshake with a random key.
+    pub fn new() -> Self {
+        let mut bytes = [0; 16];
+        fill_random(&mut bytes);
+        Self {
+            key: base64::encode(bytes),
+            protocols: vec![],
+        }
+    }
+
+    /// Get the key sent to the server.
+    pub fn key(&self) -> &str {
+        &self.key
+    }
+
+    /// Request a subprotocol. Subprotocols are sent in order of preference.
+    pub fn push_protocol(&mut self, protocol: impl Into<String>) {
+        self.protocols.push(protocol.into());
+    }
+
+    /// Get the requested subprotocols.
+    pub fn protocols(&self) -> &[String] {
+        &self.protocols
+    }
+
+    /// Set the handshake headers on a request.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a requested subprotocol is not a valid header value.
+    pub fn apply(&self, req: &mut Request) {
+        req.set_method(Method::Get);
+        req.insert_header(UPGRADE, "websocket").unwrap();
+        req.insert_header(CONNECTION, "Upgrade").unwrap();
+        req.insert_header(SEC_WEBSOCKET_KEY, self.key.as_str())
+            .unwrap();
+        req.insert_header(SEC_WEBSOCKET_VERSION, VERSION).unwrap();
+        if !self.protocols.is_empty() {
+            req.insert_header(SEC_WEBSOCKET_PROTOCOL, self.protocols.join(", "))
+                .expect("Subprotocols should be valid header values");
+        }
+    }
+
+    /// Verify the server's response to the handshake, returning the
+    /// negotiated subprotocol, if any.
+    pub fn verify(&self, res: &Response) -> crate::Result<Option<String>> {
+        ensure!(
+            res.status() == StatusCode::SwitchingProtocols,
+            502,
+            "Expected a `101 Switching Protocols` response, got `{}`",
+            res.status()
+        );
+        ensure!(
+            has_token(res.header(UPGRADE), "websocket"),
+            502,
+            "Expected `Upgrade: websocket` header"
+        );
+        ensure!(
+            has_token(res.header(CONNECTION), "upgrade"),
+            502,
+            "Expected `Connection: Upgrade` header"
+        );
+
+        let expected = accept_key(&self.key);
+        ensure!(
+            matches!(res.header(SEC_WEBSOCKET_ACCEPT), Some(accept) if accept.as_str().trim() == expected),
+            502,
+            "Invalid `Sec-WebSocket-Accept` header"
+        );
+
+        let mut selected = list(res.header(SEC_WEBSOCKET_PROTOCOL));
+        ensure!(
+            selected.len() <= 1,
+            502,
+            "Server selected more than one subprotocol"
+        );
+        let protocol = selected.pop();
+        if let Some(protocol) = &protocol {
+            ensure!(
+                self.protocols.iter().any(|p| p == protocol),
+                502,
+                "Server selected subprotocol `{}` which was not requested",
+                protocol
+            );
+        }
+        Ok(protocol)
+    }
+}
+
+impl Default for ClientHandshake {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Returns `true` if a comma-separated header contains a token, ignoring case.
+fn has_token(values: Option<&HeaderValues>, token: &str) -> bool {
+    list(values).iter().any(|t| t.eq_ignore_ascii_case(token))
+}
+
+/// Split a comma-separated header into its elements.
+fn list(values: Option<&HeaderValues>) -> Vec<String> {
+    values
+        .map(|values| {
+            values
+                .iter()
+                .flat_map(|v| v.as_str().split(','))
+                .map(|s| s.trim())
+                .filter(|s| !s.is_empty())
+                .map(|s| s.to_owned())
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Returns `true` if the headers request a WebSocket upgrade.
+///
+/// This is a cheap check to route requests; use
+/// [`ServerHandshake::from_request`] to validate the handshake.
+pub fn is_upgrade_request(headers: impl AsRef<Headers>) -> bool {
+    let headers = headers.as_ref();
+    has_token(headers.get(UPGRADE), "websocket") && has_token(headers.get(CONNECTION), "upgrade")
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn request() -> Request {
+        let mut req = Request::get("http://example.com/chat");
+        ClientHandshake::new().apply(&mut req);
+        req
+    }
+
+    #[test]
+    fn rfc_example() {
+        assert_eq!(
+            accept_key("dGhlIHNhbXBsZSBub25jZQ=="),
+            "s3pPLMBiTxaQ9kYGzzhZRbK+xOo="
+        );
+    }
+
+    #[test]
+    fn round_trip() -> crate::Result<()> {
+        let mut client = ClientHandshake::new();
+        client.push_protocol("v2.chat");
+        client.push_protocol("v1.chat");
+        let mut req = Request::get("http://example.com/chat");
+        client.apply(&mut req);
+        assert!(is_upgrade_request(&req));
+
+        let server = ServerHandshake::from_request(&req)?;
+        assert_eq!(server.protocols(), ["v2.chat", "v1.chat"]);
+        let protocol = server.negotiate(&["v1.chat"]);
+        let res = server.response(protocol);
+        assert_eq!(client.verify(&res)?.as_deref(), Some("v1.chat"));
+
+        let res = server.response(Some("v3.chat"));
+        assert_eq!(client.verify(&res).unwrap_err().status(), 502);
+
+        assert_eq!(server.negotiate(&["V1.chat"]), None);
+        let res = server.response(Some("V1.chat"));
+        assert_eq!(client.verify(&res).unwrap_err().status(), 502);
+        Ok(())
+    }
+
+    #[test]
+    fn connection_header_with_multiple_tokens() -> crate::Result<()> {
+        let mut req = request();
+        req.insert_header(CONNECTION, "keep-alive, Upgrade")?;
+        assert!(ServerHandshake::from_request(&req).is_ok());
+        Ok(())
+    }
+
+    #[test]
+    fn bad_request_on_invalid_handshake() -> crate::Result<()> {
+        let mut req = request();
+        req.set_method(Method::Post);
+        assert_eq!(
+            ServerHandshake::from_request(&req).unwrap_err().status(),
+            400
+        );
+
+        let mut req = request();
+        req.remove_header(UPGRADE);
+        assert_eq!(
+            ServerHandshake::from_request(&req).unwrap_err().status(),
+            400
+        );
+
+        let mut req = request();
+        req.insert_header(SEC_WEBSOCKET_KEY, "dG9vIHNob3J0")?;
+        assert_eq!(
+            ServerHandshake::from_request(&req).unwrap_err().status(),
+            400
+        );
+
+        let mut req = request();
+        req.insert_header(SEC_WEBSOCKET_VERSION, "8")?;
+        assert_eq!(
+            ServerHandshake::from_request(&req).unwrap_err().status(),
+            426
+        );
+        let res = ServerHandshake::version_mismatch();
+        assert_eq!(res[SEC_WEBSOCKET_VERSION], "13");
+        Ok(())
+    }
+
+    #[test]
+    fn rejects_invalid_accept() {
+        let client = ClientHandshake::new();
+        let mut req = Request::get("http://example.com/chat");
+        client.apply(&mut req);
+        let res = ServerHandshake::from_request(&request())
+            .unwrap()
+            .response(None);
+        assert!(client.verify(&res).is_err());
+    }
+}
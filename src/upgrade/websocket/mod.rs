@@ -0,0 +1,41 @@
+//! The WebSocket protocol.
+//!
+//! WebSockets are the most common target of an HTTP protocol upgrade. This
+//! module validates the opening handshake on both ends of the connection, and
+//! wraps the upgraded [`Connection`](crate::upgrade::Connection) in a codec
+//! which exchanges [`Message`]s.
+//!
+//! # Specifications
+//!
+//! - [RFC 6455: The WebSocket Protocol](https://tools.ietf.org/html/rfc6455)
+//!
+//! # Examples
+//!
+//! ```
+//! # fn main() -> http_types::Result<()> {
+//! #
+//! use http_types::upgrade::websocket::{self, ServerHandshake};
+//! use http_types::Request;
+//!
+//! let mut req = Request::get("http://example.com/chat");
+//! req.insert_header("Upgrade", "websocket");
+//! req.insert_header("Connection", "Upgrade");
+//! req.insert_header("Sec-WebSocket-Key", "dGhlIHNhbXBsZSBub25jZQ==");
+//! req.insert_header("Sec-WebSocket-Version", "13");
+//!
+//! assert!(websocket::is_upgrade_request(&req));
+//! let res = ServerHandshake::from_request(&req)?.response(None);
+//! assert_eq!(res["Sec-WebSocket-Accept"], "s3pPLMBiTxaQ9kYGzzhZRbK+xOo=");
+//! #
+//! # Ok(()) }
+//! ```
+
+mod frame;
+mod handshake;
+mod message;
+mod stream;
+
+pub use frame::{CloseCode, CloseFrame};
+pub use handshake::{accept_key, is_upgrade_request, ClientHandshake, ServerHandshake};
+pub use message::Message;
+pub use stream::{Role, WebSocket};
@@ -0,0 +1,591 @@
+use futures_lite::{future, io, prelude::*};
+use futures_sink::Sink;
+
+use std::fmt::{self, Debug};
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use crate::upgrade::websocket::frame::{Frame, OpCode, ProtocolError, MAX_CONTROL_PAYLOAD};
+use crate::upgrade::websocket::{CloseCode, CloseFrame, Message};
+use crate::upgrade::Connection;
+use crate::utils::fill_random;
+
+/// The default maximum size of a message, 64 MiB.
+const DEFAULT_MAX_MESSAGE_SIZE: usize = 64 << 20;
+
+/// The size of the chunks read from the connection.
+const READ_CHUNK_SIZE: usize = 8 * 1024;
+
+/// Which end of the connection a [`WebSocket`] is.
+///
+/// Clients mask the frames they send, and servers reject frames which are
+/// not masked.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Role {
+    /// The end which initiated the handshake.
+    Client,
+    /// The end which accepted the handshake.
+    Server,
+}
+
+/// A WebSocket connection.
+///
+/// `WebSocket` wraps an upgraded [`Connection`] and exposes it as a `Stream`
+/// of incoming [`Message`]s, and a `Sink` of outgoing messages. Pings are
+/// answered and close frames are echoed automatically.
+///
+/// # Specifications
+///
+/// - [RFC 6455: The WebSocket Protocol](https://tools.ietf.org/html/rfc6455)
+///
+/// # Examples
+///
+/// ```no_run
+/// # fn main() -> http_types::Result<()> { async_std::task::block_on(async {
+/// #
+/// use futures_lite::StreamExt;
+/// use http_types::upgrade::websocket::{Message, WebSocket};
+/// use http_types::upgrade::Connection;
+///
+/// # let stream = async_std::net::TcpStream::connect("127.0.0.1:8080").await?;
+/// let conn = Connection::new(stream);
+/// let mut ws = WebSocket::server(conn);
+/// while let Some(msg) = ws.next().await {
+///     match msg? {
+///         Message::Text(text) => ws.send(Message::Text(text)).await?,
+///         Message::Close(_) => break,
+///         _ => {}
+///     }
+/// }
+/// #
+/// # Ok(()) }) }
+/// ```
+pub struct WebSocket {
+    conn: Connection,
+    role: Role,
+    read_buf: Vec<u8>,
+    write_buf: Vec<u8>,
+    fragments: Option<(OpCode, Vec<u8>)>,
+    max_message_size: usize,
+    max_frame_size: Option<usize>,
+    close_sent: bool,
+    close_received: bool,
+    eof: bool,
+}
+
+impl Debug for WebSocket {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("WebSocket")
+            .field("conn", &self.conn)
+            .field("role", &self.role)
+            .field("close_sent", &self.close_sent)
+            .field("close_received", &self.close_received)
+            .finish()
+    }
+}
+
+impl WebSocket {
+    /// Create a new instance of `WebSocket`.
+    pub fn new(conn: Connection, role: Role) -> Self {
+        Self {
+            conn,
+            role,
+            read_buf: vec![],
+            write_buf: vec![],
+            fragments: None,
+            max_message_size: DEFAULT_MAX_MESSAGE_SIZE,
+            max_frame_size: None,
+            close_sent: false,
+            close_received: false,
+            eof: false,
+        }
+    }
+
+    /// Create the server end of a WebSocket connection.
+    pub fn server(conn: Connection) -> Self {
+        Self::new(conn, Role::Server)
+    }
+
+    /// Create the client end of a WebSocket connection.
+    pub fn client(conn: Connection) -> Self {
+        Self::new(conn, Role::Client)
+    }
+
+    /// Get the role of this end of the connection.
+    pub fn role(&self) -> Role {
+        self.role
+    }
+
+    /// Get the maximum size of an incoming message.
+    pub fn max_message_size(&self) -> usize {
+        self.max_message_size
+    }
+
+    /// Set the maximum size of an incoming message. Larger messages close the
+    /// connection with [`CloseCode::Size`].
+    pub fn set_max_message_size(&mut self, size: usize) {
+        self.max_message_size = size;
+    }
+
+    /// Get the maximum payload size of an outgoing frame.
+    pub fn max_frame_size(&self) -> Option<usize> {
+        self.max_frame_size
+    }
+
+    /// Set the maximum payload size of an outgoing frame. Larger messages are
+    /// sent fragmented. By default messages are sent as a single frame.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `size` is zero.
+    pub fn set_max_frame_size(&mut self, size: Option<usize>) {
+        assert!(size != Some(0), "Frame size must be larger than zero");
+        self.max_frame_size = size;
+    }
+
+    /// Send a message.
+    pub async fn send(&mut self, msg: impl Into<Message>) -> crate::Result<()> {
+        let msg = msg.into();
+        future::poll_fn(|cx| Pin::new(&mut *self).poll_ready(cx)).await?;
+        Pin::new(&mut *self).start_send(msg)?;
+        future::poll_fn(|cx| Pin::new(&mut *self).poll_flush(cx)).await
+    }
+
+    /// Start the closing handshake, and close the connection once the peer
+    /// has acknowledged it.
+    pub async fn close(&mut self, frame: Option<CloseFrame>) -> crate::Result<()> {
+        if !self.close_sent {
+            self.queue_close(frame)?;
+        }
+        future::poll_fn(|cx| Pin::new(&mut *self).poll_close(cx)).await
+    }
+
+    fn queue(&mut self, frame: Frame) {
+        let mask = match self.role {
+            Role::Client => {
+                let mut mask = [0; 4];
+                fill_random(&mut mask);
+                Some(mask)
+            }
+            Role::Server => None,
+        };
+        frame.encode(mask, &mut self.write_buf);
+    }
+
+    fn queue_close(&mut self, frame: Option<CloseFrame>) -> crate::Result<()> {
+        let payload = match frame {
+            Some(frame) => {
+                crate::ensure!(
+                    frame.code().is_allowed(),
+                    "Close code `{}` must not be sent",
+                    frame.code()
+                );
+                frame.encode()
+            }
+            None => vec![],
+        };
+        self.queue(Frame::new(true, OpCode::Close, payload));
+        self.close_sent = true;
+        Ok(())
+    }
+
+    fn queue_message(&mut self, msg: Message) -> crate::Result<()> {
+        crate::ensure!(!self.close_sent, "Cannot send messages after closing");
+
+        let (opcode, payload) = match msg {
+            Message::Text(text) => (OpCode::Text, text.into_bytes()),
+            Message::Binary(bytes) => (OpCode::Binary, bytes),
+            Message::Ping(bytes) => (OpCode::Ping, bytes),
+            Message::Pong(bytes) => (OpCode::Pong, bytes),
+            Message::Close(frame) => return self.queue_close(frame),
+        };
+
+        if opcode.is_control() {
+            crate::ensure!(
+                payload.len() <= MAX_CONTROL_PAYLOAD,
+                "Control frames must not exceed 125 bytes"
+            );
+            self.queue(Frame::new(true, opcode, payload));
+            return Ok(());
+        }
+
+        match self.max_frame_size {
+            Some(size) if payload.len() > size => {
+                let chunks = payload.chunks(size).collect::<Vec<_>>();
+                let last = chunks.len() - 1;
+                for (i, chunk) in chunks.into_iter().enumerate() {
+                    let opcode = if i == 0 { opcode } else { OpCode::Continuation };
+                    self.queue(Frame::new(i == last, opcode, chunk.to_vec()));
+                }
+            }
+            _ => self.queue(Frame::new(true, opcode, payload)),
+        }
+        Ok(())
+    }
+
+    /// Write out the buffered frames.
+    fn poll_write_buf(&mut self, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        while !self.write_buf.is_empty() {
+            let n = futures_lite::ready!(Pin::new(&mut self.conn).poll_write(cx, &self.write_buf))?;
+            if n == 0 {
+                return Poll::Ready(Err(io::ErrorKind::WriteZero.into()));
+            }
+            self.write_buf.drain(..n);
+        }
+        Poll::Ready(Ok(()))
+    }
+
+    /// Send a close frame for a protocol violation, and turn it into an error.
+    fn fail(&mut self, cx: &mut Context<'_>, err: ProtocolError) -> crate::Error {
+        if !self.close_sent {
+            let frame = CloseFrame::new(err.code, err.reason);
+            // The close code comes from `ProtocolError`, which only uses
+            // codes that may be sent.
+            let _ = self.queue_close(Some(frame));
+            let _ = self.poll_write_buf(cx);
+        }
+        self.close_received = true;
+        err.into()
+    }
+
+    /// Turn a frame into a message, if it completes one.
+    fn handle_frame(&mut self, frame: Frame) -> Result<Option<Message>, ProtocolError> {
+        let Frame {
+            fin,
+            opcode,
+            payload,
+        } = frame;
+
+        let (opcode, payload) = match opcode {
+            OpCode::Ping => {
+                if !self.close_sent {
+                    self.queue(Frame::new(true, OpCode::Pong, payload.clone()));
+                }
+                return Ok(Some(Message::Ping(payload)));
+            }
+            OpCode::Pong => return Ok(Some(Message::Pong(payload))),
+            OpCode::Close => {
+                let frame = CloseFrame::decode(&payload)?;
+                self.close_received = true;
+                if !self.close_sent {
+                    // Echo the status code back, as required by the spec.
+                    let echo = frame.as_ref().map(|f| CloseFrame::new(f.code(), ""));
+                    self.queue(Frame::new(
+                        true,
+                        OpCode::Close,
+                        echo.map(|f| f.encode()).unwrap_or_default(),
+                    ));
+                    self.close_sent = true;
+                }
+                return Ok(Some(Message::Close(frame)));
+            }
+            OpCode::Continuation => match self.fragments.take() {
+                Some((opcode, mut buf)) => {
+                    if buf.len() + payload.len() > self.max_message_size {
+                        return Err(ProtocolError::new(CloseCode::Size, "Message is too large"));
+                    }
+                    buf.extend_from_slice(&payload);
+                    if !fin {
+                        self.fragments = Some((opcode, buf));
+                        return Ok(None);
+                    }
+                    (opcode, buf)
+                }
+                None => {
+                    return Err(ProtocolError::new(
+                        CloseCode::Protocol,
+                        "Unexpected continuation frame",
+                    ))
+                }
+            },
+            OpCode::Text | OpCode::Binary => {
+                if self.fragments.is_some() {
+                    return Err(ProtocolError::new(
+                        CloseCode::Protocol,
+                        "Expected a continuation frame",
+                    ));
+                }
+                if !fin {
+                    self.fragments = Some((opcode, payload));
+                    return Ok(None);
+                }
+                (opcode, payload)
+            }
+        };
+
+        let msg = match opcode {
+            OpCode::Text => match String::from_utf8(payload) {
+                Ok(text) => Message::Text(text),
+                Err(_) => {
+                    return Err(ProtocolError::new(
+                        CloseCode::Invalid,
+                        "Text message must be valid UTF-8",
+                    ))
+                }
+            },
+            _ => Message::Binary(payload),
+        };
+        Ok(Some(msg))
+    }
+}
+
+impl Stream for WebSocket {
+    type Item = crate::Result<Message>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = &mut *self;
+        loop {
+            // Make progress on pongs and close frames queued while reading.
+            if let Poll::Ready(Err(err)) = this.poll_write_buf(cx) {
+                return Poll::Ready(Some(Err(err.into())));
+            }
+
+            if this.close_received || this.eof {
+                return Poll::Ready(None);
+            }
+
+            match Frame::decode(&this.read_buf, this.max_message_size) {
+                Err(err) => return Poll::Ready(Some(Err(this.fail(cx, err)))),
+                Ok(Some((frame, masked, used))) => {
+                    this.read_buf.drain(..used);
+                    let expect_masked = this.role == Role::Server;
+                    if masked != expect_masked {
+                        let reason = match expect_masked {
+                            true => "Frames sent by a client must be masked",
+                            false => "Frames sent by a server must not be masked",
+                        };
+                        let err = ProtocolError::new(CloseCode::Protocol, reason);
+                        return Poll::Ready(Some(Err(this.fail(cx, err))));
+                    }
+                    match this.handle_frame(frame) {
+                        Err(err) => return Poll::Ready(Some(Err(this.fail(cx, err)))),
+                        Ok(Some(msg)) => {
+                            let _ = this.poll_write_buf(cx);
+                            return Poll::Ready(Some(Ok(msg)));
+                        }
+                        Ok(None) => continue,
+                    }
+                }
+                Ok(None) => {
+                    let mut chunk = [0; READ_CHUNK_SIZE];
+                    let n = match Pin::new(&mut this.conn).poll_read(cx, &mut chunk) {
+                        Poll::Ready(Ok(n)) => n,
+                        Poll::Ready(Err(err)) => return Poll::Ready(Some(Err(err.into()))),
+                        Poll::Pending => return Poll::Pending,
+                    };
+                    if n == 0 {
+                        this.eof = true;
+                        return Poll::Ready(None);
+                    }
+                    this.read_buf.extend_from_slice(&chunk[..n]);
+                }
+            }
+        }
+    }
+}
+
+impl Sink<Message> for WebSocket {
+    type Error = crate::Error;
+
+    fn poll_ready(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<crate::Result<()>> {
+        self.poll_write_buf(cx).map_err(Into::into)
+    }
+
+    fn start_send(mut self: Pin<&mut Self>, msg: Message) -> crate::Result<()> {
+        self.queue_message(msg)
+    }
+
+    fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<crate::Result<()>> {
+        futures_lite::ready!(self.poll_write_buf(cx))?;
+        Pin::new(&mut self.conn).poll_flush(cx).map_err(Into::into)
+    }
+
+    fn poll_close(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<crate::Result<()>> {
+        if !self.close_sent {
+            self.queue_close(Some(CloseFrame::new(CloseCode::Normal, "")))?;
+        }
+        futures_lite::ready!(self.as_mut().poll_flush(cx))?;
+
+        // Wait for the peer to acknowledge the close before shutting down.
+        while !self.close_received && !self.eof {
+            match futures_lite::ready!(self.as_mut().poll_next(cx)) {
+                Some(Ok(_)) => continue,
+                Some(Err(err)) => return Poll::Ready(Err(err)),
+                None => break,
+            }
+        }
+        Pin::new(&mut self.conn).poll_close(cx).map_err(Into::into)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use futures_lite::io::Cursor;
+    use std::sync::{Arc, Mutex};
+
+    /// A connection which reads from a fixed buffer and records writes.
+    struct Mock {
+        input: Cursor<Vec<u8>>,
+        output: Arc<Mutex<Vec<u8>>>,
+    }
+
+    impl AsyncRead for Mock {
+        fn poll_read(
+            mut self: Pin<&mut Self>,
+            cx: &mut Context<'_>,
+            buf: &mut [u8],
+        ) -> Poll<io::Result<usize>> {
+            Pin::new(&mut self.input).poll_read(cx, buf)
+        }
+    }
+
+    impl AsyncWrite for Mock {
+        fn poll_write(
+            self: Pin<&mut Self>,
+            _: &mut Context<'_>,
+            buf: &[u8],
+        ) -> Poll<io::Result<usize>> {
+            self.output.lock().unwrap().extend_from_slice(buf);
+            Poll::Ready(Ok(buf.len()))
+        }
+
+        fn poll_flush(self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<io::Result<()>> {
+            Poll::Ready(Ok(()))
+        }
+
+        fn poll_close(self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<io::Result<()>> {
+            Poll::Ready(Ok(()))
+        }
+    }
+
+    fn socket(role: Role, frames: &[Frame]) -> (WebSocket, Arc<Mutex<Vec<u8>>>) {
+        let mask = match role {
+            Role::Server => Some([1, 2, 3, 4]),
+            Role::Client => None,
+        };
+        let mut input = vec![];
+        for frame in frames {
+            frame.encode(mask, &mut input);
+        }
+        let output = Arc::new(Mutex::new(vec![]));
+        let conn = Mock {
+            input: Cursor::new(input),
+            output: output.clone(),
+        };
+        (WebSocket::new(Connection::new(conn), role), output)
+    }
+
+    fn written(output: &Arc<Mutex<Vec<u8>>>) -> Vec<Frame> {
+        let buf = output.lock().unwrap();
+        let mut frames = vec![];
+        let mut offset = 0;
+        while let Some((frame, _, used)) = Frame::decode(&buf[offset..], usize::MAX).unwrap() {
+            frames.push(frame);
+            offset += used;
+        }
+        frames
+    }
+
+    #[async_std::test]
+    async fn fragmented_messages() -> crate::Result<()> {
+        let (mut ws, _) = socket(
+            Role::Server,
+            &[
+                Frame::new(false, OpCode::Text, b"Hel".to_vec()),
+                Frame::new(true, OpCode::Ping, b"ping".to_vec()),
+                Frame::new(true, OpCode::Continuation, b"lo".to_vec()),
+                Frame::new(true, OpCode::Binary, vec![1, 2, 3]),
+            ],
+        );
+        assert_eq!(ws.next().await.unwrap()?, Message::Ping(b"ping".to_vec()));
+        assert_eq!(ws.next().await.unwrap()?, Message::Text("Hello".into()));
+        assert_eq!(ws.next().await.unwrap()?, Message::Binary(vec![1, 2, 3]));
+        assert!(ws.next().await.is_none());
+        Ok(())
+    }
+
+    #[async_std::test]
+    async fn answers_pings_and_echoes_close() -> crate::Result<()> {
+        let close = CloseFrame::new(CloseCode::Away, "bye");
+        let (mut ws, output) = socket(
+            Role::Server,
+            &[
+                Frame::new(true, OpCode::Ping, b"hi".to_vec()),
+                Frame::new(true, OpCode::Close, close.encode()),
+            ],
+        );
+        ws.next().await.unwrap()?;
+        assert_eq!(ws.next().await.unwrap()?, Message::Close(Some(close)));
+        assert!(ws.next().await.is_none());
+
+        let frames = written(&output);
+        assert_eq!(frames[0], Frame::new(true, OpCode::Pong, b"hi".to_vec()));
+        assert_eq!(frames[1].opcode, OpCode::Close);
+        assert_eq!(
+            CloseFrame::decode(&frames[1].payload)?.unwrap().code(),
+            CloseCode::Away
+        );
+        Ok(())
+    }
+
+    #[async_std::test]
+    async fn protocol_errors_close_the_connection() {
+        // Servers require masked frames.
+        let (mut ws, output) = socket(Role::Client, &[Frame::new(true, OpCode::Text, vec![])]);
+        ws.role = Role::Server;
+        assert!(ws.next().await.unwrap().is_err());
+        assert!(ws.next().await.is_none());
+        let frames = written(&output);
+        let close = CloseFrame::decode(&frames[0].payload).unwrap().unwrap();
+        assert_eq!(close.code(), CloseCode::Protocol);
+
+        // Text messages must be valid UTF-8.
+        let (mut ws, output) = socket(
+            Role::Server,
+            &[Frame::new(true, OpCode::Text, vec![0xff, 0xfe])],
+        );
+        assert!(ws.next().await.unwrap().is_err());
+        let frames = written(&output);
+        let close = CloseFrame::decode(&frames[0].payload).unwrap().unwrap();
+        assert_eq!(close.code(), CloseCode::Invalid);
+
+        // Messages must not exceed the size limit.
+        let (mut ws, output) = socket(
+            Role::Server,
+            &[
+                Frame::new(false, OpCode::Binary, vec![0; 8]),
+                Frame::new(true, OpCode::Continuation, vec![0; 8]),
+            ],
+        );
+        ws.set_max_message_size(10);
+        assert!(ws.next().await.unwrap().is_err());
+        let frames = written(&output);
+        let close = CloseFrame::decode(&frames[0].payload).unwrap().unwrap();
+        assert_eq!(close.code(), CloseCode::Size);
+    }
+
+    #[async_std::test]
+    async fn sending() -> crate::Result<()> {
+        let (mut ws, output) = socket(Role::Client, &[]);
+        ws.send("hello").await?;
+        ws.set_max_frame_size(Some(2));
+        ws.send(vec![1, 2, 3]).await?;
+        assert!(ws.send(Message::Ping(vec![0; 126])).await.is_err());
+        ws.close(None).await?;
+        assert!(ws.send("too late").await.is_err());
+
+        let buf = output.lock().unwrap().clone();
+        let (frame, masked, used) = Frame::decode(&buf, usize::MAX)?.unwrap();
+        assert!(masked);
+        assert_eq!(frame, Frame::new(true, OpCode::Text, b"hello".to_vec()));
+
+        let frames = written(&output);
+        assert_eq!(frames.len(), 4);
+        assert_eq!(frames[1], Frame::new(false, OpCode::Binary, vec![1, 2]));
+        assert_eq!(frames[2], Frame::new(true, OpCode::Continuation, vec![3]));
+        assert_eq!(frames[3].opcode, OpCode::Close);
+        assert!(used < buf.len());
+        Ok(())
+    }
+}
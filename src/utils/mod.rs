@@ -40,3 +40,53 @@ pub(crate) fn sort_by_weight<T: PartialOrd + Clone>(props: &mut Vec<T>) {
     });
     *props = arr.into_iter().map(|(_, t)| t).collect::<Vec<T>>();
 }
+
+/// Decode a percent-encoded UTF-8 string.
+///
+/// Returns `None` if an escape is malformed or the decoded bytes aren't
+/// valid UTF-8.
+pub(crate) fn percent_decode(input: &str) -> Option<String> {
+    fn hex(byte: u8) -> Option<u8> {
+        (byte as char).to_digit(16).map(|digit| digit as u8)
+    }
+
+    let mut bytes = Vec::with_capacity(input.len());
+    let mut iter = input.bytes();
+    while let Some(byte) = iter.next() {
+        if byte == b'%' {
+            let hi = iter.next().and_then(hex)?;
+            let lo = iter.next().and_then(hex)?;
+            bytes.push(hi << 4 | lo);
+        } else {
+            bytes.push(byte);
+        }
+    }
+    String::from_utf8(bytes).ok()
+}
+
+/// Fill `bytes` from the operating system's random number generator.
+///
+/// Used for keys, nonces and salts, which must not be predictable.
+pub(crate) fn fill_random(bytes: &mut [u8]) {
+    getrandom::getrandom(bytes).expect("The system random number generator is unavailable");
+}
+
+/// Encode bytes as lowercase hexadecimal.
+#[cfg(any(feature = "digest-auth", feature = "scram"))]
+pub(crate) fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+/// Compute the HMAC of `data` with `key`, using the MAC type `M`.
+#[cfg(any(feature = "http-signatures", feature = "oauth1", feature = "scram"))]
+pub(crate) fn hmac<M: hmac::Mac + hmac::digest::KeyInit>(key: &[u8], data: &[u8]) -> Vec<u8> {
+    let mut mac = <M as hmac::Mac>::new_from_slice(key).expect("HMAC accepts keys of any length");
+    mac.update(data);
+    mac.finalize().into_bytes().to_vec()
+}
+
+/// Compare two byte slices in constant time, to avoid leaking how much of a
+/// secret matched through timing.
+pub(crate) fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0, |acc, (a, b)| acc | (a ^ b)) == 0
+}
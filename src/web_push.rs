@@ -0,0 +1,233 @@
+//! Sending messages to Web Push services.
+//!
+//! A user agent subscribes to push messages with an endpoint URL and a pair
+//! of keys. Messages are encrypted for the user agent with the `aes128gcm`
+//! content coding, and sent to the endpoint with a
+//! [`VapidAuth`] header identifying the sender.
+//!
+//! # Specifications
+//!
+//! - [RFC 8030: Generic Event Delivery Using HTTP Push](https://tools.ietf.org/html/rfc8030)
+//! - [RFC 8291: Message Encryption for Web Push](https://tools.ietf.org/html/rfc8291)
+//!
+//! # Examples
+//!
+//! ```
+//! # fn main() -> http_types::Result<()> {
+//! #
+//! use http_types::auth::VapidKey;
+//! use http_types::web_push::PushSubscription;
+//! use http_types::{Method, Url};
+//! use std::time::Duration;
+//!
+//! let key = VapidKey::generate();
+//! let subscription = PushSubscription::new(
+//!     Url::parse("https://push.example.net/push/JzLQ3raZJfFBR0aqvOMsLrt54w4rJUsV")?,
+//!     "BCVxsr7N_eNgVRqvHtD0zTZsEc6-VV-JvLexhqUzORcxaOzi6-AYWXvTBHm4bjyPjs7Vd8pZGH6SRpkNtoIAiw4",
+//!     "BTBZMqHH6r4Tts7J_aSIgg",
+//! )?;
+//!
+//! let ttl = Duration::from_secs(60 * 60);
+//! let req = subscription.request(b"Hello", &key, "mailto:push@example.com", ttl)?;
+//! assert_eq!(req.method(), Method::Post);
+//! assert_eq!(req["content-encoding"], "aes128gcm");
+//! assert_eq!(req["ttl"], "3600");
+//! #
+//! # Ok(()) }
+//! ```
+
+use crate::auth::{VapidAuth, VapidKey};
+use crate::content::{Aes128Gcm, ContentEncoding, Encoding};
+use crate::headers::TTL;
+use crate::utils::fill_random;
+use crate::{Body, Method, Request, Url};
+
+use hkdf::Hkdf;
+use p256::elliptic_curve::sec1::ToEncodedPoint;
+use p256::{PublicKey, SecretKey};
+use rand_core::OsRng;
+use sha2::Sha256;
+
+use std::time::{Duration, SystemTime};
+
+/// The largest payload which fits in a single 4096 byte record.
+const MAX_PAYLOAD_LEN: usize = 3993;
+
+/// The record size used for push messages.
+const RECORD_SIZE: u32 = 4096;
+
+/// How long the VAPID token of a request stays valid.
+const TOKEN_LIFETIME: Duration = Duration::from_secs(12 * 60 * 60);
+
+/// A push subscription of a user agent.
+///
+/// The values are those of the `endpoint` and `keys` members of the
+/// `PushSubscription` JSON object handed out by browsers.
+///
+/// # Specifications
+///
+/// - [RFC 8291, section 2: Push Message Encryption Overview](https://tools.ietf.org/html/rfc8291#section-2)
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct PushSubscription {
+    endpoint: Url,
+    p256dh: PublicKey,
+    auth: [u8; 16],
+}
+
+impl PushSubscription {
+    /// Create a new instance of `PushSubscription`.
+    ///
+    /// `p256dh` is the user agent's public key and `auth` its authentication
+    /// secret, both as URL-safe base64.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if either key is malformed.
+    pub fn new(endpoint: Url, p256dh: &str, auth: &str) -> crate::Result<Self> {
+        let p256dh = match decode(p256dh).map(|key| PublicKey::from_sec1_bytes(&key)) {
+            Some(Ok(key)) => key,
+            _ => crate::bail!("Invalid p256dh key for push subscription"),
+        };
+        let auth = match decode(auth) {
+            Some(auth) if auth.len() == 16 => {
+                let mut bytes = [0; 16];
+                bytes.copy_from_slice(&auth);
+                bytes
+            }
+            _ => crate::bail!("Push subscription auth secret must be 16 bytes long"),
+        };
+        Ok(Self {
+            endpoint,
+            p256dh,
+            auth,
+        })
+    }
+
+    /// Get the endpoint push messages are sent to.
+    pub fn endpoint(&self) -> &Url {
+        &self.endpoint
+    }
+
+    /// Encrypt `payload` for the user agent, returning the `aes128gcm`
+    /// encoded message.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the payload is longer than 3993 bytes.
+    pub fn encrypt(&self, payload: &[u8]) -> crate::Result<Vec<u8>> {
+        let mut salt = [0; 16];
+        fill_random(&mut salt);
+        self.encrypt_with(payload, &SecretKey::random(&mut OsRng), salt)
+    }
+
+    /// Create a request delivering `payload` to the push service.
+    ///
+    /// The request is signed with `key`, and asks the push service to keep
+    /// undelivered messages for `ttl`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the payload is too long, or the subject is not a
+    /// `mailto:` or `https:` URI.
+    pub fn request(
+        &self,
+        payload: &[u8],
+        key: &VapidKey,
+        subject: &str,
+        ttl: Duration,
+    ) -> crate::Result<Request> {
+        let expires = SystemTime::now() + TOKEN_LIFETIME;
+        let authz = VapidAuth::new(key, &self.endpoint, subject, expires)?;
+        let encoding = ContentEncoding::new(Encoding::Aes128Gcm);
+
+        let mut req = Request::new(Method::Post, self.endpoint.clone());
+        req.set_body(Body::from_bytes(self.encrypt(payload)?));
+        req.insert_header(&authz, &authz)?;
+        req.insert_header(&encoding, &encoding)?;
+        req.insert_header(TTL, ttl.as_secs().to_string())?;
+        Ok(req)
+    }
+
+    fn encrypt_with(
+        &self,
+        payload: &[u8],
+        key: &SecretKey,
+        salt: [u8; 16],
+    ) -> crate::Result<Vec<u8>> {
+        crate::ensure!(
+            payload.len() <= MAX_PAYLOAD_LEN,
+            "Push message payloads must be at most {} bytes long",
+            MAX_PAYLOAD_LEN
+        );
+
+        let shared = p256::ecdh::diffie_hellman(key.to_nonzero_scalar(), self.p256dh.as_affine());
+        let ua_public = self.p256dh.to_encoded_point(false);
+        let as_public = key.public_key().to_encoded_point(false);
+
+        let mut info = b"WebPush: info\0".to_vec();
+        info.extend_from_slice(ua_public.as_bytes());
+        info.extend_from_slice(as_public.as_bytes());
+        let mut ikm = [0; 32];
+        Hkdf::<Sha256>::new(Some(&self.auth), shared.raw_secret_bytes())
+            .expand(&info, &mut ikm)
+            .expect("32 bytes is a valid HKDF output length");
+
+        let mut coding = Aes128Gcm::new(ikm.to_vec());
+        coding.set_salt(salt);
+        coding.set_key_id(as_public.as_bytes().to_vec())?;
+        coding.set_record_size(RECORD_SIZE)?;
+        Ok(coding.encrypt(payload))
+    }
+}
+
+fn decode(s: &str) -> Option<Vec<u8>> {
+    base64::decode_config(s.trim_end_matches('='), base64::URL_SAFE_NO_PAD).ok()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn subscription() -> PushSubscription {
+        PushSubscription::new(
+            Url::parse("https://push.example.net/push/JzLQ3raZJfFBR0aqvOMsLrt54w4rJUsV").unwrap(),
+            "BCVxsr7N_eNgVRqvHtD0zTZsEc6-VV-JvLexhqUzORcxaOzi6-AYWXvTBHm4bjyPjs7Vd8pZGH6SRpkNtoIAiw4",
+            "BTBZMqHH6r4Tts7J_aSIgg",
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn rfc_8291_example() -> crate::Result<()> {
+        let key =
+            SecretKey::from_slice(&decode("yfWPiYE-n46HLnH0KqZOF1fJJU3MYrct3AELtAQ-oRw").unwrap())?;
+        let mut salt = [0; 16];
+        salt.copy_from_slice(&decode("DGv6ra1nlYgDCS1FRnbzlw").unwrap());
+
+        let body = subscription().encrypt_with(
+            b"When I grow up, I want to be a watermelon",
+            &key,
+            salt,
+        )?;
+        assert_eq!(
+            base64::encode_config(body, base64::URL_SAFE_NO_PAD),
+            "DGv6ra1nlYgDCS1FRnbzlwAAEABBBP4z9KsN6nGRTbVYI_c7VJSPQTBtkgcy27mlmlMoZIIgDll6e3vCYLocInmYWAmS6TlzAC8wEqKK6PBru3jl7A_yl95bQpu6cVPTpK4Mqgkf1CXztLVBSt2Ks3oZwbuwXPXLWyouBWLVWGNWQexSgSxsj_Qulcy4a-fN"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn payload_limit() {
+        let subscription = subscription();
+        assert!(subscription.encrypt(&[0; MAX_PAYLOAD_LEN]).is_ok());
+        assert!(subscription.encrypt(&[0; MAX_PAYLOAD_LEN + 1]).is_err());
+    }
+
+    #[test]
+    fn invalid_keys() {
+        let endpoint = Url::parse("https://push.example.net/").unwrap();
+        assert!(PushSubscription::new(endpoint.clone(), "AAAA", "BTBZMqHH6r4Tts7J_aSIgg").is_err());
+        let p256dh = "BCVxsr7N_eNgVRqvHtD0zTZsEc6-VV-JvLexhqUzORcxaOzi6-AYWXvTBHm4bjyPjs7Vd8pZGH6SRpkNtoIAiw4";
+        assert!(PushSubscription::new(endpoint, p256dh, "BTBZ").is_err());
+    }
+}